@@ -7,6 +7,7 @@ use wamu_cggmp::{
     generate_parties_and_simulate_share_removal, generate_parties_and_simulate_signing,
     generate_parties_and_simulate_threshold_modification, simulate_keygen,
 };
+use wamu_core::quorum_size;
 
 /// Wamu augmented CGGMP CLI.
 #[derive(Debug, Parser)]
@@ -126,7 +127,7 @@ fn main() {
             println!(
                 "Simulating key generation with threshold={}, quorum-size={}, number of parties={}",
                 threshold,
-                threshold + 1,
+                quorum_size(threshold),
                 n_parties,
             );
             let (keys, identity_providers) = simulate_keygen(threshold, n_parties);
@@ -152,7 +153,7 @@ fn main() {
             println!(
                 "Simulating key refresh with threshold={}, quorum-size={}, number of parties={}",
                 threshold,
-                threshold + 1,
+                quorum_size(threshold),
                 n_parties,
             );
             let ((keys_init, identity_providers), (keys_new, _)) =
@@ -187,7 +188,7 @@ fn main() {
             println!(
                 "Simulating signing with threshold={}, quorum-size={}, number of parties={}, number of participants={}",
                 threshold,
-                threshold + 1,
+                quorum_size(threshold),
                 n_parties,
                 n_participants,
             );
@@ -231,7 +232,7 @@ fn main() {
             println!(
                 "Simulating identity rotation with threshold={}, quorum-size={}, number of parties={}",
                 threshold,
-                threshold + 1,
+                quorum_size(threshold),
                 n_parties,
             );
             let rotating_party_idx = 2;
@@ -279,7 +280,7 @@ fn main() {
             println!(
                 "Simulating share addition with threshold={}, quorum-size={}, initial number of parties={}, number of parties to add={}",
                 threshold,
-                threshold + 1,
+                quorum_size(threshold),
                 n_parties_init,
                 n_parties_add,
             );
@@ -329,7 +330,7 @@ fn main() {
             println!(
                 "Simulating share removal with threshold={}, quorum-size={}, initial number of parties={}, number of parties to remove={}",
                 threshold,
-                threshold + 1,
+                quorum_size(threshold),
                 n_parties_init,
                 n_parties_remove,
             );
@@ -379,9 +380,9 @@ fn main() {
             println!(
                 "Simulating threshold modification with initial threshold={}, initial quorum-size={}, final threshold={}, final quorum-size={}, number of parties={}",
                 threshold_init,
-                threshold_init + 1,
+                quorum_size(threshold_init),
                 threshold_new,
-                threshold_new + 1,
+                quorum_size(threshold_new),
                 n_parties,
             );
             let (keys_init, keys_new, identity_providers) =
@@ -418,7 +419,7 @@ fn main() {
             println!(
                 "Simulating share recovery with quorum with threshold={}, quorum-size={}, number of parties={}",
                 threshold,
-                threshold + 1,
+                quorum_size(threshold),
                 n_parties,
             );
             let recovering_party_idx = 2;
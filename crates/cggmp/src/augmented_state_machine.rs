@@ -1,12 +1,22 @@
 //! Types, traits, abstractions and utilities for augmenting a [`StateMachine`](StateMachine).
 
+use cggmp_threshold_ecdsa::presign::SSID;
 use curv::elliptic::curves::{ECScalar, Scalar, Secp256k1};
 use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen::LocalKey;
 use round_based::{IsCritical, Msg, StateMachine};
+use std::collections::{BTreeSet, HashMap};
 use std::ops::Deref;
-use wamu_core::crypto::{Signature, VerifyingKey};
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::Instant;
+use wamu_core::crypto::{
+    EllipticCurve, KeyEncoding, PartyId, Signature, SignatureAlgorithm, VerifyingKey,
+};
 use wamu_core::{IdentityProvider, SecretShare, SigningShare, SubShare};
-use zeroize::Zeroize;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Domain separation tag for party set hashes.
+const PARTY_SET_HASH_TAG: &[u8] = b"wamu:party-set-hash";
 
 /// A [`StateMachine`](StateMachine) that wraps and augments another [`StateMachine`](StateMachine).
 pub trait AugmentedStateMachine {
@@ -47,6 +57,45 @@ pub trait AugmentedStateMachine {
         >,
     >;
 
+    /// Returns an immutable reference to the augmentation verification failure tracker.
+    fn augmentation_failures(&self) -> &AugmentationFailures;
+
+    /// Returns a mutable reference to the augmentation verification failure tracker.
+    fn augmentation_failures_mut(&mut self) -> &mut AugmentationFailures;
+
+    /// Sets the number of consecutive augmentation verification failures after which a party is banned
+    /// (i.e all further messages from the party are ignored for the rest of the protocol).
+    ///
+    /// **NOTE:** Defaults to [`DEFAULT_MAX_AUGMENTATION_FAILURES`].
+    fn set_max_augmentation_failures(&mut self, max_failures: u16) {
+        self.augmentation_failures_mut().max_failures = max_failures;
+    }
+
+    /// Returns the ceremony-wide deadline (if any).
+    fn deadline(&self) -> Option<Instant>;
+
+    /// Returns a mutable reference to the ceremony-wide deadline (if any).
+    fn deadline_mut(&mut self) -> &mut Option<Instant>;
+
+    /// Sets a wall-clock deadline for the entire ceremony
+    /// after which both [`augmented_handle_incoming`](Self::augmented_handle_incoming) and [`augmented_proceed`](Self::augmented_proceed)
+    /// refuse to proceed and return an [`Error::DeadlineExceeded`] error.
+    ///
+    /// **NOTE:** Unlike round timeouts, the deadline caps the total duration of the ceremony (e.g for multi-tenant services).
+    fn set_deadline(&mut self, deadline: Instant) {
+        *self.deadline_mut() = Some(deadline);
+    }
+
+    /// Returns an [`Error::DeadlineExceeded`] error (with the current round) if the ceremony-wide deadline (if any) has passed.
+    fn check_deadline(&self) -> Result<(), Error<<Self::StateMachineType as StateMachine>::Err>> {
+        match self.deadline() {
+            Some(deadline) if Instant::now() >= deadline => Err(Error::DeadlineExceeded {
+                last_round: self.state_machine().current_round(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
     /// Augmentations to run before calling `handle_incoming` on the wrapped `StateMachine`.
     fn pre_handle_incoming(
         &mut self,
@@ -137,13 +186,66 @@ pub trait AugmentedStateMachine {
             >,
         >,
     ) -> Result<(), Error<<Self::StateMachineType as StateMachine>::Err>> {
+        // NOTE: `tracing` events never include secrets (e.g message bodies, shares or outputs).
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            party = self.state_machine().party_ind(),
+            sender = msg.sender,
+            round = self.state_machine().current_round(),
+            "message received"
+        );
+
+        // Refuses to proceed once past the ceremony-wide deadline (if any).
+        self.check_deadline()?;
+
+        // Ignores messages from banned parties (i.e without verifying their augmentations).
+        if self.augmentation_failures().is_banned(msg.sender) {
+            return Err(Error::PartyBanned { party: msg.sender });
+        }
+
         // Hook to run augmentations before calling `handle_incoming`.
-        self.pre_handle_incoming(&msg)?;
+        let augmentation_result = self.pre_handle_incoming(&msg);
+        #[cfg(feature = "tracing")]
+        if augmentation_result.is_ok() {
+            tracing::debug!(
+                party = self.state_machine().party_ind(),
+                sender = msg.sender,
+                "augmentation verified"
+            );
+        } else {
+            tracing::warn!(
+                party = self.state_machine().party_ind(),
+                sender = msg.sender,
+                "augmentation verification failed"
+            );
+        }
+        // Tracks consecutive augmentation verification failures and bans the sender after too many failures.
+        match augmentation_result {
+            Ok(()) => self.augmentation_failures_mut().record_success(msg.sender),
+            // Verification failures only drop the message (until the sender is banned).
+            Err(error @ (Error::Core(_) | Error::MissingParams { .. })) => {
+                return if self.augmentation_failures_mut().record_failure(msg.sender) {
+                    Err(Error::PartyBanned { party: msg.sender })
+                } else {
+                    Err(Error::RejectedAugmentation {
+                        party: msg.sender,
+                        reason: Box::new(error),
+                    })
+                };
+            }
+            // Other failures (e.g parties that disagree on the set of parties) can't be recovered from.
+            Err(error) => return Err(error),
+        }
 
-        // Forwards all incoming messages to wrapped state machine.
-        self.state_machine_mut()
-            .handle_incoming(msg.map_body(|msg_body| msg_body.base))
-            .map_err(Error::StateMachine)?;
+        // Forwards all incoming messages to wrapped state machine
+        // (and converts panics from the wrapped state machine into errors e.g for malformed messages).
+        let round = self.state_machine().current_round();
+        std::panic::catch_unwind(AssertUnwindSafe(|| {
+            self.state_machine_mut()
+                .handle_incoming(msg.map_body(|msg_body| msg_body.base))
+        }))
+        .map_err(|_| Error::InnerPanic { round })?
+        .map_err(Error::StateMachine)?;
 
         // Updates the augmented message queue.
         self.update_augmented_message_queue()
@@ -153,14 +255,30 @@ pub trait AugmentedStateMachine {
     fn augmented_proceed(
         &mut self,
     ) -> Result<(), Error<<Self::StateMachineType as StateMachine>::Err>> {
+        // Refuses to proceed once past the ceremony-wide deadline (if any).
+        self.check_deadline()?;
+
         // Hook to run augmentations before calling `proceed`.
         self.pre_proceed()?;
 
-        // Call `proceed` on the wrapped state machine.
-        self.state_machine_mut()
-            .proceed()
+        let prev_round = self.state_machine().current_round();
+
+        // Call `proceed` on the wrapped state machine
+        // (and converts panics from the wrapped state machine into errors).
+        std::panic::catch_unwind(AssertUnwindSafe(|| self.state_machine_mut().proceed()))
+            .map_err(|_| Error::InnerPanic { round: prev_round })?
             .map_err(Error::StateMachine)?;
 
+        #[cfg(feature = "tracing")]
+        if self.state_machine().current_round() != prev_round {
+            tracing::debug!(
+                party = self.state_machine().party_ind(),
+                from = prev_round,
+                to = self.state_machine().current_round(),
+                "round advanced"
+            );
+        }
+
         // Updates the augmented message queue.
         self.update_augmented_message_queue()
     }
@@ -187,7 +305,7 @@ pub trait AugmentedStateMachine {
         let result = self.state_machine_mut().pick_output()?;
 
         // Augments output with additional parameters or returns wrapped state machine error.
-        Some(match result {
+        let result = match result {
             Ok(output) => {
                 match self.augment_output(output) {
                     // Adds augmentations (if any).
@@ -197,12 +315,35 @@ pub trait AugmentedStateMachine {
                 }
             }
             Err(error) => Err(Error::StateMachine(error)),
-        })
+        };
+
+        #[cfg(feature = "tracing")]
+        if result.is_ok() {
+            tracing::debug!(party = self.state_machine().party_ind(), "output produced");
+        } else {
+            tracing::warn!(party = self.state_machine().party_ind(), "protocol failed");
+        }
+
+        Some(result)
     }
 }
 
+/// A [`StateMachine`](StateMachine) with human-readable round labels (e.g for user-facing progress reporting).
+pub trait RoundLabel {
+    /// Returns a human-readable label for the current round (e.g "Commitment" or "Key Refresh Round 2").
+    fn round_label(&self) -> String;
+}
+
+/// A [`StateMachine`](StateMachine) with a stable party identifier
+/// (i.e unlike the party index, it isn't reassigned across key refresh, share addition and share removal).
+pub trait PartyIdentity {
+    /// Returns the stable identifier of the party (e.g for audit logs and transcripts).
+    fn party_id(&self) -> PartyId;
+}
+
 /// A generic augmented type.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AugmentedType<T, E> {
     /// Base parameters.
     pub base: T,
@@ -212,16 +353,193 @@ pub struct AugmentedType<T, E> {
 
 /// Additional parameters for identity authentication.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IdentityAuthParams {
     /// Verifying key of the party (i.e `sk_i`).
     pub verifying_key: VerifyingKey,
     /// Verifying signature (e.g `varphi_i` or `psi`).
     pub verifying_signature: Signature,
+    /// Commitment to the set of parties of the sender (see [`party_set_hash`]).
+    pub party_set_hash: [u8; 32],
+    /// The current threshold claimed by the sender (i.e only set by new parties for key refresh).
+    pub current_threshold: Option<u16>,
+}
+
+impl IdentityAuthParams {
+    /// Given the parameter hash of a message and the party set hash of the sender (see [`party_set_hash`]),
+    /// returns the bytes signed by the sender (i.e the signature binds the parameter hash to the committed set of parties).
+    ///
+    /// **NOTE:** The party set hash has a fixed length, so appending it to the parameter hash is unambiguous.
+    pub(crate) fn signed_bytes(parameter_hash: &[u8], party_set_hash: &[u8; 32]) -> Vec<u8> {
+        [parameter_hash, party_set_hash.as_slice()].concat()
+    }
+
+    /// Given the index of the sender and the expected party set hash (see [`party_set_hash`]),
+    /// returns an ok result if the sender committed to the same set of parties, or a [`Error::PartySetMismatch`] error otherwise.
+    ///
+    /// **NOTE:** The signature over [`signed_bytes`](Self::signed_bytes) must be verified first,
+    /// so that a mismatch is only ever attributed to the authenticated sender.
+    pub(crate) fn verify_party_set<T: IsCritical>(
+        &self,
+        sender: u16,
+        expected_party_set_hash: &[u8; 32],
+    ) -> Result<(), Error<T>> {
+        if &self.party_set_hash == expected_party_set_hash {
+            Ok(())
+        } else {
+            Err(Error::PartySetMismatch { party: sender })
+        }
+    }
+}
+
+/// Given the verifying key of a party and its verified parties,
+/// returns a commitment (i.e a SHA-256 hash) to the set of parties (e.g for detecting parties that disagree on the set of parties).
+///
+/// **NOTE:** The hash is independent of the order of the verified parties and of whether or not they include the party itself.
+pub fn party_set_hash(verifying_key: &VerifyingKey, verified_parties: &[VerifyingKey]) -> [u8; 32] {
+    use sha2::Digest;
+    let party_ids: BTreeSet<PartyId> = verified_parties
+        .iter()
+        .chain(std::iter::once(verifying_key))
+        .map(PartyId::from_verifying_key)
+        .collect();
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(PARTY_SET_HASH_TAG);
+    for party_id in party_ids {
+        hasher.update(party_id.to_bytes());
+    }
+    hasher.finalize().into()
 }
 
 /// Additional output as "signing share" and "sub-share" tuple.
 pub type SubShareOutput = (SigningShare, SubShare);
 
+/// Verifying keys for the verified parties of a party, either borrowed or shared
+/// (i.e reference counted, so that the party owns its set of parties e.g for parties held by long-lived async tasks).
+#[derive(Debug, Clone)]
+pub enum PartyList<'a> {
+    /// A borrowed list of verifying keys.
+    Borrowed(&'a [VerifyingKey]),
+    /// A shared (i.e reference counted) list of verifying keys.
+    Shared(Arc<[VerifyingKey]>),
+}
+
+impl Deref for PartyList<'_> {
+    type Target = [VerifyingKey];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            PartyList::Borrowed(verified_parties) => verified_parties,
+            PartyList::Shared(verified_parties) => verified_parties,
+        }
+    }
+}
+
+impl<'a> From<&'a [VerifyingKey]> for PartyList<'a> {
+    fn from(verified_parties: &'a [VerifyingKey]) -> Self {
+        PartyList::Borrowed(verified_parties)
+    }
+}
+
+impl<'a> From<&'a Vec<VerifyingKey>> for PartyList<'a> {
+    fn from(verified_parties: &'a Vec<VerifyingKey>) -> Self {
+        PartyList::Borrowed(verified_parties)
+    }
+}
+
+impl From<Arc<[VerifyingKey]>> for PartyList<'_> {
+    fn from(verified_parties: Arc<[VerifyingKey]>) -> Self {
+        PartyList::Shared(verified_parties)
+    }
+}
+
+/// The group (i.e shared) public key of a key generation (or key refresh) output as a `wamu-core` verifying key
+/// (i.e without exposing `curv` or `multi-party-ecdsa` types).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupKey(VerifyingKey);
+
+impl GroupKey {
+    /// Returns the group public key as an ECDSA/Secp256k1 verifying key (in compressed SEC1 format).
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.0
+    }
+
+    /// Given a chain code and a network, returns the group public key as a serialized (i.e Base58Check encoded)
+    /// BIP-32 extended public key (i.e an "xpub" or "tpub") for deriving child public keys (e.g receive addresses).
+    ///
+    /// **NOTE:** The extended public key is a master key (i.e depth zero, no parent fingerprint and child number zero),
+    /// and contains only public key material, so hardened child keys can't be derived from it.
+    ///
+    /// Ref: <https://github.com/bitcoin/bips/blob/master/bip-0032.mediawiki#serialization-format>.
+    pub fn to_xpub(&self, chain_code: [u8; 32], network: Network) -> String {
+        let mut bytes = Vec::with_capacity(78);
+        // Version bytes.
+        bytes.extend_from_slice(&network.xpub_version());
+        // Depth (i.e zero for master keys).
+        bytes.push(0);
+        // Parent fingerprint (i.e zero for master keys).
+        bytes.extend_from_slice(&[0; 4]);
+        // Child number (i.e zero for master keys).
+        bytes.extend_from_slice(&[0; 4]);
+        // Chain code.
+        bytes.extend_from_slice(&chain_code);
+        // Public key (in compressed SEC1 format).
+        bytes.extend_from_slice(&self.0.key);
+        bs58::encode(bytes).with_check().into_string()
+    }
+}
+
+/// A network for serializing BIP-32 extended keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Network {
+    /// Mainnet (i.e "xpub" extended public keys).
+    Mainnet,
+    /// Testnet (i.e "tpub" extended public keys).
+    Testnet,
+}
+
+impl Network {
+    /// Returns the BIP-32 version bytes for extended public keys on the network.
+    fn xpub_version(&self) -> [u8; 4] {
+        match self {
+            Network::Mainnet => [0x04, 0x88, 0xB2, 0x1E],
+            Network::Testnet => [0x04, 0x35, 0x87, 0xCF],
+        }
+    }
+}
+
+impl TryFrom<&LocalKey<Secp256k1>> for GroupKey {
+    type Error = wamu_core::Error;
+
+    /// Converts the public key of a `LocalKey<Secp256k1>` into a group key.
+    fn try_from(value: &LocalKey<Secp256k1>) -> Result<Self, Self::Error> {
+        let public_key = value.public_key();
+        // The point at infinity is not a valid public key.
+        if public_key.is_zero() {
+            return Err(wamu_core::Error::Encoding);
+        }
+        Ok(Self(VerifyingKey {
+            key: public_key.to_bytes(true).to_vec(),
+            algo: SignatureAlgorithm::ECDSA,
+            curve: EllipticCurve::Secp256k1,
+            enc: KeyEncoding::SEC1,
+        }))
+    }
+}
+
+impl From<GroupKey> for VerifyingKey {
+    fn from(group_key: GroupKey) -> Self {
+        group_key.0
+    }
+}
+
+impl<E> AugmentedType<LocalKey<Secp256k1>, E> {
+    /// Returns the group public key of the key output.
+    pub fn group_key(&self) -> Result<GroupKey, wamu_core::Error> {
+        GroupKey::try_from(&self.base)
+    }
+}
+
 /// A generic augmented state machine error.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Error<T: IsCritical> {
@@ -233,6 +551,45 @@ pub enum Error<T: IsCritical> {
     MissingParams { bad_actors: Vec<usize> },
     /// An insecure FS-DKR threshold (i.e t > n/2, breaking the honest majority assumption).
     BadFSDKRThreshold,
+    /// An invalid party index (i.e not in the range `1..=n_parties`).
+    BadPartyIndex,
+    /// An invalid map of current to new party indices (i.e new indices that are either not in the range `1..=n_parties` or not unique).
+    BadIndexMap,
+    /// Missing "signing share" and/or "sub-share" for an existing party.
+    MissingShares,
+    /// A key refresh output whose group public key differs from the group public key before the refresh.
+    PublicKeyChanged,
+    /// The wrapped state machine panicked (e.g on a malformed message) in the given round.
+    InnerPanic { round: u16 },
+    /// A presignature that has already been used to sign a message.
+    PresignatureReused,
+    /// A party that's banned after too many consecutive augmentation verification failures
+    /// (i.e all further messages from the party are ignored).
+    PartyBanned { party: u16 },
+    /// A message whose augmentations failed verification (i.e the message is ignored),
+    /// the sender is banned after too many consecutive failures (see [`Error::PartyBanned`]).
+    RejectedAugmentation { party: u16, reason: Box<Error<T>> },
+    /// A verifying key that's associated with more than one party
+    /// (e.g duplicate verified parties, verified parties that don't align with party indices
+    /// or a party signing with the verifying key of another party).
+    AmbiguousPartyIdentity { bad_actors: Vec<usize> },
+    /// A local message to be signed that doesn't match the agreed message commitment.
+    MessageCommitmentMismatch,
+    /// A key that isn't a 2-of-2 key (i.e `n_parties = 2` and `threshold = 1`) used with a two-party helper.
+    NotTwoPartyKey,
+    /// Fewer available signing parties than required (i.e `threshold + 1`).
+    InsufficientSigners { have: usize, need: usize },
+    /// A party that committed to a different set of parties (i.e a misconfigured list of verified parties).
+    PartySetMismatch { party: u16 },
+    /// The ceremony-wide deadline has passed (i.e the protocol was aborted in the given round).
+    DeadlineExceeded { last_round: u16 },
+    /// A new party that claimed a different current threshold for key refresh.
+    ThresholdMismatch { party: u16 },
+    /// A presignature computed by a different set of parties than the signing parties (both in ascending order).
+    PresignerSetMismatch {
+        presigners: Vec<u16>,
+        signers: Vec<u16>,
+    },
 }
 
 impl<T: IsCritical> IsCritical for Error<T> {
@@ -246,6 +603,34 @@ impl<T: IsCritical> IsCritical for Error<T> {
             Error::MissingParams { .. } => true,
             // FS-DKR assumptions can't be broken for key refresh.
             Error::BadFSDKRThreshold => true,
+            // Invalid configurations can't be recovered from.
+            Error::BadPartyIndex | Error::BadIndexMap | Error::MissingShares => true,
+            // The group public key must never change.
+            Error::PublicKeyChanged => true,
+            // The wrapped state machine may be left in an inconsistent state after a panic.
+            Error::InnerPanic { .. } => true,
+            // Reusing a presignature leaks the secret share.
+            Error::PresignatureReused => true,
+            // A party's position must be unambiguous.
+            Error::AmbiguousPartyIdentity { .. } => true,
+            // Parties must never sign a message other than the agreed one.
+            Error::MessageCommitmentMismatch => true,
+            // Invalid configurations can't be recovered from.
+            Error::NotTwoPartyKey => true,
+            // Signing can't complete without enough signing parties.
+            Error::InsufficientSigners { .. } => true,
+            // Parties that disagree on the set of parties can't complete the protocol.
+            Error::PartySetMismatch { .. } => true,
+            // The ceremony can't proceed past its deadline.
+            Error::DeadlineExceeded { .. } => true,
+            // Parties that disagree on the current threshold can't complete the key refresh consistently.
+            Error::ThresholdMismatch { .. } => true,
+            // Signing with a presignature from a different set of parties produces an invalid signature.
+            Error::PresignerSetMismatch { .. } => true,
+            // Messages from banned parties are ignored, other parties can still complete the protocol (if possible).
+            Error::PartyBanned { .. } => false,
+            // Rejected messages are ignored until the sender is banned.
+            Error::RejectedAugmentation { .. } => false,
         }
     }
 }
@@ -326,9 +711,10 @@ macro_rules! impl_state_machine_for_augmented_state_machine {
 
 /// Implements all required `AugmentedStateMachine` methods (i.e methods with no default implementation).
 ///
-/// Requires names of the fields that store the wrapped `StateMachine` and the augment message queue.
+/// Requires names of the fields that store the wrapped `StateMachine`, the augment message queue,
+/// the augmentation verification failure tracker and the ceremony-wide deadline.
 macro_rules! impl_required_augmented_state_machine_methods {
-    ($state_machine:ident, $message_queue:ident) => {
+    ($state_machine:ident, $message_queue:ident, $augmentation_failures:ident, $deadline:ident) => {
         /// Returns an immutable reference to the wrapped state machine.
         fn state_machine(&self) -> &Self::StateMachineType {
             &self.$state_machine
@@ -366,6 +752,26 @@ macro_rules! impl_required_augmented_state_machine_methods {
         > {
             &mut self.$message_queue
         }
+
+        /// Returns an immutable reference to the augmentation verification failure tracker.
+        fn augmentation_failures(&self) -> &AugmentationFailures {
+            &self.$augmentation_failures
+        }
+
+        /// Returns a mutable reference to the augmentation verification failure tracker.
+        fn augmentation_failures_mut(&mut self) -> &mut AugmentationFailures {
+            &mut self.$augmentation_failures
+        }
+
+        /// Returns the ceremony-wide deadline (if any).
+        fn deadline(&self) -> Option<std::time::Instant> {
+            self.$deadline
+        }
+
+        /// Returns a mutable reference to the ceremony-wide deadline (if any).
+        fn deadline_mut(&mut self) -> &mut Option<std::time::Instant> {
+            &mut self.$deadline
+        }
     };
 }
 
@@ -391,6 +797,95 @@ from_state_machine_error! {
     multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen => (key_gen_state_machine, Keygen),
 }
 
+/// Bookkeeping of the parties that have delivered their message for each round (e.g for monitoring round progress).
+#[derive(Debug, Clone, Default)]
+pub struct RoundProgress {
+    /// Indices of the parties that have delivered their message keyed by round.
+    received: HashMap<u16, BTreeSet<u16>>,
+}
+
+impl RoundProgress {
+    /// Records that a party has delivered their message for a round.
+    pub fn record(&mut self, round: u16, sender: u16) {
+        self.received.entry(round).or_default().insert(sender);
+    }
+
+    /// Given a round, returns the indices of the parties that have delivered their message for the round (in ascending order).
+    pub fn received_parties(&self, round: u16) -> Vec<u16> {
+        self.received
+            .get(&round)
+            .map(|senders| senders.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Given a round and the indices of the parties expected to deliver a message for the round,
+    /// returns the indices of the parties whose message for the round is still outstanding.
+    pub fn pending_parties(
+        &self,
+        round: u16,
+        expected_senders: impl IntoIterator<Item = u16>,
+    ) -> Vec<u16> {
+        let received = self.received.get(&round);
+        expected_senders
+            .into_iter()
+            .filter(|sender| !received.is_some_and(|senders| senders.contains(sender)))
+            .collect()
+    }
+}
+
+/// The default number of consecutive augmentation verification failures after which a party is banned.
+pub const DEFAULT_MAX_AUGMENTATION_FAILURES: u16 = 3;
+
+/// Bookkeeping of consecutive augmentation verification failures for each party
+/// (e.g to cap the cost of verifying spammed invalid augmentation signatures).
+#[derive(Debug, Clone)]
+pub struct AugmentationFailures {
+    /// The number of consecutive failures after which a party is banned.
+    max_failures: u16,
+    /// The number of consecutive failures keyed by party index.
+    consecutive_failures: HashMap<u16, u16>,
+    /// Indices of banned parties.
+    banned: BTreeSet<u16>,
+}
+
+impl Default for AugmentationFailures {
+    fn default() -> Self {
+        Self {
+            max_failures: DEFAULT_MAX_AUGMENTATION_FAILURES,
+            consecutive_failures: HashMap::new(),
+            banned: BTreeSet::new(),
+        }
+    }
+}
+
+impl AugmentationFailures {
+    /// Records a successful augmentation verification for a party (i.e resets its consecutive failures).
+    pub fn record_success(&mut self, party: u16) {
+        self.consecutive_failures.remove(&party);
+    }
+
+    /// Records a failed augmentation verification for a party,
+    /// and returns true if the party is banned (i.e it reached the maximum number of consecutive failures).
+    pub fn record_failure(&mut self, party: u16) -> bool {
+        let failures = self.consecutive_failures.entry(party).or_default();
+        *failures = failures.saturating_add(1);
+        if *failures >= self.max_failures {
+            self.banned.insert(party);
+        }
+        self.is_banned(party)
+    }
+
+    /// Returns true if the party is banned.
+    pub fn is_banned(&self, party: u16) -> bool {
+        self.banned.contains(&party)
+    }
+
+    /// Returns the indices of banned parties (in ascending order).
+    pub fn banned_parties(&self) -> Vec<u16> {
+        self.banned.iter().copied().collect()
+    }
+}
+
 /// Given an identity provider and key output (e.g from key generation or key refresh),
 /// returns augmented key output with the secret share cleared/zerorized,
 /// along with its split "signing share" and "sub-share"
@@ -403,14 +898,14 @@ pub fn split_key_output(
 ) -> Result<AugmentedType<LocalKey<Secp256k1>, SubShareOutput>, wamu_core::Error> {
     // Retrieves secret share.
     let secret_share = SecretShare::try_from(output.keys_linear.x_i.to_bytes().deref())?;
-    // Splits the secret share or returns appropriate error.
-    let sub_shares = wamu_core::share_split_reconstruct::split(&secret_share, identity_provider)?;
-    // Zerorize the secret share in `LocalKey<Secp256k1>` output.
+    // Splits the secret share.
+    let sub_shares_result =
+        wamu_core::share_split_reconstruct::split(&secret_share, identity_provider);
+    // Zerorize the secret share in `LocalKey<Secp256k1>` output (on both success and error paths).
     // NOTE: `wamu_core::SecretShare` implements `ZerorizeOnDrop` so we don't need to zerorize it explicitly.
-    if let Some(raw_x_i) = output.keys_linear.x_i.into_raw().underlying_mut() {
-        raw_x_i.zeroize();
-    }
-    output.keys_linear.x_i = Scalar::<Secp256k1>::zero();
+    clear_secret_share(&mut output);
+    // Returns appropriate error if splitting failed.
+    let sub_shares = sub_shares_result?;
     // Return augmented key output.
     Ok(AugmentedType {
         base: output,
@@ -418,6 +913,71 @@ pub fn split_key_output(
     })
 }
 
+/// Given a reconstructed secret share, returns it as a `Scalar<Secp256k1>` (e.g for setting the secret share of a key output).
+///
+/// **NOTE:** The intermediate byte representation of the secret share is zerorized on both success and error paths.
+pub(crate) fn secret_share_scalar(
+    secret_share: &SecretShare,
+) -> Result<Scalar<Secp256k1>, wamu_core::Error> {
+    let secret_share_bytes = Zeroizing::new(secret_share.to_be_bytes());
+    Scalar::<Secp256k1>::from_bytes(secret_share_bytes.as_slice())
+        .map_err(|_| wamu_core::Error::Encoding)
+}
+
+/// Zerorizes the secret share of a key output (e.g from key generation or key refresh).
+pub(crate) fn clear_secret_share(output: &mut LocalKey<Secp256k1>) {
+    if let Some(raw_x_i) = output.keys_linear.x_i.into_raw().underlying_mut() {
+        raw_x_i.zeroize();
+    }
+    output.keys_linear.x_i = Scalar::<Secp256k1>::zero();
+}
+
+/// A key that holds a (reconstructed) secret share (e.g a `LocalKey<Secp256k1>` or the `SSID<Secp256k1>` of a signing party).
+pub(crate) trait ClearSecretShare {
+    /// Zerorizes the secret share.
+    fn clear_secret_share(&mut self);
+}
+
+impl ClearSecretShare for LocalKey<Secp256k1> {
+    fn clear_secret_share(&mut self) {
+        clear_secret_share(self);
+    }
+}
+
+impl ClearSecretShare for SSID<Secp256k1> {
+    fn clear_secret_share(&mut self) {
+        clear_secret_share(&mut self.X);
+    }
+}
+
+impl<K: ClearSecretShare> ClearSecretShare for Option<K> {
+    fn clear_secret_share(&mut self) {
+        if let Some(key) = self.as_mut() {
+            key.clear_secret_share();
+        }
+    }
+}
+
+/// A key with a reconstructed secret share that's zerorized when it's dropped
+/// (i.e on both success and error paths of an augmented state machine constructor).
+///
+/// **NOTE:** The wrapped state machine should be initialized with a copy of the key (see [`handoff`](Self::handoff)),
+/// that copy is owned (and dropped) by the wrapped state machine.
+pub(crate) struct TransientSecretShare<K: ClearSecretShare>(pub(crate) K);
+
+impl<K: ClearSecretShare + Clone> TransientSecretShare<K> {
+    /// Returns a copy of the key for initializing the wrapped state machine.
+    pub(crate) fn handoff(&self) -> K {
+        self.0.clone()
+    }
+}
+
+impl<K: ClearSecretShare> Drop for TransientSecretShare<K> {
+    fn drop(&mut self) {
+        self.0.clear_secret_share();
+    }
+}
+
 // Implement `Debug` trait for `AugmentedType` for test simulations.
 #[cfg(any(test, feature = "dev"))]
 impl<T, E> std::fmt::Debug for AugmentedType<T, E> {
@@ -425,3 +985,306 @@ impl<T, E> std::fmt::Debug for AugmentedType<T, E> {
         write!(f, "Augmented Type")
     }
 }
+
+/// Given a "signing share", a "sub-share" and an identity provider,
+/// returns the reconstructed secret share as a `Scalar<Secp256k1>` (e.g for (re)loading the secret share of a `LocalKey<Secp256k1>`)
+/// as described by [Wamu's share reconstruction protocol](https://wamu.tech/specification#share-reconstruction).
+///
+/// **NOTE:** This is the inverse of [`split_key_output`].
+pub fn reconstruct_secret_scalar(
+    signing_share: &SigningShare,
+    sub_share: &SubShare,
+    identity_provider: &impl IdentityProvider,
+) -> Result<Scalar<Secp256k1>, wamu_core::Error> {
+    let secret_share = wamu_core::share_split_reconstruct::reconstruct(
+        signing_share,
+        sub_share,
+        identity_provider,
+    )?;
+    secret_share_scalar(&secret_share)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+    use wamu_core::test_utils::hex_bytes;
+
+    /// A `StateMachine` that panics on a specific message body.
+    struct PanickingStateMachine {
+        message_queue: Vec<Msg<u8>>,
+    }
+
+    /// Message body that panics the `PanickingStateMachine`.
+    const PANIC_BODY: u8 = u8::MAX;
+
+    #[derive(Debug)]
+    struct MockError;
+
+    impl IsCritical for MockError {
+        fn is_critical(&self) -> bool {
+            true
+        }
+    }
+
+    impl StateMachine for PanickingStateMachine {
+        type MessageBody = u8;
+        type Err = MockError;
+        type Output = ();
+
+        fn handle_incoming(&mut self, msg: Msg<Self::MessageBody>) -> Result<(), Self::Err> {
+            assert_ne!(msg.body, PANIC_BODY, "malformed message");
+            Ok(())
+        }
+
+        fn message_queue(&mut self) -> &mut Vec<Msg<Self::MessageBody>> {
+            &mut self.message_queue
+        }
+
+        fn wants_to_proceed(&self) -> bool {
+            false
+        }
+
+        fn proceed(&mut self) -> Result<(), Self::Err> {
+            Ok(())
+        }
+
+        fn round_timeout(&self) -> Option<Duration> {
+            None
+        }
+
+        fn round_timeout_reached(&mut self) -> Self::Err {
+            MockError
+        }
+
+        fn is_finished(&self) -> bool {
+            false
+        }
+
+        fn pick_output(&mut self) -> Option<Result<Self::Output, Self::Err>> {
+            None
+        }
+
+        fn current_round(&self) -> u16 {
+            1
+        }
+
+        fn total_rounds(&self) -> Option<u16> {
+            Some(1)
+        }
+
+        fn party_ind(&self) -> u16 {
+            1
+        }
+
+        fn parties(&self) -> u16 {
+            2
+        }
+    }
+
+    /// An `AugmentedStateMachine` that wraps the `PanickingStateMachine`.
+    struct AugmentedPanickingStateMachine {
+        state_machine: PanickingStateMachine,
+        message_queue: Vec<Msg<AugmentedType<u8, ()>>>,
+        augmentation_failures: AugmentationFailures,
+        deadline: Option<Instant>,
+    }
+
+    impl AugmentedStateMachine for AugmentedPanickingStateMachine {
+        type StateMachineType = PanickingStateMachine;
+        type AdditionalParams = ();
+        type AdditionalOutput = ();
+
+        // Implements all required `AugmentedStateMachine` methods.
+        impl_required_augmented_state_machine_methods!(
+            state_machine,
+            message_queue,
+            augmentation_failures,
+            deadline
+        );
+    }
+
+    #[test]
+    fn inner_panic_is_converted_to_error() {
+        let mut aug_state_machine = AugmentedPanickingStateMachine {
+            state_machine: PanickingStateMachine {
+                message_queue: Vec::new(),
+            },
+            message_queue: Vec::new(),
+            augmentation_failures: AugmentationFailures::default(),
+            deadline: None,
+        };
+
+        for (body, expected_error) in [
+            // Well-formed messages should be handled.
+            (0, None),
+            // Messages that panic the wrapped state machine should return an error instead of crashing.
+            (PANIC_BODY, Some(Error::InnerPanic { round: 1 })),
+        ] {
+            let result = aug_state_machine.augmented_handle_incoming(Msg {
+                sender: 2,
+                receiver: None,
+                body: AugmentedType {
+                    base: body,
+                    extra: None,
+                },
+            });
+
+            // Verifies expected result.
+            assert_eq!(
+                result.err().map(|error| std::mem::discriminant(&error)),
+                expected_error.map(|error| std::mem::discriminant(&error))
+            );
+        }
+
+        // Verifies that the error is critical.
+        assert!(Error::<MockError>::InnerPanic { round: 1 }.is_critical());
+    }
+
+    #[test]
+    fn past_deadline_aborts_ceremony() {
+        let mut aug_state_machine = AugmentedPanickingStateMachine {
+            state_machine: PanickingStateMachine {
+                message_queue: Vec::new(),
+            },
+            message_queue: Vec::new(),
+            augmentation_failures: AugmentationFailures::default(),
+            deadline: None,
+        };
+
+        // Sets a deadline in the past.
+        aug_state_machine.set_deadline(Instant::now() - Duration::from_secs(1));
+
+        // Verifies that both incoming messages and state transitions are refused (with the last round reported).
+        let result = aug_state_machine.augmented_handle_incoming(Msg {
+            sender: 2,
+            receiver: None,
+            body: AugmentedType {
+                base: 0,
+                extra: None,
+            },
+        });
+        assert!(matches!(
+            result,
+            Err(Error::DeadlineExceeded { last_round: 1 })
+        ));
+        assert!(matches!(
+            aug_state_machine.augmented_proceed(),
+            Err(Error::DeadlineExceeded { last_round: 1 })
+        ));
+
+        // Verifies that the error is critical.
+        assert!(Error::<MockError>::DeadlineExceeded { last_round: 1 }.is_critical());
+
+        // Verifies that a deadline in the future doesn't abort the ceremony.
+        aug_state_machine.set_deadline(Instant::now() + Duration::from_secs(60));
+        assert!(aug_state_machine.augmented_proceed().is_ok());
+    }
+
+    /// A key that records whether its secret share was zerorized.
+    #[derive(Clone)]
+    struct ObservedKey {
+        secret_share: [u8; 32],
+        cleared: Arc<AtomicBool>,
+    }
+
+    impl ClearSecretShare for ObservedKey {
+        fn clear_secret_share(&mut self) {
+            self.secret_share.zeroize();
+            self.cleared.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn transient_secret_share_is_zeroized() {
+        for (key_option, fail) in [(true, true), (true, false), (false, true), (false, false)] {
+            let cleared = Arc::new(AtomicBool::new(false));
+            // Simulates a constructor that sets a reconstructed secret share (if any)
+            // and either fails (i.e an early error) or initializes the wrapped state machine with a copy of the key.
+            let init = || -> Result<Option<ObservedKey>, wamu_core::Error> {
+                let mut key = TransientSecretShare(key_option.then(|| ObservedKey {
+                    secret_share: [0; 32],
+                    cleared: cleared.clone(),
+                }));
+                if let Some(key) = key.0.as_mut() {
+                    key.secret_share = [1; 32];
+                }
+                if fail {
+                    return Err(wamu_core::Error::Encoding);
+                }
+                Ok(key.handoff())
+            };
+            let result = init();
+
+            // Verifies that the transient secret share (if any) is zerorized on both success and error paths,
+            // but the copy handed off to the wrapped state machine is intact.
+            assert_eq!(cleared.load(Ordering::SeqCst), key_option);
+            match result {
+                Ok(Some(key)) => assert_eq!(key.secret_share, [1; 32]),
+                Ok(None) => assert!(!key_option),
+                Err(_) => assert!(fail),
+            }
+        }
+    }
+
+    #[test]
+    fn group_key_to_xpub_works() {
+        use hmac::{Hmac, Mac};
+        use k256::elliptic_curve::group::GroupEncoding;
+        use k256::elliptic_curve::PrimeField;
+
+        // Verifies the serialization against BIP-32 test vector 1 (i.e chain m).
+        // Ref: <https://github.com/bitcoin/bips/blob/master/bip-0032.mediawiki#test-vector-1>.
+        let mut chain_code = [0; 32];
+        chain_code.copy_from_slice(&hex_bytes(
+            "873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508",
+        ));
+        let group_key = GroupKey(VerifyingKey {
+            key: hex_bytes("0339a36013301597daef41fbe593a02cc513d0b55527ec2df1050e2e8ff49c85c2"),
+            algo: SignatureAlgorithm::ECDSA,
+            curve: EllipticCurve::Secp256k1,
+            enc: KeyEncoding::SEC1,
+        });
+        assert_eq!(
+            group_key.to_xpub(chain_code, Network::Mainnet),
+            "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8"
+        );
+        assert!(group_key
+            .to_xpub(chain_code, Network::Testnet)
+            .starts_with("tpub"));
+
+        // Generates a group key and serializes it as an extended public key.
+        let (keys, _) = crate::keygen::tests::simulate_keygen(1, 2);
+        let group_key = keys[0].group_key().unwrap();
+        let chain_code = wamu_core::crypto::Random32Bytes::generate().to_be_bytes();
+        let xpub = group_key.to_xpub(chain_code, Network::Mainnet);
+        assert!(xpub.starts_with("xpub"));
+
+        // Deserializes the extended public key.
+        let bytes = bs58::decode(&xpub).with_check(None).into_vec().unwrap();
+        assert_eq!(bytes.len(), 78);
+        assert_eq!(&bytes[45..], group_key.verifying_key().key.as_slice());
+        let parent_key = k256::PublicKey::from_sec1_bytes(&bytes[45..]).unwrap();
+
+        // Derives non-hardened child public keys (i.e CKDpub) and verifies that they're valid and distinct.
+        // Ref: <https://github.com/bitcoin/bips/blob/master/bip-0032.mediawiki#public-parent-key--public-child-key>.
+        let mut child_keys = Vec::new();
+        for child_number in [0u32, 1] {
+            let mut mac = Hmac::<sha2::Sha512>::new_from_slice(&bytes[13..45]).unwrap();
+            mac.update(&bytes[45..]);
+            mac.update(&child_number.to_be_bytes());
+            let output = mac.finalize().into_bytes();
+            let tweak = Option::<k256::Scalar>::from(k256::Scalar::from_repr(
+                *k256::FieldBytes::from_slice(&output[..32]),
+            ))
+            .unwrap();
+            let child_point = k256::ProjectivePoint::GENERATOR * tweak + parent_key.to_projective();
+            let child_key =
+                k256::PublicKey::from_sec1_bytes(&child_point.to_affine().to_bytes()).unwrap();
+            assert_ne!(child_key, parent_key);
+            assert!(!child_keys.contains(&child_key));
+            child_keys.push(child_key);
+        }
+    }
+}
@@ -2,16 +2,30 @@
 //!
 //! NOTE: Used by share addition, share removal, threshold modification and share recovery with quorum protocols.
 
+use curv::elliptic::curves::Secp256k1;
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen::LocalKey;
 use round_based::{IsCritical, Msg, StateMachine};
-use wamu_core::IdentityProvider;
+use std::time::Instant;
+use wamu_core::{IdentityProvider, SigningShare, SubShare};
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
-use crate::key_refresh::AugmentedKeyRefresh;
+use crate::augmented_state_machine::{ClearSecretShare, GroupKey};
+use crate::key_refresh::{AugmentedKeyRefresh, RefreshSummary};
 use crate::{IdentityAuthentication, QuorumApproval};
 
+/// An authorization state machine whose request can be namespaced to an application-wide domain.
+pub trait DomainSeparated<'a> {
+    /// Namespaces the authorization request to an application-wide domain (e.g `"com.example.wallet"`),
+    /// re-initiating the request of the initiating party (if necessary).
+    ///
+    /// **NOTE:** The domain must be configured identically by all parties before any messages are exchanged.
+    fn set_domain(&mut self, domain: &'a str);
+}
+
 /// A [`StateMachine`](StateMachine) that executes an authorization state machine (e.g. identity authenticated or quorum approved) and then a key refresh state machine in sequence.
 pub trait AuthorizedKeyRefresh<'a, I: IdentityProvider + 'a>: StateMachine {
     /// The type of the authorization state machine.
-    type InitStateMachineType: StateMachine;
+    type InitStateMachineType: StateMachine + DomainSeparated<'a>;
 
     /// Returns an immutable reference to the authorization state machine.
     fn auth_state_machine(&self) -> &Self::InitStateMachineType;
@@ -48,6 +62,46 @@ pub trait AuthorizedKeyRefresh<'a, I: IdentityProvider + 'a>: StateMachine {
         &mut self,
     ) -> &mut Vec<Msg<Message<'a, I, <Self::InitStateMachineType as StateMachine>::MessageBody>>>;
 
+    /// Returns the ceremony-wide deadline (if any).
+    fn deadline(&self) -> Option<Instant>;
+
+    /// Returns a mutable reference to the ceremony-wide deadline (if any).
+    fn deadline_mut(&mut self) -> &mut Option<Instant>;
+
+    /// Sets a wall-clock deadline for the entire ceremony (i.e both the authorization and key refresh phases)
+    /// after which both [`handle_incoming`](StateMachine::handle_incoming) and [`proceed`](StateMachine::proceed)
+    /// refuse to proceed and return an [`Error::DeadlineExceeded`] error.
+    fn set_deadline(&mut self, deadline: Instant) {
+        *self.deadline_mut() = Some(deadline);
+    }
+
+    /// Namespaces the authorization request to an application-wide domain (e.g `"com.example.wallet"`),
+    /// see [`wamu_core::identity_authed_request::initiate_with_domain`].
+    ///
+    /// **NOTE:** The domain must be configured identically by all parties before any messages are exchanged.
+    fn set_domain(
+        &mut self,
+        domain: &'a str,
+    ) -> Result<(), Error<'a, I, <Self::InitStateMachineType as StateMachine>::Err>> {
+        // Discards any authorization messages queued before the domain was set (i.e a request initiated without the domain).
+        self.composite_message_queue_mut()
+            .retain(|msg| !matches!(msg.body, Message::Init(_)));
+        self.auth_state_machine_mut().set_domain(domain);
+        self.update_composite_message_queue()
+    }
+
+    /// Returns an [`Error::DeadlineExceeded`] error (with the current aggregate round) if the ceremony-wide deadline (if any) has passed.
+    fn check_deadline(
+        &self,
+    ) -> Result<(), Error<'a, I, <Self::InitStateMachineType as StateMachine>::Err>> {
+        match self.deadline() {
+            Some(deadline) if Instant::now() >= deadline => Err(Error::DeadlineExceeded {
+                last_round: self.current_round(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
     /// Returns an initialized key refresh state machine (if possible).
     fn create_key_refresh(
         &mut self,
@@ -107,11 +161,16 @@ pub trait AuthorizedKeyRefresh<'a, I: IdentityProvider + 'a>: StateMachine {
             if !out_of_order_messages.is_empty() {
                 for msg in out_of_order_messages {
                     if let Message::Refresh(msg_body) = msg.body {
-                        key_refresh.handle_incoming(Msg {
+                        // Only critical errors abort the transition (i.e rejected messages are dropped).
+                        if let Err(error) = key_refresh.handle_incoming(Msg {
                             sender: msg.sender,
                             receiver: msg.receiver,
                             body: *msg_body,
-                        })?;
+                        }) {
+                            if error.is_critical() {
+                                return Err(Error::Refresh(error));
+                            }
+                        }
                     }
                 }
             }
@@ -127,6 +186,56 @@ pub trait AuthorizedKeyRefresh<'a, I: IdentityProvider + 'a>: StateMachine {
     }
 }
 
+/// The "signing share" and "sub-share" of a party after an authorized key refresh.
+///
+/// **NOTE:** Both shares are zerorized when the bundle is dropped.
+pub struct KeyShareBundle {
+    /// The "signing share" of the party.
+    pub signing_share: SigningShare,
+    /// The "sub-share" of the party.
+    pub sub_share: SubShare,
+}
+
+/// The post-processed output of a completed authorized key refresh (see `finish` on the authorized key refresh protocols).
+///
+/// **NOTE:** The secret share of the local key and the key share bundle are zerorized when the update is dropped.
+pub struct WalletUpdate {
+    /// Local key of the party (with secret share cleared/zerorized).
+    pub local_key: LocalKey<Secp256k1>,
+    /// The "signing share" and "sub-share" of the party.
+    pub bundle: KeyShareBundle,
+    /// The group public key (i.e unchanged by the key refresh).
+    pub group_key: GroupKey,
+    /// A summary of the changes to the parties and threshold.
+    pub summary: RefreshSummary,
+}
+
+impl Zeroize for KeyShareBundle {
+    fn zeroize(&mut self) {
+        self.signing_share.zeroize();
+        self.sub_share.zeroize();
+    }
+}
+
+// Both shares are zerorized when they're dropped.
+impl ZeroizeOnDrop for KeyShareBundle {}
+
+impl Zeroize for WalletUpdate {
+    fn zeroize(&mut self) {
+        self.local_key.clear_secret_share();
+        self.bundle.zeroize();
+    }
+}
+
+impl Drop for WalletUpdate {
+    fn drop(&mut self) {
+        // The key share bundle zerorizes itself when it's dropped.
+        self.local_key.clear_secret_share();
+    }
+}
+
+impl ZeroizeOnDrop for WalletUpdate {}
+
 /// A generic authorized key refresh message.
 #[derive(Clone)]
 pub enum Message<'a, I: IdentityProvider, T> {
@@ -140,8 +249,12 @@ pub enum Error<'a, I: IdentityProvider, E> {
     Init(E),
     Refresh(<AugmentedKeyRefresh<'a, I> as StateMachine>::Err),
     AlreadyPicked,
-    InvalidInput,
+    IncompleteOutput,
+    InvalidInput { reason: &'static str },
+    InvalidState,
+    InvalidThreshold,
     OutOfOrderMessage,
+    DeadlineExceeded { last_round: u16 },
 }
 
 impl<'a, I: IdentityProvider, E> IsCritical for Error<'a, I, E> {
@@ -174,6 +287,9 @@ macro_rules! impl_state_machine_for_authorized_key_refresh {
             type Output = <AugmentedKeyRefresh<'a, I> as StateMachine>::Output;
 
             fn handle_incoming(&mut self, msg: Msg<Self::MessageBody>) -> Result<(), Self::Err> {
+                // Refuses to proceed once past the ceremony-wide deadline (if any).
+                self.check_deadline()?;
+
                 match msg.body {
                     // Initialization messages are forwarded to the initialization state machine if it's still active,
                     // otherwise an error is returned.
@@ -237,6 +353,9 @@ macro_rules! impl_state_machine_for_authorized_key_refresh {
             }
 
             fn proceed(&mut self) -> Result<(), Self::Err> {
+                // Refuses to proceed once past the ceremony-wide deadline (if any).
+                self.check_deadline()?;
+
                 // `proceed` is forwarded to the active state machine.
                 match self.refresh_state_machine_mut() {
                     None => self.auth_state_machine_mut().proceed()?,
@@ -251,11 +370,22 @@ macro_rules! impl_state_machine_for_authorized_key_refresh {
             }
 
             fn round_timeout(&self) -> Option<Duration> {
-                None
+                // `round_timeout` is forwarded to the initialization state machine while it's active
+                // (the refresh state machine doesn't set timeouts).
+                match self.refresh_state_machine() {
+                    None => self.auth_state_machine().round_timeout(),
+                    Some(_) => None,
+                }
             }
 
             fn round_timeout_reached(&mut self) -> Self::Err {
-                panic!("no timeout was set")
+                // `round_timeout_reached` is forwarded to the initialization state machine while it's active,
+                // while reaching a timeout that was never set (i.e during key refresh) is a misuse of the state machine.
+                if self.refresh_state_machine().is_none() {
+                    Error::Init(self.auth_state_machine_mut().round_timeout_reached())
+                } else {
+                    Error::InvalidState
+                }
             }
 
             fn is_finished(&self) -> bool {
@@ -300,15 +430,70 @@ macro_rules! impl_state_machine_for_authorized_key_refresh {
                 self.$n_parties
             }
         }
+
+        impl<'a, I: IdentityProvider> $name<'a, I> {
+            /// Picks the output of the completed protocol and returns it as a wallet update
+            /// (i.e the local key, the "signing share" and "sub-share", the group public key and a summary of the changes),
+            /// or an appropriate error if the protocol isn't finished.
+            pub fn finish(
+                mut self,
+            ) -> Result<crate::authorized_key_refresh::WalletUpdate, <Self as StateMachine>::Err> {
+                // Picks the output and extracts the "signing share", "sub-share" and group public key.
+                let output = self
+                    .pick_output()
+                    .ok_or(crate::authorized_key_refresh::Error::IncompleteOutput)??;
+                let group_key = output
+                    .group_key()
+                    .map_err(|error| crate::authorized_key_refresh::Error::Refresh(error.into()))?;
+                let (signing_share, sub_share, summary) = output
+                    .extra
+                    .ok_or(crate::authorized_key_refresh::Error::IncompleteOutput)?;
+
+                Ok(crate::authorized_key_refresh::WalletUpdate {
+                    local_key: output.base,
+                    bundle: crate::authorized_key_refresh::KeyShareBundle {
+                        signing_share,
+                        sub_share,
+                    },
+                    group_key,
+                    summary,
+                })
+            }
+        }
+
+        impl<'a, I: IdentityProvider> crate::augmented_state_machine::PartyIdentity
+            for $name<'a, I>
+        {
+            fn party_id(&self) -> wamu_core::crypto::PartyId {
+                // The party identifier is independent of the active state machine.
+                crate::augmented_state_machine::PartyIdentity::party_id(self.auth_state_machine())
+            }
+        }
+
+        impl<'a, I: IdentityProvider> crate::augmented_state_machine::RoundLabel for $name<'a, I> {
+            fn round_label(&self) -> String {
+                // Labels the current round based on the active state machine.
+                match self.refresh_state_machine() {
+                    None => crate::augmented_state_machine::RoundLabel::round_label(
+                        self.auth_state_machine(),
+                    ),
+                    Some(refresh_state_machine) => {
+                        crate::augmented_state_machine::RoundLabel::round_label(
+                            refresh_state_machine,
+                        )
+                    }
+                }
+            }
+        }
     };
 }
 
 /// Implements all required `AuthorizedKeyRefresh` getters.
 ///
 /// Requires names of the associated fields
-/// (.ie the authorization and key refresh `StateMachine` and the composite message queue).
+/// (.ie the authorization and key refresh `StateMachine`, the composite message queue, the "out of order" message buffer and the ceremony-wide deadline).
 macro_rules! impl_required_authorized_key_refresh_getters {
-    ($auth_state_machine:ident, $refresh_state_machine:ident, $message_queue:ident, $out_of_order_buffer:ident) => {
+    ($auth_state_machine:ident, $refresh_state_machine:ident, $message_queue:ident, $out_of_order_buffer:ident, $deadline:ident) => {
         fn auth_state_machine(&self) -> &Self::InitStateMachineType {
             &self.$auth_state_machine
         }
@@ -384,6 +569,14 @@ macro_rules! impl_required_authorized_key_refresh_getters {
         > {
             self.$out_of_order_buffer.as_mut()
         }
+
+        fn deadline(&self) -> Option<std::time::Instant> {
+            self.$deadline
+        }
+
+        fn deadline_mut(&mut self) -> &mut Option<std::time::Instant> {
+            &mut self.$deadline
+        }
     };
 }
 
@@ -413,3 +606,185 @@ impl<'a, I: IdentityProvider, T> std::fmt::Debug for Message<'a, I, T> {
         write!(f, "Authorized Key Refresh Message")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keygen::tests::simulate_keygen;
+    use crate::{
+        MembershipChange, ShareAddition, ShareRecoveryQuorum, ShareRemoval, ThresholdModification,
+    };
+    use std::collections::HashMap;
+    use wamu_core::crypto::VerifyingKey;
+    use wamu_core::{IdentityAuthedRequestError, QuorumApprovedRequestError};
+
+    #[test]
+    fn no_verified_parties_fails() {
+        // Runs key gen simulation.
+        let (keys, identity_providers) = simulate_keygen(1, 2);
+        let (signing_share, sub_share) = keys[0].extra.as_ref().unwrap();
+        let local_key = keys[0].base.clone();
+        let current_to_new_idx_map = HashMap::from([(1, 1), (2, 2)]);
+
+        // Verifies that an empty list of verified parties is rejected by all authorized key refresh protocols.
+        let is_no_verified_parties = |error: &crate::quorum_approval::Error| {
+            matches!(
+                error,
+                crate::quorum_approval::Error::Quorum(QuorumApprovedRequestError::Unauthorized(
+                    wamu_core::Error::NoVerifiedParties
+                ))
+            )
+        };
+        assert!(matches!(
+            ShareAddition::new(
+                Some(signing_share),
+                Some(sub_share),
+                &identity_providers[0],
+                &[],
+                Some(local_key.clone()),
+                None,
+                3,
+                &current_to_new_idx_map,
+                None,
+                None,
+                true,
+            ),
+            Err(Error::Init(error)) if is_no_verified_parties(&error)
+        ));
+        assert!(matches!(
+            ShareRemoval::new(
+                signing_share,
+                sub_share,
+                &identity_providers[0],
+                &[],
+                local_key.clone(),
+                2,
+                &current_to_new_idx_map,
+                true,
+            ),
+            Err(Error::Init(error)) if is_no_verified_parties(&error)
+        ));
+        assert!(matches!(
+            ThresholdModification::new(
+                signing_share,
+                sub_share,
+                &identity_providers[0],
+                &[],
+                local_key.clone(),
+                1,
+                &current_to_new_idx_map,
+                true,
+            ),
+            Err(Error::Init(error)) if is_no_verified_parties(&error)
+        ));
+        assert!(matches!(
+            ShareRecoveryQuorum::new(
+                Some(signing_share),
+                Some(sub_share),
+                &identity_providers[0],
+                &[],
+                Some(local_key),
+                None,
+                2,
+                &current_to_new_idx_map,
+                None,
+            ),
+            Err(Error::Init(crate::identity_auth::Error::Core(
+                IdentityAuthedRequestError::Unauthorized(wamu_core::Error::NoVerifiedParties)
+            )))
+        ));
+    }
+
+    #[test]
+    fn zero_threshold_fails() {
+        // Runs key gen simulation.
+        let (keys, identity_providers) = simulate_keygen(1, 2);
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let (signing_share, sub_share) = keys[0].extra.as_ref().unwrap();
+        let current_to_new_idx_map = HashMap::from([(1, 1), (2, 2)]);
+
+        // Sets a threshold of zero.
+        let mut local_key = keys[0].base.clone();
+        local_key.t = 0;
+
+        // Verifies that a threshold of zero is rejected by all authorized key refresh protocols.
+        assert!(matches!(
+            ShareAddition::new(
+                Some(signing_share),
+                Some(sub_share),
+                &identity_providers[0],
+                &verifying_keys,
+                Some(local_key.clone()),
+                None,
+                3,
+                &current_to_new_idx_map,
+                None,
+                None,
+                true,
+            ),
+            Err(Error::InvalidThreshold)
+        ));
+        assert!(matches!(
+            ShareRemoval::new(
+                signing_share,
+                sub_share,
+                &identity_providers[0],
+                &verifying_keys,
+                local_key.clone(),
+                2,
+                &current_to_new_idx_map,
+                true,
+            ),
+            Err(Error::InvalidThreshold)
+        ));
+        assert!(matches!(
+            MembershipChange::new(
+                Some(signing_share),
+                Some(sub_share),
+                &identity_providers[0],
+                &verifying_keys,
+                &verifying_keys,
+                Some(local_key.clone()),
+                None,
+                2,
+                &current_to_new_idx_map,
+                None,
+                None,
+                true,
+            ),
+            Err(Error::InvalidThreshold)
+        ));
+        for (local_key, new_threshold) in [(local_key.clone(), 1), (keys[0].base.clone(), 0)] {
+            assert!(matches!(
+                ThresholdModification::new(
+                    signing_share,
+                    sub_share,
+                    &identity_providers[0],
+                    &verifying_keys,
+                    local_key,
+                    new_threshold,
+                    &current_to_new_idx_map,
+                    true,
+                ),
+                Err(Error::InvalidThreshold)
+            ));
+        }
+        assert!(matches!(
+            ShareRecoveryQuorum::new(
+                Some(signing_share),
+                Some(sub_share),
+                &identity_providers[0],
+                &verifying_keys,
+                Some(local_key),
+                None,
+                2,
+                &current_to_new_idx_map,
+                None,
+            ),
+            Err(Error::InvalidThreshold)
+        ));
+    }
+}
@@ -0,0 +1,54 @@
+//! Format versions for serialized state (e.g public wallet state and presignatures),
+//! so that future format changes never silently corrupt previously persisted state.
+
+/// The current format version of serialized state.
+pub const FORMAT_VERSION: u16 = 1;
+
+/// The format version of state serialized before format versions were introduced (i.e state without a format version).
+#[cfg(feature = "serde")]
+pub(crate) const LEGACY_FORMAT_VERSION: u16 = 0;
+
+/// A format version error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatVersionError {
+    /// An unknown (e.g future) format version without a migration path to the current format version.
+    UnsupportedFormatVersion(u16),
+}
+
+impl std::fmt::Display for FormatVersionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// Deserialized (i.e unvalidated) state that can be migrated from known older format versions.
+#[cfg(feature = "serde")]
+pub(crate) trait Migrate: Sized {
+    /// Returns the format version of the deserialized state.
+    fn format_version(&self) -> u16;
+
+    /// Returns a mutable reference to the format version of the deserialized state.
+    fn format_version_mut(&mut self) -> &mut u16;
+
+    /// Given deserialized state in a known older format version (i.e [`LEGACY_FORMAT_VERSION`]),
+    /// returns the state migrated to the current format version.
+    ///
+    /// **NOTE:** The default implementation only updates the format version
+    /// (i.e for state whose layout is otherwise unchanged).
+    fn migrate(mut self) -> Self {
+        *self.format_version_mut() = FORMAT_VERSION;
+        self
+    }
+}
+
+/// Given deserialized state, returns the state in the current format version
+/// (i.e after migrating state in a known older format version),
+/// or an appropriate error for unknown (e.g future) format versions.
+#[cfg(feature = "serde")]
+pub(crate) fn upgrade<T: Migrate>(state: T) -> Result<T, FormatVersionError> {
+    match state.format_version() {
+        FORMAT_VERSION => Ok(state),
+        LEGACY_FORMAT_VERSION => Ok(state.migrate()),
+        format_version => Err(FormatVersionError::UnsupportedFormatVersion(format_version)),
+    }
+}
@@ -6,16 +6,24 @@
 //!
 //! Ref: <https://wamu.tech/specification#identity-challenge>.
 
+use crypto_bigint::U256;
 use round_based::{IsCritical, Msg, StateMachine};
 use std::collections::HashMap;
 use std::time::Duration;
-use wamu_core::crypto::{Random32Bytes, VerifyingKey};
-use wamu_core::{IdentityAuthedRequestError, IdentityAuthedRequestPayload, IdentityProvider};
+use wamu_core::crypto::{PartyId, Random32Bytes, VerifyingKey};
+use wamu_core::{
+    wrappers, Command, IdentityAuthedRequestError, IdentityAuthedRequestPayload, IdentityProvider,
+};
+
+use crate::augmented_state_machine::{PartyIdentity, RoundLabel};
+use crate::authorized_key_refresh::DomainSeparated;
 
 /// A [StateMachine](StateMachine) that implements [identity authentication](https://wamu.tech/specification#identity-authed-request) (including [identity challenge](https://wamu.tech/specification#identity-challenge)) as described by the Wamu protocol.
 pub struct IdentityAuthentication<'a, I: IdentityProvider> {
     /// The command for the request being initiated.
-    command: &'static str,
+    command: Command,
+    /// The application-wide domain that the request is namespaced to (if any).
+    domain: Option<&'a str>,
     /// The decentralized identity provider of the party.
     identity_provider: &'a I,
     /// Verifying keys for other the parties.
@@ -41,7 +49,7 @@ pub struct IdentityAuthentication<'a, I: IdentityProvider> {
 impl<'a, I: IdentityProvider> IdentityAuthentication<'a, I> {
     /// Initializes party for the identity authentication protocol.
     pub fn new(
-        command: &'static str,
+        command: Command,
         identity_provider: &'a I,
         verified_parties: &'a [VerifyingKey],
         idx: u16,
@@ -64,6 +72,7 @@ impl<'a, I: IdentityProvider> IdentityAuthentication<'a, I> {
         // Returns identity authentication machine.
         Self {
             command,
+            domain: None,
             identity_provider,
             verified_parties,
             is_initiator,
@@ -76,6 +85,30 @@ impl<'a, I: IdentityProvider> IdentityAuthentication<'a, I> {
             received_verification_outcomes: HashMap::new(),
         }
     }
+
+    /// Namespaces the identity authenticated request to an application-wide domain (e.g `"com.example.wallet"`),
+    /// see [`wamu_core::identity_authed_request::initiate_with_domain`].
+    ///
+    /// **NOTE:** The domain must be configured identically by all parties before any messages are exchanged.
+    pub fn with_domain(mut self, domain: &'a str) -> Self {
+        self.set_domain(domain);
+        self
+    }
+
+    /// Returns the challenge fragments attributed to the verifying keys of the parties that contributed them
+    /// (i.e so that the challenge response binds each challenge fragment to its verifier),
+    /// or an `UnauthorizedParty` error if a challenge fragment is from an unknown party.
+    fn fragment_attribution(&self) -> Result<HashMap<VerifyingKey, U256>, Error> {
+        self.challenge_fragments
+            .iter()
+            .map(|(idx, fragment)| {
+                self.verified_parties
+                    .get((*idx as usize).wrapping_sub(1))
+                    .map(|verifying_key| (verifying_key.clone(), fragment.as_u256()))
+                    .ok_or(Error::from(wamu_core::Error::UnauthorizedParty))
+            })
+            .collect()
+    }
 }
 
 impl<'a, I: IdentityProvider> StateMachine for IdentityAuthentication<'a, I> {
@@ -91,12 +124,21 @@ impl<'a, I: IdentityProvider> StateMachine for IdentityAuthentication<'a, I> {
                 // while other parties verify the identity authentication request
                 // and immediately process the next round if the identity authentication request verification is successful.
                 if !self.is_initiator {
-                    let challenge_fragment =
-                        wamu_core::wrappers::verify_identity_authed_request_and_initiate_challenge(
-                            self.command,
+                    let challenge_fragment = match self.domain {
+                        Some(domain) => {
+                            wrappers::verify_identity_authed_request_and_initiate_challenge_with_domain(
+                                self.command.as_str(),
+                                domain,
+                                &request,
+                                self.verified_parties,
+                            )
+                        }
+                        None => wrappers::verify_identity_authed_request_and_initiate_challenge(
+                            self.command.as_str(),
                             &request,
                             self.verified_parties,
-                        )?;
+                        ),
+                    }?;
 
                     // Moves on to the next round.
                     self.round = Round::Two;
@@ -122,13 +164,9 @@ impl<'a, I: IdentityProvider> StateMachine for IdentityAuthentication<'a, I> {
                 // while other parties verify the challenge response
                 // and immediately process the next round if the challenge response verification is successful.
                 if !self.is_initiator {
-                    wamu_core::identity_challenge::verify(
+                    wamu_core::identity_challenge::verify_with_attribution(
                         &signature,
-                        &self
-                            .challenge_fragments
-                            .values()
-                            .copied()
-                            .collect::<Vec<Random32Bytes>>(),
+                        &self.fragment_attribution()?,
                         &self.verified_parties[msg.sender as usize - 1],
                     )?;
 
@@ -202,12 +240,8 @@ impl<'a, I: IdentityProvider> StateMachine for IdentityAuthentication<'a, I> {
             Round::Two => {
                 // Only the initiating party needs to respond to the challenge.
                 if self.is_initiator {
-                    let signature = wamu_core::identity_challenge::respond(
-                        &self
-                            .challenge_fragments
-                            .values()
-                            .copied()
-                            .collect::<Vec<Random32Bytes>>(),
+                    let signature = wamu_core::identity_challenge::respond_with_attribution(
+                        &self.fragment_attribution()?,
                         self.identity_provider,
                     );
                     self.message_queue.push(Msg {
@@ -285,6 +319,41 @@ impl<'a, I: IdentityProvider> StateMachine for IdentityAuthentication<'a, I> {
     }
 }
 
+impl<'a, I: IdentityProvider> DomainSeparated<'a> for IdentityAuthentication<'a, I> {
+    fn set_domain(&mut self, domain: &'a str) {
+        self.domain = Some(domain);
+        if self.is_initiator {
+            // Replaces the request initiated without the domain.
+            self.message_queue
+                .retain(|msg| !matches!(msg.body, Message::Round1(_)));
+            self.message_queue.push(Msg {
+                sender: self.idx,
+                receiver: None,
+                body: Message::Round1(wamu_core::identity_authed_request::initiate_with_domain(
+                    self.command,
+                    domain,
+                    self.identity_provider,
+                )),
+            });
+        }
+    }
+}
+
+impl<'a, I: IdentityProvider> PartyIdentity for IdentityAuthentication<'a, I> {
+    fn party_id(&self) -> PartyId {
+        PartyId::from_verifying_key(&self.identity_provider.verifying_key())
+    }
+}
+
+impl<'a, I: IdentityProvider> RoundLabel for IdentityAuthentication<'a, I> {
+    fn round_label(&self) -> String {
+        match self.round {
+            Round::Final | Round::Gone => "Identity Authentication Finished".to_string(),
+            _ => format!("Identity Authentication Round {}", self.current_round()),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 enum Round {
     One,
@@ -371,7 +440,7 @@ mod tests {
         // Adds parties to simulation.
         for (identity_provider, idx, is_initiator) in party_key_configs {
             simulation.add_party(IdentityAuthentication::new(
-                "command",
+                Command::Signing,
                 identity_provider,
                 &verifying_keys,
                 idx,
@@ -398,7 +467,7 @@ mod tests {
         let mut party_key_configs = Vec::new();
         for (i, identity_provider) in identity_providers.iter().enumerate() {
             // Create party key config and index entry.
-            let idx = i as u16 + 1;
+            let idx = wamu_core::party_index(i).unwrap();
             party_key_configs.push((identity_provider, idx, idx == initiating_party_idx));
         }
 
@@ -411,4 +480,70 @@ mod tests {
             assert!(outcome);
         }
     }
+
+    #[test]
+    fn identity_authentication_with_domain_works() {
+        // Creates identity providers for the initiator and another party.
+        let identity_providers: Vec<MockECDSAIdentityProvider> = (0..2)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(|identity_provider| identity_provider.verifying_key())
+            .collect();
+
+        for (initiator_domain, party_domain, expected_ok) in [
+            // Matching domains should be ok.
+            (Some("com.example.wallet"), Some("com.example.wallet"), true),
+            // Mismatched domains should fail.
+            (Some("com.example.wallet"), Some("com.example.other"), false),
+            // Request without a domain should fail when a domain is configured.
+            (None, Some("com.example.wallet"), false),
+            // Request with a domain should fail when no domain is configured.
+            (Some("com.example.wallet"), None, false),
+        ] {
+            // Initializes the initiator and retrieves the identity authenticated request.
+            let mut initiator = IdentityAuthentication::new(
+                Command::Signing,
+                &identity_providers[0],
+                &verifying_keys,
+                1,
+                2,
+                true,
+            );
+            if let Some(domain) = initiator_domain {
+                initiator = initiator.with_domain(domain);
+            }
+            let mut messages = initiator.message_queue().split_off(0);
+            assert_eq!(messages.len(), 1);
+
+            // Initializes the other party and verifies the identity authenticated request.
+            let mut party = IdentityAuthentication::new(
+                Command::Signing,
+                &identity_providers[1],
+                &verifying_keys,
+                2,
+                2,
+                false,
+            );
+            if let Some(domain) = party_domain {
+                party = party.with_domain(domain);
+            }
+            let result = party.handle_incoming(messages.remove(0));
+
+            // Verifies expected result.
+            if expected_ok {
+                assert!(result.is_ok());
+            } else {
+                assert!(matches!(
+                    result,
+                    Err(Error::Core(IdentityAuthedRequestError::Unauthorized(
+                        wamu_core::Error::Crypto(
+                            wamu_core::CryptoError::SignatureVerificationFailed
+                        )
+                    )))
+                ));
+            }
+        }
+    }
 }
@@ -104,7 +104,8 @@ impl<'a, I: IdentityProvider> StateMachine for IdentityRotation<'a, I> {
                         wamu_core::identity_rotation::verify_request_and_initiate_challenge(
                             &request,
                             self.verified_parties,
-                        )?;
+                        )?
+                        .fragment;
 
                     // Moves on to the next round.
                     self.round = Round::Two;
@@ -409,7 +410,7 @@ pub mod tests {
 
         // Adds parties to simulation.
         for (i, identity_provider) in identity_providers.iter().enumerate() {
-            let party_idx = i as u16 + 1;
+            let party_idx = wamu_core::party_index(i).unwrap();
             let is_rotating_party = party_idx == idx;
             let new_identity_provider_option = is_rotating_party.then_some(new_identity_provider);
             let signing_share_option = is_rotating_party.then_some(signing_share);
@@ -456,7 +457,7 @@ pub mod tests {
         // Runs identity rotation simulation for test parameters.
         let results = simulate_identity_rotation(
             rotating_party_idx,
-            identity_providers.len() as u16,
+            wamu_core::party_count(identity_providers.len()).unwrap(),
             &identity_providers,
             &new_identity_provider,
             signing_share,
@@ -466,7 +467,7 @@ pub mod tests {
         // Verifies the output for all parties.
         assert_eq!(results.len(), n_parties as usize);
         for (i, (share_option, verified_keys_option)) in results.iter().enumerate() {
-            let party_idx = i as u16 + 1;
+            let party_idx = wamu_core::party_index(i).unwrap();
             if party_idx == rotating_party_idx {
                 // Verifies that the rotating party has a new "signing share" and "sub-share"
                 // that reconstruct the same "secret share" as the previous "signing share" and "sub-share".
@@ -5,23 +5,79 @@
 use cggmp_threshold_ecdsa::refresh::state_machine::{KeyRefresh, M};
 use cggmp_threshold_ecdsa::utilities::sha2::Sha256;
 use curv::arithmetic::Converter;
-use curv::elliptic::curves::{Scalar, Secp256k1};
+use curv::elliptic::curves::Secp256k1;
+use curv::BigInt;
 use fs_dkr::add_party_message::JoinMessage;
 use fs_dkr::refresh_message::RefreshMessage;
 use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen::LocalKey;
 use round_based::{Msg, StateMachine};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ops::Deref;
-use std::time::Duration;
-use wamu_core::crypto::VerifyingKey;
+use std::time::{Duration, Instant};
+use wamu_core::crypto::{PartyId, VerifyingKey};
 use wamu_core::{IdentityProvider, SigningShare, SubShare};
 
 use crate::augmented_state_machine;
 use crate::augmented_state_machine::Error;
 use crate::augmented_state_machine::{
-    AugmentedStateMachine, AugmentedType, IdentityAuthParams, SubShareOutput,
+    AugmentationFailures, AugmentedStateMachine, AugmentedType, GroupKey, IdentityAuthParams,
+    PartyIdentity, PartyList, RoundLabel, SubShareOutput, TransientSecretShare,
 };
 
+/// Domain separation tag for parameter hashes of join messages (i.e from new parties).
+const JOIN_PARAMETER_HASH_TAG: &[u8] = b"wamu:fs-dkr-join-parameter-hash";
+
+/// Domain separation tag for parameter hashes of refresh messages (i.e from existing parties).
+const REFRESH_PARAMETER_HASH_TAG: &[u8] = b"wamu:fs-dkr-refresh-parameter-hash";
+
+/// Verifying keys for all the parties of a key refresh indexed by new party index
+/// (i.e the verifying key of the party with new index `i` is at position `i - 1`).
+///
+/// **NOTE:** Key refresh requires exactly one verifying key per new party index (i.e `n_parties` verifying keys).
+#[derive(Debug, Clone)]
+pub struct IndexedParties<'a>(PartyList<'a>);
+
+impl<'a> IndexedParties<'a> {
+    /// Given either borrowed or shared verifying keys for all the parties that are already in new party index order
+    /// (i.e the verifying key of the party with new index `i` must be at position `i - 1`), returns indexed parties.
+    pub fn from_ordered(verified_parties: impl Into<PartyList<'a>>) -> Self {
+        Self(verified_parties.into())
+    }
+
+    /// Given a map of new party indices to verifying keys, returns indexed parties,
+    /// or a `BadIndexMap` error if the new party indices aren't exactly `1..=n` for `n` verifying keys.
+    pub fn from_map(
+        verified_parties: &BTreeMap<u16, VerifyingKey>,
+    ) -> Result<Self, Error<<KeyRefresh as StateMachine>::Err>> {
+        // New party indices must be contiguous starting from one (i.e map keys are iterated in ascending order).
+        if !verified_parties
+            .keys()
+            .zip(1..)
+            .all(|(new_idx, expected_idx)| *new_idx == expected_idx)
+        {
+            return Err(Error::BadIndexMap);
+        }
+        Ok(Self(PartyList::Shared(
+            verified_parties.values().cloned().collect(),
+        )))
+    }
+
+    /// Returns the verifying key of the party with the given new party index (if any).
+    pub fn verifying_key(&self, new_idx: u16) -> Option<&VerifyingKey> {
+        usize::from(new_idx)
+            .checked_sub(1)
+            .and_then(|position| self.0.get(position))
+    }
+}
+
+impl Deref for IndexedParties<'_> {
+    type Target = [VerifyingKey];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 /// A wrapper around the [`cggmp-threshold-ecdsa` Key Refresh StateMachine](https://github.com/webb-tools/cggmp-threshold-ecdsa/blob/main/src/refresh/state_machine.rs) that [augments key refresh as described by the Wamu protocol](https://wamu.tech/specification#key-refresh).
 pub struct AugmentedKeyRefresh<'a, I: IdentityProvider> {
     /// Wrapped `cggmp-threshold-ecdsa` Key Refresh `StateMachine`.
@@ -31,21 +87,141 @@ pub struct AugmentedKeyRefresh<'a, I: IdentityProvider> {
         Vec<Msg<AugmentedType<<KeyRefresh as StateMachine>::MessageBody, IdentityAuthParams>>>,
     /// The decentralized identity provider of the party.
     identity_provider: &'a I,
-    /// Verifying keys for other the parties.
-    verified_parties: &'a [VerifyingKey],
+    /// Verifying keys for all the parties indexed by new party index.
+    verified_parties: IndexedParties<'a>,
     /// Indexes of existing parties.
     existing_parties: Vec<u16>,
+    /// The group public key before the refresh (if known), which the refreshed key must preserve.
+    expected_public_key: Option<VerifyingKey>,
+    /// The current threshold (i.e from the `LocalKey<Secp256k1>` for existing parties or as claimed by new parties).
+    current_threshold: Option<u16>,
+    /// Whether or not the party is an existing party (i.e it knows the actual current threshold).
+    is_existing_party: bool,
+    /// A summary of the changes to the parties and threshold.
+    summary: RefreshSummary,
+    /// Consecutive augmentation verification failures for each party.
+    augmentation_failures: AugmentationFailures,
+    /// Ceremony-wide deadline (if any).
+    deadline: Option<Instant>,
+}
+
+/// Additional output for key refresh (i.e the "signing share", "sub-share" and a summary of the changes).
+pub type RefreshOutput = (SigningShare, SubShare, RefreshSummary);
+
+impl From<AugmentedType<LocalKey<Secp256k1>, RefreshOutput>>
+    for AugmentedType<LocalKey<Secp256k1>, SubShareOutput>
+{
+    /// Drops the summary from a key refresh output (e.g for storing it like a key generation output).
+    fn from(output: AugmentedType<LocalKey<Secp256k1>, RefreshOutput>) -> Self {
+        Self {
+            base: output.base,
+            extra: output
+                .extra
+                .map(|(signing_share, sub_share, _)| (signing_share, sub_share)),
+        }
+    }
+}
+
+/// A summary of how a key refresh changes the parties and threshold (e.g for user notifications).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefreshSummary {
+    /// New indices of the parties added by the key refresh (in ascending order).
+    pub added: Vec<u16>,
+    /// Current indices of the parties removed by the key refresh (in ascending order).
+    ///
+    /// **NOTE:** Only existing parties know the current number of parties, so this is always empty for new parties.
+    pub removed: Vec<u16>,
+    /// New indices of the continuing parties (i.e parties whose secret shares are re-randomized) (in ascending order).
+    pub continued: Vec<u16>,
+    /// Whether or not the key refresh changes the threshold.
+    pub threshold_changed: bool,
+}
+
+impl RefreshSummary {
+    /// Given a map of current to new party indices, the current threshold and number of parties (if known)
+    /// and the new threshold and number of parties, returns a summary of the changes to the parties and threshold.
+    fn new(
+        old_to_new_map: &HashMap<u16, u16>,
+        current_threshold_option: Option<u16>,
+        current_n_parties_option: Option<u16>,
+        new_threshold: u16,
+        n_parties: u16,
+    ) -> Self {
+        let mut continued: Vec<u16> = old_to_new_map.values().copied().collect();
+        continued.sort_unstable();
+        Self {
+            added: (1..=n_parties)
+                .filter(|idx| !continued.contains(idx))
+                .collect(),
+            removed: current_n_parties_option
+                .map(|current_n_parties| {
+                    (1..=current_n_parties)
+                        .filter(|idx| !old_to_new_map.contains_key(idx))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            continued,
+            threshold_changed: current_threshold_option
+                .is_some_and(|current_threshold| current_threshold != new_threshold),
+        }
+    }
+}
+
+/// An audit record of how a `LocalKey<Secp256k1>` changed (e.g after a key refresh or membership change).
+///
+/// **NOTE:** The record is computed from public key material only (i.e it never reveals any secret shares).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocalKeyDiff {
+    /// Whether or not the group public key is unchanged.
+    pub public_key_unchanged: bool,
+    /// The change in the threshold (i.e new threshold - current threshold).
+    pub threshold_delta: i32,
+    /// The change in the number of parties (i.e new number of parties - current number of parties).
+    pub n_parties_delta: i32,
+    /// Whether or not the index of the party changed.
+    pub party_index_changed: bool,
+    /// Whether or not the secret share of the party was rotated (i.e its public key share `x_i * G` changed).
+    pub secret_share_rotated: bool,
+}
+
+/// Given the `LocalKey<Secp256k1>` of a party before and after a key refresh (or membership change),
+/// returns an audit record of the changes to the group public key, threshold, number of parties and secret share.
+///
+/// **NOTE:** Secret share rotation is detected by comparing the public key shares of the party,
+/// so it works for keys whose secret share is cleared/zerorized (e.g augmented key outputs).
+pub fn diff_local_keys(before: &LocalKey<Secp256k1>, after: &LocalKey<Secp256k1>) -> LocalKeyDiff {
+    // Returns the public key share (i.e `x_i * G`) of the party (if any).
+    let public_key_share = |local_key: &LocalKey<Secp256k1>| {
+        local_key
+            .i
+            .checked_sub(1)
+            .and_then(|idx| local_key.pk_vec.get(usize::from(idx)))
+            .cloned()
+    };
+    LocalKeyDiff {
+        public_key_unchanged: before.public_key() == after.public_key(),
+        threshold_delta: i32::from(after.t) - i32::from(before.t),
+        n_parties_delta: i32::from(after.n) - i32::from(before.n),
+        party_index_changed: before.i != after.i,
+        secret_share_rotated: public_key_share(before) != public_key_share(after),
+    }
 }
 
 impl<'a, I: IdentityProvider> AugmentedKeyRefresh<'a, I> {
     /// Initializes party for the augmented key refresh protocol.
+    ///
+    /// **NOTE:** The verified parties must include exactly one verifying key per new party index (see [`IndexedParties`]).
+    /// Callers that previously passed verifying keys in new party index order to `new` or `new_shared`
+    /// should wrap them with [`IndexedParties::from_ordered`] (i.e for either borrowed or shared verifying keys),
+    /// or build them with [`IndexedParties::from_map`] from a map of new party indices to verifying keys.
     pub fn new(
         signing_share_option: Option<&SigningShare>,
         sub_share_option: Option<&SubShare>,
         identity_provider: &'a I,
-        verified_parties: &'a [VerifyingKey],
+        verified_parties: IndexedParties<'a>,
         // `LocalKey<Secp256k1>` with secret share set to zero.
-        mut local_key_option: Option<LocalKey<Secp256k1>>,
+        local_key_option: Option<LocalKey<Secp256k1>>,
         new_party_index_option: Option<u16>,
         old_to_new_map: &HashMap<u16, u16>,
         // NOTE: FS-DKR operates in the honest majority setting, so threshold <= n_parties/2 must hold.
@@ -53,36 +229,75 @@ impl<'a, I: IdentityProvider> AugmentedKeyRefresh<'a, I> {
         n_parties: u16,
         current_threshold_option: Option<u16>,
     ) -> Result<Self, Error<<KeyRefresh as StateMachine>::Err>> {
-        // For `cggmp-threshold-ecdsa`, key refresh is based on FS-DKR,
-        // which is a modified version of FS-DKG (Fouque-Stern Distributed Key Generation).
-        // FS-DKR operates in the honest majority setting, so threshold <= n_parties/2 must hold.
-        // Ref: <https://github.com/ZenGo-X/fs-dkr#adjusting-fs-dkg-to-dkr-and-threshold-ecdsa>.
-        // Ref: <https://inria.hal.science/inria-00565274/document>.
-        if new_threshold > n_parties / 2 {
-            return Err(Error::BadFSDKRThreshold);
+        // Runs all up-front checks.
+        Self::validate(
+            signing_share_option,
+            sub_share_option,
+            &verified_parties,
+            local_key_option.as_ref(),
+            new_party_index_option,
+            old_to_new_map,
+            new_threshold,
+            n_parties,
+        )?;
+
+        // The party's own verifying key must align with its new party index.
+        let own_new_idx_option = new_party_index_option.or_else(|| {
+            local_key_option
+                .as_ref()
+                .and_then(|local_key| old_to_new_map.get(&local_key.i).copied())
+        });
+        if let Some(own_new_idx) = own_new_idx_option {
+            if verified_parties.verifying_key(own_new_idx)
+                != Some(&identity_provider.verifying_key())
+            {
+                return Err(Error::AmbiguousPartyIdentity {
+                    bad_actors: vec![usize::from(own_new_idx)],
+                });
+            }
         }
 
-        // Reconstruct secret share if "signing share" and "sub-share" are provided and update `LocalKey<Secp256k1>` (if provided) with the reconstructed secret share.
+        // Summarizes the changes to the parties and threshold.
+        let current_threshold = local_key_option
+            .as_ref()
+            .map(|local_key| local_key.t)
+            .or(current_threshold_option);
+        let is_existing_party = local_key_option.is_some();
+        let summary = RefreshSummary::new(
+            old_to_new_map,
+            current_threshold,
+            local_key_option.as_ref().map(|local_key| local_key.n),
+            new_threshold,
+            n_parties,
+        );
+
+        // Existing parties derive the group public key that the refresh must preserve from their `LocalKey<Secp256k1>`.
+        let expected_public_key = local_key_option
+            .as_ref()
+            .map(GroupKey::try_from)
+            .transpose()?
+            .map(VerifyingKey::from);
+
+        // Reconstruct secret share if "signing share" and "sub-share" are provided and update `LocalKey<Secp256k1>` (if provided) with the reconstructed secret share
+        // (which is zerorized on both success and error paths).
+        let mut local_key_option = TransientSecretShare(local_key_option);
         if let Some((local_key, (signing_share, sub_share))) = local_key_option
+            .0
             .as_mut()
             .zip(signing_share_option.zip(sub_share_option))
         {
-            // Reconstructs secret share.
-            let secret_share = wamu_core::share_split_reconstruct::reconstruct(
+            // Reconstructs and sets the secret share.
+            local_key.keys_linear.x_i = augmented_state_machine::reconstruct_secret_scalar(
                 signing_share,
                 sub_share,
                 identity_provider,
             )?;
-            // Sets the reconstructed secret share.
-            local_key.keys_linear.x_i =
-                Scalar::<Secp256k1>::from_bytes(&secret_share.to_be_bytes())
-                    .map_err(|_| Error::Core(wamu_core::Error::Encoding))?;
         }
 
         // Initializes state machine.
         let mut aug_key_refresh = Self {
             state_machine: KeyRefresh::new(
-                local_key_option,
+                local_key_option.handoff(),
                 new_party_index_option,
                 old_to_new_map,
                 new_threshold,
@@ -93,6 +308,12 @@ impl<'a, I: IdentityProvider> AugmentedKeyRefresh<'a, I> {
             identity_provider,
             verified_parties,
             existing_parties: old_to_new_map.values().copied().collect::<Vec<u16>>(),
+            expected_public_key,
+            current_threshold,
+            is_existing_party,
+            summary,
+            augmentation_failures: AugmentationFailures::default(),
+            deadline: None,
         };
 
         // Retrieves messages from immediate state transitions (if any) and augments them.
@@ -102,6 +323,122 @@ impl<'a, I: IdentityProvider> AugmentedKeyRefresh<'a, I> {
         Ok(aug_key_refresh)
     }
 
+    /// Sets the group public key from before the refresh, which the refreshed key must preserve
+    /// (e.g for new parties that don't have a `LocalKey<Secp256k1>` to derive it from).
+    ///
+    /// **NOTE:** Existing parties derive the group public key from their `LocalKey<Secp256k1>` automatically.
+    pub fn set_expected_public_key(&mut self, verifying_key: VerifyingKey) {
+        self.expected_public_key = Some(verifying_key);
+    }
+
+    /// Returns a summary of how the key refresh changes the parties and threshold (e.g for user notifications).
+    pub fn summary(&self) -> &RefreshSummary {
+        &self.summary
+    }
+
+    /// Validates the configuration for the augmented key refresh protocol without initializing the wrapped state machine
+    /// (e.g to pre-flight a ceremony), returns an ok result for a valid configuration or an appropriate error result otherwise.
+    ///
+    /// **NOTE:** Runs all the up-front checks performed by [`new`](Self::new).
+    pub fn validate(
+        signing_share_option: Option<&SigningShare>,
+        sub_share_option: Option<&SubShare>,
+        verified_parties: &IndexedParties,
+        local_key_option: Option<&LocalKey<Secp256k1>>,
+        new_party_index_option: Option<u16>,
+        old_to_new_map: &HashMap<u16, u16>,
+        new_threshold: u16,
+        n_parties: u16,
+    ) -> Result<(), Error<<KeyRefresh as StateMachine>::Err>> {
+        // Verified parties must be provided (i.e an empty list means no other party is authorized).
+        if verified_parties.is_empty() {
+            return Err(Error::Core(wamu_core::Error::NoVerifiedParties));
+        }
+
+        // Verified parties must be distinct (i.e each verifying key must be associated with a single party).
+        let mut unique_verified_parties = HashSet::new();
+        let duplicate_positions: Vec<usize> = verified_parties
+            .iter()
+            .enumerate()
+            .filter(|(_, verifying_key)| !unique_verified_parties.insert(*verifying_key))
+            .map(|(position, _)| position + 1)
+            .collect();
+        if !duplicate_positions.is_empty() {
+            return Err(Error::AmbiguousPartyIdentity {
+                bad_actors: duplicate_positions,
+            });
+        }
+
+        // A threshold of zero means that any single party can sign, so it's never allowed.
+        if new_threshold == 0 {
+            return Err(Error::Core(wamu_core::Error::InvalidThreshold));
+        }
+
+        // For `cggmp-threshold-ecdsa`, key refresh is based on FS-DKR,
+        // which is a modified version of FS-DKG (Fouque-Stern Distributed Key Generation).
+        // FS-DKR operates in the honest majority setting, so threshold <= n_parties/2 must hold.
+        // Ref: <https://github.com/ZenGo-X/fs-dkr#adjusting-fs-dkg-to-dkr-and-threshold-ecdsa>.
+        // Ref: <https://inria.hal.science/inria-00565274/document>.
+        if new_threshold > n_parties / 2 {
+            return Err(Error::BadFSDKRThreshold);
+        }
+
+        // New party indices (including the index of a new party) must be in the range `1..=n_parties` and unique.
+        let mut new_indices = HashSet::new();
+        for new_idx in old_to_new_map
+            .values()
+            .copied()
+            .chain(new_party_index_option)
+        {
+            if new_idx == 0 || new_idx > n_parties || !new_indices.insert(new_idx) {
+                return Err(Error::BadIndexMap);
+            }
+        }
+
+        // Verified parties must align with the new party indices (i.e exactly one verifying key per new party index).
+        let (n_verified_parties, n_parties) = (verified_parties.len(), usize::from(n_parties));
+        if n_verified_parties != n_parties {
+            return Err(Error::AmbiguousPartyIdentity {
+                bad_actors: (n_verified_parties.min(n_parties) + 1
+                    ..=n_verified_parties.max(n_parties))
+                    .collect(),
+            });
+        }
+
+        // Existing parties must provide a "signing share" and "sub-share" for reconstructing their secret share.
+        if local_key_option.is_some()
+            && (signing_share_option.is_none() || sub_share_option.is_none())
+        {
+            return Err(Error::MissingShares);
+        }
+
+        Ok(())
+    }
+
+    /// Returns an ok result if the verifying key that a party signed with aligns with its party index,
+    /// or an `AmbiguousPartyIdentity` error otherwise (e.g a party signing with the verifying key of another party).
+    fn verify_party_alignment(
+        &self,
+        sender: u16,
+        verifying_key: &VerifyingKey,
+    ) -> Result<(), Error<<KeyRefresh as StateMachine>::Err>> {
+        if self.verified_parties.verifying_key(sender) == Some(verifying_key) {
+            Ok(())
+        } else {
+            Err(Error::AmbiguousPartyIdentity {
+                bad_actors: vec![usize::from(sender)],
+            })
+        }
+    }
+
+    /// Returns the commitment to the set of parties of the party.
+    fn party_set_hash(&self) -> [u8; 32] {
+        augmented_state_machine::party_set_hash(
+            &self.identity_provider.verifying_key(),
+            &self.verified_parties,
+        )
+    }
+
     // For `cggmp-threshold-ecdsa`, key refresh is based on FS-DKR,
     // which is a modified version of FS-DKG (Fouque-Stern Distributed Key Generation).
     // So we hash parameters from Round 1 (for new parties) or Round 2 (for existing parties)
@@ -109,46 +446,85 @@ impl<'a, I: IdentityProvider> AugmentedKeyRefresh<'a, I> {
     // Ref: <https://github.com/ZenGo-X/fs-dkr#adjusting-fs-dkg-to-dkr-and-threshold-ecdsa>.
     // Ref: <https://inria.hal.science/inria-00565274/document>.
     fn parameter_hash(sender: u16, msg: InitiationMessage) -> Vec<u8> {
-        let (ek_n, rp_n, rp_s, rp_t) = match msg {
-            InitiationMessage::Join(inner_msg) => (
+        let (tag, ek_n, rp_n, rp_s, rp_t, current_threshold_option) = match &msg {
+            InitiationMessage::Join(inner_msg, current_threshold_option) => (
+                JOIN_PARAMETER_HASH_TAG,
                 &inner_msg.ek.n,
                 &inner_msg.ring_pedersen_statement.N,
                 &inner_msg.ring_pedersen_statement.S,
                 &inner_msg.ring_pedersen_statement.T,
+                *current_threshold_option,
             ),
             InitiationMessage::Refresh(inner_msg) => (
+                REFRESH_PARAMETER_HASH_TAG,
                 &inner_msg.ek.n,
                 &inner_msg.ring_pedersen_statement.N,
                 &inner_msg.ring_pedersen_statement.S,
                 &inner_msg.ring_pedersen_statement.T,
+                None,
             ),
         };
-        use sha2::{digest::Update, Digest};
-        let hasher = sha2::Sha256::new();
-        hasher
-            .chain(sender.to_be_bytes())
-            .chain(ek_n.to_bytes())
-            .chain(rp_n.to_bytes())
-            .chain(rp_s.to_bytes())
-            .chain(rp_t.to_bytes())
-            .finalize()
-            .deref()
-            .to_vec()
+        hash_parameters(
+            tag,
+            sender,
+            [ek_n, rp_n, rp_s, rp_t],
+            current_threshold_option,
+        )
+    }
+}
+
+/// Given a domain separation tag (i.e for either join or refresh messages), the sender,
+/// the Paillier and ring-Pedersen parameters (i.e `ek_n || rp_n || rp_s || rp_t`)
+/// and the claimed current threshold of a new party (if any), returns the domain separated parameter hash
+/// i.e `SHA-256(domain_separated_message_bytes(tag, sender || (length || parameter)* || threshold))`,
+/// where lengths are 8 byte big-endian unsigned integers
+/// and the threshold is encoded as `0` for `None` or `1 || threshold` for `Some(threshold)`
+/// (see [`wamu_core::utils::domain_separated_message_bytes`]).
+fn hash_parameters(
+    tag: &[u8],
+    sender: u16,
+    parameters: [&BigInt; 4],
+    current_threshold_option: Option<u16>,
+) -> Vec<u8> {
+    let mut message = sender.to_be_bytes().to_vec();
+    for parameter in parameters {
+        let parameter_bytes = parameter.to_bytes();
+        message.extend_from_slice(&(parameter_bytes.len() as u64).to_be_bytes());
+        message.extend_from_slice(&parameter_bytes);
     }
+    // New parties also commit to their claimed current threshold.
+    match current_threshold_option {
+        Some(current_threshold) => {
+            message.push(1);
+            message.extend_from_slice(&current_threshold.to_be_bytes());
+        }
+        None => message.push(0),
+    }
+    use sha2::Digest;
+    sha2::Sha256::digest(wamu_core::utils::domain_separated_message_bytes(
+        tag, &message,
+    ))
+    .to_vec()
 }
 
 enum InitiationMessage<'a> {
-    Join(&'a JoinMessage<Secp256k1, Sha256, 80>),
+    /// A join message from a new party and its claimed current threshold.
+    Join(&'a JoinMessage<Secp256k1, Sha256, 80>, Option<u16>),
     Refresh(&'a RefreshMessage<Secp256k1, Sha256, 80>),
 }
 
 impl<'a, I: IdentityProvider> AugmentedStateMachine for AugmentedKeyRefresh<'a, I> {
     type StateMachineType = KeyRefresh;
     type AdditionalParams = IdentityAuthParams;
-    type AdditionalOutput = SubShareOutput;
+    type AdditionalOutput = RefreshOutput;
 
     // Implements all required `AugmentedStateMachine` methods.
-    impl_required_augmented_state_machine_methods!(state_machine, message_queue);
+    impl_required_augmented_state_machine_methods!(
+        state_machine,
+        message_queue,
+        augmentation_failures,
+        deadline
+    );
 
     fn pre_handle_incoming(
         &mut self,
@@ -166,12 +542,30 @@ impl<'a, I: IdentityProvider> AugmentedStateMachine for AugmentedKeyRefresh<'a,
                     match out_msg_option.as_ref().zip(msg.body.extra.as_ref()) {
                         // Verifies that signer is an expected party/signatory and the signature is valid.
                         Some((out_msg, params)) => {
-                            Ok(wamu_core::wrappers::verify_request_with_signature(
-                                &Self::parameter_hash(msg.sender, InitiationMessage::Join(out_msg)),
+                            wamu_core::wrappers::verify_request_with_signature(
+                                &IdentityAuthParams::signed_bytes(
+                                    &Self::parameter_hash(
+                                        msg.sender,
+                                        InitiationMessage::Join(out_msg, params.current_threshold),
+                                    ),
+                                    &params.party_set_hash,
+                                ),
                                 &params.verifying_key,
                                 &params.verifying_signature,
-                                self.verified_parties,
-                            )?)
+                                &*self.verified_parties,
+                            )?;
+                            // Verifies that the (authenticated) verifying key aligns with the sender's party index.
+                            self.verify_party_alignment(msg.sender, &params.verifying_key)?;
+                            // Verifies that the (authenticated) sender committed to the same set of parties.
+                            params.verify_party_set(msg.sender, &self.party_set_hash())?;
+                            // Verifies that the (authenticated) current threshold claimed by the new party
+                            // matches the actual current threshold (i.e only existing parties know it).
+                            if self.is_existing_party
+                                && params.current_threshold != self.current_threshold
+                            {
+                                return Err(Error::ThresholdMismatch { party: msg.sender });
+                            }
+                            Ok(())
                         }
                         // Returns an error if expected additional parameters are missing for new parties.
                         None => Err(Error::MissingParams {
@@ -189,15 +583,22 @@ impl<'a, I: IdentityProvider> AugmentedStateMachine for AugmentedKeyRefresh<'a,
                     match out_msg_option.as_ref().zip(msg.body.extra.as_ref()) {
                         // Verifies that signer is an expected party/signatory and the signature is valid.
                         Some((out_msg, params)) => {
-                            Ok(wamu_core::wrappers::verify_request_with_signature(
-                                &Self::parameter_hash(
-                                    msg.sender,
-                                    InitiationMessage::Refresh(out_msg),
+                            wamu_core::wrappers::verify_request_with_signature(
+                                &IdentityAuthParams::signed_bytes(
+                                    &Self::parameter_hash(
+                                        msg.sender,
+                                        InitiationMessage::Refresh(out_msg),
+                                    ),
+                                    &params.party_set_hash,
                                 ),
                                 &params.verifying_key,
                                 &params.verifying_signature,
-                                self.verified_parties,
-                            )?)
+                                &*self.verified_parties,
+                            )?;
+                            // Verifies that the (authenticated) verifying key aligns with the sender's party index.
+                            self.verify_party_alignment(msg.sender, &params.verifying_key)?;
+                            // Verifies that the (authenticated) sender committed to the same set of parties.
+                            params.verify_party_set(msg.sender, &self.party_set_hash())
                         }
                         // Returns an error if expected additional parameters are missing for existing parties.
                         None => Err(Error::MissingParams {
@@ -223,14 +624,23 @@ impl<'a, I: IdentityProvider> AugmentedStateMachine for AugmentedKeyRefresh<'a,
             M::Round1(it) => {
                 if !self.existing_parties.contains(&sender) {
                     Ok(it.as_ref().map(|out_msg| {
+                        let party_set_hash = self.party_set_hash();
                         let (verifying_key, verifying_signature) =
                             wamu_core::wrappers::initiate_request_with_signature(
-                                &Self::parameter_hash(sender, InitiationMessage::Join(out_msg)),
+                                &IdentityAuthParams::signed_bytes(
+                                    &Self::parameter_hash(
+                                        sender,
+                                        InitiationMessage::Join(out_msg, self.current_threshold),
+                                    ),
+                                    &party_set_hash,
+                                ),
                                 self.identity_provider,
                             );
                         IdentityAuthParams {
                             verifying_key,
                             verifying_signature,
+                            party_set_hash,
+                            current_threshold: self.current_threshold,
                         }
                     }))
                 } else {
@@ -242,14 +652,23 @@ impl<'a, I: IdentityProvider> AugmentedStateMachine for AugmentedKeyRefresh<'a,
             M::Round2(it) => {
                 if self.existing_parties.contains(&sender) {
                     Ok(it.as_ref().map(|out_msg| {
+                        let party_set_hash = self.party_set_hash();
                         let (verifying_key, verifying_signature) =
                             wamu_core::wrappers::initiate_request_with_signature(
-                                &Self::parameter_hash(sender, InitiationMessage::Refresh(out_msg)),
+                                &IdentityAuthParams::signed_bytes(
+                                    &Self::parameter_hash(
+                                        sender,
+                                        InitiationMessage::Refresh(out_msg),
+                                    ),
+                                    &party_set_hash,
+                                ),
                                 self.identity_provider,
                             );
                         IdentityAuthParams {
                             verifying_key,
                             verifying_signature,
+                            party_set_hash,
+                            current_threshold: None,
                         }
                     }))
                 } else {
@@ -267,10 +686,33 @@ impl<'a, I: IdentityProvider> AugmentedStateMachine for AugmentedKeyRefresh<'a,
         AugmentedType<<Self::StateMachineType as StateMachine>::Output, Self::AdditionalOutput>,
         Error<<Self::StateMachineType as StateMachine>::Err>,
     > {
-        Ok(augmented_state_machine::split_key_output(
-            self.identity_provider,
-            output,
-        )?)
+        // The refresh must preserve the group public key.
+        if let Some(expected_public_key) = self.expected_public_key.as_ref() {
+            if GroupKey::try_from(&output)?.verifying_key() != expected_public_key {
+                return Err(Error::PublicKeyChanged);
+            }
+        }
+
+        // Splits the key output and adds the summary.
+        let output = augmented_state_machine::split_key_output(self.identity_provider, output)?;
+        Ok(AugmentedType {
+            base: output.base,
+            extra: output
+                .extra
+                .map(|(signing_share, sub_share)| (signing_share, sub_share, self.summary.clone())),
+        })
+    }
+}
+
+impl<'a, I: IdentityProvider> PartyIdentity for AugmentedKeyRefresh<'a, I> {
+    fn party_id(&self) -> PartyId {
+        PartyId::from_verifying_key(&self.identity_provider.verifying_key())
+    }
+}
+
+impl<'a, I: IdentityProvider> RoundLabel for AugmentedKeyRefresh<'a, I> {
+    fn round_label(&self) -> String {
+        format!("Key Refresh Round {}", self.state_machine.current_round())
     }
 }
 
@@ -279,7 +721,7 @@ impl_state_machine_for_augmented_state_machine!(
     AugmentedKeyRefresh,
     KeyRefresh,
     IdentityAuthParams,
-    SubShareOutput
+    RefreshOutput
 );
 
 // Implement `Debug` trait for `AugmentedKeyRefresh` for test simulations.
@@ -294,6 +736,7 @@ impl<'a, I: IdentityProvider> std::fmt::Debug for AugmentedKeyRefresh<'a, I> {
 pub mod tests {
     use super::*;
     use crate::keygen;
+    use curv::elliptic::curves::{Point, Scalar};
     use round_based::dev::Simulation;
     use wamu_core::test_utils::MockECDSAIdentityProvider;
 
@@ -312,7 +755,7 @@ pub mod tests {
         // NOTE: Quorum size = threshold + 1
         threshold: u16,
         n_parties: u16,
-    ) -> Vec<AugmentedType<LocalKey<Secp256k1>, SubShareOutput>> {
+    ) -> Vec<AugmentedType<LocalKey<Secp256k1>, RefreshOutput>> {
         // Creates simulation.
         let mut simulation = Simulation::new();
 
@@ -337,7 +780,7 @@ pub mod tests {
                     signing_share,
                     sub_share,
                     identity_provider,
-                    &verifying_keys,
+                    IndexedParties::from_ordered(&verifying_keys),
                     local_key,
                     new_party_index,
                     current_to_new_idx_map,
@@ -401,7 +844,7 @@ pub mod tests {
         let mut current_to_new_idx_map = HashMap::new();
         for (i, identity_provider) in identity_providers.iter().enumerate() {
             // Create party key config and index entry.
-            let idx = i as u16 + 1;
+            let idx = wamu_core::party_index(i).unwrap();
             let key_option = keys.get(i);
             let local_key_option = key_option.map(|key| key.base.clone());
             let share_output_option = key_option.map(|key| key.extra.as_ref().unwrap());
@@ -438,7 +881,15 @@ pub mod tests {
             assert_eq!(key.base.keys_linear.x_i, Scalar::<Secp256k1>::zero());
             // Verifies that the public key hasn't changed.
             assert_eq!(key.base.public_key(), pub_key_init);
+            // Verifies the summary of the changes.
+            let (_, _, summary) = key.extra.as_ref().unwrap();
+            assert_eq!(
+                summary.added,
+                (n_parties_init.min(n_parties_new) + 1..=n_parties_new).collect::<Vec<u16>>()
+            );
+            assert_eq!(summary.threshold_changed, threshold_new != threshold_init);
         }
+        let keys_new = keys_new.into_iter().map(Into::into).collect();
 
         (
             (keys_init, identity_providers_init),
@@ -481,4 +932,689 @@ pub mod tests {
     fn key_refresh_remove_parties_new_threshold_works() {
         generate_parties_and_simulate_key_refresh(2, 4, 1, 3);
     }
+
+    #[test]
+    fn key_refresh_public_key_change_fails() {
+        // Runs keygen simulation.
+        let (threshold, n_parties) = (1, 3);
+        let (keys, identity_providers) = keygen::tests::simulate_keygen(threshold, n_parties);
+
+        // Creates a list of verifying keys for all parties.
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Initializes key refresh for an existing party.
+        let current_to_new_idx_map: HashMap<u16, u16> = (1..=n_parties).map(|i| (i, i)).collect();
+        let key = &keys[0];
+        let (signing_share, sub_share) = key.extra.as_ref().unwrap();
+        let aug_key_refresh = AugmentedKeyRefresh::new(
+            Some(signing_share),
+            Some(sub_share),
+            &identity_providers[0],
+            IndexedParties::from_ordered(&verifying_keys),
+            Some(key.base.clone()),
+            None,
+            &current_to_new_idx_map,
+            threshold,
+            n_parties,
+            None,
+        )
+        .unwrap();
+
+        // Restores the secret share of the `LocalKey<Secp256k1>` (i.e as it would be after a refresh).
+        let secret_share = wamu_core::share_split_reconstruct::reconstruct(
+            signing_share,
+            sub_share,
+            &identity_providers[0],
+        )
+        .unwrap();
+        let mut unaltered_key = key.base.clone();
+        unaltered_key.keys_linear.x_i =
+            Scalar::<Secp256k1>::from_bytes(&secret_share.to_be_bytes()).unwrap();
+
+        // Simulates a key altering fault by shifting the group public key of the refreshed key.
+        let mut altered_key = unaltered_key.clone();
+        altered_key.y_sum_s = &altered_key.y_sum_s + Point::<Secp256k1>::generator().to_point();
+
+        // Verifies that the altered key is rejected, while the unaltered key is accepted.
+        assert!(matches!(
+            aug_key_refresh.augment_output(altered_key),
+            Err(Error::PublicKeyChanged)
+        ));
+        assert!(aug_key_refresh.augment_output(unaltered_key).is_ok());
+    }
+
+    #[test]
+    fn diff_local_keys_works() {
+        // Runs key refresh simulation that removes a party.
+        let ((keys_init, _), (keys_new, _)) = generate_parties_and_simulate_key_refresh(1, 3, 1, 2);
+        let key_init = &keys_init[0].base;
+        let key_new = &keys_new[0].base;
+
+        // Simulates a key altering fault by shifting the group public key of the refreshed key.
+        let mut altered_key = key_new.clone();
+        altered_key.y_sum_s = &altered_key.y_sum_s + Point::<Secp256k1>::generator().to_point();
+
+        for (before, after, expected_diff) in [
+            // Pre- and post-refresh keys.
+            (
+                key_init,
+                key_new,
+                LocalKeyDiff {
+                    public_key_unchanged: true,
+                    threshold_delta: 0,
+                    n_parties_delta: -1,
+                    party_index_changed: false,
+                    secret_share_rotated: true,
+                },
+            ),
+            // Same key.
+            (
+                key_init,
+                key_init,
+                LocalKeyDiff {
+                    public_key_unchanged: true,
+                    threshold_delta: 0,
+                    n_parties_delta: 0,
+                    party_index_changed: false,
+                    secret_share_rotated: false,
+                },
+            ),
+            // Altered group public key.
+            (
+                key_new,
+                &altered_key,
+                LocalKeyDiff {
+                    public_key_unchanged: false,
+                    threshold_delta: 0,
+                    n_parties_delta: 0,
+                    party_index_changed: false,
+                    secret_share_rotated: false,
+                },
+            ),
+        ] {
+            // Verifies expected diff.
+            assert_eq!(diff_local_keys(before, after), expected_diff);
+        }
+    }
+
+    #[test]
+    fn parameter_hash_is_domain_separated() {
+        // Generates identical parameters for join and refresh messages.
+        let parameters: Vec<BigInt> = (1u8..=4).map(|i| BigInt::from_bytes(&[i; 32])).collect();
+        let parameter_refs = [
+            &parameters[0],
+            &parameters[1],
+            &parameters[2],
+            &parameters[3],
+        ];
+
+        // Verifies that join and refresh message hashes over identical parameters differ.
+        let refresh_hash = hash_parameters(REFRESH_PARAMETER_HASH_TAG, 1, parameter_refs, None);
+        for current_threshold_option in [None, Some(1)] {
+            let join_hash = hash_parameters(
+                JOIN_PARAMETER_HASH_TAG,
+                1,
+                parameter_refs,
+                current_threshold_option,
+            );
+            assert_ne!(join_hash, refresh_hash);
+        }
+
+        // Verifies that the hash is deterministic.
+        assert_eq!(
+            hash_parameters(REFRESH_PARAMETER_HASH_TAG, 1, parameter_refs, None),
+            refresh_hash
+        );
+
+        // Verifies that parameter boundaries are unambiguous (i.e `0x01 || 0x0203` vs `0x0102 || 0x03`).
+        let (a, b, c, d) = (
+            BigInt::from_bytes(&[1]),
+            BigInt::from_bytes(&[2, 3]),
+            BigInt::from_bytes(&[1, 2]),
+            BigInt::from_bytes(&[3]),
+        );
+        assert_ne!(
+            hash_parameters(REFRESH_PARAMETER_HASH_TAG, 1, [&a, &b, &a, &a], None),
+            hash_parameters(REFRESH_PARAMETER_HASH_TAG, 1, [&c, &d, &a, &a], None)
+        );
+    }
+
+    #[test]
+    fn parameter_hash_works_for_join_and_refresh_messages() {
+        use round_based::IsCritical;
+
+        // Runs keygen simulation for 2 parties.
+        let (threshold, n_parties) = (1, 2);
+        let (keys, mut identity_providers) = keygen::tests::simulate_keygen(threshold, n_parties);
+        identity_providers.push(MockECDSAIdentityProvider::generate());
+
+        // Creates a list of verifying keys for all parties (including the new party).
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Initializes existing parties and a new party (at index 3).
+        let current_to_new_idx_map = HashMap::from([(1, 1), (2, 2)]);
+        let mut parties: Vec<_> = keys
+            .iter()
+            .zip(identity_providers.iter())
+            .map(|(key, identity_provider)| {
+                let (signing_share, sub_share) = key.extra.as_ref().unwrap();
+                AugmentedKeyRefresh::new(
+                    Some(signing_share),
+                    Some(sub_share),
+                    identity_provider,
+                    IndexedParties::from_ordered(&verifying_keys),
+                    Some(key.base.clone()),
+                    None,
+                    &current_to_new_idx_map,
+                    threshold,
+                    n_parties + 1,
+                    None,
+                )
+                .unwrap()
+            })
+            .collect();
+        parties.push(
+            AugmentedKeyRefresh::new(
+                None,
+                None,
+                &identity_providers[2],
+                IndexedParties::from_ordered(&verifying_keys),
+                None,
+                Some(3),
+                &current_to_new_idx_map,
+                threshold,
+                n_parties + 1,
+                Some(threshold),
+            )
+            .unwrap(),
+        );
+
+        // Runs the protocol until both a join and a refresh message are observed.
+        let (mut join_msg, mut refresh_msg) = (None, None);
+        while join_msg.is_none() || refresh_msg.is_none() {
+            let mut outgoing = Vec::new();
+            for party in parties.iter_mut() {
+                if party.wants_to_proceed() {
+                    party.proceed().unwrap();
+                }
+                outgoing.append(party.message_queue());
+            }
+            assert!(!outgoing.is_empty(), "protocol stalled");
+            for msg in outgoing {
+                match &msg.body.base.0 {
+                    M::Round1(Some(_)) if join_msg.is_none() => join_msg = Some(msg.clone()),
+                    M::Round2(Some(_)) if refresh_msg.is_none() => refresh_msg = Some(msg.clone()),
+                    _ => (),
+                }
+                for party in parties.iter_mut() {
+                    let idx = party.party_ind();
+                    if idx != msg.sender && (msg.receiver.is_none() || msg.receiver == Some(idx)) {
+                        // Non-critical errors (e.g from out of order messages) are skipped.
+                        if let Err(error) = party.handle_incoming(msg.clone()) {
+                            assert!(!error.is_critical(), "{error:?}");
+                        }
+                    }
+                }
+            }
+        }
+        let (join_msg, refresh_msg) = (join_msg.unwrap(), refresh_msg.unwrap());
+        let (M::Round1(Some(join)), M::Round2(Some(refresh))) =
+            (&join_msg.body.base.0, &refresh_msg.body.base.0)
+        else {
+            unreachable!("messages are matched above");
+        };
+
+        for (sender, initiation_message, params) in [
+            (
+                join_msg.sender,
+                InitiationMessage::Join(join, Some(threshold)),
+                join_msg.body.extra.as_ref().unwrap(),
+            ),
+            (
+                refresh_msg.sender,
+                InitiationMessage::Refresh(refresh),
+                refresh_msg.body.extra.as_ref().unwrap(),
+            ),
+        ] {
+            // Verifies that the sender signed the parameter hash.
+            let hash = AugmentedKeyRefresh::<MockECDSAIdentityProvider>::parameter_hash(
+                sender,
+                initiation_message,
+            );
+            assert!(wamu_core::wrappers::verify_request_with_signature(
+                &IdentityAuthParams::signed_bytes(&hash, &params.party_set_hash),
+                &params.verifying_key,
+                &params.verifying_signature,
+                &verifying_keys,
+            )
+            .is_ok());
+        }
+
+        // Verifies that the hash of a join message is bound to both the message type and the claimed current threshold.
+        let join_hash = AugmentedKeyRefresh::<MockECDSAIdentityProvider>::parameter_hash(
+            join_msg.sender,
+            InitiationMessage::Join(join, Some(threshold)),
+        );
+        let join_params = [
+            &join.ek.n,
+            &join.ring_pedersen_statement.N,
+            &join.ring_pedersen_statement.S,
+            &join.ring_pedersen_statement.T,
+        ];
+        for other_hash in [
+            AugmentedKeyRefresh::<MockECDSAIdentityProvider>::parameter_hash(
+                join_msg.sender,
+                InitiationMessage::Join(join, None),
+            ),
+            AugmentedKeyRefresh::<MockECDSAIdentityProvider>::parameter_hash(
+                join_msg.sender,
+                InitiationMessage::Join(join, Some(threshold + 1)),
+            ),
+            hash_parameters(
+                REFRESH_PARAMETER_HASH_TAG,
+                join_msg.sender,
+                join_params,
+                Some(threshold),
+            ),
+        ] {
+            assert_ne!(other_hash, join_hash);
+        }
+    }
+
+    #[test]
+    fn key_refresh_summary_works() {
+        // Runs keygen simulation for 3 parties.
+        let (keys, mut identity_providers) = keygen::tests::simulate_keygen(1, 3);
+        identity_providers.push(MockECDSAIdentityProvider::generate());
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let (signing_share, sub_share) = keys[0].extra.as_ref().unwrap();
+
+        for (
+            verified_parties,
+            current_to_new_idx_map,
+            new_party_index,
+            new_threshold,
+            n_parties,
+            expected_summary,
+        ) in [
+            // Adds a party and changes the threshold.
+            (
+                verifying_keys.clone(),
+                HashMap::from([(1, 1), (2, 2), (3, 3)]),
+                Some(4),
+                2,
+                4,
+                RefreshSummary {
+                    added: vec![4],
+                    removed: vec![],
+                    continued: vec![1, 2, 3],
+                    threshold_changed: true,
+                },
+            ),
+            // Removes a party with the same threshold.
+            (
+                vec![verifying_keys[0].clone(), verifying_keys[2].clone()],
+                HashMap::from([(1, 1), (3, 2)]),
+                None,
+                1,
+                2,
+                RefreshSummary {
+                    added: vec![],
+                    removed: vec![2],
+                    continued: vec![1, 2],
+                    threshold_changed: false,
+                },
+            ),
+        ] {
+            // Initializes the first party as an existing party.
+            let aug_key_refresh = AugmentedKeyRefresh::new(
+                Some(signing_share),
+                Some(sub_share),
+                &identity_providers[0],
+                IndexedParties::from_ordered(&verified_parties),
+                Some(keys[0].base.clone()),
+                None,
+                &current_to_new_idx_map,
+                new_threshold,
+                n_parties,
+                None,
+            )
+            .unwrap();
+
+            // Verifies the summary for the existing party.
+            assert_eq!(aug_key_refresh.summary(), &expected_summary);
+
+            // Verifies the summary for the new party (if any).
+            if let Some(new_party_index) = new_party_index {
+                let aug_key_refresh = AugmentedKeyRefresh::new(
+                    None,
+                    None,
+                    &identity_providers[3],
+                    IndexedParties::from_ordered(&verified_parties),
+                    None,
+                    Some(new_party_index),
+                    &current_to_new_idx_map,
+                    new_threshold,
+                    n_parties,
+                    Some(keys[0].base.t),
+                )
+                .unwrap();
+                assert_eq!(aug_key_refresh.summary(), &expected_summary);
+            }
+        }
+    }
+
+    #[test]
+    fn key_refresh_validation_works() {
+        // Sets threshold and number of parties.
+        let threshold = 1;
+        let n_parties = 3;
+
+        // Creates a list of verifying keys for all parties.
+        let verifying_keys: Vec<VerifyingKey> = (1..=n_parties)
+            .map(|_| MockECDSAIdentityProvider::generate().verifying_key())
+            .collect();
+        // Creates a list of verifying keys where the first party's key also appears in the third position.
+        let duplicate_verifying_keys = [
+            verifying_keys[0].clone(),
+            verifying_keys[1].clone(),
+            verifying_keys[0].clone(),
+        ];
+
+        for (
+            verified_parties,
+            current_to_new_idx_map,
+            new_party_index,
+            new_threshold,
+            expected_result,
+        ) in [
+            // Valid configuration should be ok.
+            (
+                &verifying_keys[..],
+                HashMap::from([(1, 1), (2, 2)]),
+                Some(3),
+                threshold,
+                Ok(()),
+            ),
+            // No verified parties should fail.
+            (
+                &verifying_keys[..0],
+                HashMap::from([(1, 1), (2, 2)]),
+                Some(3),
+                threshold,
+                Err(Error::Core(wamu_core::Error::NoVerifiedParties)),
+            ),
+            // Duplicate verified parties should fail.
+            (
+                &duplicate_verifying_keys[..],
+                HashMap::from([(1, 1), (2, 2)]),
+                Some(3),
+                threshold,
+                Err(Error::AmbiguousPartyIdentity {
+                    bad_actors: vec![3],
+                }),
+            ),
+            // Zero threshold should fail.
+            (
+                &verifying_keys[..],
+                HashMap::from([(1, 1), (2, 2)]),
+                Some(3),
+                0,
+                Err(Error::Core(wamu_core::Error::InvalidThreshold)),
+            ),
+            // An insecure FS-DKR threshold (i.e t > n/2) should fail.
+            (
+                &verifying_keys[..],
+                HashMap::from([(1, 1), (2, 2)]),
+                Some(3),
+                threshold + 1,
+                Err(Error::BadFSDKRThreshold),
+            ),
+            // Duplicate new indices should fail.
+            (
+                &verifying_keys[..],
+                HashMap::from([(1, 1), (2, 1)]),
+                Some(3),
+                threshold,
+                Err(Error::BadIndexMap),
+            ),
+            // New indices outside the range `1..=n_parties` should fail.
+            (
+                &verifying_keys[..],
+                HashMap::from([(1, 1), (2, 4)]),
+                Some(3),
+                threshold,
+                Err(Error::BadIndexMap),
+            ),
+            // A new party index that collides with an existing party's new index should fail.
+            (
+                &verifying_keys[..],
+                HashMap::from([(1, 1), (2, 2)]),
+                Some(2),
+                threshold,
+                Err(Error::BadIndexMap),
+            ),
+            // Verified parties that don't align with the new party indices should fail.
+            (
+                &verifying_keys[..2],
+                HashMap::from([(1, 1), (2, 2)]),
+                Some(3),
+                threshold,
+                Err(Error::AmbiguousPartyIdentity {
+                    bad_actors: vec![3],
+                }),
+            ),
+        ] {
+            // Validates configuration for a new party.
+            let result = AugmentedKeyRefresh::<MockECDSAIdentityProvider>::validate(
+                None,
+                None,
+                &IndexedParties::from_ordered(verified_parties),
+                None,
+                new_party_index,
+                &current_to_new_idx_map,
+                new_threshold,
+                n_parties,
+            );
+
+            // Verifies expected result.
+            assert_eq!(
+                result.as_ref().map_err(std::mem::discriminant),
+                expected_result.as_ref().map_err(std::mem::discriminant)
+            );
+        }
+    }
+
+    #[test]
+    fn indexed_parties_from_map_works() {
+        let verifying_keys: Vec<VerifyingKey> = (0..3)
+            .map(|_| MockECDSAIdentityProvider::generate().verifying_key())
+            .collect();
+
+        // Verifies that verifying keys are ordered by new party index (regardless of insertion order).
+        let verified_parties = IndexedParties::from_map(&BTreeMap::from([
+            (3, verifying_keys[2].clone()),
+            (1, verifying_keys[0].clone()),
+            (2, verifying_keys[1].clone()),
+        ]))
+        .unwrap();
+        assert_eq!(&*verified_parties, verifying_keys.as_slice());
+        assert_eq!(verified_parties.verifying_key(2), Some(&verifying_keys[1]));
+        assert_eq!(verified_parties.verifying_key(0), None);
+        assert_eq!(verified_parties.verifying_key(4), None);
+
+        // Verifies that new party indices that aren't exactly `1..=n` are rejected.
+        for new_indices in [[1, 2, 4], [0, 1, 2], [2, 3, 4]] {
+            let verified_parties: BTreeMap<u16, VerifyingKey> = new_indices
+                .into_iter()
+                .zip(verifying_keys.iter().cloned())
+                .collect();
+            assert!(matches!(
+                IndexedParties::from_map(&verified_parties),
+                Err(Error::BadIndexMap)
+            ));
+        }
+    }
+
+    #[test]
+    fn key_refresh_current_threshold_mismatch_fails() {
+        use crate::test_utils::{ChaosConfig, ChaosError, ChaosRouter};
+
+        // Runs keygen simulation for 2 parties.
+        let (threshold, n_parties) = (1, 2);
+        let (keys, mut identity_providers) = keygen::tests::simulate_keygen(threshold, n_parties);
+        identity_providers.push(MockECDSAIdentityProvider::generate());
+
+        // Creates a list of verifying keys for all parties (including the new party).
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Adds existing parties to the router.
+        let current_to_new_idx_map = HashMap::from([(1, 1), (2, 2)]);
+        let mut router = ChaosRouter::new(ChaosConfig::reliable(1)).unwrap();
+        for (key, identity_provider) in keys.iter().zip(identity_providers.iter()) {
+            let (signing_share, sub_share) = key.extra.as_ref().unwrap();
+            router.add_party(
+                AugmentedKeyRefresh::new(
+                    Some(signing_share),
+                    Some(sub_share),
+                    identity_provider,
+                    IndexedParties::from_ordered(&verifying_keys),
+                    Some(key.base.clone()),
+                    None,
+                    &current_to_new_idx_map,
+                    threshold,
+                    n_parties + 1,
+                    None,
+                )
+                .unwrap(),
+            );
+        }
+
+        // Adds a new party (at index 3) that lies about the current threshold.
+        router.add_party(
+            AugmentedKeyRefresh::new(
+                None,
+                None,
+                &identity_providers[2],
+                IndexedParties::from_ordered(&verifying_keys),
+                None,
+                Some(3),
+                &current_to_new_idx_map,
+                threshold,
+                n_parties + 1,
+                Some(threshold + 1),
+            )
+            .unwrap(),
+        );
+
+        // Verifies that the new party is caught by the existing parties.
+        assert!(matches!(
+            router.run(),
+            Err(ChaosError::Party {
+                error: Error::ThresholdMismatch { party: 3 },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn key_refresh_misaligned_party_identity_fails() {
+        use crate::test_utils::{ChaosConfig, ChaosError, ChaosRouter};
+
+        // Runs keygen simulation for 2 parties.
+        let (keys, mut identity_providers) = keygen::tests::simulate_keygen(1, 2);
+        identity_providers.push(MockECDSAIdentityProvider::generate());
+
+        // Creates a list of verifying keys for all parties (including the new party).
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        // Creates an intentionally misaligned ordering of the verifying keys
+        // (i.e the first and third parties are swapped).
+        let misaligned_verifying_keys = [
+            verifying_keys[2].clone(),
+            verifying_keys[1].clone(),
+            verifying_keys[0].clone(),
+        ];
+
+        // Adds existing parties to the router.
+        let current_to_new_idx_map = HashMap::from([(1, 1), (2, 2)]);
+        let mut router = ChaosRouter::new(ChaosConfig::reliable(1)).unwrap();
+        for (key, identity_provider) in keys.iter().zip(identity_providers.iter()) {
+            let (signing_share, sub_share) = key.extra.as_ref().unwrap();
+            router.add_party(
+                AugmentedKeyRefresh::new(
+                    Some(signing_share),
+                    Some(sub_share),
+                    identity_provider,
+                    IndexedParties::from_ordered(&verifying_keys),
+                    Some(key.base.clone()),
+                    None,
+                    &current_to_new_idx_map,
+                    1,
+                    3,
+                    None,
+                )
+                .unwrap(),
+            );
+        }
+
+        // Verifies that a new party (at index 3) whose verifying key isn't aligned with its party index is rejected up front.
+        assert!(matches!(
+            AugmentedKeyRefresh::new(
+                None,
+                None,
+                &identity_providers[0],
+                IndexedParties::from_ordered(&verifying_keys),
+                None,
+                Some(3),
+                &current_to_new_idx_map,
+                1,
+                3,
+                Some(1),
+            ),
+            Err(Error::AmbiguousPartyIdentity { bad_actors }) if bad_actors == vec![3]
+        ));
+
+        // Adds a new party (at index 3) that signs with the verifying key of the first party
+        // using an intentionally misaligned ordering of the verified parties
+        // (i.e its verifying key is a verified party but isn't aligned with its party index for the other parties).
+        router.add_party(
+            AugmentedKeyRefresh::new(
+                None,
+                None,
+                &identity_providers[0],
+                IndexedParties::from_ordered(&misaligned_verifying_keys[..]),
+                None,
+                Some(3),
+                &current_to_new_idx_map,
+                1,
+                3,
+                Some(1),
+            )
+            .unwrap(),
+        );
+
+        // Verifies that the ambiguous party identity is detected.
+        assert!(matches!(
+            router.run(),
+            Err(ChaosError::Party {
+                error: Error::AmbiguousPartyIdentity { .. },
+                ..
+            })
+        ));
+    }
 }
@@ -2,19 +2,28 @@
 //!
 //! Ref: <https://wamu.tech/specification#key-generation>.
 
+use cggmp_threshold_ecdsa::utilities::sha2::Sha256;
 use curv::arithmetic::Converter;
-use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::party_i::KeyGenBroadcastMessage1;
-use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen::{Keygen, M};
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
+use curv::elliptic::curves::Secp256k1;
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::party_i::{
+    KeyGenBroadcastMessage1, Keys, SharedKeys,
+};
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen::{
+    Keygen, LocalKey, M,
+};
 use round_based::{Msg, StateMachine};
 use std::ops::Deref;
-use std::time::Duration;
-use wamu_core::crypto::VerifyingKey;
-use wamu_core::IdentityProvider;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use wamu_core::crypto::{PartyId, VerifyingKey};
+use wamu_core::{IdentityProvider, SecretShare};
 
 use crate::augmented_state_machine;
 use crate::augmented_state_machine::Error;
 use crate::augmented_state_machine::{
-    AugmentedStateMachine, AugmentedType, IdentityAuthParams, SubShareOutput,
+    AugmentationFailures, AugmentedStateMachine, AugmentedType, IdentityAuthParams, PartyIdentity,
+    PartyList, RoundLabel, RoundProgress, SubShareOutput,
 };
 
 /// A wrapper around the [`cggmp-threshold-ecdsa` Key Generation StateMachine](https://github.com/ZenGo-X/multi-party-ecdsa/blob/master/src/protocols/multi_party_ecdsa/gg_2020/state_machine/keygen.rs) that [augments key generation as described by the Wamu protocol](https://wamu.tech/specification#key-generation).
@@ -27,7 +36,18 @@ pub struct AugmentedKeyGen<'a, I: IdentityProvider> {
     /// The decentralized identity provider of the party.
     identity_provider: &'a I,
     /// Verifying keys for other the parties.
-    parties: &'a [VerifyingKey],
+    parties: PartyList<'a>,
+    /// Parties that have delivered their message for each round.
+    round_progress: RoundProgress,
+    /// The raw key generation output with its secret share cleared/zerorized
+    /// and the secret share itself (if augmenting the output failed).
+    ///
+    /// **NOTE:** A `Mutex` is used because the raw output is retained by [`AugmentedStateMachine::augment_output`] (which takes `&self`).
+    raw_output: Mutex<Option<(LocalKey<Secp256k1>, SecretShare)>>,
+    /// Consecutive augmentation verification failures for each party.
+    augmentation_failures: AugmentationFailures,
+    /// Ceremony-wide deadline (if any).
+    deadline: Option<Instant>,
 }
 
 impl<'a, I: IdentityProvider> AugmentedKeyGen<'a, I> {
@@ -39,12 +59,42 @@ impl<'a, I: IdentityProvider> AugmentedKeyGen<'a, I> {
         threshold: u16,
         n_parties: u16,
     ) -> Result<Self, Error<<Keygen as StateMachine>::Err>> {
+        Self::init(identity_provider, parties.into(), idx, threshold, n_parties)
+    }
+
+    /// Initializes party for the augmented key generation protocol with a shared (i.e reference counted) list of verifying keys,
+    /// so that the party owns its set of parties (e.g for parties held by long-lived async tasks).
+    pub fn new_shared(
+        identity_provider: &'a I,
+        parties: Arc<[VerifyingKey]>,
+        idx: u16,
+        threshold: u16,
+        n_parties: u16,
+    ) -> Result<Self, Error<<Keygen as StateMachine>::Err>> {
+        Self::init(identity_provider, parties.into(), idx, threshold, n_parties)
+    }
+
+    /// Initializes party for the augmented key generation protocol with either borrowed or shared verifying keys.
+    fn init(
+        identity_provider: &'a I,
+        parties: PartyList<'a>,
+        idx: u16,
+        threshold: u16,
+        n_parties: u16,
+    ) -> Result<Self, Error<<Keygen as StateMachine>::Err>> {
+        // Runs all up-front checks.
+        Self::validate(&parties, idx, threshold, n_parties)?;
+
         // Initializes state machine.
         let mut aug_key_gen = Self {
             state_machine: Keygen::new(idx, threshold, n_parties)?,
             message_queue: Vec::new(),
             identity_provider,
             parties,
+            round_progress: RoundProgress::default(),
+            raw_output: Mutex::new(None),
+            augmentation_failures: AugmentationFailures::default(),
+            deadline: None,
         };
 
         // Retrieves messages from immediate state transitions (if any) and augments them.
@@ -54,6 +104,109 @@ impl<'a, I: IdentityProvider> AugmentedKeyGen<'a, I> {
         Ok(aug_key_gen)
     }
 
+    /// Initializes party for the augmented key generation protocol for a 2-of-2 key
+    /// (i.e `n_parties = 2` and `threshold = 1` e.g for pairing a mobile and a desktop device).
+    ///
+    /// **NOTE:** The party index must be either `1` or `2`.
+    pub fn two_party(
+        identity_provider: &'a I,
+        parties: &'a [VerifyingKey],
+        idx: u16,
+    ) -> Result<Self, Error<<Keygen as StateMachine>::Err>> {
+        Self::new(identity_provider, parties, idx, 1, 2)
+    }
+
+    /// Validates the configuration for the augmented key generation protocol without initializing the wrapped state machine
+    /// (e.g to pre-flight a ceremony), returns an ok result for a valid configuration or an appropriate error result otherwise.
+    ///
+    /// **NOTE:** Runs all the up-front checks performed by [`new`](Self::new).
+    pub fn validate(
+        parties: &[VerifyingKey],
+        idx: u16,
+        threshold: u16,
+        n_parties: u16,
+    ) -> Result<(), Error<<Keygen as StateMachine>::Err>> {
+        // Parties must be provided (i.e an empty list means no other party is authorized).
+        if parties.is_empty() {
+            return Err(Error::Core(wamu_core::Error::NoVerifiedParties));
+        }
+
+        // Threshold must be in the range `1 <= threshold < n_parties`.
+        wamu_core::validate_threshold(threshold, n_parties)?;
+
+        // Party index must be in the range `1..=n_parties`.
+        if idx == 0 || idx > n_parties {
+            return Err(Error::BadPartyIndex);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the indices of the parties that have delivered their message for the current round (in ascending order).
+    pub fn received_parties(&self) -> Vec<u16> {
+        self.round_progress
+            .received_parties(self.state_machine.current_round())
+    }
+
+    /// Returns the indices of the other parties whose message for the current round is still outstanding
+    /// (i.e an empty list if no round is in progress e.g before the initial computations or after the protocol is finished).
+    pub fn pending_parties(&self) -> Vec<u16> {
+        let round = self.state_machine.current_round();
+        if round == 0
+            || self
+                .state_machine
+                .total_rounds()
+                .is_some_and(|total_rounds| round > total_rounds)
+        {
+            return Vec::new();
+        }
+        let party_idx = self.state_machine.party_ind();
+        self.round_progress.pending_parties(
+            round,
+            (1..=self.state_machine.parties()).filter(|idx| *idx != party_idx),
+        )
+    }
+
+    /// Returns the raw key generation output (i.e a `LocalKey<Secp256k1>` with the secret share intact)
+    /// if the wrapped key generation protocol finished successfully but augmenting its output failed
+    /// (e.g because the identity provider was temporarily unavailable), or `None` otherwise.
+    ///
+    /// **NOTE:** The augmentation can be retried with [`finalize_augmentation`] without re-running the key generation protocol.
+    ///
+    /// **NOTE:** The raw output can only be taken once.
+    pub fn take_raw_output(&mut self) -> Option<LocalKey<Secp256k1>> {
+        let (mut local_key, secret_share) = self
+            .raw_output
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()?;
+        // Restores the secret share (which was retrieved from a valid scalar, so the conversion can't fail).
+        local_key.keys_linear.x_i =
+            augmented_state_machine::secret_share_scalar(&secret_share).ok()?;
+        Some(local_key)
+    }
+
+    /// Zerorizes transient secret material cached by the party (i.e the secret share of the raw key generation output if any).
+    ///
+    /// **NOTE:** This is also called when the party is dropped, so it's only needed to reduce the window
+    /// in which secrets live in memory for a long-lived party (e.g after giving up on retrying an interrupted augmentation).
+    pub fn zeroize_transients(&mut self) {
+        // NOTE: The secret share of the retained key output is already cleared/zerorized,
+        // and `wamu_core::SecretShare` implements `ZerorizeOnDrop` so we don't need to zerorize it explicitly.
+        self.raw_output
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+    }
+
+    /// Returns the commitment to the set of parties of the party.
+    fn party_set_hash(&self) -> [u8; 32] {
+        augmented_state_machine::party_set_hash(
+            &self.identity_provider.verifying_key(),
+            &self.parties,
+        )
+    }
+
     // For `cggmp-threshold-ecdsa`, key generation uses the GG20 implementation from ZenGo's `multi-party-ecdsa`.
     // So we hash parameters from Round 1 to achieve a similar commitment to V_i in CGGMP20.
     // Ref: <https://github.com/ZenGo-X/multi-party-ecdsa/>.
@@ -77,7 +230,12 @@ impl<'a, I: IdentityProvider> AugmentedStateMachine for AugmentedKeyGen<'a, I> {
     type AdditionalOutput = SubShareOutput;
 
     // Implements all required `AugmentedStateMachine` methods.
-    impl_required_augmented_state_machine_methods!(state_machine, message_queue);
+    impl_required_augmented_state_machine_methods!(
+        state_machine,
+        message_queue,
+        augmentation_failures,
+        deadline
+    );
 
     fn pre_handle_incoming(
         &mut self,
@@ -88,24 +246,43 @@ impl<'a, I: IdentityProvider> AugmentedStateMachine for AugmentedKeyGen<'a, I> {
             >,
         >,
     ) -> Result<(), Error<<Self::StateMachineType as StateMachine>::Err>> {
-        match &msg.body.base.0 {
+        let (round, result) = match &msg.body.base.0 {
             // Verifies the expected additional parameters from Round 1.
-            M::Round1(out_msg) => match msg.body.extra.as_ref() {
-                // Verifies that signer is an expected party/signatory and the signature is valid.
-                Some(params) => Ok(wamu_core::wrappers::verify_request_with_signature(
-                    &Self::parameter_hash(msg.sender, out_msg),
-                    &params.verifying_key,
-                    &params.verifying_signature,
-                    self.parties,
-                )?),
-                // Returns an error if expected additional parameters are missing.
-                None => Err(Error::MissingParams {
-                    bad_actors: vec![msg.sender as usize],
-                }),
-            },
+            M::Round1(out_msg) => (
+                1,
+                match msg.body.extra.as_ref() {
+                    // Verifies that signer is an expected party/signatory and the signature is valid.
+                    Some(params) => {
+                        wamu_core::wrappers::verify_request_with_signature(
+                            &IdentityAuthParams::signed_bytes(
+                                &Self::parameter_hash(msg.sender, out_msg),
+                                &params.party_set_hash,
+                            ),
+                            &params.verifying_key,
+                            &params.verifying_signature,
+                            &*self.parties,
+                        )?;
+                        // Verifies that the (authenticated) sender committed to the same set of parties.
+                        params.verify_party_set(msg.sender, &self.party_set_hash())
+                    }
+                    // Returns an error if expected additional parameters are missing.
+                    None => Err(Error::MissingParams {
+                        bad_actors: vec![msg.sender as usize],
+                    }),
+                },
+            ),
             // No modifications for other rounds.
-            _ => Ok(()),
+            M::Round2(_) => (2, Ok(())),
+            M::Round3(_) => (3, Ok(())),
+            M::Round4(_) => (4, Ok(())),
+        };
+
+        // Records the sender as having delivered their message for the round (if the message is valid).
+        if result.is_ok() {
+            self.round_progress.record(round, msg.sender);
         }
+
+        result
     }
 
     fn augment_outgoing_message(
@@ -117,14 +294,20 @@ impl<'a, I: IdentityProvider> AugmentedStateMachine for AugmentedKeyGen<'a, I> {
         match &msg_body.0 {
             // Adds additional parameters to Round 1 messages.
             M::Round1(out_msg) => {
+                let party_set_hash = self.party_set_hash();
                 let (verifying_key, verifying_signature) =
                     wamu_core::wrappers::initiate_request_with_signature(
-                        &Self::parameter_hash(sender, out_msg),
+                        &IdentityAuthParams::signed_bytes(
+                            &Self::parameter_hash(sender, out_msg),
+                            &party_set_hash,
+                        ),
                         self.identity_provider,
                     );
                 Ok(Some(IdentityAuthParams {
                     verifying_key,
                     verifying_signature,
+                    party_set_hash,
+                    current_threshold: None,
                 }))
             }
             // No modifications for other rounds.
@@ -134,15 +317,109 @@ impl<'a, I: IdentityProvider> AugmentedStateMachine for AugmentedKeyGen<'a, I> {
 
     fn augment_output(
         &self,
-        output: <Self::StateMachineType as StateMachine>::Output,
+        mut output: <Self::StateMachineType as StateMachine>::Output,
     ) -> Result<
         AugmentedType<<Self::StateMachineType as StateMachine>::Output, Self::AdditionalOutput>,
         Error<<Self::StateMachineType as StateMachine>::Err>,
     > {
-        Ok(augmented_state_machine::split_key_output(
-            self.identity_provider,
-            output,
-        )?)
+        // Retrieves the secret share and zerorizes it in the `LocalKey<Secp256k1>` output (on both success and error paths).
+        let secret_share = SecretShare::try_from(output.keys_linear.x_i.to_bytes().deref())?;
+        augmented_state_machine::clear_secret_share(&mut output);
+        // Splits the secret share.
+        match wamu_core::share_split_reconstruct::split(&secret_share, self.identity_provider) {
+            // Returns augmented key output.
+            Ok(sub_shares) => Ok(AugmentedType {
+                base: output,
+                extra: Some(sub_shares),
+            }),
+            // Otherwise retains the raw output, so that the augmentation can be retried without re-running key generation.
+            Err(error) => {
+                *self
+                    .raw_output
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner) = Some((output, secret_share));
+                Err(error.into())
+            }
+        }
+    }
+}
+
+impl<'a, I: IdentityProvider> Drop for AugmentedKeyGen<'a, I> {
+    fn drop(&mut self) {
+        // Zerorizes transient secret material on both success and error paths.
+        self.zeroize_transients();
+    }
+}
+
+/// Given a raw key generation output (i.e from [`AugmentedKeyGen::take_raw_output`]) and the party's identity provider,
+/// returns the augmented output (i.e a `LocalKey<Secp256k1>` with the secret share cleared/zerorized, "signing share" and "sub-share")
+/// without re-running the key generation protocol (e.g to resume after an interrupted augmentation).
+///
+/// **NOTE:** The identity provider must be the same one that was used for the key generation protocol.
+pub fn finalize_augmentation(
+    local_key: LocalKey<Secp256k1>,
+    identity_provider: &impl IdentityProvider,
+) -> Result<AugmentedType<LocalKey<Secp256k1>, SubShareOutput>, wamu_core::Error> {
+    // Splits the secret share into a "signing share" and "sub-share" and clears/zerorizes it.
+    augmented_state_machine::split_key_output(identity_provider, local_key)
+}
+
+/// Given an identity provider, returns a 1-of-1 key (i.e a single party with threshold zero) generated locally
+/// without any network rounds (e.g for onboarding flows that start with a single signer).
+///
+/// **NOTE:** The output has the same shape as the output of [`AugmentedKeyGen`]
+/// (i.e a `LocalKey<Secp256k1>` with the secret share cleared/zerorized, "signing share" and "sub-share"),
+/// so the key can later be extended to more parties with [`ShareAddition`](crate::ShareAddition).
+pub fn keygen_single(
+    identity_provider: &impl IdentityProvider,
+) -> Result<AugmentedType<LocalKey<Secp256k1>, SubShareOutput>, wamu_core::Error> {
+    // Generates the party's secret, Paillier keys and h1, h2, N-tilde parameters as in GG20 key generation.
+    let party_keys = Keys::create(1);
+    let (bc1, _) = party_keys.phase1_broadcast_phase3_proof_of_correct_key_proof_of_correct_h1h2();
+
+    // Shares the secret with a polynomial of degree zero (i.e the only share is the secret itself).
+    let (vss_scheme, _) = VerifiableSS::<Secp256k1, Sha256>::share(0, 1, &party_keys.u_i);
+
+    // Composes the `LocalKey<Secp256k1>` (i.e the output of GG20 key generation for a single party).
+    let local_key = LocalKey {
+        paillier_dk: party_keys.dk.clone(),
+        pk_vec: vec![party_keys.y_i.clone()],
+        keys_linear: SharedKeys {
+            y: party_keys.y_i.clone(),
+            x_i: party_keys.u_i.clone(),
+        },
+        paillier_key_vec: vec![party_keys.ek.clone()],
+        y_sum_s: party_keys.y_i.clone(),
+        h1_h2_n_tilde_vec: vec![bc1.dlog_statement],
+        vss_scheme,
+        i: 1,
+        t: 0,
+        n: 1,
+    };
+
+    // Splits the secret share into a "signing share" and "sub-share" and clears/zerorizes it.
+    augmented_state_machine::split_key_output(identity_provider, local_key)
+}
+
+impl<'a, I: IdentityProvider> PartyIdentity for AugmentedKeyGen<'a, I> {
+    fn party_id(&self) -> PartyId {
+        PartyId::from_verifying_key(&self.identity_provider.verifying_key())
+    }
+}
+
+impl<'a, I: IdentityProvider> RoundLabel for AugmentedKeyGen<'a, I> {
+    fn round_label(&self) -> String {
+        // Names the rounds of GG20 key generation.
+        // Ref: <https://eprint.iacr.org/2020/540.pdf>.
+        match self.state_machine.current_round() {
+            0 => "Initialization",
+            1 => "Commitment",
+            2 => "Decommitment",
+            3 => "Secret Shares",
+            4 => "Proof of Knowledge",
+            _ => "Finished",
+        }
+        .to_string()
     }
 }
 
@@ -165,11 +442,41 @@ impl<'a, I: IdentityProvider> std::fmt::Debug for AugmentedKeyGen<'a, I> {
 #[cfg(any(test, feature = "dev"))]
 pub mod tests {
     use super::*;
-    use curv::elliptic::curves::{Scalar, Secp256k1};
-    use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen::LocalKey;
+    use crate::augmented_state_machine::reconstruct_secret_scalar;
+    use curv::elliptic::curves::{Point, Scalar};
     use round_based::dev::Simulation;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
     use wamu_core::test_utils::MockECDSAIdentityProvider;
 
+    /// An identity provider that returns invalid "sub-share" signatures while it's unavailable.
+    #[derive(Debug, Clone)]
+    pub struct FlakyIdentityProvider {
+        /// The wrapped identity provider.
+        pub inner: MockECDSAIdentityProvider,
+        /// Whether or not the identity provider is unavailable.
+        pub unavailable: Arc<AtomicBool>,
+    }
+
+    impl IdentityProvider for FlakyIdentityProvider {
+        fn verifying_key(&self) -> VerifyingKey {
+            self.inner.verifying_key()
+        }
+
+        fn sign(&self, msg: &[u8]) -> wamu_core::crypto::Signature {
+            self.inner.sign(msg)
+        }
+
+        fn sign_message_share(&self, msg: &[u8]) -> ([u8; 32], [u8; 32]) {
+            if self.unavailable.load(Ordering::SeqCst) {
+                // Coordinates greater than the order of the curve are rejected when splitting the secret share.
+                ([0xff; 32], [0xff; 32])
+            } else {
+                self.inner.sign_message_share(msg)
+            }
+        }
+    }
+
     pub fn simulate_keygen(
         threshold: u16,
         n_parties: u16,
@@ -197,7 +504,7 @@ pub mod tests {
                 AugmentedKeyGen::new(
                     identity_provider,
                     &verifying_keys,
-                    (idx + 1) as u16,
+                    wamu_core::party_index(idx).unwrap(),
                     threshold,
                     n_parties,
                 )
@@ -238,4 +545,629 @@ pub mod tests {
             }
         }
     }
+
+    #[test]
+    fn keygen_round_progress_works() {
+        // Sets threshold and number of parties.
+        let (threshold, n_parties) = (2, 4);
+
+        // Creates identity providers and a list of verifying keys for all parties.
+        let identity_providers: Vec<MockECDSAIdentityProvider> = (1..=n_parties)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Initializes all parties.
+        let mut parties: Vec<AugmentedKeyGen<MockECDSAIdentityProvider>> = identity_providers
+            .iter()
+            .enumerate()
+            .map(|(idx, identity_provider)| {
+                AugmentedKeyGen::new(
+                    identity_provider,
+                    &verifying_keys,
+                    wamu_core::party_index(idx).unwrap(),
+                    threshold,
+                    n_parties,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        // Runs the (expensive) initial computations to generate round 1 messages for all parties.
+        for party in parties.iter_mut() {
+            party.proceed().unwrap();
+        }
+
+        // Verifies that all other parties are pending before any round 1 message is delivered.
+        assert_eq!(parties[0].current_round(), 1);
+        assert!(parties[0].received_parties().is_empty());
+        assert_eq!(parties[0].pending_parties(), vec![2, 3, 4]);
+
+        // Delivers k of the n - 1 round 1 messages to the first party.
+        let k = 2;
+        for sender_idx in 1..=k {
+            let messages = parties[sender_idx].message_queue().split_off(0);
+            for msg in messages {
+                parties[0].handle_incoming(msg).unwrap();
+            }
+        }
+
+        // Verifies that only the remaining parties are pending.
+        assert_eq!(parties[0].current_round(), 1);
+        assert_eq!(parties[0].received_parties(), vec![2, 3]);
+        assert_eq!(parties[0].pending_parties(), vec![4]);
+    }
+
+    #[test]
+    fn party_set_mismatch_is_detected_early() {
+        use round_based::IsCritical;
+
+        // Creates identity providers and a list of verifying keys for 3 parties.
+        let identity_providers: Vec<MockECDSAIdentityProvider> = (1..=3)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let outsider_key = MockECDSAIdentityProvider::generate().verifying_key();
+
+        // Sets the verified parties for each party.
+        let party_sets = [
+            // The reference set of parties.
+            verifying_keys.clone(),
+            // The same set of parties in a different order and without the party itself.
+            vec![verifying_keys[2].clone(), verifying_keys[0].clone()],
+            // A divergent set of parties (i.e the second party is replaced by an outsider).
+            vec![
+                verifying_keys[0].clone(),
+                outsider_key,
+                verifying_keys[2].clone(),
+            ],
+        ];
+
+        // Initializes all parties and runs the initial computations to generate round 1 messages.
+        let mut parties: Vec<AugmentedKeyGen<MockECDSAIdentityProvider>> = identity_providers
+            .iter()
+            .zip(party_sets.iter())
+            .enumerate()
+            .map(|(idx, (identity_provider, party_set))| {
+                AugmentedKeyGen::new(
+                    identity_provider,
+                    party_set,
+                    wamu_core::party_index(idx).unwrap(),
+                    1,
+                    3,
+                )
+                .unwrap()
+            })
+            .collect();
+        for party in parties.iter_mut() {
+            party.proceed().unwrap();
+        }
+        let round1_messages: Vec<_> = parties
+            .iter_mut()
+            .map(|party| party.message_queue().pop().unwrap())
+            .collect();
+
+        for (receiver_idx, sender_idx, expected_mismatch) in [
+            // Parties that agree on the set of parties accept each other's round 1 messages.
+            (0, 1, None),
+            (1, 0, None),
+            // Parties that disagree on the set of parties reject each other's round 1 messages.
+            (0, 2, Some(3)),
+            (2, 0, Some(1)),
+        ] {
+            let result = parties[receiver_idx].handle_incoming(round1_messages[sender_idx].clone());
+            match expected_mismatch {
+                None => assert!(result.is_ok()),
+                Some(party) => {
+                    assert!(matches!(
+                        result,
+                        Err(Error::PartySetMismatch { party: bad_party }) if bad_party == party
+                    ));
+                    assert!(result.unwrap_err().is_critical());
+                }
+            }
+        }
+
+        // Parties that aren't verified by the receiver are rejected before their set of parties is checked.
+        let result = parties[2].handle_incoming(round1_messages[1].clone());
+        assert!(matches!(
+            &result,
+            Err(Error::RejectedAugmentation { party: 2, reason })
+                if matches!(**reason, Error::Core(wamu_core::Error::UnauthorizedParty))
+        ));
+        assert!(!result.unwrap_err().is_critical());
+
+        // A party set hash that doesn't match the signature is rejected before it's compared
+        // (i.e a tampered party set hash can't be used to blame the sender).
+        let mut tampered_msg = round1_messages[2].clone();
+        tampered_msg.body.extra.as_mut().unwrap().party_set_hash = round1_messages[0]
+            .body
+            .extra
+            .as_ref()
+            .unwrap()
+            .party_set_hash;
+        let result = parties[1].handle_incoming(tampered_msg);
+        assert!(matches!(
+            &result,
+            Err(Error::RejectedAugmentation { party: 3, reason })
+                if matches!(
+                    **reason,
+                    Error::Core(wamu_core::Error::Crypto(
+                        wamu_core::CryptoError::SignatureVerificationFailed
+                    ))
+                )
+        ));
+        assert!(!result.unwrap_err().is_critical());
+    }
+
+    #[test]
+    fn repeated_augmentation_failures_ban_party() {
+        use round_based::IsCritical;
+
+        // Creates identity providers and a list of verifying keys for 2 parties.
+        let identity_providers: Vec<MockECDSAIdentityProvider> = (1..=2)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Initializes both parties and runs the initial computations to generate round 1 messages.
+        let mut parties: Vec<AugmentedKeyGen<MockECDSAIdentityProvider>> = identity_providers
+            .iter()
+            .enumerate()
+            .map(|(idx, identity_provider)| {
+                AugmentedKeyGen::new(
+                    identity_provider,
+                    &verifying_keys,
+                    wamu_core::party_index(idx).unwrap(),
+                    1,
+                    2,
+                )
+                .unwrap()
+            })
+            .collect();
+        for party in parties.iter_mut() {
+            party.proceed().unwrap();
+        }
+
+        // Sets the maximum number of consecutive augmentation verification failures for the first party.
+        let max_failures = 3;
+        parties[0].set_max_augmentation_failures(max_failures);
+
+        // Forges an invalid augmentation signature for the round 1 message of the second party.
+        let msg = parties[1].message_queue().pop().unwrap();
+        let mut bad_msg = msg.clone();
+        bad_msg.body.extra.as_mut().unwrap().verifying_signature =
+            identity_providers[1].sign(b"Hello, world!");
+
+        // Verifies that the second party is only banned after the maximum number of consecutive failures
+        // (i.e earlier failures only reject the message without aborting the protocol).
+        for _ in 1..max_failures {
+            let result = parties[0].handle_incoming(bad_msg.clone());
+            assert!(matches!(
+                &result,
+                Err(Error::RejectedAugmentation { party: 2, reason })
+                    if matches!(**reason, Error::Core(_))
+            ));
+            assert!(!result.unwrap_err().is_critical());
+        }
+        let result = parties[0].handle_incoming(bad_msg);
+        assert!(matches!(result, Err(Error::PartyBanned { party: 2 })));
+        assert!(!result.unwrap_err().is_critical());
+        assert_eq!(parties[0].augmentation_failures().banned_parties(), vec![2]);
+
+        // Verifies that further messages (even valid ones) from the banned party are ignored.
+        let result = parties[0].handle_incoming(msg);
+        assert!(matches!(result, Err(Error::PartyBanned { party: 2 })));
+        assert!(!result.unwrap_err().is_critical());
+        assert!(parties[0].received_parties().is_empty());
+    }
+
+    #[test]
+    fn keygen_with_shared_parties_works() {
+        // Sets threshold and number of parties.
+        let (threshold, n_parties) = (1, 2);
+
+        // Creates identity providers for all parties.
+        let identity_providers: Vec<MockECDSAIdentityProvider> = (1..=n_parties)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+
+        // Initializes all parties with a shared list of verifying keys
+        // (i.e the parties outlive the scope of the original list of verifying keys).
+        let parties: Vec<AugmentedKeyGen<MockECDSAIdentityProvider>> = {
+            let verifying_keys: Vec<VerifyingKey> = identity_providers
+                .iter()
+                .map(IdentityProvider::verifying_key)
+                .collect();
+            let shared_verifying_keys: Arc<[VerifyingKey]> = Arc::from(verifying_keys.as_slice());
+            identity_providers
+                .iter()
+                .enumerate()
+                .map(|(idx, identity_provider)| {
+                    AugmentedKeyGen::new_shared(
+                        identity_provider,
+                        Arc::clone(&shared_verifying_keys),
+                        wamu_core::party_index(idx).unwrap(),
+                        threshold,
+                        n_parties,
+                    )
+                    .unwrap()
+                })
+                .collect()
+        };
+
+        // Runs the simulation on a spawned thread.
+        let keys = std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    let mut simulation = Simulation::new();
+                    for party in parties {
+                        simulation.add_party(party);
+                    }
+                    simulation.run().unwrap()
+                })
+                .join()
+                .unwrap()
+        });
+
+        // Verifies the generated keys.
+        assert_eq!(keys.len(), n_parties as usize);
+        for key in &keys {
+            assert_eq!(key.base.public_key(), keys[0].base.public_key());
+        }
+    }
+
+    #[test]
+    fn keygen_validation_works() {
+        // Creates a list of verifying keys for all parties.
+        let verifying_keys: Vec<VerifyingKey> = (1..=2)
+            .map(|_| MockECDSAIdentityProvider::generate().verifying_key())
+            .collect();
+
+        for (parties, idx, threshold, n_parties, expected_result) in [
+            // Valid configuration should be ok.
+            (&verifying_keys[..], 1, 1, 2, Ok(())),
+            // No verified parties should fail.
+            (
+                &verifying_keys[..0],
+                1,
+                1,
+                2,
+                Err(Error::Core(wamu_core::Error::NoVerifiedParties)),
+            ),
+            // Zero threshold should fail.
+            (
+                &verifying_keys[..],
+                1,
+                0,
+                2,
+                Err(Error::Core(wamu_core::Error::InvalidThreshold)),
+            ),
+            // Threshold that's not less than the number of parties should fail.
+            (
+                &verifying_keys[..],
+                1,
+                2,
+                2,
+                Err(Error::Core(wamu_core::Error::InvalidThreshold)),
+            ),
+            // Zero party index should fail.
+            (&verifying_keys[..], 0, 1, 2, Err(Error::BadPartyIndex)),
+            // Party index greater than the number of parties should fail.
+            (&verifying_keys[..], 3, 1, 2, Err(Error::BadPartyIndex)),
+        ] {
+            // Validates configuration.
+            let result = AugmentedKeyGen::<MockECDSAIdentityProvider>::validate(
+                parties, idx, threshold, n_parties,
+            );
+
+            // Verifies expected result.
+            assert_eq!(
+                result.as_ref().map_err(std::mem::discriminant),
+                expected_result.as_ref().map_err(std::mem::discriminant)
+            );
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn keygen_emits_round_advance_events() {
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing_subscriber::layer::{Context, SubscriberExt};
+        use tracing_subscriber::Layer;
+
+        // Captures (`party`, `from`, `to`) for "round advanced" events.
+        #[derive(Clone, Default)]
+        struct RoundAdvanceCapture(Arc<Mutex<Vec<(u64, u64, u64)>>>);
+
+        #[derive(Default)]
+        struct RoundAdvanceVisitor {
+            message: String,
+            party: u64,
+            from: u64,
+            to: u64,
+        }
+
+        impl Visit for RoundAdvanceVisitor {
+            fn record_u64(&mut self, field: &Field, value: u64) {
+                match field.name() {
+                    "party" => self.party = value,
+                    "from" => self.from = value,
+                    "to" => self.to = value,
+                    _ => (),
+                }
+            }
+
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                if field.name() == "message" {
+                    self.message = format!("{value:?}");
+                }
+            }
+        }
+
+        impl<S: tracing::Subscriber> Layer<S> for RoundAdvanceCapture {
+            fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+                let mut visitor = RoundAdvanceVisitor::default();
+                event.record(&mut visitor);
+                if visitor.message == "round advanced" {
+                    self.0
+                        .lock()
+                        .unwrap()
+                        .push((visitor.party, visitor.from, visitor.to));
+                }
+            }
+        }
+
+        // Runs keygen simulation with a subscriber that captures round advance events.
+        let (threshold, n_parties) = (1, 3);
+        let capture = RoundAdvanceCapture::default();
+        let subscriber = tracing_subscriber::registry().with(capture.clone());
+        tracing::subscriber::with_default(subscriber, || simulate_keygen(threshold, n_parties));
+
+        // Verifies that each party emits a sequence of consecutive round advance events starting from the first round.
+        let events = capture.0.lock().unwrap().clone();
+        for party in 1..=n_parties as u64 {
+            let rounds: Vec<(u64, u64)> = events
+                .iter()
+                .filter(|(idx, ..)| *idx == party)
+                .map(|(_, from, to)| (*from, *to))
+                .collect();
+            assert!(!rounds.is_empty());
+            assert_eq!(rounds[0].0, 0);
+            for (idx, (from, to)) in rounds.iter().enumerate() {
+                assert_eq!(*to, from + 1);
+                if idx > 0 {
+                    assert_eq!(*from, rounds[idx - 1].1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn keygen_single_works() {
+        // Generates a 1-of-1 key.
+        let identity_provider = MockECDSAIdentityProvider::generate();
+        let key = keygen_single(&identity_provider).unwrap();
+
+        // Verifies threshold, number of parties and that the secret share was cleared/zerorized.
+        assert_eq!(key.base.t, 0);
+        assert_eq!(key.base.n, 1);
+        assert_eq!(key.base.keys_linear.x_i, Scalar::<Secp256k1>::zero());
+        let pub_key = key.base.public_key();
+
+        // Extends the key to 2 parties via share addition.
+        let identity_providers = [identity_provider, MockECDSAIdentityProvider::generate()];
+        let (signing_share, sub_share) = key.extra.as_ref().unwrap();
+        let new_keys = crate::share_addition::tests::simulate_share_addition(
+            vec![
+                (
+                    Some(signing_share),
+                    Some(sub_share),
+                    &identity_providers[0],
+                    Some(key.base.clone()),
+                    None,
+                    None,
+                    None,
+                    true,
+                ),
+                (
+                    None,
+                    None,
+                    &identity_providers[1],
+                    None,
+                    Some(2),
+                    Some(0),
+                    Some(1),
+                    false,
+                ),
+            ],
+            &std::collections::HashMap::from([(1, 1)]),
+            2,
+        );
+        for new_key in &new_keys {
+            assert_eq!(new_key.base.n, 2);
+            assert_eq!(new_key.base.public_key(), pub_key);
+        }
+
+        // Signs with the extended key.
+        let pre_signing_output_idx = 1; // l in the CGGMP20 paper.
+        let pre_sign_inputs =
+            crate::sign::tests::generate_pre_sign_input(&new_keys, &identity_providers, 2);
+        let ssids: Vec<_> = pre_sign_inputs
+            .iter()
+            .map(|(_, _, _, ssid, ..)| ssid.clone())
+            .collect();
+        let pre_sign_results =
+            crate::sign::tests::simulate_pre_sign(pre_sign_inputs, pre_signing_output_idx);
+        let results = crate::sign::tests::simulate_sign(
+            pre_sign_results
+                .into_iter()
+                .map(|result| {
+                    let (output, transcript) = result.base.unwrap();
+                    let idx = output.i as usize - 1;
+                    let (signing_share, sub_share) = new_keys[idx].extra.as_ref().unwrap();
+                    (
+                        signing_share,
+                        sub_share,
+                        &identity_providers[idx],
+                        ssids[idx].clone(),
+                        std::collections::HashMap::from([(
+                            pre_signing_output_idx as u16,
+                            (output, transcript),
+                        )]),
+                    )
+                })
+                .collect(),
+            b"Hello, world!",
+            pre_signing_output_idx,
+        );
+        assert!(results.iter().all(|result| result.base.is_some()));
+    }
+
+    #[test]
+    fn finalize_augmentation_works() {
+        use crate::test_utils::{ChaosConfig, ChaosError, ChaosRouter};
+
+        // Creates identity providers (the first of which is unavailable) and verifying keys for 2 parties.
+        let unavailable = Arc::new(AtomicBool::new(true));
+        let identity_providers = [
+            FlakyIdentityProvider {
+                inner: MockECDSAIdentityProvider::generate(),
+                unavailable: unavailable.clone(),
+            },
+            FlakyIdentityProvider {
+                inner: MockECDSAIdentityProvider::generate(),
+                unavailable: Arc::new(AtomicBool::new(false)),
+            },
+        ];
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Runs key generation and verifies that the augmentation fails for the first party.
+        let mut router = ChaosRouter::new(ChaosConfig::reliable(1)).unwrap();
+        for (idx, identity_provider) in identity_providers.iter().enumerate() {
+            router.add_party(
+                AugmentedKeyGen::new(
+                    identity_provider,
+                    &verifying_keys,
+                    wamu_core::party_index(idx).unwrap(),
+                    1,
+                    2,
+                )
+                .unwrap(),
+            );
+        }
+        assert!(matches!(
+            router.run(),
+            Err(ChaosError::Party {
+                idx: 1,
+                error: Error::Core(_)
+            })
+        ));
+        let mut parties = router.into_parties();
+
+        // Retries the augmentation for the first party once its identity provider is available again.
+        let raw_output = parties[0].take_raw_output().unwrap();
+        assert!(parties[0].take_raw_output().is_none());
+        unavailable.store(false, Ordering::SeqCst);
+        let key_a = finalize_augmentation(raw_output, &identity_providers[0]).unwrap();
+
+        // Retrieves the output for the second party (which doesn't retain its raw output).
+        let key_b = parties[1].pick_output().unwrap().unwrap();
+        assert!(parties[1].take_raw_output().is_none());
+
+        // Verifies that both parties share the same public key and the secret shares were cleared/zerorized.
+        assert_eq!(key_a.base.public_key(), key_b.base.public_key());
+        for key in [&key_a, &key_b] {
+            assert_eq!(key.base.keys_linear.x_i, Scalar::<Secp256k1>::zero());
+            assert!(key.extra.is_some());
+        }
+    }
+
+    #[test]
+    fn reconstruct_secret_scalar_works() {
+        // Runs key gen simulation.
+        let (keys, identity_providers) = simulate_keygen(1, 3);
+
+        for (key, identity_provider) in keys.iter().zip(&identity_providers) {
+            // Reconstructs the secret share.
+            let (signing_share, sub_share) = key.extra.as_ref().unwrap();
+            let secret_share =
+                reconstruct_secret_scalar(signing_share, sub_share, identity_provider).unwrap();
+
+            // Verifies that the reconstructed secret share matches the public key share of the party from key generation.
+            assert_ne!(secret_share, Scalar::<Secp256k1>::zero());
+            assert_eq!(
+                Point::<Secp256k1>::generator() * &secret_share,
+                key.base.pk_vec[key.base.i as usize - 1]
+            );
+        }
+    }
+
+    #[test]
+    fn zeroize_transients_works() {
+        use crate::test_utils::{ChaosConfig, ChaosError, ChaosRouter};
+
+        // Creates identity providers (the first of which is unavailable) and verifying keys for 2 parties.
+        let identity_providers = [
+            FlakyIdentityProvider {
+                inner: MockECDSAIdentityProvider::generate(),
+                unavailable: Arc::new(AtomicBool::new(true)),
+            },
+            FlakyIdentityProvider {
+                inner: MockECDSAIdentityProvider::generate(),
+                unavailable: Arc::new(AtomicBool::new(false)),
+            },
+        ];
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Runs key generation until the augmentation fails (i.e an early error) for the first party.
+        let mut router = ChaosRouter::new(ChaosConfig::reliable(1)).unwrap();
+        for (idx, identity_provider) in identity_providers.iter().enumerate() {
+            router.add_party(
+                AugmentedKeyGen::new(
+                    identity_provider,
+                    &verifying_keys,
+                    wamu_core::party_index(idx).unwrap(),
+                    1,
+                    2,
+                )
+                .unwrap(),
+            );
+        }
+        assert!(matches!(
+            router.run(),
+            Err(ChaosError::Party { idx: 1, .. })
+        ));
+        let mut parties = router.into_parties();
+
+        // Verifies that the raw output is retained after the early error with its secret share cleared/zerorized.
+        let (raw_output, _) = parties[0].raw_output.get_mut().unwrap().as_ref().unwrap();
+        assert_eq!(raw_output.keys_linear.x_i, Scalar::<Secp256k1>::zero());
+
+        // Verifies that transient secrets are wiped.
+        parties[0].zeroize_transients();
+        assert!(parties[0].take_raw_output().is_none());
+
+        // Verifies that zerorizing transient secrets doesn't affect parties without transient secrets.
+        parties[1].zeroize_transients();
+        let key = parties[1].pick_output().unwrap().unwrap();
+        assert_eq!(key.base.keys_linear.x_i, Scalar::<Secp256k1>::zero());
+        assert!(key.extra.is_some());
+    }
 }
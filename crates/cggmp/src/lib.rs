@@ -3,13 +3,46 @@
 #![feature(doc_cfg)]
 
 pub use self::{
-    identity_auth::IdentityAuthentication, identity_rotation::IdentityRotation,
-    key_refresh::AugmentedKeyRefresh, keygen::AugmentedKeyGen, quorum_approval::QuorumApproval,
-    share_addition::ShareAddition, share_recovery_quorum::ShareRecoveryQuorum,
-    share_removal::ShareRemoval, sign::AugmentedPreSigning, sign::AugmentedSigning,
+    augmented_state_machine::{
+        reconstruct_secret_scalar, GroupKey, Network, PartyIdentity, PartyList, RoundLabel,
+    },
+    format_version::{FormatVersionError, FORMAT_VERSION},
+    identity_auth::IdentityAuthentication,
+    identity_rotation::IdentityRotation,
+    key_refresh::{
+        diff_local_keys, AugmentedKeyRefresh, IndexedParties, LocalKeyDiff, RefreshOutput,
+        RefreshSummary,
+    },
+    keygen::{finalize_augmentation, keygen_single, AugmentedKeyGen},
+    membership_change::MembershipChange,
+    multi_key_wallet::{MultiKeyWallet, MultiKeyWalletError},
+    quorum_approval::QuorumApproval,
+    share_addition::ShareAddition,
+    share_proof::{prove_share_validity, verify_share_validity, ShareProof},
+    share_recovery_quorum::ShareRecoveryQuorum,
+    share_removal::ShareRemoval,
+    sign::message_commitment,
+    sign::select_signers,
+    sign::sign_two_party,
+    sign::verify_against_local_key,
+    sign::AggregationError,
+    sign::AugmentedPreSigning,
+    sign::AugmentedSigning,
+    sign::ConsumedPresignatures,
+    sign::PartialSignature,
+    sign::Presignature,
+    sign::SignatureScheme,
+    sign::SigningOptions,
     threshold_modification::ThresholdModification,
 };
 
+#[cfg(feature = "serde")]
+#[doc(cfg(feature = "serde"))]
+pub use self::{
+    message_codec::{decode_message, encode_message, MAX_FRAME_SIZE},
+    wallet_state::{PublicWalletState, PublicWalletStateError},
+};
+
 #[cfg(feature = "dev")]
 #[doc(cfg(feature = "dev"))]
 pub use self::{
@@ -18,6 +51,9 @@ pub use self::{
     },
     key_refresh::tests::{generate_parties_and_simulate_key_refresh, simulate_key_refresh},
     keygen::tests::simulate_keygen,
+    membership_change::tests::{
+        generate_parties_and_simulate_membership_change, simulate_membership_change,
+    },
     share_addition::tests::{
         generate_parties_and_simulate_share_addition, simulate_share_addition,
     },
@@ -38,13 +74,24 @@ pub use self::{
 pub mod augmented_state_machine;
 #[macro_use]
 pub mod authorized_key_refresh;
+mod format_version;
 mod identity_auth;
 mod identity_rotation;
 mod key_refresh;
 mod keygen;
+mod membership_change;
+#[cfg(feature = "serde")]
+mod message_codec;
+mod multi_key_wallet;
 mod quorum_approval;
 mod share_addition;
+mod share_proof;
 mod share_recovery_quorum;
 mod share_removal;
 mod sign;
+#[cfg(any(test, feature = "test-utils"))]
+#[doc(cfg(feature = "test-utils"))]
+pub mod test_utils;
 mod threshold_modification;
+#[cfg(feature = "serde")]
+mod wallet_state;
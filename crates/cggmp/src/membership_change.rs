@@ -0,0 +1,608 @@
+//! Membership change (i.e interleaved share addition and share removal) implementation.
+//!
+//! Ref: <https://wamu.tech/specification#share-addition>.
+//!
+//! Ref: <https://wamu.tech/specification#share-removal>.
+
+use curv::elliptic::curves::Secp256k1;
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen::LocalKey;
+use round_based::{Msg, StateMachine};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use wamu_core::crypto::VerifyingKey;
+use wamu_core::{Command, IdentityProvider, SigningShare, SubShare};
+
+use crate::augmented_state_machine;
+use crate::authorized_key_refresh::{AuthorizedKeyRefresh, Error, Message};
+use crate::key_refresh::{AugmentedKeyRefresh, IndexedParties};
+use crate::quorum_approval;
+use crate::quorum_approval::QuorumApproval;
+
+const MEMBERSHIP_CHANGE: Command = Command::MembershipChange;
+
+/// A [StateMachine](StateMachine) that adds and removes parties in a single ceremony
+/// (i.e a single quorum approval followed by a single key refresh with an index map that reflects both the added and removed parties).
+///
+/// **NOTE:** Removed parties don't participate, while new parties are dormant during the quorum approval.
+///
+/// **NOTE:** The quorum approval runs over the current index space (i.e current indices and verifying keys in current index order),
+/// while the key refresh runs over the new index space (i.e new indices and verifying keys in new index order).
+/// New parties are assigned quorum approval indices after all current indices, so they never collide with current parties.
+pub struct MembershipChange<'a, I: IdentityProvider> {
+    // Quorum approval.
+    /// The decentralized identity provider of the party.
+    identity_provider: &'a I,
+    /// Verifying keys for other the parties (in new index order).
+    verified_parties: &'a [VerifyingKey],
+    /// Party index (i.e the quorum approval index until the key refresh is initialized, and the new index afterwards).
+    idx: u16,
+    /// New party index.
+    new_idx: u16,
+    /// Total number of parties (after the membership change).
+    n_parties: u16,
+
+    // Key refresh.
+    /// The "signing share" of the party
+    /// (only `None` for the new parties, `Some` for all other parties).
+    signing_share_option: Option<&'a SigningShare>,
+    /// The "sub-share" of the party
+    /// (only `None` for the new party, `Some` for all other parties).
+    sub_share_option: Option<&'a SubShare>,
+    /// Local key of the party (with secret share cleared/zerorized).
+    local_key_option: Option<LocalKey<Secp256k1>>,
+    /// Maps existing indices to new ones for continuing parties (i.e removed parties are omitted).
+    old_to_new_map: &'a HashMap<u16, u16>,
+    /// The threshold.
+    // NOTE: Quorum size = threshold + 1
+    threshold: u16,
+
+    // State machine management.
+    /// Outgoing message queue.
+    message_queue: Vec<Msg<Message<'a, I, quorum_approval::Message>>>,
+    /// Quorum approval state machine (must succeed before key refresh is performed).
+    auth_state_machine: QuorumApproval<'a, I>,
+    /// Key refresh state machine (activated after successful quorum approval).
+    refresh_state_machine: Option<AugmentedKeyRefresh<'a, I>>,
+    /// Stores "out of order" messages.
+    out_of_order_buffer: Vec<Msg<Message<'a, I, quorum_approval::Message>>>,
+    /// Ceremony-wide deadline (if any).
+    deadline: Option<Instant>,
+}
+
+impl<'a, I: IdentityProvider> MembershipChange<'a, I> {
+    /// Initializes party for the membership change protocol.
+    pub fn new(
+        signing_share_option: Option<&'a SigningShare>,
+        sub_share_option: Option<&'a SubShare>,
+        identity_provider: &'a I,
+        // Verifying keys for the current parties in current index order (i.e including removed parties).
+        current_verified_parties: &'a [VerifyingKey],
+        // Verifying keys for the parties after the membership change in new index order.
+        verified_parties: &'a [VerifyingKey],
+        // `LocalKey<Secp256k1>` with secret share set to zero.
+        local_key_option: Option<LocalKey<Secp256k1>>,
+        new_party_index_option: Option<u16>,
+        n_parties: u16,
+        old_to_new_map: &'a HashMap<u16, u16>,
+        // NOTE: Quorum size = threshold + 1
+        current_threshold_option: Option<u16>,
+        current_n_parties_option: Option<u16>,
+        is_initiator: bool,
+    ) -> Result<MembershipChange<'a, I>, Error<'a, I, <QuorumApproval<'a, I> as StateMachine>::Err>>
+    {
+        // Verified parties must be provided (i.e an empty list means no other party is authorized).
+        if current_verified_parties.is_empty() || verified_parties.is_empty() {
+            return Err(Error::Init(wamu_core::Error::NoVerifiedParties.into()));
+        }
+
+        // Initializes quorum approval state machine.
+        let threshold = local_key_option
+            .as_ref()
+            .map(|it| it.t)
+            .or(current_threshold_option)
+            .ok_or(Error::InvalidInput {
+                reason: "missing current threshold",
+            })?;
+        let current_n_parties = local_key_option
+            .as_ref()
+            .map(|it| it.n)
+            .or(current_n_parties_option)
+            .ok_or(Error::InvalidInput {
+                reason: "missing current number of parties",
+            })?;
+
+        // Existing parties use their current index for the quorum approval and their mapped index for the key refresh,
+        // while new parties use an index after all current indices for the quorum approval (i.e so that they never collide with current parties)
+        // and their new party index for the key refresh.
+        let n_continuing_parties = old_to_new_map.len() as u16;
+        let (idx, new_idx) = match (local_key_option.as_ref(), new_party_index_option) {
+            (Some(local_key), _) => (
+                local_key.i,
+                *old_to_new_map
+                    .get(&local_key.i)
+                    .ok_or(Error::InvalidInput {
+                        reason: "missing new party index",
+                    })?,
+            ),
+            (None, Some(new_idx)) if new_idx > n_continuing_parties => (
+                current_n_parties + (new_idx - n_continuing_parties),
+                new_idx,
+            ),
+            (None, Some(_)) => {
+                return Err(Error::InvalidInput {
+                    reason: "new party index collides with a continuing party",
+                })
+            }
+            (None, None) => {
+                return Err(Error::InvalidInput {
+                    reason: "missing party index",
+                })
+            }
+        };
+
+        // A threshold of zero means that any single party can sign, so it's never allowed.
+        if threshold == 0 {
+            return Err(Error::InvalidThreshold);
+        }
+
+        // FS-DKR operates in the honest majority setting, so threshold <= n_parties/2 must hold for the new set of parties.
+        // NOTE: This is checked up-front so that an invalid membership change is rejected before the quorum approval.
+        if threshold > n_parties / 2 {
+            return Err(Error::Refresh(
+                augmented_state_machine::Error::BadFSDKRThreshold,
+            ));
+        }
+
+        let auth_state_machine = QuorumApproval::new(
+            MEMBERSHIP_CHANGE,
+            identity_provider,
+            current_verified_parties,
+            idx,
+            threshold,
+            current_n_parties,
+            is_initiator,
+            local_key_option.is_none(),
+        );
+
+        // Initializes membership change state machine.
+        let mut membership_change = Self {
+            // Quorum approval.
+            identity_provider,
+            verified_parties,
+            idx,
+            new_idx,
+            n_parties,
+            // Key refresh.
+            signing_share_option,
+            sub_share_option,
+            local_key_option,
+            old_to_new_map,
+            threshold,
+            // State machine management.
+            message_queue: Vec::new(),
+            auth_state_machine,
+            refresh_state_machine: None,
+            out_of_order_buffer: Vec::new(),
+            deadline: None,
+        };
+
+        // Retrieves messages from immediate state transitions (if any) and wraps them.
+        membership_change.update_composite_message_queue()?;
+
+        // Returns membership change machine.
+        Ok(membership_change)
+    }
+}
+
+impl<'a, I: IdentityProvider> AuthorizedKeyRefresh<'a, I> for MembershipChange<'a, I> {
+    type InitStateMachineType = QuorumApproval<'a, I>;
+
+    impl_required_authorized_key_refresh_getters!(
+        auth_state_machine,
+        refresh_state_machine,
+        message_queue,
+        out_of_order_buffer,
+        deadline
+    );
+
+    fn create_key_refresh(
+        &mut self,
+    ) -> Result<
+        AugmentedKeyRefresh<'a, I>,
+        Error<'a, I, <Self::InitStateMachineType as StateMachine>::Err>,
+    > {
+        // Switches to the new index space for the key refresh.
+        self.idx = self.new_idx;
+
+        // Initializes key refresh state machine.
+        let is_new_party = self.local_key_option.is_none();
+        Ok(AugmentedKeyRefresh::new(
+            self.signing_share_option,
+            self.sub_share_option,
+            self.identity_provider,
+            IndexedParties::from_ordered(self.verified_parties),
+            self.local_key_option.take(),
+            is_new_party.then_some(self.idx),
+            self.old_to_new_map,
+            self.threshold,
+            self.n_parties,
+            is_new_party.then_some(self.threshold),
+        )?)
+    }
+}
+
+impl_state_machine_for_authorized_key_refresh!(MembershipChange, idx, n_parties);
+
+// Implement `Debug` trait for `MembershipChange` for test simulations.
+#[cfg(any(test, feature = "dev"))]
+impl<'a, I: IdentityProvider> std::fmt::Debug for MembershipChange<'a, I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Membership Change")
+    }
+}
+
+#[cfg(any(test, feature = "dev"))]
+pub mod tests {
+    use super::*;
+    use crate::augmented_state_machine::{AugmentedType, PartyIdentity, SubShareOutput};
+    use crate::keygen::tests::simulate_keygen;
+    use curv::elliptic::curves::Scalar;
+    use round_based::dev::Simulation;
+    use wamu_core::crypto::PartyId;
+    use wamu_core::test_utils::MockECDSAIdentityProvider;
+
+    pub fn simulate_membership_change(
+        // Party key configs including the "signing share", "sub-share", identity provider and
+        // `LocalKey<Secp256k1>` from `multi-party-ecdsa` with the secret share cleared/zerorized.
+        party_key_configs: Vec<(
+            Option<&SigningShare>,
+            Option<&SubShare>,
+            &impl IdentityProvider,
+            Option<LocalKey<Secp256k1>>,
+            Option<u16>, // new party party index,
+            Option<u16>, // current threshold (needed by new parties),
+            Option<u16>, // current number of parties (needed by new parties),
+            bool,        // Whether or not this party is the initiator.
+        )>,
+        // Verifying keys for the current parties in current index order.
+        current_verifying_keys: &[VerifyingKey],
+        current_to_new_idx_map: &HashMap<u16, u16>,
+        n_parties: u16,
+    ) -> Vec<AugmentedType<LocalKey<Secp256k1>, SubShareOutput>> {
+        // Creates simulation.
+        let mut simulation = Simulation::new();
+
+        // Creates a list of verifying keys for all parties (in new index order).
+        let verifying_keys: Vec<VerifyingKey> = party_key_configs
+            .iter()
+            .map(|(_, _, identity_provider, ..)| identity_provider.verifying_key())
+            .collect();
+
+        // Adds parties to simulation.
+        for (
+            signing_share,
+            sub_share,
+            identity_provider,
+            local_key,
+            new_party_index,
+            current_threshold_option,
+            current_n_parties_option,
+            is_initiator,
+        ) in party_key_configs
+        {
+            simulation.add_party(
+                MembershipChange::new(
+                    signing_share,
+                    sub_share,
+                    identity_provider,
+                    current_verifying_keys,
+                    &verifying_keys,
+                    local_key,
+                    new_party_index,
+                    n_parties,
+                    current_to_new_idx_map,
+                    current_threshold_option,
+                    current_n_parties_option,
+                    is_initiator,
+                )
+                .unwrap(),
+            );
+        }
+
+        // Runs simulation and returns output.
+        simulation
+            .run()
+            .unwrap()
+            .into_iter()
+            .map(Into::into)
+            .collect()
+    }
+
+    pub fn generate_parties_and_simulate_membership_change(
+        threshold: u16,
+        n_parties_init: u16,
+        // Current indices of the removed parties.
+        removed_parties: &[u16],
+        n_added_parties: u16,
+        initiating_party_idx: u16,
+    ) -> (
+        (
+            Vec<AugmentedType<LocalKey<Secp256k1>, SubShareOutput>>,
+            Vec<MockECDSAIdentityProvider>,
+        ),
+        (
+            Vec<AugmentedType<LocalKey<Secp256k1>, SubShareOutput>>,
+            Vec<MockECDSAIdentityProvider>,
+        ),
+    ) {
+        // Verifies parameter invariants.
+        let n_parties_new = n_parties_init - wamu_core::party_count(removed_parties.len()).unwrap()
+            + n_added_parties;
+        assert!(threshold >= 1, "minimum threshold is one");
+        assert!(
+            n_parties_init > threshold,
+            "threshold must be less than the total number of parties"
+        );
+        assert!(
+            threshold <= n_parties_new / 2,
+            "threshold must be less than or equal to half the new number of parties"
+        );
+        assert!(
+            !removed_parties.contains(&initiating_party_idx),
+            "initiating party can't be a removed party"
+        );
+
+        // Runs key gen simulation for test parameters.
+        let (keys, identity_providers) = simulate_keygen(threshold, n_parties_init);
+        // Verifies that we got enough keys and identities for "existing" parties from keygen.
+        assert_eq!(keys.len(), identity_providers.len());
+        assert_eq!(keys.len(), n_parties_init as usize);
+
+        // Keep copy of current public key for later verification.
+        let pub_key_init = keys[0].base.public_key();
+
+        // Retrieves keys and identity providers for continuing parties (i.e excluding removed parties).
+        let (continuing_keys, mut new_identity_providers): (
+            Vec<&AugmentedType<LocalKey<Secp256k1>, SubShareOutput>>,
+            Vec<MockECDSAIdentityProvider>,
+        ) = keys
+            .iter()
+            .zip(identity_providers.iter())
+            .filter(|(key, _)| !removed_parties.contains(&key.base.i))
+            .map(|(key, identity_provider)| (key, identity_provider.clone()))
+            .unzip();
+
+        // Creates identity providers for new parties.
+        new_identity_providers
+            .extend((1..=n_added_parties).map(|_| MockECDSAIdentityProvider::generate()));
+
+        // Creates key configs and party indices for continuing and new parties (in order of new party index).
+        let mut party_key_configs = Vec::new();
+        let mut current_to_new_idx_map = HashMap::new();
+        for (i, identity_provider) in new_identity_providers.iter().enumerate() {
+            // Create party key config and index entry.
+            let idx = wamu_core::party_index(i).unwrap();
+            let key_option = continuing_keys.get(i);
+            let local_key_option = key_option.map(|key| key.base.clone());
+            let share_output_option = key_option.map(|key| key.extra.as_ref().unwrap());
+            let signing_share_option = share_output_option.map(|(signing_share, _)| signing_share);
+            let sub_share_option = share_output_option.map(|(_, sub_share)| sub_share);
+            let is_initiator = local_key_option
+                .as_ref()
+                .is_some_and(|local_key| local_key.i == initiating_party_idx);
+            if let Some(local_key) = local_key_option.as_ref() {
+                current_to_new_idx_map.insert(local_key.i, idx);
+            }
+            party_key_configs.push((
+                signing_share_option,
+                sub_share_option,
+                identity_provider,
+                local_key_option,
+                key_option.is_none().then_some(idx),
+                key_option.is_none().then_some(threshold),
+                key_option.is_none().then_some(n_parties_init),
+                is_initiator,
+            ));
+        }
+
+        // Runs membership change simulation for test parameters.
+        let current_verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let new_keys = simulate_membership_change(
+            party_key_configs,
+            &current_verifying_keys,
+            &current_to_new_idx_map,
+            n_parties_new,
+        );
+
+        // Verifies the refreshed/generated keys and configuration for all parties.
+        assert_eq!(new_keys.len(), n_parties_new as usize);
+        for (i, new_key) in new_keys.iter().enumerate() {
+            // Verifies party index, threshold and number of parties.
+            assert_eq!(new_key.base.i, wamu_core::party_index(i).unwrap());
+            assert_eq!(new_key.base.t, threshold);
+            assert_eq!(new_key.base.n, n_parties_new);
+            // Verifies that the secret share was cleared/zerorized.
+            assert_eq!(new_key.base.keys_linear.x_i, Scalar::<Secp256k1>::zero());
+            // Verifies that the public key hasn't changed.
+            assert_eq!(new_key.base.public_key(), pub_key_init);
+        }
+
+        (
+            (keys, identity_providers),
+            (new_keys, new_identity_providers),
+        )
+    }
+
+    #[test]
+    fn membership_change_works() {
+        // Removes party 2 and adds 2 new parties in a single run (i.e 4 -> 5 parties with threshold 2).
+        let (_, (new_keys, new_identity_providers)) =
+            generate_parties_and_simulate_membership_change(2, 4, &[2], 2, 1);
+
+        // Verifies that all parties (including the new parties) can sign.
+        let n_participants = wamu_core::party_count(new_keys.len()).unwrap();
+        let pre_signing_output_idx = 1; // l in the CGGMP20 paper.
+        let pre_sign_inputs = crate::sign::tests::generate_pre_sign_input(
+            &new_keys,
+            &new_identity_providers,
+            n_participants,
+        );
+        let ssids: Vec<_> = pre_sign_inputs
+            .iter()
+            .map(|(_, _, _, ssid, ..)| ssid.clone())
+            .collect();
+        let pre_sign_results =
+            crate::sign::tests::simulate_pre_sign(pre_sign_inputs, pre_signing_output_idx);
+        let results = crate::sign::tests::simulate_sign(
+            pre_sign_results
+                .into_iter()
+                .map(|result| {
+                    let (output, transcript) = result.base.unwrap();
+                    let idx = output.i as usize - 1;
+                    let (signing_share, sub_share) = new_keys[idx].extra.as_ref().unwrap();
+                    (
+                        signing_share,
+                        sub_share,
+                        &new_identity_providers[idx],
+                        ssids[idx].clone(),
+                        HashMap::from([(pre_signing_output_idx as u16, (output, transcript))]),
+                    )
+                })
+                .collect(),
+            b"Hello, world!",
+            pre_signing_output_idx,
+        );
+        assert!(results.iter().all(|result| result.base.is_some()));
+    }
+
+    #[test]
+    fn membership_change_removing_middle_party_works() {
+        for (threshold, n_parties_init, removed_parties, n_added_parties, initiating_party_idx) in [
+            // Removes party 3 of 5 and adds 1 new party (i.e the initiator is re-indexed from 4 to 3).
+            (2, 5, vec![3], 1, 4),
+            // Removes parties 2 and 3 of 5 and adds 1 new party (i.e the new party's index collides with a current index).
+            (1, 5, vec![2, 3], 1, 5),
+        ] {
+            let ((keys, identity_providers), (new_keys, new_identity_providers)) =
+                generate_parties_and_simulate_membership_change(
+                    threshold,
+                    n_parties_init,
+                    &removed_parties,
+                    n_added_parties,
+                    initiating_party_idx,
+                );
+
+            // Verifies that continuing parties are re-indexed in order and removed parties are excluded.
+            for (new_key, identity_provider) in new_keys.iter().zip(new_identity_providers.iter()) {
+                let prev_key = keys
+                    .iter()
+                    .zip(identity_providers.iter())
+                    .find(|(_, it)| it.verifying_key() == identity_provider.verifying_key())
+                    .map(|(key, _)| key);
+                if let Some(prev_key) = prev_key {
+                    assert!(!removed_parties.contains(&prev_key.base.i));
+                    let n_removed_before = removed_parties
+                        .iter()
+                        .filter(|idx| **idx < prev_key.base.i)
+                        .count() as u16;
+                    assert_eq!(new_key.base.i, prev_key.base.i - n_removed_before);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn party_id_is_stable_across_index_changes() {
+        // Removes party 1 (i.e parties 2 and 3 are re-indexed as parties 1 and 2).
+        let ((keys, identity_providers), (new_keys, new_identity_providers)) =
+            generate_parties_and_simulate_membership_change(1, 3, &[1], 0, 2);
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let new_verifying_keys: Vec<VerifyingKey> = new_identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        for (new_key, identity_provider) in new_keys.iter().zip(new_identity_providers.iter()) {
+            // Retrieves the key of the party before the membership change.
+            let key = keys
+                .iter()
+                .zip(identity_providers.iter())
+                .find(|(_, it)| it.verifying_key() == identity_provider.verifying_key())
+                .map(|(key, _)| key)
+                .unwrap();
+
+            // Initializes state machines for the party before and after the membership change.
+            let prev_state_machine = QuorumApproval::new(
+                MEMBERSHIP_CHANGE,
+                identity_provider,
+                &verifying_keys,
+                key.base.i,
+                key.base.t,
+                key.base.n,
+                false,
+                false,
+            );
+            let new_state_machine = QuorumApproval::new(
+                MEMBERSHIP_CHANGE,
+                identity_provider,
+                &new_verifying_keys,
+                new_key.base.i,
+                new_key.base.t,
+                new_key.base.n,
+                false,
+                false,
+            );
+
+            // Verifies that the party index changed but the party identifier didn't.
+            assert_ne!(
+                prev_state_machine.party_ind(),
+                new_state_machine.party_ind()
+            );
+            assert_eq!(prev_state_machine.party_id(), new_state_machine.party_id());
+            assert_eq!(
+                new_state_machine.party_id(),
+                PartyId::from_verifying_key(&identity_provider.verifying_key())
+            );
+        }
+    }
+
+    #[test]
+    fn membership_change_insecure_threshold_fails() {
+        // Runs keygen simulation.
+        let (keys, identity_providers) = simulate_keygen(2, 4);
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Removing 1 party without adding any breaks the honest majority assumption (i.e 2 > 3/2).
+        let (signing_share, sub_share) = keys[0].extra.as_ref().unwrap();
+        let old_to_new_map = HashMap::from([(1, 1), (3, 2), (4, 3)]);
+        let result = MembershipChange::new(
+            Some(signing_share),
+            Some(sub_share),
+            &identity_providers[0],
+            &verifying_keys,
+            &verifying_keys,
+            Some(keys[0].base.clone()),
+            None,
+            3,
+            &old_to_new_map,
+            None,
+            None,
+            true,
+        );
+        assert!(matches!(
+            result,
+            Err(Error::Refresh(
+                augmented_state_machine::Error::BadFSDKRThreshold
+            ))
+        ));
+    }
+}
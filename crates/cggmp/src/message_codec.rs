@@ -0,0 +1,153 @@
+//! Length-prefixed framing for (augmented) round messages (e.g for integration with network streams).
+//!
+//! Each frame is a 4 byte big-endian length prefix followed by the JSON serialized [`Msg`](Msg).
+
+use round_based::Msg;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{self, Read, Write};
+
+/// The maximum size (in bytes) of a framed message (i.e 16 MiB)
+/// (i.e larger length prefixes are rejected when decoding to bound the work done for untrusted input).
+pub const MAX_FRAME_SIZE: u32 = 16 * 1024 * 1024;
+
+/// Given a round message and a writer, writes the message to the writer as a length-prefixed frame
+/// without allocating an intermediate buffer for the serialized message.
+///
+/// **NOTE:** The message is serialized twice (i.e once to compute the length prefix and once to write it).
+pub fn encode_message<B: Serialize>(msg: &Msg<B>, mut writer: impl Write) -> io::Result<()> {
+    // Computes the length of the serialized message.
+    let mut counter = ByteCounter::default();
+    serde_json::to_writer(&mut counter, msg)?;
+    let len = u32::try_from(counter.0)
+        .ok()
+        .filter(|len| *len <= MAX_FRAME_SIZE)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "message too large"))?;
+
+    // Writes the length prefix followed by the serialized message.
+    writer.write_all(&len.to_be_bytes())?;
+    serde_json::to_writer(&mut writer, msg)?;
+    writer.flush()
+}
+
+/// Given a reader, returns the next round message read from a length-prefixed frame (see [`encode_message`]),
+/// or an appropriate error if the frame is either incomplete, too large or malformed.
+///
+/// **NOTE:** Only the bytes of the frame are read from the reader (i.e subsequent frames can be read from the same reader).
+pub fn decode_message<B: DeserializeOwned>(mut reader: impl Read) -> io::Result<Msg<B>> {
+    // Reads the length prefix.
+    let mut len_bytes = [0; 4];
+    reader.read_exact(&mut len_bytes)?;
+    let len = u32::from_be_bytes(len_bytes);
+    if len > MAX_FRAME_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "message too large",
+        ));
+    }
+
+    // Deserializes the message from exactly the bytes of the frame.
+    Ok(serde_json::from_reader(reader.take(u64::from(len)))?)
+}
+
+/// A writer that only counts the number of bytes written to it.
+#[derive(Default)]
+struct ByteCounter(usize);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::augmented_state_machine::{AugmentedType, IdentityAuthParams};
+    use crate::AugmentedKeyGen;
+    use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen::Keygen;
+    use round_based::StateMachine;
+    use std::io::Cursor;
+    use wamu_core::crypto::VerifyingKey;
+    use wamu_core::test_utils::MockECDSAIdentityProvider;
+    use wamu_core::IdentityProvider;
+
+    type KeygenMessageBody =
+        AugmentedType<<Keygen as StateMachine>::MessageBody, IdentityAuthParams>;
+
+    #[test]
+    fn message_framing_works() {
+        // Creates identity providers and a list of verifying keys for 2 parties.
+        let identity_providers: Vec<MockECDSAIdentityProvider> = (1..=2)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Initializes both parties and runs the initial computations to generate round 1 messages.
+        let mut parties: Vec<AugmentedKeyGen<MockECDSAIdentityProvider>> = identity_providers
+            .iter()
+            .enumerate()
+            .map(|(idx, identity_provider)| {
+                AugmentedKeyGen::new(
+                    identity_provider,
+                    &verifying_keys,
+                    wamu_core::party_index(idx).unwrap(),
+                    1,
+                    2,
+                )
+                .unwrap()
+            })
+            .collect();
+        for party in parties.iter_mut() {
+            party.proceed().unwrap();
+        }
+        let msg = parties[1].message_queue().pop().unwrap();
+
+        // Writes the augmented round 1 message into an in-memory pipe twice (i.e two consecutive frames).
+        let mut pipe = Vec::new();
+        encode_message(&msg, &mut pipe).unwrap();
+        let frame_len = pipe.len();
+        encode_message(&msg, &mut pipe).unwrap();
+        assert_eq!(pipe.len(), 2 * frame_len);
+
+        // Reads both frames back from the pipe.
+        let mut reader = Cursor::new(pipe.clone());
+        for _ in 0..2 {
+            let decoded: Msg<KeygenMessageBody> = decode_message(&mut reader).unwrap();
+            assert_eq!(decoded.sender, msg.sender);
+            assert_eq!(decoded.receiver, msg.receiver);
+            assert_eq!(
+                serde_json::to_string(&decoded).unwrap(),
+                serde_json::to_string(&msg).unwrap()
+            );
+        }
+        // Verifies that no frames are left.
+        assert!(decode_message::<KeygenMessageBody>(&mut reader).is_err());
+
+        // Verifies that the decoded message is accepted by the other party.
+        let decoded: Msg<KeygenMessageBody> = decode_message(pipe.as_slice()).unwrap();
+        assert!(parties[0].handle_incoming(decoded).is_ok());
+
+        for (frame, expected_error_kind) in [
+            // A truncated frame should be rejected.
+            (pipe[..frame_len - 1].to_vec(), io::ErrorKind::UnexpectedEof),
+            // A length prefix larger than the maximum frame size should be rejected.
+            (
+                (MAX_FRAME_SIZE + 1).to_be_bytes().to_vec(),
+                io::ErrorKind::InvalidData,
+            ),
+        ] {
+            // Verifies expected error.
+            let result = decode_message::<KeygenMessageBody>(frame.as_slice());
+            assert_eq!(result.unwrap_err().kind(), expected_error_kind);
+        }
+    }
+}
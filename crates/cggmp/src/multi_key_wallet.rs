@@ -0,0 +1,285 @@
+//! A wallet with multiple linked keys (i.e keys tagged by purpose that share the same party set and identities).
+
+use cggmp_threshold_ecdsa::presign::{PresigningOutput, PresigningTranscript, SSID};
+use cggmp_threshold_ecdsa::sign::state_machine::Signing;
+use curv::elliptic::curves::Secp256k1;
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen::{
+    Keygen, LocalKey,
+};
+use round_based::StateMachine;
+use std::collections::{BTreeMap, HashMap};
+use wamu_core::crypto::VerifyingKey;
+use wamu_core::IdentityProvider;
+
+use crate::augmented_state_machine::{AugmentedType, Error, SubShareOutput};
+use crate::keygen::AugmentedKeyGen;
+use crate::sign::{AugmentedSigning, SigningOptions};
+
+/// A wallet that tracks multiple linked keys tagged by purpose (e.g one key for Ethereum and one for Bitcoin),
+/// where all keys share the same party set and identities.
+pub struct MultiKeyWallet<'a, I: IdentityProvider> {
+    /// The decentralized identity provider of the party.
+    identity_provider: &'a I,
+    /// Verifying keys for other the parties.
+    verified_parties: &'a [VerifyingKey],
+    /// Index of the party.
+    idx: u16,
+    /// The total number of parties.
+    n_parties: u16,
+    /// Key outputs (i.e `LocalKey<Secp256k1>` with the secret share cleared/zerorized, "signing share" and "sub-share") keyed by purpose.
+    keys: BTreeMap<String, AugmentedType<LocalKey<Secp256k1>, SubShareOutput>>,
+}
+
+impl<'a, I: IdentityProvider> MultiKeyWallet<'a, I> {
+    /// Initializes an empty wallet for the party.
+    pub fn new(
+        identity_provider: &'a I,
+        verified_parties: &'a [VerifyingKey],
+        idx: u16,
+        n_parties: u16,
+    ) -> Self {
+        Self {
+            identity_provider,
+            verified_parties,
+            idx,
+            n_parties,
+            keys: BTreeMap::new(),
+        }
+    }
+
+    /// Initializes party for the augmented key generation protocol for a new linked key
+    /// (i.e with the same party set and identities as all other keys in the wallet).
+    ///
+    /// **NOTE:** The key output should be added to the wallet with [`insert_key`](Self::insert_key).
+    pub fn keygen(
+        &self,
+        threshold: u16,
+    ) -> Result<AugmentedKeyGen<'a, I>, Error<<Keygen as StateMachine>::Err>> {
+        AugmentedKeyGen::new(
+            self.identity_provider,
+            self.verified_parties,
+            self.idx,
+            threshold,
+            self.n_parties,
+        )
+    }
+
+    /// Adds a key output (e.g from [`keygen`](Self::keygen)) to the wallet for the given purpose,
+    /// returns the previous key output for the purpose (if any) or an appropriate error
+    /// if the key doesn't belong to the wallet's party set.
+    pub fn insert_key(
+        &mut self,
+        purpose: impl Into<String>,
+        key: AugmentedType<LocalKey<Secp256k1>, SubShareOutput>,
+    ) -> Result<Option<AugmentedType<LocalKey<Secp256k1>, SubShareOutput>>, MultiKeyWalletError>
+    {
+        // Linked keys must share the same party set.
+        if key.base.i != self.idx || key.base.n != self.n_parties || key.extra.is_none() {
+            return Err(MultiKeyWalletError::PartyMismatch);
+        }
+        Ok(self.keys.insert(purpose.into(), key))
+    }
+
+    /// Returns the key output for the given purpose (if any).
+    pub fn key(
+        &self,
+        purpose: &str,
+    ) -> Option<&AugmentedType<LocalKey<Secp256k1>, SubShareOutput>> {
+        self.keys.get(purpose)
+    }
+
+    /// Returns the purposes of all keys in the wallet (in ascending order).
+    pub fn purposes(&self) -> impl Iterator<Item = &str> {
+        self.keys.keys().map(String::as_str)
+    }
+
+    /// Initializes party for the augmented signing protocol with the key for the given purpose.
+    ///
+    /// **NOTE:** The `LocalKey<Secp256k1>` of the SSID must match the key for the given purpose
+    /// (i.e the same party index and group public key), otherwise a `KeyMismatch` error is returned.
+    ///
+    /// See [`AugmentedSigning::new`] for details about the application-level `context`.
+    pub fn sign(
+        &self,
+        purpose: &str,
+        message: &'a [u8],
+        context: Vec<u8>,
+        ssid: SSID<Secp256k1>,
+        presigning_data: HashMap<
+            u16,
+            (PresigningOutput<Secp256k1>, PresigningTranscript<Secp256k1>),
+        >,
+        // l in the CGGMP20 paper.
+        pre_signing_output_idx: usize,
+    ) -> Result<AugmentedSigning<'a, I>, MultiKeyWalletError> {
+        let key = self
+            .keys
+            .get(purpose)
+            .ok_or(MultiKeyWalletError::UnknownPurpose)?;
+        let (signing_share, sub_share) = key
+            .extra
+            .as_ref()
+            .ok_or(MultiKeyWalletError::PartyMismatch)?;
+        // The SSID must be for the key for the given purpose.
+        if ssid.X.i != key.base.i || ssid.X.public_key() != key.base.public_key() {
+            return Err(MultiKeyWalletError::KeyMismatch);
+        }
+        AugmentedSigning::new(
+            signing_share,
+            sub_share,
+            self.identity_provider,
+            self.verified_parties,
+            message,
+            context,
+            ssid,
+            SigningOptions::new(presigning_data, pre_signing_output_idx),
+        )
+        .map_err(MultiKeyWalletError::Signing)
+    }
+}
+
+/// A multi-key wallet error.
+#[derive(Debug)]
+pub enum MultiKeyWalletError {
+    /// An SSID whose `LocalKey<Secp256k1>` doesn't match the key for the given purpose.
+    KeyMismatch,
+    /// A key that doesn't belong to the wallet's party set (i.e a different party index or number of parties)
+    /// or is missing its "signing share" and "sub-share".
+    PartyMismatch,
+    /// A wrapped augmented signing error.
+    Signing(Error<<Signing as StateMachine>::Err>),
+    /// No key for the given purpose.
+    UnknownPurpose,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sign::tests::{generate_pre_sign_input, simulate_pre_sign, to_der_signature};
+    use round_based::dev::Simulation;
+    use wamu_core::test_utils::MockECDSAIdentityProvider;
+
+    #[test]
+    fn multi_key_wallet_works() {
+        // Sets threshold and number of parties.
+        let (threshold, n_parties) = (1, 2);
+
+        // Creates identity providers and a list of verifying keys for all parties.
+        let identity_providers: Vec<MockECDSAIdentityProvider> = (1..=n_parties)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Initializes wallets for all parties.
+        let mut wallets: Vec<MultiKeyWallet<MockECDSAIdentityProvider>> = identity_providers
+            .iter()
+            .enumerate()
+            .map(|(idx, identity_provider)| {
+                MultiKeyWallet::new(
+                    identity_provider,
+                    &verifying_keys,
+                    wamu_core::party_index(idx).unwrap(),
+                    n_parties,
+                )
+            })
+            .collect();
+
+        // Generates linked keys for each purpose.
+        let purposes = ["bitcoin", "ethereum"];
+        for purpose in purposes {
+            let mut simulation = Simulation::new();
+            for wallet in &wallets {
+                simulation.add_party(wallet.keygen(threshold).unwrap());
+            }
+            let keys = simulation.run().unwrap();
+            for (wallet, key) in wallets.iter_mut().zip(keys) {
+                assert!(wallet.insert_key(purpose, key).unwrap().is_none());
+            }
+        }
+
+        // Verifies that all purposes are tracked and the linked keys are distinct.
+        assert_eq!(wallets[0].purposes().collect::<Vec<&str>>(), purposes);
+        let group_keys: Vec<VerifyingKey> = purposes
+            .iter()
+            .map(|purpose| {
+                VerifyingKey::from(wallets[0].key(purpose).unwrap().group_key().unwrap())
+            })
+            .collect();
+        assert_ne!(group_keys[0], group_keys[1]);
+
+        // Signs with each key and verifies the signature against the group key for the purpose.
+        let message = b"Hello, world!";
+        for (purpose, group_key) in purposes.iter().zip(group_keys) {
+            // Runs pre-signing simulation for the key.
+            let pre_signing_output_idx = 1; // l in the CGGMP20 paper.
+            let keys: Vec<AugmentedType<LocalKey<Secp256k1>, SubShareOutput>> = wallets
+                .iter()
+                .map(|wallet| wallet.key(purpose).unwrap().clone())
+                .collect();
+            let pre_sign_inputs = generate_pre_sign_input(&keys, &identity_providers, n_parties);
+            let ssids: Vec<SSID<Secp256k1>> = pre_sign_inputs
+                .iter()
+                .map(|(_, _, _, ssid, ..)| ssid.clone())
+                .collect();
+            let pre_sign_results = simulate_pre_sign(pre_sign_inputs, pre_signing_output_idx);
+
+            // Verifies that unknown purposes are rejected.
+            assert!(matches!(
+                wallets[0].sign(
+                    "unknown",
+                    message,
+                    Vec::new(),
+                    ssids[0].clone(),
+                    HashMap::new(),
+                    pre_signing_output_idx
+                ),
+                Err(MultiKeyWalletError::UnknownPurpose)
+            ));
+
+            // Verifies that an SSID for a different key is rejected.
+            let other_purpose = purposes.iter().find(|it| *it != purpose).unwrap();
+            assert!(matches!(
+                wallets[0].sign(
+                    other_purpose,
+                    message,
+                    Vec::new(),
+                    ssids[0].clone(),
+                    HashMap::new(),
+                    pre_signing_output_idx
+                ),
+                Err(MultiKeyWalletError::KeyMismatch)
+            ));
+
+            // Runs signing simulation with the key for the purpose.
+            let mut simulation = Simulation::new();
+            for result in pre_sign_results {
+                let (output, transcript) = result.base.unwrap();
+                let idx = output.i as usize - 1;
+                simulation.add_party(
+                    wallets[idx]
+                        .sign(
+                            purpose,
+                            message,
+                            Vec::new(),
+                            ssids[idx].clone(),
+                            HashMap::from([(pre_signing_output_idx as u16, (output, transcript))]),
+                            pre_signing_output_idx,
+                        )
+                        .unwrap(),
+                );
+            }
+            let results = simulation.run().unwrap();
+
+            // Verifies signature against the group key for the purpose.
+            assert!(wamu_core::crypto::verify_signature(
+                &group_key,
+                message,
+                &to_der_signature(results[0].base.as_ref().unwrap()),
+            )
+            .is_ok());
+        }
+    }
+}
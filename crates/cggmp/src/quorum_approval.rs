@@ -3,18 +3,30 @@
 //! Ref: <https://wamu.tech/specification#quorum-approved-request>.
 
 use round_based::{IsCritical, Msg, StateMachine};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
-use wamu_core::crypto::VerifyingKey;
+use wamu_core::crypto::{PartyId, VerifyingKey};
+use wamu_core::quorum_approved_request::ApprovalPolicy;
 use wamu_core::{
-    CommandApprovalPayload, IdentityAuthedRequestError, IdentityAuthedRequestPayload,
-    IdentityProvider, QuorumApprovedChallengeResponsePayload, QuorumApprovedRequestError,
+    ApprovalRejection, Command, CommandApprovalPayload, IdentityAuthedRequestError,
+    IdentityAuthedRequestPayload, IdentityProvider, QuorumApprovedChallengeResponsePayload,
+    QuorumApprovedRequestError, QuorumDecision, ThresholdModificationRequestPayload,
 };
 
+use crate::augmented_state_machine::{PartyIdentity, RoundLabel};
+use crate::authorized_key_refresh::DomainSeparated;
+
+/// How long non-initiating parties wait for the identity authenticated request from the initiating party.
+const INITIATOR_TIMEOUT: Duration = Duration::from_secs(5 * 60); // 5 minutes.
+
 /// A [StateMachine](StateMachine) that implements [quorum approval as described by the Wamu protocol](https://wamu.tech/specification#quorum-approved-request).
 pub struct QuorumApproval<'a, I: IdentityProvider> {
     /// The command for the request being initiated.
-    command: &'static str,
+    command: Command,
+    /// The application-wide domain that the request is namespaced to (if any).
+    domain: Option<&'a str>,
+    /// The proposed new threshold that the request is bound to (only for threshold modification requests).
+    new_threshold: Option<u16>,
     /// The decentralized identity provider of the party.
     identity_provider: &'a I,
     /// Verifying keys for other the parties.
@@ -27,6 +39,11 @@ pub struct QuorumApproval<'a, I: IdentityProvider> {
     n_parties: u16,
     /// Whether or not this party is the request initiator.
     is_initiator: bool,
+    /// Whether or not the request initiator must also explicitly approve the request
+    /// (i.e instead of being an implicit approval).
+    initiator_must_approve: bool,
+    /// Index of the request initiator (if known).
+    initiator: Option<u16>,
     /// Current round.
     round: Round,
     /// Outgoing message queue.
@@ -35,6 +52,8 @@ pub struct QuorumApproval<'a, I: IdentityProvider> {
     request: Option<IdentityAuthedRequestPayload>,
     /// Command approvals.
     command_approvals: HashMap<u16, CommandApprovalPayload>,
+    /// Indices of parties with verified approvals (including the implicit approval of the initiating party).
+    verified_approvals: HashSet<u16>,
     /// Outcome of the identity authentication verification.
     verification_outcome: Option<bool>,
     /// Outcome of the identity authentication verification.
@@ -47,7 +66,7 @@ pub struct QuorumApproval<'a, I: IdentityProvider> {
 impl<'a, I: IdentityProvider> QuorumApproval<'a, I> {
     /// Initializes party for the identity authentication protocol.
     pub fn new(
-        command: &'static str,
+        command: Command,
         identity_provider: &'a I,
         verified_parties: &'a [VerifyingKey],
         idx: u16,
@@ -77,9 +96,13 @@ impl<'a, I: IdentityProvider> QuorumApproval<'a, I> {
         // Returns quorum approval machine.
         Self {
             command,
+            domain: None,
+            new_threshold: None,
             identity_provider,
             verified_parties,
             is_initiator,
+            initiator_must_approve: false,
+            initiator: is_initiator.then_some(idx),
             idx,
             threshold,
             n_parties,
@@ -87,11 +110,289 @@ impl<'a, I: IdentityProvider> QuorumApproval<'a, I> {
             message_queue,
             request: request_option,
             command_approvals: HashMap::new(),
+            verified_approvals: is_initiator.then_some(idx).into_iter().collect(),
             verification_outcome: None,
             received_verification_outcomes: HashMap::new(),
             is_dormant,
         }
     }
+
+    /// Namespaces the quorum approved request to an application-wide domain (e.g `"com.example.wallet"`),
+    /// see [`wamu_core::quorum_approved_request::initiate_with_domain`].
+    ///
+    /// **NOTE:** The domain must be configured identically by all parties before any messages are exchanged.
+    pub fn with_domain(mut self, domain: &'a str) -> Self {
+        self.set_domain(domain);
+        self
+    }
+
+    /// Requires the request initiator to also explicitly approve the request
+    /// (i.e the initiator's explicit approval must be among the collected quorum instead of being an implicit approval).
+    ///
+    /// **NOTE:** All parties must require the initiator's approval before any messages are exchanged.
+    pub fn with_initiator_must_approve(mut self) -> Self {
+        self.initiator_must_approve = true;
+        // The initiating party's approval is no longer implicit.
+        if self.is_initiator {
+            self.verified_approvals.remove(&self.idx);
+        }
+        self
+    }
+
+    /// Binds the quorum approved request (and approvals) to a proposed new threshold
+    /// (i.e for threshold modification, see [`wamu_core::threshold_modification`]),
+    /// so that the initiating party sends a [`ThresholdModificationRequestPayload`].
+    ///
+    /// **NOTE:** All parties must propose the same (valid) threshold before any messages are exchanged
+    /// (see [`wamu_core::threshold_modification::validate_proposed_threshold`]).
+    pub fn with_new_threshold(mut self, new_threshold: u16) -> Self {
+        self.new_threshold = Some(new_threshold);
+        self.reinitiate_request();
+        self
+    }
+
+    /// Returns the number of distinct verified approvals collected so far
+    /// (including the implicit approval of the initiating party).
+    pub fn approvals_collected(&self) -> usize {
+        self.verified_approvals.len()
+    }
+
+    /// Returns the number of distinct verified approvals needed to form a quorum (i.e threshold + 1).
+    pub fn approvals_needed(&self) -> usize {
+        wamu_core::quorum_size(self.threshold)
+    }
+
+    /// Returns the number of implicit approvals among the verified approvals
+    /// (i.e one for the initiating party, unless it must explicitly approve the request).
+    fn implicit_approvals(&self) -> usize {
+        usize::from(!self.initiator_must_approve)
+    }
+
+    /// Returns true if the initiator's approval is among the verified approvals or the initiator is an implicit approval.
+    pub fn has_initiator_approval(&self) -> bool {
+        !self.initiator_must_approve
+            || self
+                .initiator
+                .is_some_and(|initiator| self.verified_approvals.contains(&initiator))
+    }
+
+    /// Returns the command approvals from parties other than the initiator
+    /// (i.e the initiator's explicit approval (if any) never counts toward the challenge response
+    /// since the initiator is an implicit approval of the challenge response).
+    fn non_initiator_approvals(&self) -> impl Iterator<Item = &CommandApprovalPayload> {
+        let initiator = self.initiator;
+        self.command_approvals
+            .iter()
+            .filter(move |(sender, _)| Some(**sender) != initiator)
+            .map(|(_, approval)| approval)
+    }
+
+    /// Returns the domain that the request and approvals are bound to (if any)
+    /// i.e the application-wide domain and/or the domain of the proposed threshold for threshold modification requests
+    /// (see [`wamu_core::threshold_modification::threshold_domain`]).
+    fn request_domain(&self) -> Option<String> {
+        match (self.domain, self.new_threshold) {
+            (Some(domain), Some(new_threshold)) => Some(format!(
+                "{domain}:{}",
+                wamu_core::threshold_modification::threshold_domain(new_threshold)
+            )),
+            (None, Some(new_threshold)) => Some(
+                wamu_core::threshold_modification::threshold_domain(new_threshold),
+            ),
+            (Some(domain), None) => Some(domain.to_string()),
+            (None, None) => None,
+        }
+    }
+
+    /// Replaces the queued request of the initiating party (if any)
+    /// with one bound to the current domain and proposed threshold (if any).
+    fn reinitiate_request(&mut self) {
+        if !self.is_initiator {
+            return;
+        }
+        let request = match self.request_domain() {
+            Some(domain) => wamu_core::quorum_approved_request::initiate_with_domain(
+                self.command,
+                &domain,
+                self.identity_provider,
+            ),
+            None => {
+                wamu_core::quorum_approved_request::initiate(self.command, self.identity_provider)
+            }
+        };
+        let body = match self.new_threshold {
+            Some(new_threshold) => {
+                Message::ThresholdModificationRound1(ThresholdModificationRequestPayload {
+                    request: request.clone(),
+                    new_threshold,
+                })
+            }
+            None => Message::Round1(request.clone()),
+        };
+        self.message_queue.retain(|msg| {
+            !matches!(
+                msg.body,
+                Message::Round1(_) | Message::ThresholdModificationRound1(_)
+            )
+        });
+        self.message_queue.push(Msg {
+            sender: self.idx,
+            receiver: None,
+            body,
+        });
+        self.request = Some(request);
+    }
+
+    /// Verifies the quorum approved request (bound to the request domain, if any) and returns the party's command approval.
+    fn verify_request_and_approve(
+        &self,
+        request: &IdentityAuthedRequestPayload,
+    ) -> Result<CommandApprovalPayload, IdentityAuthedRequestError> {
+        match self.request_domain() {
+            Some(domain) => {
+                wamu_core::quorum_approved_request::verify_request_and_initiate_challenge_with_domain(
+                    self.command.as_str(),
+                    &domain,
+                    request,
+                    self.identity_provider,
+                    self.verified_parties,
+                )
+            }
+            None => wamu_core::quorum_approved_request::verify_request_and_initiate_challenge(
+                self.command.as_str(),
+                request,
+                self.identity_provider,
+                self.verified_parties,
+            ),
+        }
+    }
+
+    /// Returns true if the command approval is from the sender and is valid for the request (if known).
+    fn is_valid_approval(&self, sender: u16, approval: &CommandApprovalPayload) -> bool {
+        self.request
+            .as_ref()
+            .is_some_and(|request| self.verify_approval(sender, approval, request).is_ok())
+    }
+
+    /// Given the sender, a command approval and the request,
+    /// returns an `Ok` result if the command approval is from the sender and is valid for the request,
+    /// or the reason for rejecting it otherwise.
+    fn verify_approval(
+        &self,
+        sender: u16,
+        approval: &CommandApprovalPayload,
+        request: &IdentityAuthedRequestPayload,
+    ) -> Result<(), ApprovalRejection> {
+        // Approvals must be from the verified party with the sender's index.
+        if !self
+            .verified_parties
+            .get((sender as usize).wrapping_sub(1))
+            .is_some_and(|verifying_key| verifying_key == &approval.verifying_key)
+        {
+            return Err(ApprovalRejection::Unauthorized(
+                wamu_core::Error::UnauthorizedParty,
+            ));
+        }
+        match self.request_domain() {
+            Some(domain) => wamu_core::quorum_approved_request::verify_approval_with_domain(
+                approval,
+                request,
+                &domain,
+                self.verified_parties,
+            ),
+            None => wamu_core::quorum_approved_request::verify_approval(
+                approval,
+                request,
+                self.verified_parties,
+            ),
+        }
+        .map_err(|error| match error {
+            QuorumApprovedRequestError::Unauthorized(error) => {
+                ApprovalRejection::Unauthorized(error)
+            }
+            // Single approval verification only fails for unauthorized approvals.
+            QuorumApprovedRequestError::InsufficientApprovals(_) => {
+                ApprovalRejection::Unauthorized(wamu_core::Error::UnauthorizedParty)
+            }
+        })
+    }
+
+    /// Returns the senders of the received command approvals that weren't verified and the reason for rejecting them
+    /// (in ascending order of sender).
+    fn rejected_approvals(&self) -> Vec<(usize, ApprovalRejection)> {
+        let mut rejected_approvals: Vec<(usize, ApprovalRejection)> = self
+            .command_approvals
+            .iter()
+            .filter(|(sender, _)| !self.verified_approvals.contains(sender))
+            .map(|(sender, approval)| {
+                // Approvals that can't be verified (e.g without the request) are unacknowledged.
+                let rejection = self
+                    .request
+                    .as_ref()
+                    .and_then(|request| self.verify_approval(*sender, approval, request).err())
+                    .unwrap_or(ApprovalRejection::Unacknowledged);
+                (*sender as usize, rejection)
+            })
+            .collect();
+        rejected_approvals.sort_by_key(|(sender, _)| *sender);
+        rejected_approvals
+    }
+
+    /// Given the sender, the quorum approved request and the proposed new threshold (only for threshold modification requests),
+    /// verifies the request and approves it (for parties other than the initiating party).
+    fn handle_request(
+        &mut self,
+        sender: u16,
+        request: IdentityAuthedRequestPayload,
+        new_threshold: Option<u16>,
+    ) -> Result<(), Error> {
+        // Only one party can initiate the request.
+        if self.initiator.is_some_and(|initiator| initiator != sender) {
+            return Err(Error::MultipleInitiators);
+        }
+        self.initiator = Some(sender);
+
+        // The initiating party doesn't need to do anything further for this round,
+        // while other parties verify the identity authentication request
+        // and immediately process the next round if the identity authentication request verification is successful.
+        if !self.is_initiator && !self.is_dormant {
+            // Threshold modification requests must propose the expected threshold
+            // (and other requests can't propose a threshold).
+            if new_threshold != self.new_threshold {
+                return Err(IdentityAuthedRequestError::InvalidProposedThreshold.into());
+            }
+            let command_approval = self.verify_request_and_approve(&request)?;
+            // Saves the request payload.
+            self.request = Some(request);
+
+            // Records the implicit approval of the initiating party (unless it must explicitly approve the request),
+            // the party's own approval and verifies any approvals received before the request.
+            if !self.initiator_must_approve {
+                self.verified_approvals.insert(sender);
+            }
+            self.verified_approvals.insert(self.idx);
+            let early_approvals: Vec<u16> = self
+                .command_approvals
+                .iter()
+                .filter(|(sender, approval)| self.is_valid_approval(**sender, approval))
+                .map(|(sender, _)| *sender)
+                .collect();
+            self.verified_approvals.extend(early_approvals);
+
+            // Moves on to the next round.
+            self.round = Round::Two;
+            // Stores the party's own challenge fragment.
+            self.command_approvals
+                .insert(self.idx, command_approval.clone());
+            // Adds challenge fragment to the message queue for Round 2.
+            self.message_queue.push(Msg {
+                sender: self.idx,
+                receiver: None,
+                body: Message::Round2(command_approval),
+            });
+        }
+        Ok(())
+    }
 }
 
 impl<'a, I: IdentityProvider> StateMachine for QuorumApproval<'a, I> {
@@ -102,36 +403,17 @@ impl<'a, I: IdentityProvider> StateMachine for QuorumApproval<'a, I> {
     fn handle_incoming(&mut self, msg: Msg<Self::MessageBody>) -> Result<(), Self::Err> {
         match msg.body {
             // All other parties verify the identity authentication request.
-            Message::Round1(request) => {
-                // The initiating party doesn't need to do anything further for this round,
-                // while other parties verify the identity authentication request
-                // and immediately process the next round if the identity authentication request verification is successful.
-                if !self.is_initiator && !self.is_dormant {
-                    let command_approval =
-                        wamu_core::quorum_approved_request::verify_request_and_initiate_challenge(
-                            self.command,
-                            &request,
-                            self.identity_provider,
-                            self.verified_parties,
-                        )?;
-                    // Saves the request payload.
-                    self.request = Some(request);
-
-                    // Moves on to the next round.
-                    self.round = Round::Two;
-                    // Stores the party's own challenge fragment.
-                    self.command_approvals
-                        .insert(self.idx, command_approval.clone());
-                    // Adds challenge fragment to the message queue for Round 2.
-                    self.message_queue.push(Msg {
-                        sender: self.idx,
-                        receiver: None,
-                        body: Message::Round2(command_approval),
-                    });
-                }
+            Message::Round1(request) => self.handle_request(msg.sender, request, None)?,
+            // All other parties verify the threshold modification request.
+            Message::ThresholdModificationRound1(payload) => {
+                self.handle_request(msg.sender, payload.request, Some(payload.new_threshold))?
             }
             // All parties store the received identity challenges.
             Message::Round2(challenge_fragment) => {
+                // Records the approval as verified if the request is known and the approval is valid.
+                if self.is_valid_approval(msg.sender, &challenge_fragment) {
+                    self.verified_approvals.insert(msg.sender);
+                }
                 self.command_approvals
                     .insert(msg.sender, challenge_fragment);
             }
@@ -142,17 +424,22 @@ impl<'a, I: IdentityProvider> StateMachine for QuorumApproval<'a, I> {
                 // and immediately process the next round if the challenge response verification is successful.
                 if !self.is_initiator && !self.is_dormant {
                     let request = self.request.as_ref().ok_or(Error::InvalidState)?;
+                    let approvals: Vec<CommandApprovalPayload> =
+                        self.non_initiator_approvals().cloned().collect();
+                    let initiator_verifying_key = &self.verified_parties[msg.sender as usize - 1];
+                    let domain = self.request_domain();
+                    // In this case threshold is enough since the initiator is an implicit approval.
+                    let policy = ApprovalPolicy::new(self.threshold as usize);
                     wamu_core::quorum_approved_request::verify_challenge_response(
                         &response,
-                        &self
-                            .command_approvals
-                            .values()
-                            .cloned()
-                            .collect::<Vec<CommandApprovalPayload>>(),
-                        &self.verified_parties[msg.sender as usize - 1],
+                        &approvals,
+                        initiator_verifying_key,
                         request,
-                        self.threshold as usize, // In this case threshold is enough since the initiator is an implicit approval.
                         self.verified_parties,
+                        &match domain.as_deref() {
+                            Some(domain) => policy.with_domain(domain),
+                            None => policy,
+                        },
                     )?;
 
                     // Moves on the next round.
@@ -192,7 +479,7 @@ impl<'a, I: IdentityProvider> StateMachine for QuorumApproval<'a, I> {
             // Initiating party needs to receive challenge fragments from at least the threshold (i.e >= threshold) since its also an approval (i.e quorum size = threshold + 1),
             // while other parties need to receive challenge fragments from at least the threshold - 1 (i.e >= threshold - 1) since they can be the final approval.
             Round::Two => {
-                self.command_approvals.len()
+                self.non_initiator_approvals().count()
                     >= self.threshold as usize
                         - if self.is_initiator || self.is_dormant {
                             0
@@ -211,14 +498,19 @@ impl<'a, I: IdentityProvider> StateMachine for QuorumApproval<'a, I> {
             }
             // Initiating party needs to receive outcomes from at least the threshold (i.e >= threshold) since its also an approval (i.e quorum size = threshold + 1),
             // while other parties need to receive outcomes from at least the threshold - 1 (i.e >= threshold - 1) since they can be the final approval.
+            // All active parties also need a quorum of distinct verified approvals (i.e threshold + 1) before they can finish
+            // (including the initiator's explicit approval if the initiator must approve the request).
             Round::Four => {
-                self.command_approvals.len()
+                self.non_initiator_approvals().count()
                     >= self.threshold as usize
                         - if self.is_initiator || self.is_dormant {
                             0
                         } else {
                             1
                         }
+                    && (self.is_dormant
+                        || (self.approvals_collected() >= self.approvals_needed()
+                            && self.has_initiator_approval()))
             }
             // The protocol is completed at this point and output should be picked.
             Round::Final | Round::Gone => false,
@@ -236,16 +528,33 @@ impl<'a, I: IdentityProvider> StateMachine for QuorumApproval<'a, I> {
                 // Only the initiating party needs to respond to the challenge.
                 if self.is_initiator {
                     let request = self.request.as_ref().ok_or(Error::InvalidState)?;
+                    // Explicitly approves the request (if necessary) and broadcasts the approval to other parties.
+                    if self.initiator_must_approve
+                        && !self.command_approvals.contains_key(&self.idx)
+                    {
+                        let command_approval = self.verify_request_and_approve(request)?;
+                        self.command_approvals
+                            .insert(self.idx, command_approval.clone());
+                        self.verified_approvals.insert(self.idx);
+                        self.message_queue.push(Msg {
+                            sender: self.idx,
+                            receiver: None,
+                            body: Message::Round2(command_approval),
+                        });
+                    }
+                    let approvals: Vec<CommandApprovalPayload> =
+                        self.non_initiator_approvals().cloned().collect();
+                    let domain = self.request_domain();
+                    let policy = ApprovalPolicy::new(wamu_core::quorum_size(self.threshold));
                     let result = wamu_core::quorum_approved_request::challenge_response(
-                        &self
-                            .command_approvals
-                            .values()
-                            .cloned()
-                            .collect::<Vec<CommandApprovalPayload>>(),
+                        &approvals,
                         self.identity_provider,
                         request,
-                        self.threshold as usize + 1, // quorum size = threshold + 1.
                         self.verified_parties,
+                        &match domain.as_deref() {
+                            Some(domain) => policy.with_domain(domain),
+                            None => policy,
+                        },
                     );
                     match result {
                         Ok(quorum_challenge_response) => {
@@ -261,7 +570,7 @@ impl<'a, I: IdentityProvider> StateMachine for QuorumApproval<'a, I> {
                             // Keep try if there aren't enough approvals but messages haven't been received from all parties, otherwise return an error.
                             return if matches!(
                                 error,
-                                QuorumApprovedRequestError::InsufficientApprovals
+                                QuorumApprovedRequestError::InsufficientApprovals(_)
                             ) && self.command_approvals.len() < self.n_parties as usize
                             {
                                 Ok(())
@@ -283,6 +592,29 @@ impl<'a, I: IdentityProvider> StateMachine for QuorumApproval<'a, I> {
             // Initiating party simply confirms that it received enough confirmations from other parties in this round,
             // while other parties are already done at this point.
             Round::Four => {
+                // Active parties never finish (i.e authorize follow-up protocols like key refresh)
+                // without a quorum of distinct verified approvals.
+                if !self.is_dormant && self.approvals_collected() < self.approvals_needed() {
+                    // Approval counts exclude the initiator's implicit approval (if any),
+                    // and rejected approvals are reported by the sender's party index.
+                    let implicit_approvals = self.implicit_approvals();
+                    let valid_approvals =
+                        self.approvals_collected()
+                            .saturating_sub(implicit_approvals) as u64;
+                    let required =
+                        self.approvals_needed().saturating_sub(implicit_approvals) as u64;
+                    return Err(Error::Quorum(
+                        QuorumApprovedRequestError::InsufficientApprovals(QuorumDecision {
+                            valid_approvals,
+                            invalid_approvals: self.rejected_approvals(),
+                            required,
+                        }),
+                    ));
+                }
+                // Active parties never finish without the initiator's explicit approval (if necessary).
+                if !self.is_dormant && !self.has_initiator_approval() {
+                    return Err(Error::MissingInitiatorApproval);
+                }
                 // Everyone moves on to the final round.
                 self.round = Round::Final;
             }
@@ -293,11 +625,19 @@ impl<'a, I: IdentityProvider> StateMachine for QuorumApproval<'a, I> {
     }
 
     fn round_timeout(&self) -> Option<Duration> {
-        None
+        // Non-initiating parties only wait for the identity authenticated request from the initiating party for a limited time.
+        (self.round == Round::One && self.initiator.is_none() && !self.is_dormant)
+            .then_some(INITIATOR_TIMEOUT)
     }
 
     fn round_timeout_reached(&mut self) -> Self::Err {
-        panic!("no timeout was set")
+        // A timeout is only set while waiting for the identity authenticated request from the initiating party,
+        // so reaching a timeout that was never set is a misuse of the state machine (i.e an error rather than a panic).
+        if self.round == Round::One && self.initiator.is_none() {
+            Error::NoInitiator
+        } else {
+            Error::InvalidState
+        }
     }
 
     fn is_finished(&self) -> bool {
@@ -341,6 +681,29 @@ impl<'a, I: IdentityProvider> StateMachine for QuorumApproval<'a, I> {
     }
 }
 
+impl<'a, I: IdentityProvider> DomainSeparated<'a> for QuorumApproval<'a, I> {
+    fn set_domain(&mut self, domain: &'a str) {
+        self.domain = Some(domain);
+        // Replaces the request initiated without the domain (if any).
+        self.reinitiate_request();
+    }
+}
+
+impl<'a, I: IdentityProvider> PartyIdentity for QuorumApproval<'a, I> {
+    fn party_id(&self) -> PartyId {
+        PartyId::from_verifying_key(&self.identity_provider.verifying_key())
+    }
+}
+
+impl<'a, I: IdentityProvider> RoundLabel for QuorumApproval<'a, I> {
+    fn round_label(&self) -> String {
+        match self.round {
+            Round::Final | Round::Gone => "Quorum Approval Finished".to_string(),
+            _ => format!("Quorum Approval Round {}", self.current_round()),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 enum Round {
     One,
@@ -354,6 +717,7 @@ enum Round {
 #[derive(Debug, Clone)]
 pub enum Message {
     Round1(IdentityAuthedRequestPayload),
+    ThresholdModificationRound1(ThresholdModificationRequestPayload),
     Round2(CommandApprovalPayload),
     Round3(QuorumApprovedChallengeResponsePayload),
     Round4(Option<bool>),
@@ -365,6 +729,9 @@ pub enum Error {
     Identity(IdentityAuthedRequestError),
     AlreadyPicked,
     InvalidState,
+    MultipleInitiators,
+    NoInitiator,
+    MissingInitiatorApproval,
 }
 
 impl From<QuorumApprovedRequestError> for Error {
@@ -410,6 +777,7 @@ impl<'a, I: IdentityProvider> std::fmt::Debug for QuorumApproval<'a, I> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{ChaosConfig, ChaosError, ChaosRouter};
     use round_based::dev::Simulation;
     use wamu_core::test_utils::MockECDSAIdentityProvider;
 
@@ -437,7 +805,7 @@ mod tests {
         // Adds parties to simulation.
         for (identity_provider, idx, is_initiator) in party_key_configs {
             simulation.add_party(QuorumApproval::new(
-                "command",
+                Command::Signing,
                 identity_provider,
                 &verifying_keys,
                 idx,
@@ -483,7 +851,7 @@ mod tests {
         let mut party_key_configs = Vec::new();
         for (i, identity_provider) in identity_providers.iter().enumerate() {
             // Create party key config and index entry.
-            let idx = i as u16 + 1;
+            let idx = wamu_core::party_index(i).unwrap();
             party_key_configs.push((identity_provider, idx, idx == initiating_party_idx));
         }
 
@@ -496,4 +864,346 @@ mod tests {
             assert!(outcome);
         }
     }
+
+    #[test]
+    fn quorum_approval_under_quorum_does_not_finish() {
+        let threshold = 2;
+        let n_parties = 3;
+
+        // Creates identity providers and a list of verifying keys for all parties.
+        let identity_providers: Vec<MockECDSAIdentityProvider> = (1..=n_parties)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Initializes parties with party 1 as the initiator.
+        let mut parties: Vec<QuorumApproval<MockECDSAIdentityProvider>> = identity_providers
+            .iter()
+            .enumerate()
+            .map(|(i, identity_provider)| {
+                QuorumApproval::new(
+                    Command::Signing,
+                    identity_provider,
+                    &verifying_keys,
+                    wamu_core::party_index(i).unwrap(),
+                    threshold,
+                    n_parties,
+                    i == 0,
+                    false,
+                )
+            })
+            .collect();
+
+        // Delivers the request only to party 2, and the approval from party 2 only to the initiator.
+        let requests = parties[0].message_queue().split_off(0);
+        for msg in requests {
+            parties[1].handle_incoming(msg).unwrap();
+        }
+        let approvals = parties[1].message_queue().split_off(0);
+        for msg in approvals {
+            parties[0].handle_incoming(msg).unwrap();
+        }
+
+        // Proceeds as far as possible.
+        for party in parties[..2].iter_mut() {
+            while party.wants_to_proceed() {
+                party.proceed().unwrap();
+            }
+        }
+
+        // Verifies that only threshold approvals (i.e the initiator and party 2) are collected
+        // and that neither party finishes (i.e the key refresh phase can't start).
+        for party in &parties[..2] {
+            assert_eq!(party.approvals_collected(), threshold as usize);
+            assert_eq!(party.approvals_needed(), threshold as usize + 1);
+            assert!(!party.is_finished());
+        }
+    }
+
+    #[test]
+    fn quorum_approval_reports_rejected_approvals() {
+        let threshold = 2;
+        let n_parties = 3;
+
+        // Creates identity providers and a list of verifying keys for all parties.
+        let identity_providers: Vec<MockECDSAIdentityProvider> = (1..=n_parties)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Generates test cases for whether or not the initiator must explicitly approve the request.
+        for (initiator_must_approve, required) in [(false, 2), (true, 3)] {
+            // Initializes parties with party 1 as the initiator.
+            let mut parties: Vec<QuorumApproval<MockECDSAIdentityProvider>> = identity_providers
+                .iter()
+                .enumerate()
+                .map(|(i, identity_provider)| {
+                    let party = QuorumApproval::new(
+                        Command::Signing,
+                        identity_provider,
+                        &verifying_keys,
+                        wamu_core::party_index(i).unwrap(),
+                        threshold,
+                        n_parties,
+                        i == 0,
+                        false,
+                    );
+                    if initiator_must_approve {
+                        party.with_initiator_must_approve()
+                    } else {
+                        party
+                    }
+                })
+                .collect();
+
+            // Delivers the request to party 2, and then party 2's own approval to party 2 as if it was sent by party 3
+            // (i.e an approval that doesn't match the sender's verifying key).
+            let requests = parties[0].message_queue().split_off(0);
+            for msg in requests {
+                parties[1].handle_incoming(msg).unwrap();
+            }
+            let approvals = parties[1].message_queue().split_off(0);
+            for msg in approvals {
+                parties[1]
+                    .handle_incoming(Msg {
+                        sender: 3,
+                        receiver: None,
+                        body: msg.body,
+                    })
+                    .unwrap();
+            }
+
+            // Verifies that the quorum check reports the rejected approval and how close the request was to a quorum
+            // (i.e the approval from party 2 but not the initiator's implicit approval (if any),
+            // while an initiator that must explicitly approve the request counts toward the required approvals).
+            parties[1].round = Round::Four;
+            let expected_decision = QuorumDecision {
+                valid_approvals: 1,
+                invalid_approvals: vec![(
+                    3,
+                    ApprovalRejection::Unauthorized(wamu_core::Error::UnauthorizedParty),
+                )],
+                required,
+            };
+            assert!(matches!(
+                parties[1].proceed(),
+                Err(Error::Quorum(QuorumApprovedRequestError::InsufficientApprovals(decision)))
+                    if decision == expected_decision
+            ));
+        }
+    }
+
+    #[test]
+    fn quorum_approval_initiator_must_approve_works() {
+        let threshold = 1;
+        let n_parties = 3;
+
+        // Creates identity providers and a list of verifying keys for all parties.
+        let identity_providers: Vec<MockECDSAIdentityProvider> = (1..=n_parties)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Generates test cases for whether or not the initiator (i.e party 1) explicitly approves the request,
+        // while all other parties require the initiator's approval.
+        for (initiator_approves, expected_finished) in [(true, true), (false, false)] {
+            let mut router = ChaosRouter::new(ChaosConfig::reliable(1)).unwrap();
+            for (i, identity_provider) in identity_providers.iter().enumerate() {
+                let party = QuorumApproval::new(
+                    Command::Signing,
+                    identity_provider,
+                    &verifying_keys,
+                    wamu_core::party_index(i).unwrap(),
+                    threshold,
+                    n_parties,
+                    i == 0,
+                    false,
+                );
+                router.add_party(if i != 0 || initiator_approves {
+                    party.with_initiator_must_approve()
+                } else {
+                    party
+                });
+            }
+
+            // Verifies that parties only finish (i.e transition) with the initiator's approval.
+            let result = router.run();
+            if expected_finished {
+                assert!(result.unwrap().into_iter().all(|outcome| outcome));
+            } else {
+                assert!(matches!(result, Err(ChaosError::Stalled { .. })));
+                let parties = router.into_parties();
+                for party in &parties[1..] {
+                    assert!(!party.has_initiator_approval());
+                    assert!(!party.is_finished());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn quorum_approval_multiple_initiators_fails() {
+        let threshold = 1;
+        let n_parties = 3;
+
+        // Creates identity providers and a list of verifying keys for all parties.
+        let identity_providers: Vec<MockECDSAIdentityProvider> = (1..=n_parties)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Adds parties to the router with parties 1 and 2 both claiming to be the initiator.
+        let mut router = ChaosRouter::new(ChaosConfig::reliable(1)).unwrap();
+        for (i, identity_provider) in identity_providers.iter().enumerate() {
+            let idx = wamu_core::party_index(i).unwrap();
+            router.add_party(QuorumApproval::new(
+                Command::Signing,
+                identity_provider,
+                &verifying_keys,
+                idx,
+                threshold,
+                n_parties,
+                idx <= 2,
+                false,
+            ));
+        }
+
+        // Verifies that the multiple initiators are detected.
+        assert!(matches!(
+            router.run(),
+            Err(ChaosError::Party {
+                error: Error::MultipleInitiators,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn quorum_approval_with_domain_works() {
+        let threshold = 1;
+        let n_parties = 3;
+
+        // Creates identity providers and a list of verifying keys for all parties.
+        let identity_providers: Vec<MockECDSAIdentityProvider> = (1..=n_parties)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        for (other_domain, expected_ok) in [
+            // All parties configured with the same domain should succeed.
+            ("com.example.wallet", true),
+            // A party configured with a different domain should reject the request.
+            ("com.example.other", false),
+        ] {
+            // Adds parties to the router with party 1 as the initiator and party 3 using `other_domain`.
+            let mut router = ChaosRouter::new(ChaosConfig::reliable(1)).unwrap();
+            for (i, identity_provider) in identity_providers.iter().enumerate() {
+                let idx = wamu_core::party_index(i).unwrap();
+                router.add_party(
+                    QuorumApproval::new(
+                        Command::Signing,
+                        identity_provider,
+                        &verifying_keys,
+                        idx,
+                        threshold,
+                        n_parties,
+                        idx == 1,
+                        false,
+                    )
+                    .with_domain(if idx == 3 {
+                        other_domain
+                    } else {
+                        "com.example.wallet"
+                    }),
+                );
+            }
+
+            // Verifies expected result.
+            let result = router.run();
+            if expected_ok {
+                assert!(result.unwrap().into_iter().all(|outcome| outcome));
+            } else {
+                assert!(matches!(
+                    result,
+                    Err(ChaosError::Party {
+                        idx: 3,
+                        error: Error::Identity(IdentityAuthedRequestError::Unauthorized(_)),
+                    })
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn quorum_approval_no_initiator_times_out() {
+        let threshold = 1;
+        let n_parties = 3;
+
+        // Creates identity providers and a list of verifying keys for all parties.
+        let identity_providers: Vec<MockECDSAIdentityProvider> = (1..=n_parties)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Initializes parties with no initiator.
+        let mut parties: Vec<QuorumApproval<MockECDSAIdentityProvider>> = identity_providers
+            .iter()
+            .enumerate()
+            .map(|(i, identity_provider)| {
+                QuorumApproval::new(
+                    Command::Signing,
+                    identity_provider,
+                    &verifying_keys,
+                    wamu_core::party_index(i).unwrap(),
+                    threshold,
+                    n_parties,
+                    false,
+                    false,
+                )
+            })
+            .collect();
+
+        // Verifies that no party can make progress and that all parties time out with a `NoInitiator` error.
+        for party in parties.iter_mut() {
+            assert!(!party.wants_to_proceed());
+            assert!(party.message_queue().is_empty());
+            assert_eq!(party.round_timeout(), Some(INITIATOR_TIMEOUT));
+            assert!(matches!(party.round_timeout_reached(), Error::NoInitiator));
+        }
+
+        // Verifies that the initiating party never sets a timeout, and that reaching one anyway returns an error.
+        let mut initiator = QuorumApproval::new(
+            Command::Signing,
+            &identity_providers[0],
+            &verifying_keys,
+            1,
+            threshold,
+            n_parties,
+            true,
+            false,
+        );
+        assert_eq!(initiator.round_timeout(), None);
+        assert!(matches!(
+            initiator.round_timeout_reached(),
+            Error::InvalidState
+        ));
+    }
 }
@@ -6,16 +6,16 @@ use curv::elliptic::curves::Secp256k1;
 use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen::LocalKey;
 use round_based::{Msg, StateMachine};
 use std::collections::HashMap;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use wamu_core::crypto::VerifyingKey;
-use wamu_core::{IdentityProvider, SigningShare, SubShare};
+use wamu_core::{Command, IdentityProvider, SigningShare, SubShare};
 
 use crate::authorized_key_refresh::{AuthorizedKeyRefresh, Error, Message};
-use crate::key_refresh::AugmentedKeyRefresh;
+use crate::key_refresh::{AugmentedKeyRefresh, IndexedParties};
 use crate::quorum_approval;
 use crate::quorum_approval::QuorumApproval;
 
-const SHARE_ADDITION: &str = "share-addition";
+const SHARE_ADDITION: Command = Command::ShareAddition;
 
 /// A [StateMachine](StateMachine) that implements [share addition as described by the Wamu protocol](https://wamu.tech/specification#share-addition).
 pub struct ShareAddition<'a, I: IdentityProvider> {
@@ -53,6 +53,8 @@ pub struct ShareAddition<'a, I: IdentityProvider> {
     refresh_state_machine: Option<AugmentedKeyRefresh<'a, I>>,
     /// Stores "out of order" messages.
     out_of_order_buffer: Vec<Msg<Message<'a, I, quorum_approval::Message>>>,
+    /// Ceremony-wide deadline (if any).
+    deadline: Option<Instant>,
 }
 
 impl<'a, I: IdentityProvider> ShareAddition<'a, I> {
@@ -73,22 +75,51 @@ impl<'a, I: IdentityProvider> ShareAddition<'a, I> {
         is_initiator: bool,
     ) -> Result<ShareAddition<'a, I>, Error<'a, I, <QuorumApproval<'a, I> as StateMachine>::Err>>
     {
+        // Verified parties must be provided (i.e an empty list means no other party is authorized).
+        if verified_parties.is_empty() {
+            return Err(Error::Init(wamu_core::Error::NoVerifiedParties.into()));
+        }
+
         // Initializes quorum approval state machine.
         let idx = local_key_option
             .as_ref()
             .map(|it| it.i)
             .or(new_party_index_option)
-            .ok_or(Error::InvalidInput)?;
+            .ok_or(Error::InvalidInput {
+                reason: "missing party index",
+            })?;
         let threshold = local_key_option
             .as_ref()
             .map(|it| it.t)
             .or(current_threshold_option)
-            .ok_or(Error::InvalidInput)?;
+            .ok_or(Error::InvalidInput {
+                reason: "missing current threshold",
+            })?;
         let current_n_parties = local_key_option
             .as_ref()
             .map(|it| it.n)
             .or(current_n_parties_option)
-            .ok_or(Error::InvalidInput)?;
+            .ok_or(Error::InvalidInput {
+                reason: "missing current number of parties",
+            })?;
+
+        // A threshold of zero means that any single party can sign, so it's only allowed for a single-party key
+        // (see [`keygen_single`](crate::keygen_single)), which is extended with a threshold of one.
+        if threshold == 0 && current_n_parties != 1 {
+            return Err(Error::InvalidThreshold);
+        }
+        // Share addition must increase the number of parties.
+        if n_parties <= current_n_parties {
+            return Err(Error::InvalidInput {
+                reason: "new number of parties must exceed the current number of parties",
+            });
+        }
+        // The index of a new party can't collide with the new index of an existing party.
+        if local_key_option.is_none() && old_to_new_map.values().any(|new_idx| *new_idx == idx) {
+            return Err(Error::InvalidInput {
+                reason: "new party index collides with the index of an existing party",
+            });
+        }
         let auth_state_machine = QuorumApproval::new(
             SHARE_ADDITION,
             identity_provider,
@@ -118,6 +149,7 @@ impl<'a, I: IdentityProvider> ShareAddition<'a, I> {
             auth_state_machine,
             refresh_state_machine: None,
             out_of_order_buffer: Vec::new(),
+            deadline: None,
         };
 
         // Retrieves messages from immediate state transitions (if any) and wraps them.
@@ -135,7 +167,8 @@ impl<'a, I: IdentityProvider> AuthorizedKeyRefresh<'a, I> for ShareAddition<'a,
         auth_state_machine,
         refresh_state_machine,
         message_queue,
-        out_of_order_buffer
+        out_of_order_buffer,
+        deadline
     );
 
     fn create_key_refresh(
@@ -150,11 +183,12 @@ impl<'a, I: IdentityProvider> AuthorizedKeyRefresh<'a, I> for ShareAddition<'a,
             self.signing_share_option,
             self.sub_share_option,
             self.identity_provider,
-            self.verified_parties,
+            IndexedParties::from_ordered(self.verified_parties),
             self.local_key_option.take(),
             is_new_party.then_some(self.idx),
             self.old_to_new_map,
-            self.threshold,
+            // Single-party keys (i.e threshold zero) are extended with a threshold of one.
+            self.threshold.max(1),
             self.n_parties,
             is_new_party.then_some(self.threshold),
         )?)
@@ -174,11 +208,14 @@ impl<'a, I: IdentityProvider> std::fmt::Debug for ShareAddition<'a, I> {
 #[cfg(any(test, feature = "dev"))]
 pub mod tests {
     use super::*;
-    use crate::augmented_state_machine::{AugmentedType, SubShareOutput};
+    use crate::augmented_state_machine::{AugmentedType, GroupKey, SubShareOutput};
     use crate::keygen::tests::simulate_keygen;
+    use crate::test_utils::{ChaosConfig, ChaosRouter};
+    use crypto_bigint::U256;
     use curv::elliptic::curves::Scalar;
     use round_based::dev::Simulation;
     use wamu_core::test_utils::MockECDSAIdentityProvider;
+    use zeroize::Zeroize;
 
     pub fn simulate_share_addition(
         // Party key configs including the "signing share", "sub-share", identity provider and
@@ -236,7 +273,12 @@ pub mod tests {
         }
 
         // Runs simulation and returns output.
-        simulation.run().unwrap()
+        simulation
+            .run()
+            .unwrap()
+            .into_iter()
+            .map(Into::into)
+            .collect()
     }
 
     pub fn generate_parties_and_simulate_share_addition(
@@ -288,7 +330,7 @@ pub mod tests {
         let mut current_to_new_idx_map = HashMap::new();
         for (i, identity_provider) in identity_providers.iter().enumerate() {
             // Create party key config and index entry.
-            let idx = i as u16 + 1;
+            let idx = wamu_core::party_index(i).unwrap();
             let key_option = keys.get(i);
             let local_key_option = key_option.map(|key| key.base.clone());
             let share_output_option = key_option.map(|key| key.extra.as_ref().unwrap());
@@ -345,4 +387,163 @@ pub mod tests {
     fn share_addition_works() {
         generate_parties_and_simulate_share_addition(2, 4, 5, 2);
     }
+
+    #[test]
+    fn share_addition_finish_works() {
+        // Runs key gen simulation and creates an identity provider for a new party.
+        let (threshold, n_parties_init, n_parties_new) = (1, 2, 3);
+        let (keys, mut identity_providers) = simulate_keygen(threshold, n_parties_init);
+        identity_providers.push(MockECDSAIdentityProvider::generate());
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let current_to_new_idx_map = HashMap::from([(1, 1), (2, 2)]);
+        let pub_key_init = keys[0].base.public_key();
+
+        // Adds existing parties and the new party to the router.
+        let mut router = ChaosRouter::new(ChaosConfig::reliable(1)).unwrap();
+        for (i, identity_provider) in identity_providers.iter().enumerate() {
+            let key_option = keys.get(i);
+            let share_output_option = key_option.map(|key| key.extra.as_ref().unwrap());
+            router.add_party(
+                ShareAddition::new(
+                    share_output_option.map(|(signing_share, _)| signing_share),
+                    share_output_option.map(|(_, sub_share)| sub_share),
+                    identity_provider,
+                    &verifying_keys,
+                    key_option.map(|key| key.base.clone()),
+                    key_option.is_none().then_some(n_parties_new),
+                    n_parties_new,
+                    &current_to_new_idx_map,
+                    key_option.is_none().then_some(threshold),
+                    key_option.is_none().then_some(n_parties_init),
+                    i == 0,
+                )
+                .unwrap(),
+            );
+        }
+
+        // Runs the protocol without picking the output.
+        router.run_until_finished().unwrap();
+
+        for party in router.into_parties() {
+            // Finishes the protocol for the party.
+            let idx = party.party_ind();
+            let mut update = party.finish().unwrap();
+
+            // Verifies the local key (i.e the secret share was cleared/zerorized).
+            assert_eq!(update.local_key.i, idx);
+            assert_eq!(update.local_key.t, threshold);
+            assert_eq!(update.local_key.n, n_parties_new);
+            assert_eq!(
+                update.local_key.keys_linear.x_i,
+                Scalar::<Secp256k1>::zero()
+            );
+
+            // Verifies that the group key is unchanged and matches the local key.
+            assert_eq!(update.local_key.public_key(), pub_key_init);
+            assert_eq!(
+                update.group_key,
+                GroupKey::try_from(&update.local_key).unwrap()
+            );
+
+            // Verifies that the "signing share" and "sub-share" reconstruct the secret share.
+            assert!(crate::reconstruct_secret_scalar(
+                &update.bundle.signing_share,
+                &update.bundle.sub_share,
+                &identity_providers[idx as usize - 1],
+            )
+            .is_ok());
+
+            // Verifies the summary.
+            assert_eq!(update.summary.added, vec![3]);
+            assert_eq!(update.summary.continued, vec![1, 2]);
+            assert!(!update.summary.threshold_changed);
+
+            // Verifies that zerorizing the update clears the "signing share" and "sub-share".
+            update.zeroize();
+            assert_eq!(update.bundle.signing_share.to_be_bytes(), [0u8; 32]);
+            assert_eq!(update.bundle.sub_share.as_tuple(), (U256::ZERO, U256::ZERO));
+        }
+    }
+
+    #[test]
+    fn share_addition_validation_works() {
+        // Runs key gen simulation and creates an identity provider for a new party.
+        let (keys, mut identity_providers) = simulate_keygen(1, 3);
+        identity_providers.push(MockECDSAIdentityProvider::generate());
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let (signing_share, sub_share) = keys[0].extra.as_ref().unwrap();
+        let current_to_new_idx_map: HashMap<u16, u16> = (1..=3).map(|i| (i, i)).collect();
+
+        for (is_new_party, new_party_index, n_parties, expected_reason) in [
+            // Valid configurations for an existing and a new party.
+            (false, None, 4, None),
+            (true, Some(4), 4, None),
+            // Non-increasing number of parties.
+            (
+                false,
+                None,
+                3,
+                Some("new number of parties must exceed the current number of parties"),
+            ),
+            (
+                true,
+                Some(3),
+                3,
+                Some("new number of parties must exceed the current number of parties"),
+            ),
+            // New party index that collides with the index of an existing party.
+            (
+                true,
+                Some(2),
+                4,
+                Some("new party index collides with the index of an existing party"),
+            ),
+        ] {
+            // Initializes the party as either an existing or a new party.
+            let result = if is_new_party {
+                ShareAddition::new(
+                    None,
+                    None,
+                    &identity_providers[3],
+                    &verifying_keys,
+                    None,
+                    new_party_index,
+                    n_parties,
+                    &current_to_new_idx_map,
+                    Some(1),
+                    Some(3),
+                    false,
+                )
+            } else {
+                ShareAddition::new(
+                    Some(signing_share),
+                    Some(sub_share),
+                    &identity_providers[0],
+                    &verifying_keys,
+                    Some(keys[0].base.clone()),
+                    new_party_index,
+                    n_parties,
+                    &current_to_new_idx_map,
+                    None,
+                    None,
+                    false,
+                )
+            };
+
+            // Verifies expected result.
+            match expected_reason {
+                None => assert!(result.is_ok()),
+                Some(expected_reason) => assert!(matches!(
+                    result,
+                    Err(Error::InvalidInput { reason }) if reason == expected_reason
+                )),
+            }
+        }
+    }
 }
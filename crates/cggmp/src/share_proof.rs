@@ -0,0 +1,191 @@
+//! Share validity proofs (e.g for periodic "liveness of funds" checks).
+//!
+//! A party proves that its "signing share" and "sub-share" reconstruct to the secret share
+//! that matches its public key share (i.e `G * x_i`) from key generation (or key refresh) without revealing the secret share,
+//! using a Schnorr proof of knowledge of the reconstructed secret share.
+//!
+//! Ref: <https://en.wikipedia.org/wiki/Proof_of_knowledge#Schnorr_protocol>.
+
+use curv::arithmetic::Converter;
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use curv::BigInt;
+use wamu_core::{IdentityProvider, SigningShare, SubShare};
+
+use crate::augmented_state_machine;
+use crate::augmented_state_machine::GroupKey;
+
+/// Domain separation tag for share validity proof challenges.
+const SHARE_PROOF_TAG: &[u8] = b"wamu:share-validity-proof";
+
+/// A (non-interactive) Schnorr proof of knowledge of the secret share of a party
+/// bound to the group public key and a verifier-supplied context.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShareProof {
+    /// The group public key the proof is bound to.
+    pub group_key: GroupKey,
+    /// The commitment (i.e `R = G * k` for a random nonce `k`).
+    pub commitment: Point<Secp256k1>,
+    /// The response (i.e `s = k + c * x_i` where `c` is the challenge).
+    pub response: Scalar<Secp256k1>,
+}
+
+/// Given a "signing share", a "sub-share", an identity provider, the group public key and
+/// a verifier-supplied context (e.g a fresh random challenge for each "liveness of funds" check),
+/// returns a proof that the party holds a valid share without revealing the secret share.
+///
+/// **NOTE:** The proof is only valid if the shares reconstruct to the secret share that matches the public key share of the party
+/// and for the same context (see [`verify_share_validity`]), so a proof for a fresh context can't be replayed from an earlier check.
+pub fn prove_share_validity(
+    signing_share: &SigningShare,
+    sub_share: &SubShare,
+    identity_provider: &impl IdentityProvider,
+    group_key: &GroupKey,
+    context: &[u8],
+) -> Result<ShareProof, wamu_core::Error> {
+    // Reconstructs the secret share.
+    let secret_share = augmented_state_machine::reconstruct_secret_scalar(
+        signing_share,
+        sub_share,
+        identity_provider,
+    )?;
+    let public_point = Point::<Secp256k1>::generator() * &secret_share;
+
+    // Computes the commitment for a random nonce.
+    let nonce = Scalar::<Secp256k1>::random();
+    let commitment = Point::<Secp256k1>::generator() * &nonce;
+
+    // Computes the response for the Fiat-Shamir challenge.
+    let challenge = challenge(group_key, &public_point, &commitment, context);
+    let response = nonce + challenge * secret_share;
+
+    Ok(ShareProof {
+        group_key: group_key.clone(),
+        commitment,
+        response,
+    })
+}
+
+/// Given a share validity proof, the public key share of the party (i.e `G * x_i` e.g `pk_vec[i - 1]` of the `LocalKey<Secp256k1>`)
+/// and the context supplied by the verifier,
+/// returns true if the proof is valid (i.e the party holds the secret share that matches the public key share).
+///
+/// **NOTE:** Callers should also verify that the group public key of the proof is the expected one.
+pub fn verify_share_validity(
+    proof: &ShareProof,
+    party_public_point: &Point<Secp256k1>,
+    context: &[u8],
+) -> bool {
+    let challenge = challenge(
+        &proof.group_key,
+        party_public_point,
+        &proof.commitment,
+        context,
+    );
+    // Verifies that `G * s = R + X * c`.
+    Point::<Secp256k1>::generator() * &proof.response
+        == &proof.commitment + party_public_point * &challenge
+}
+
+/// Returns the Fiat-Shamir challenge for a share validity proof
+/// i.e SHA256(tag || group public key (compressed SEC1 format) || `X` (compressed SEC1 format) || `R` (compressed SEC1 format) || context length || context),
+/// where the context length is an 8 byte big-endian unsigned integer.
+fn challenge(
+    group_key: &GroupKey,
+    public_point: &Point<Secp256k1>,
+    commitment: &Point<Secp256k1>,
+    context: &[u8],
+) -> Scalar<Secp256k1> {
+    use sha2::Digest;
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(SHARE_PROOF_TAG);
+    hasher.update(&group_key.verifying_key().key);
+    hasher.update(public_point.to_bytes(true));
+    hasher.update(commitment.to_bytes(true));
+    hasher.update((context.len() as u64).to_be_bytes());
+    hasher.update(context);
+    Scalar::<Secp256k1>::from_bigint(&BigInt::from_bytes(&hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keygen::tests::simulate_keygen;
+
+    #[test]
+    fn share_validity_proof_works() {
+        // Runs key gen simulation.
+        let (keys, identity_providers) = simulate_keygen(1, 3);
+        let group_key = keys[0].group_key().unwrap();
+
+        let context = b"liveness-check:1";
+
+        for (i, (key, identity_provider)) in keys.iter().zip(&identity_providers).enumerate() {
+            // Generates a share validity proof.
+            let (signing_share, sub_share) = key.extra.as_ref().unwrap();
+            let proof = prove_share_validity(
+                signing_share,
+                sub_share,
+                identity_provider,
+                &group_key,
+                context,
+            )
+            .unwrap();
+            let party_public_point = &key.base.pk_vec[key.base.i as usize - 1];
+            let other_public_point = &key.base.pk_vec[key.base.i as usize % key.base.pk_vec.len()];
+
+            // Generates a share validity proof with the "signing share" of another party (i.e a wrong share).
+            let (other_signing_share, _) = keys[(i + 1) % keys.len()].extra.as_ref().unwrap();
+            let wrong_share_proof = prove_share_validity(
+                other_signing_share,
+                sub_share,
+                identity_provider,
+                &group_key,
+                context,
+            )
+            .unwrap();
+
+            for (proof_to_verify, public_point, verifier_context, expected_result) in [
+                // A valid proof should verify.
+                (proof.clone(), party_public_point, &context[..], true),
+                // A proof against the public key share of another party should fail.
+                (proof.clone(), other_public_point, &context[..], false),
+                // A proof for a different context (e.g a replayed proof from an earlier check) should fail.
+                (
+                    proof.clone(),
+                    party_public_point,
+                    &b"liveness-check:2"[..],
+                    false,
+                ),
+                // A proof with a wrong share should fail.
+                (wrong_share_proof, party_public_point, &context[..], false),
+                // A tampered response should fail.
+                (
+                    ShareProof {
+                        response: &proof.response
+                            + Scalar::<Secp256k1>::from_bigint(&BigInt::from(1)),
+                        ..proof.clone()
+                    },
+                    party_public_point,
+                    &context[..],
+                    false,
+                ),
+                // A tampered commitment should fail.
+                (
+                    ShareProof {
+                        commitment: &proof.commitment + Point::<Secp256k1>::generator().to_point(),
+                        ..proof.clone()
+                    },
+                    party_public_point,
+                    &context[..],
+                    false,
+                ),
+            ] {
+                // Verifies expected result.
+                assert_eq!(
+                    verify_share_validity(&proof_to_verify, public_point, verifier_context),
+                    expected_result
+                );
+            }
+        }
+    }
+}
@@ -6,16 +6,16 @@ use curv::elliptic::curves::Secp256k1;
 use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen::LocalKey;
 use round_based::{Msg, StateMachine};
 use std::collections::HashMap;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use wamu_core::crypto::VerifyingKey;
-use wamu_core::{IdentityProvider, SigningShare, SubShare};
+use wamu_core::{Command, IdentityProvider, SigningShare, SubShare};
 
 use crate::authorized_key_refresh::{AuthorizedKeyRefresh, Error, Message};
 use crate::identity_auth;
 use crate::identity_auth::IdentityAuthentication;
-use crate::key_refresh::AugmentedKeyRefresh;
+use crate::key_refresh::{AugmentedKeyRefresh, IndexedParties};
 
-const SHARE_RECOVERY_QUORUM: &str = "share-recovery-quorum";
+const SHARE_RECOVERY_QUORUM: Command = Command::ShareRecoveryQuorum;
 
 /// A [StateMachine](StateMachine) that implements [share recovery with a surviving quorum of honest parties as described by the Wamu protocol](https://wamu.tech/specification#share-recovery-quorum).
 pub struct ShareRecoveryQuorum<'a, I: IdentityProvider> {
@@ -53,6 +53,8 @@ pub struct ShareRecoveryQuorum<'a, I: IdentityProvider> {
     refresh_state_machine: Option<AugmentedKeyRefresh<'a, I>>,
     /// Stores "out of order" messages.
     out_of_order_buffer: Vec<Msg<Message<'a, I, identity_auth::Message>>>,
+    /// Ceremony-wide deadline (if any).
+    deadline: Option<Instant>,
 }
 
 impl<'a, I: IdentityProvider> ShareRecoveryQuorum<'a, I> {
@@ -73,12 +75,29 @@ impl<'a, I: IdentityProvider> ShareRecoveryQuorum<'a, I> {
         ShareRecoveryQuorum<'a, I>,
         Error<'a, I, <IdentityAuthentication<'a, I> as StateMachine>::Err>,
     > {
+        // Verified parties must be provided (i.e an empty list means no other party is authorized).
+        if verified_parties.is_empty() {
+            return Err(Error::Init(wamu_core::Error::NoVerifiedParties.into()));
+        }
+
+        // A threshold of zero means that any single party can sign, so it's never allowed.
+        if local_key_option
+            .as_ref()
+            .map(|it| it.t)
+            .or(current_threshold_option)
+            == Some(0)
+        {
+            return Err(Error::InvalidThreshold);
+        }
+
         // Initializes identity authentication state machine.
         let idx = local_key_option
             .as_ref()
             .map(|it| it.i)
             .or(party_index_option)
-            .ok_or(Error::InvalidInput)?;
+            .ok_or(Error::InvalidInput {
+                reason: "missing party index",
+            })?;
         let auth_state_machine = IdentityAuthentication::new(
             SHARE_RECOVERY_QUORUM,
             identity_provider,
@@ -93,7 +112,9 @@ impl<'a, I: IdentityProvider> ShareRecoveryQuorum<'a, I> {
             .as_ref()
             .map(|it| it.t)
             .or(current_threshold_option)
-            .ok_or(Error::InvalidInput)?;
+            .ok_or(Error::InvalidInput {
+                reason: "missing current threshold",
+            })?;
         let mut share_recovery_quorum = Self {
             // Identity authentication.
             identity_provider,
@@ -111,6 +132,7 @@ impl<'a, I: IdentityProvider> ShareRecoveryQuorum<'a, I> {
             auth_state_machine,
             refresh_state_machine: None,
             out_of_order_buffer: Vec::new(),
+            deadline: None,
         };
 
         // Retrieves messages from immediate state transitions (if any) and wraps them.
@@ -128,7 +150,8 @@ impl<'a, I: IdentityProvider> AuthorizedKeyRefresh<'a, I> for ShareRecoveryQuoru
         auth_state_machine,
         refresh_state_machine,
         message_queue,
-        out_of_order_buffer
+        out_of_order_buffer,
+        deadline
     );
 
     fn create_key_refresh(
@@ -143,7 +166,7 @@ impl<'a, I: IdentityProvider> AuthorizedKeyRefresh<'a, I> for ShareRecoveryQuoru
             self.signing_share_option,
             self.sub_share_option,
             self.identity_provider,
-            self.verified_parties,
+            IndexedParties::from_ordered(self.verified_parties),
             self.local_key_option.take(),
             is_initiator.then_some(self.idx),
             self.old_to_new_map,
@@ -223,7 +246,12 @@ pub mod tests {
         }
 
         // Runs simulation and returns output.
-        simulation.run().unwrap()
+        simulation
+            .run()
+            .unwrap()
+            .into_iter()
+            .map(Into::into)
+            .collect()
     }
 
     pub fn generate_parties_and_simulate_share_recovery_quorum(
@@ -249,7 +277,7 @@ pub mod tests {
         let mut current_to_new_idx_map = HashMap::new();
         for (i, key) in keys.iter().enumerate() {
             // Create party key config and index entry.
-            let idx = i as u16 + 1;
+            let idx = wamu_core::party_index(i).unwrap();
             let (signing_share, sub_share) = key.extra.as_ref().unwrap();
             let local_key = key.base.clone();
             if idx == recovering_party_idx {
@@ -6,16 +6,16 @@ use curv::elliptic::curves::Secp256k1;
 use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen::LocalKey;
 use round_based::{Msg, StateMachine};
 use std::collections::HashMap;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use wamu_core::crypto::VerifyingKey;
-use wamu_core::{IdentityProvider, SigningShare, SubShare};
+use wamu_core::{Command, IdentityProvider, SigningShare, SubShare};
 
 use crate::authorized_key_refresh::{AuthorizedKeyRefresh, Error, Message};
-use crate::key_refresh::AugmentedKeyRefresh;
+use crate::key_refresh::{AugmentedKeyRefresh, IndexedParties};
 use crate::quorum_approval;
 use crate::quorum_approval::QuorumApproval;
 
-const SHARE_REMOVAL: &str = "share-removal";
+const SHARE_REMOVAL: Command = Command::ShareRemoval;
 
 /// A [StateMachine](StateMachine) that implements [share removal as described by the Wamu protocol](https://wamu.tech/specification#share-removal).
 pub struct ShareRemoval<'a, I: IdentityProvider> {
@@ -50,10 +50,15 @@ pub struct ShareRemoval<'a, I: IdentityProvider> {
     refresh_state_machine: Option<AugmentedKeyRefresh<'a, I>>,
     /// Stores "out of order" messages.
     out_of_order_buffer: Vec<Msg<Message<'a, I, quorum_approval::Message>>>,
+    /// Ceremony-wide deadline (if any).
+    deadline: Option<Instant>,
 }
 
 impl<'a, I: IdentityProvider> ShareRemoval<'a, I> {
     /// Initializes party for the share removal protocol.
+    ///
+    /// **NOTE:** The verified parties must be the continuing parties in new party index order
+    /// (see [`AugmentedKeyRefresh::new`]).
     pub fn new(
         signing_share: &'a SigningShare,
         sub_share: &'a SubShare,
@@ -66,6 +71,16 @@ impl<'a, I: IdentityProvider> ShareRemoval<'a, I> {
         is_initiator: bool,
     ) -> Result<ShareRemoval<'a, I>, Error<'a, I, <QuorumApproval<'a, I> as StateMachine>::Err>>
     {
+        // Verified parties must be provided (i.e an empty list means no other party is authorized).
+        if verified_parties.is_empty() {
+            return Err(Error::Init(wamu_core::Error::NoVerifiedParties.into()));
+        }
+
+        // A threshold of zero means that any single party can sign, so it's never allowed.
+        if local_key.t == 0 {
+            return Err(Error::InvalidThreshold);
+        }
+
         // Initializes quorum approval state machine.
         let auth_state_machine = QuorumApproval::new(
             SHARE_REMOVAL,
@@ -95,6 +110,7 @@ impl<'a, I: IdentityProvider> ShareRemoval<'a, I> {
             auth_state_machine,
             refresh_state_machine: None,
             out_of_order_buffer: Vec::new(),
+            deadline: None,
         };
 
         // Retrieves messages from immediate state transitions (if any) and wraps them.
@@ -112,7 +128,8 @@ impl<'a, I: IdentityProvider> AuthorizedKeyRefresh<'a, I> for ShareRemoval<'a, I
         auth_state_machine,
         refresh_state_machine,
         message_queue,
-        out_of_order_buffer
+        out_of_order_buffer,
+        deadline
     );
 
     fn create_key_refresh(
@@ -126,7 +143,7 @@ impl<'a, I: IdentityProvider> AuthorizedKeyRefresh<'a, I> for ShareRemoval<'a, I
             Some(self.signing_share),
             Some(self.sub_share),
             self.identity_provider,
-            self.verified_parties,
+            IndexedParties::from_ordered(self.verified_parties),
             Some(self.local_key.clone()),
             None,
             self.old_to_new_map,
@@ -150,11 +167,15 @@ impl<'a, I: IdentityProvider> std::fmt::Debug for ShareRemoval<'a, I> {
 #[cfg(any(test, feature = "dev"))]
 pub mod tests {
     use super::*;
+    use crate::augmented_state_machine::RoundLabel;
     use crate::augmented_state_machine::{AugmentedType, SubShareOutput};
     use crate::keygen::tests::simulate_keygen;
+    use crate::test_utils::{ChaosConfig, ChaosError, ChaosRouter};
     use curv::elliptic::curves::Scalar;
     use round_based::dev::Simulation;
+    use std::collections::BTreeSet;
     use wamu_core::test_utils::MockECDSAIdentityProvider;
+    use wamu_core::IdentityAuthedRequestError;
 
     pub fn simulate_share_removal(
         // Party key configs including the "signing share", "sub-share", identity provider and
@@ -198,7 +219,12 @@ pub mod tests {
         }
 
         // Runs simulation and returns output.
-        simulation.run().unwrap()
+        simulation
+            .run()
+            .unwrap()
+            .into_iter()
+            .map(Into::into)
+            .collect()
     }
 
     pub fn generate_parties_and_simulate_share_removal(
@@ -253,7 +279,7 @@ pub mod tests {
         let mut current_to_new_idx_map = HashMap::new();
         for (i, key) in keys.iter().enumerate() {
             // Create party key config and index entry.
-            let idx = i as u16 + 1;
+            let idx = wamu_core::party_index(i).unwrap();
             let (signing_share, sub_share) = key.extra.as_ref().unwrap();
             let local_key = key.base.clone();
             current_to_new_idx_map.insert(local_key.i, idx);
@@ -302,4 +328,128 @@ pub mod tests {
     fn share_removal_works() {
         generate_parties_and_simulate_share_removal(2, 5, 4, 2);
     }
+
+    #[test]
+    fn share_removal_round_labels_work() {
+        // Sets threshold and number of parties.
+        let (threshold, n_parties_init, n_parties_new) = (1, 3, 2);
+
+        // Runs key gen simulation for test parameters and removes the last party.
+        let (mut keys, mut identity_providers) = simulate_keygen(threshold, n_parties_init);
+        keys.truncate(n_parties_new as usize);
+        identity_providers.truncate(n_parties_new as usize);
+
+        // Creates a list of verifying keys and party indices for continuing parties.
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let current_to_new_idx_map: HashMap<u16, u16> =
+            keys.iter().map(|key| (key.base.i, key.base.i)).collect();
+
+        // Adds parties to the router.
+        let mut router = ChaosRouter::new(ChaosConfig::reliable(1)).unwrap();
+        for (i, key) in keys.iter().enumerate() {
+            let (signing_share, sub_share) = key.extra.as_ref().unwrap();
+            router.add_party(
+                ShareRemoval::new(
+                    signing_share,
+                    sub_share,
+                    &identity_providers[i],
+                    &verifying_keys,
+                    key.base.clone(),
+                    n_parties_new,
+                    &current_to_new_idx_map,
+                    i == 0,
+                )
+                .unwrap(),
+            );
+        }
+
+        // Runs the protocol and records the round labels observed by all parties.
+        let mut labels = BTreeSet::new();
+        let results = router
+            .run_with_inspector(|party| {
+                labels.insert(party.round_label());
+            })
+            .unwrap();
+        assert_eq!(results.len(), n_parties_new as usize);
+
+        // Verifies that labels for both the quorum approval and key refresh phases were observed.
+        for expected_prefix in ["Quorum Approval Round ", "Key Refresh Round "] {
+            assert!(
+                labels
+                    .iter()
+                    .any(|label| label.starts_with(expected_prefix)),
+                "missing {expected_prefix}label in {labels:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn share_removal_with_domain_works() {
+        // Sets threshold and number of parties.
+        let (threshold, n_parties_init, n_parties_new) = (1, 3, 2);
+
+        // Runs key gen simulation for test parameters and removes the last party.
+        let (mut keys, mut identity_providers) = simulate_keygen(threshold, n_parties_init);
+        keys.truncate(n_parties_new as usize);
+        identity_providers.truncate(n_parties_new as usize);
+
+        // Creates a list of verifying keys and party indices for continuing parties.
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let current_to_new_idx_map: HashMap<u16, u16> =
+            keys.iter().map(|key| (key.base.i, key.base.i)).collect();
+
+        for (other_domain, expected_ok) in [
+            // All parties configured with the same domain should succeed.
+            ("com.example.wallet", true),
+            // A party configured with a different domain should reject the request.
+            ("com.example.other", false),
+        ] {
+            // Adds parties to the router with the first party as the initiator and the second party using `other_domain`.
+            let mut router = ChaosRouter::new(ChaosConfig::reliable(1)).unwrap();
+            for (i, key) in keys.iter().enumerate() {
+                let (signing_share, sub_share) = key.extra.as_ref().unwrap();
+                let mut party = ShareRemoval::new(
+                    signing_share,
+                    sub_share,
+                    &identity_providers[i],
+                    &verifying_keys,
+                    key.base.clone(),
+                    n_parties_new,
+                    &current_to_new_idx_map,
+                    i == 0,
+                )
+                .unwrap();
+                party
+                    .set_domain(if i == 0 {
+                        "com.example.wallet"
+                    } else {
+                        other_domain
+                    })
+                    .unwrap();
+                router.add_party(party);
+            }
+
+            // Verifies expected result.
+            let result = router.run();
+            if expected_ok {
+                assert_eq!(result.unwrap().len(), n_parties_new as usize);
+            } else {
+                assert!(matches!(
+                    result,
+                    Err(ChaosError::Party {
+                        idx: 2,
+                        error: Error::Init(quorum_approval::Error::Identity(
+                            IdentityAuthedRequestError::Unauthorized(_)
+                        )),
+                    })
+                ));
+            }
+        }
+    }
 }
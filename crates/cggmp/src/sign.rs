@@ -7,17 +7,134 @@ use cggmp_threshold_ecdsa::presign::{
     PreSigningSecrets, PresigningOutput, PresigningTranscript, SSID,
 };
 use cggmp_threshold_ecdsa::sign::state_machine::{Signing, M};
-use curv::arithmetic::Converter;
+use curv::arithmetic::{Converter, Modulo};
 use curv::elliptic::curves::{Scalar, Secp256k1};
 use curv::BigInt;
-use round_based::{Msg, StateMachine};
-use std::collections::HashMap;
-use std::time::Duration;
-use wamu_core::crypto::VerifyingKey;
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen::LocalKey;
+use round_based::{IsCritical, Msg, StateMachine};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use wamu_core::crypto::{MessageDigest, PartyId, VerifyingKey};
 use wamu_core::{IdentityProvider, SigningShare, SubShare};
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
+use crate::augmented_state_machine;
 use crate::augmented_state_machine::Error;
-use crate::augmented_state_machine::{AugmentedStateMachine, AugmentedType, IdentityAuthParams};
+use crate::augmented_state_machine::{
+    AugmentationFailures, AugmentedStateMachine, AugmentedType, ClearSecretShare, GroupKey,
+    IdentityAuthParams, PartyIdentity, PartyList, RoundLabel, RoundProgress, TransientSecretShare,
+};
+use crate::format_version::FORMAT_VERSION;
+
+/// Domain separation tag for message commitments.
+const MESSAGE_COMMITMENT_TAG: &[u8] = b"wamu:signing-message-commitment";
+/// Domain tag for the parameters signed by pre-signing parties.
+const PRE_SIGNING_PARAMETERS_TAG: &[u8] = b"wamu:pre-signing-parameters";
+
+/// A threshold signature scheme for signing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignatureScheme {
+    /// ECDSA/Secp256k1 signatures (i.e CGGMP20).
+    #[default]
+    ECDSA,
+    /// Schnorr/Secp256k1 signatures as specified by BIP-340 (e.g for Bitcoin Taproot).
+    ///
+    /// **NOTE:** Requires a different threshold signing protocol (e.g FROST), so it's not supported yet
+    /// (see [`verify_signature`](wamu_core::crypto::verify_signature) for verifying BIP-340 signatures).
+    ///
+    /// Ref: <https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki>.
+    Schnorr,
+}
+
+/// Options for initializing party for the augmented signing protocol (see [`AugmentedSigning::new`])
+/// i.e the pre-signing output to sign with, the signature scheme and an optional message commitment.
+pub struct SigningOptions<'c> {
+    /// The pre-signing output to sign with.
+    presigning: PresigningSource<'c>,
+    /// The signature scheme.
+    scheme: SignatureScheme,
+    /// A message commitment agreed out-of-band (if any).
+    message_commitment: Option<[u8; 32]>,
+}
+
+impl<'c> SigningOptions<'c> {
+    /// Given pre-signing data (i.e the output of [`AugmentedPreSigning`]) and the associated `l` in the CGGMP20 paper,
+    /// returns signing options for ECDSA signatures without a message commitment.
+    pub fn new(
+        presigning_data: HashMap<
+            u16,
+            (PresigningOutput<Secp256k1>, PresigningTranscript<Secp256k1>),
+        >,
+        // l in the CGGMP20 paper.
+        pre_signing_output_idx: usize,
+    ) -> Self {
+        Self::with_presigning(PresigningSource::Data {
+            presigning_data,
+            pre_signing_output_idx,
+        })
+    }
+
+    /// Given a previously persisted presignature (e.g a presignature computed on a networked device and later used on an air-gapped device)
+    /// and the log of consumed presignatures, returns signing options for ECDSA signatures without a message commitment.
+    ///
+    /// **NOTE:** Initializing signing returns a `PresignatureReused` error if the presignature has already been used to sign a message,
+    /// otherwise the presignature is recorded as consumed, so the consumed presignatures log must be persisted
+    /// alongside the remaining presignatures.
+    pub fn from_presignature(
+        presignature: Presignature,
+        consumed_presignatures: &'c mut ConsumedPresignatures,
+    ) -> Self {
+        Self::with_presigning(PresigningSource::Presignature {
+            presignature,
+            consumed_presignatures,
+        })
+    }
+
+    /// Returns signing options for the given pre-signing output with default settings.
+    fn with_presigning(presigning: PresigningSource<'c>) -> Self {
+        Self {
+            presigning,
+            scheme: SignatureScheme::default(),
+            message_commitment: None,
+        }
+    }
+
+    /// Sets the signature scheme.
+    ///
+    /// **NOTE:** Initializing signing returns an `UnsupportedScheme` error for signature schemes
+    /// without a threshold signing protocol (see [`SignatureScheme`]).
+    pub fn with_scheme(mut self, scheme: SignatureScheme) -> Self {
+        self.scheme = scheme;
+        self
+    }
+
+    /// Sets a message commitment agreed out-of-band
+    /// (e.g so that a coordinator that relays messages between parties never sees the plaintext message).
+    ///
+    /// **NOTE:** Each party verifies the commitment against its locally-held message independently before signing (see [`message_commitment`]),
+    /// so a party with a mismatched local message aborts the session with a `MessageCommitmentMismatch` error
+    /// without producing any signing messages.
+    pub fn with_message_commitment(mut self, message_commitment: [u8; 32]) -> Self {
+        self.message_commitment = Some(message_commitment);
+        self
+    }
+}
+
+/// The pre-signing output to sign with.
+enum PresigningSource<'c> {
+    /// Pre-signing data and `l` in the CGGMP20 paper.
+    Data {
+        presigning_data:
+            HashMap<u16, (PresigningOutput<Secp256k1>, PresigningTranscript<Secp256k1>)>,
+        pre_signing_output_idx: usize,
+    },
+    /// A persisted presignature and the log of consumed presignatures.
+    Presignature {
+        presignature: Presignature,
+        consumed_presignatures: &'c mut ConsumedPresignatures,
+    },
+}
 
 /// A wrapper around the [`cggmp-threshold-ecdsa` Signing StateMachine](https://github.com/webb-tools/cggmp-threshold-ecdsa/blob/main/src/sign/state_machine.rs) that [augments signing as described by the Wamu protocol](https://wamu.tech/specification#signing).
 pub struct AugmentedSigning<'a, I: IdentityProvider> {
@@ -29,20 +146,120 @@ pub struct AugmentedSigning<'a, I: IdentityProvider> {
     /// The decentralized identity provider of the party.
     identity_provider: &'a I,
     /// Verifying keys for other the parties.
-    verified_parties: &'a [VerifyingKey],
+    verified_parties: PartyList<'a>,
     /// A byte representation of the message to be signed.
     message: &'a [u8],
+    /// SHA-256 digest of the application-level context of the signing request (e.g chain id, nonce or memo).
+    context_digest: [u8; 32],
+    /// The party's partial signature share (for optional external aggregation).
+    partial_signature: Option<PartialSignature>,
+    /// Whether or not the party's partial signature share is exported (see [`with_partial_signature_export`](Self::with_partial_signature_export)).
+    export_partial_signature: bool,
+    /// Indices of the other signing parties.
+    other_signers: Vec<u16>,
+    /// Parties that have delivered their message for each round.
+    round_progress: RoundProgress,
+    /// Consecutive augmentation verification failures for each party.
+    augmentation_failures: AugmentationFailures,
+    /// Ceremony-wide deadline (if any).
+    deadline: Option<Instant>,
 }
 
 impl<'a, I: IdentityProvider> AugmentedSigning<'a, I> {
-    /// Initializes party for the augmented signing protocol.
+    /// Initializes party for the augmented signing protocol with the given signing options (see [`SigningOptions`]).
+    ///
+    /// **NOTE:** Verifying keys for the other parties can be either borrowed or shared (see [`PartyList`])
+    /// e.g so that parties held by long-lived async tasks own their set of verified parties.
+    ///
+    /// **NOTE:** The opaque application-level `context` (e.g chain id, nonce or memo) isn't part of the signed message,
+    /// but its digest is bound into the identity authenticated parameters of each party,
+    /// so all parties must use the same context or the session is aborted.
     pub fn new(
         signing_share: &SigningShare,
         sub_share: &SubShare,
         identity_provider: &'a I,
-        verified_parties: &'a [VerifyingKey],
+        verified_parties: impl Into<PartyList<'a>>,
+        message: &'a [u8],
+        context: Vec<u8>,
+        ssid: SSID<Secp256k1>,
+        options: SigningOptions<'_>,
+    ) -> Result<Self, Error<<Signing as StateMachine>::Err>> {
+        // Only signature schemes with a threshold signing protocol are supported (see [`SignatureScheme`]).
+        if options.scheme == SignatureScheme::Schnorr {
+            return Err(wamu_core::CryptoError::UnsupportedScheme.into());
+        }
+
+        // The local message must match the agreed message commitment (if any).
+        if options
+            .message_commitment
+            .is_some_and(|commitment| commitment != message_commitment(message))
+        {
+            return Err(Error::MessageCommitmentMismatch);
+        }
+
+        match options.presigning {
+            PresigningSource::Data {
+                presigning_data,
+                pre_signing_output_idx,
+            } => Self::init(
+                signing_share,
+                sub_share,
+                identity_provider,
+                verified_parties.into(),
+                message,
+                context,
+                ssid,
+                presigning_data,
+                pre_signing_output_idx,
+            ),
+            PresigningSource::Presignature {
+                presignature,
+                consumed_presignatures,
+            } => {
+                // Presignatures must never be reused (i.e reusing a presignature for 2 different messages leaks the secret share).
+                let id = presignature.id();
+                if consumed_presignatures.contains_id(&id) {
+                    return Err(Error::PresignatureReused);
+                }
+
+                // Initializes state machine (no further pre-signing rounds are required).
+                let pre_signing_output_idx = presignature.pre_signing_output_idx;
+                let presigning_data_idx = u16::try_from(pre_signing_output_idx)
+                    .map_err(|_| Error::Core(wamu_core::Error::TooManyParties))?;
+                // NOTE: The wrapped state machine owns a copy of the presignature,
+                // while the (consumed) presignature is zerorized when it's dropped.
+                let aug_signing = Self::init(
+                    signing_share,
+                    sub_share,
+                    identity_provider,
+                    verified_parties.into(),
+                    message,
+                    context,
+                    ssid,
+                    HashMap::from([(
+                        presigning_data_idx,
+                        (presignature.output.clone(), presignature.transcript.clone()),
+                    )]),
+                    pre_signing_output_idx,
+                )?;
+
+                // Records the presignature as consumed.
+                consumed_presignatures.ids.insert(id);
+
+                Ok(aug_signing)
+            }
+        }
+    }
+
+    /// Initializes party for the augmented signing protocol with either borrowed or shared verifying keys.
+    fn init(
+        signing_share: &SigningShare,
+        sub_share: &SubShare,
+        identity_provider: &'a I,
+        verified_parties: PartyList<'a>,
         message: &'a [u8],
-        mut ssid: SSID<Secp256k1>,
+        context: Vec<u8>,
+        ssid: SSID<Secp256k1>,
         presigning_data: HashMap<
             u16,
             (PresigningOutput<Secp256k1>, PresigningTranscript<Secp256k1>),
@@ -50,34 +267,83 @@ impl<'a, I: IdentityProvider> AugmentedSigning<'a, I> {
         // l in the CGGMP20 paper.
         pre_signing_output_idx: usize,
     ) -> Result<Self, Error<<Signing as StateMachine>::Err>> {
-        // Reconstructs secret share.
-        let secret_share = wamu_core::share_split_reconstruct::reconstruct(
+        // Verified parties must be provided (i.e an empty list means no other party is authorized).
+        if verified_parties.is_empty() {
+            return Err(Error::Core(wamu_core::Error::NoVerifiedParties));
+        }
+
+        // The pre-signing output index must fit in a `u16` (i.e the key type of the presigning data).
+        let presigning_data_idx = u16::try_from(pre_signing_output_idx)
+            .map_err(|_| Error::Core(wamu_core::Error::TooManyParties))?;
+
+        // A threshold of zero means that any single party can sign, so it's never allowed.
+        if ssid.X.t == 0 {
+            return Err(Error::Core(wamu_core::Error::InvalidThreshold));
+        }
+
+        // The signing party set must be well-formed.
+        let signers = validate_party_set(&ssid)?;
+
+        // The presignature (if any) must have been computed by exactly the signing parties
+        // (i.e every presigning party's share contributes to the presignature,
+        // so signing with either more or fewer parties produces an invalid signature).
+        if let Some((output, _)) = presigning_data.get(&presigning_data_idx) {
+            let presigners = sorted_party_indices(&output.ssid.P)?;
+            if presigners != signers {
+                return Err(Error::PresignerSetMismatch {
+                    presigners,
+                    signers,
+                });
+            }
+        }
+
+        // Reconstructs and sets the secret share (which is zerorized on both success and error paths).
+        let mut ssid = TransientSecretShare(ssid);
+        ssid.0.X.keys_linear.x_i = augmented_state_machine::reconstruct_secret_scalar(
             signing_share,
             sub_share,
             identity_provider,
         )?;
-        // Sets the reconstructed secret share.
-        ssid.X.keys_linear.x_i = Scalar::<Secp256k1>::from_bytes(&secret_share.to_be_bytes())
-            .map_err(|_| Error::Core(wamu_core::Error::Encoding))?;
 
         // Creates a SHA256 message digest.
         use sha2::Digest;
         let mut hasher = sha2::Sha256::new();
         hasher.update(message);
-        let message_digest = hasher.finalize();
+        let message_digest = BigInt::from_bytes(&hasher.finalize());
+
+        // Computes the party's partial signature share for optional external aggregation.
+        let partial_signature = presigning_data
+            .get(&presigning_data_idx)
+            .and_then(|(output, _)| PartialSignature::new(output, &message_digest));
+
+        // Retrieves the indices of the other signing parties.
+        let other_signers = ssid
+            .0
+            .P
+            .iter()
+            .copied()
+            .filter(|idx| *idx != ssid.0.X.i)
+            .collect();
 
         // Initializes state machine.
         let mut aug_signing = Self {
             state_machine: Signing::new(
-                ssid,
+                ssid.handoff(),
                 pre_signing_output_idx,
-                BigInt::from_bytes(&message_digest),
+                message_digest,
                 presigning_data,
             )?,
             message_queue: Vec::new(),
             identity_provider,
             verified_parties,
             message,
+            context_digest: sha2::Sha256::digest(&context).into(),
+            partial_signature,
+            export_partial_signature: false,
+            other_signers,
+            round_progress: RoundProgress::default(),
+            augmentation_failures: AugmentationFailures::default(),
+            deadline: None,
         };
 
         // Retrieves messages from immediate state transitions (if any) and augments them.
@@ -86,6 +352,62 @@ impl<'a, I: IdentityProvider> AugmentedSigning<'a, I> {
         // Returns augmented state machine.
         Ok(aug_signing)
     }
+
+    /// Returns the SHA-256 digest of the application-level context of the signing request (e.g for audit records).
+    pub fn context_digest(&self) -> [u8; 32] {
+        self.context_digest
+    }
+
+    /// Returns the bytes signed by each party's identity provider
+    /// (i.e the message to be signed bound to the digest of the application-level context).
+    fn request_bytes(&self) -> Vec<u8> {
+        wamu_core::utils::domain_separated_message_bytes(&self.context_digest, self.message)
+    }
+
+    /// Returns the commitment to the set of parties of the party.
+    fn party_set_hash(&self) -> [u8; 32] {
+        augmented_state_machine::party_set_hash(
+            &self.identity_provider.verifying_key(),
+            &self.verified_parties,
+        )
+    }
+
+    /// Enables exporting the party's partial signature share (i.e `sigma_i`) for external aggregation
+    /// (see [`partial_signature`](Self::partial_signature)).
+    ///
+    /// **NOTE:** Partial signature shares aren't exported by default,
+    /// since anyone that collects the partial signature shares of all signing parties can produce the signature.
+    pub fn with_partial_signature_export(mut self) -> Self {
+        self.export_partial_signature = true;
+        self
+    }
+
+    /// Returns the party's partial signature share (i.e `sigma_i`) for external aggregation
+    /// (only if exporting is enabled, see [`with_partial_signature_export`](Self::with_partial_signature_export)).
+    ///
+    /// **NOTE:** The partial signature share is also included as additional output of the protocol (if exporting is enabled).
+    pub fn partial_signature(&self) -> Option<&PartialSignature> {
+        self.partial_signature
+            .as_ref()
+            .filter(|_| self.export_partial_signature)
+    }
+
+    /// Returns the indices of the parties that have delivered their message for the current round (in ascending order).
+    ///
+    /// **NOTE:** `cggmp-threshold-ecdsa` Signing has a single message round (i.e Round 1).
+    pub fn received_parties(&self) -> Vec<u16> {
+        self.round_progress.received_parties(1)
+    }
+
+    /// Returns the indices of the other signing parties whose message for the current round is still outstanding
+    /// (i.e an empty list if the protocol is finished).
+    pub fn pending_parties(&self) -> Vec<u16> {
+        if self.state_machine.is_finished() {
+            return Vec::new();
+        }
+        self.round_progress
+            .pending_parties(1, self.other_signers.iter().copied())
+    }
 }
 
 impl<'a, I: IdentityProvider> AugmentedStateMachine for AugmentedSigning<'a, I> {
@@ -94,7 +416,12 @@ impl<'a, I: IdentityProvider> AugmentedStateMachine for AugmentedSigning<'a, I>
     type AdditionalOutput = AdditionalOutput;
 
     // Implements all required `AugmentedStateMachine` methods.
-    impl_required_augmented_state_machine_methods!(state_machine, message_queue);
+    impl_required_augmented_state_machine_methods!(
+        state_machine,
+        message_queue,
+        augmentation_failures,
+        deadline
+    );
 
     fn pre_handle_incoming(
         &mut self,
@@ -105,17 +432,25 @@ impl<'a, I: IdentityProvider> AugmentedStateMachine for AugmentedSigning<'a, I>
             >,
         >,
     ) -> Result<(), Error<<Self::StateMachineType as StateMachine>::Err>> {
-        match msg.body.base.0 {
+        let result = match msg.body.base.0 {
             // Verifies the expected additional parameters from Round 1.
             // Round 2 of `cggmp-threshold-ecdsa` Signing is the Output phase,
             M::Round1(_) => match msg.body.extra.as_ref() {
                 // Verifies that signer is an expected party/signatory and the signature is valid.
-                Some(params) => Ok(wamu_core::wrappers::verify_request_with_signature(
-                    self.message,
-                    &params.verifying_key,
-                    &params.verifying_signature,
-                    self.verified_parties,
-                )?),
+                // NOTE: Parties that use a different context produce an invalid signature.
+                Some(params) => {
+                    wamu_core::wrappers::verify_request_with_signature(
+                        &IdentityAuthParams::signed_bytes(
+                            &self.request_bytes(),
+                            &params.party_set_hash,
+                        ),
+                        &params.verifying_key,
+                        &params.verifying_signature,
+                        &*self.verified_parties,
+                    )?;
+                    // Verifies that the (authenticated) sender committed to the same set of parties.
+                    params.verify_party_set(msg.sender, &self.party_set_hash())
+                }
                 // Returns an error if expected additional parameters are missing.
                 None => Err(Error::MissingParams {
                     bad_actors: vec![msg.sender as usize],
@@ -123,7 +458,14 @@ impl<'a, I: IdentityProvider> AugmentedStateMachine for AugmentedSigning<'a, I>
             },
             // No modifications for other rounds.
             _ => Ok(()),
+        };
+
+        // Records the sender as having delivered their message for the round (if the message is valid).
+        if result.is_ok() {
+            self.round_progress.record(1, msg.sender);
         }
+
+        result
     }
 
     fn augment_outgoing_message(
@@ -135,24 +477,536 @@ impl<'a, I: IdentityProvider> AugmentedStateMachine for AugmentedSigning<'a, I>
         match msg_body.0 {
             // Adds additional parameters to Round 1 messages.
             M::Round1(_) => {
+                let party_set_hash = self.party_set_hash();
                 let (verifying_key, verifying_signature) =
                     wamu_core::wrappers::initiate_request_with_signature(
-                        self.message,
+                        &IdentityAuthParams::signed_bytes(&self.request_bytes(), &party_set_hash),
                         self.identity_provider,
                     );
                 Ok(Some(IdentityAuthParams {
                     verifying_key,
                     verifying_signature,
+                    party_set_hash,
+                    current_threshold: None,
                 }))
             }
             // No modifications for other rounds.
             _ => Ok(None),
         }
     }
+
+    fn augment_output(
+        &self,
+        output: <Self::StateMachineType as StateMachine>::Output,
+    ) -> Result<
+        AugmentedType<<Self::StateMachineType as StateMachine>::Output, Self::AdditionalOutput>,
+        Error<<Self::StateMachineType as StateMachine>::Err>,
+    > {
+        // Adds the party's partial signature share to the output (if exporting is enabled).
+        Ok(AugmentedType {
+            base: output,
+            extra: self.partial_signature().cloned(),
+        })
+    }
 }
 
-// No additional output.
-type AdditionalOutput = ();
+/// Given a message to be signed, returns a commitment to the message
+/// (i.e the SHA-256 digest of the domain separated message) for agreeing on the message out-of-band.
+///
+/// **NOTE:** The commitment isn't hiding for low-entropy (i.e guessable) messages,
+/// so the message should include a random nonce if it must be hidden from a relaying coordinator.
+pub fn message_commitment(message: &[u8]) -> [u8; 32] {
+    use sha2::Digest;
+    sha2::Sha256::digest(wamu_core::utils::domain_separated_message_bytes(
+        MESSAGE_COMMITMENT_TAG,
+        message,
+    ))
+    .into()
+}
+
+/// Initializes party for the augmented signing protocol for a 2-of-2 key
+/// (i.e `n_parties = 2` and `threshold = 1` e.g for a mobile and desktop device pairing),
+/// returns an error if the key isn't a 2-of-2 key or the signing parties aren't both parties.
+///
+/// **NOTE:** Both parties are always signers (i.e the signing parties must be `[1, 2]`),
+/// so the presigning data must be computed by both parties.
+pub fn sign_two_party<'a, I: IdentityProvider>(
+    signing_share: &SigningShare,
+    sub_share: &SubShare,
+    identity_provider: &'a I,
+    verified_parties: impl Into<PartyList<'a>>,
+    message: &'a [u8],
+    context: Vec<u8>,
+    ssid: SSID<Secp256k1>,
+    options: SigningOptions<'_>,
+) -> Result<AugmentedSigning<'a, I>, Error<<Signing as StateMachine>::Err>> {
+    // The key must be a 2-of-2 key.
+    if ssid.X.t != 1 || ssid.X.n != 2 {
+        return Err(Error::NotTwoPartyKey);
+    }
+
+    // All signing parties must be in the range `1..=2`
+    // (i.e missing or duplicate parties are rejected when the party set is validated).
+    if !ssid.P.iter().all(|idx| (1..=2).contains(idx)) {
+        return Err(Error::BadPartyIndex);
+    }
+
+    // Initializes state machine.
+    AugmentedSigning::new(
+        signing_share,
+        sub_share,
+        identity_provider,
+        verified_parties,
+        message,
+        context,
+        ssid,
+        options,
+    )
+}
+
+/// Given a list of available (e.g online) party indices and the local key of the party,
+/// returns an ok result with a signing party set of size `threshold + 1` (in ascending order and including the party itself)
+/// or an appropriate error result if any index is invalid or fewer than `threshold + 1` parties are available.
+///
+/// **NOTE:** Duplicate indices are ignored, and the lowest available indices of the other parties are selected.
+pub fn select_signers(
+    available: &[u16],
+    local_key: &LocalKey<Secp256k1>,
+) -> Result<Vec<u16>, Error<<Signing as StateMachine>::Err>> {
+    // All indices must be in the range `1..=n_parties`.
+    let is_valid_idx = |idx: &u16| (1..=local_key.n).contains(idx);
+    if !is_valid_idx(&local_key.i) || !available.iter().all(is_valid_idx) {
+        return Err(Error::BadPartyIndex);
+    }
+
+    // The party itself is always a signer.
+    let mut other_signers: Vec<u16> = available
+        .iter()
+        .copied()
+        .filter(|idx| *idx != local_key.i)
+        .collect();
+    other_signers.sort_unstable();
+    other_signers.dedup();
+
+    // Selects `threshold + 1` signers.
+    let need = wamu_core::quorum_size(local_key.t);
+    let have = other_signers.len() + 1;
+    if have < need {
+        return Err(Error::InsufficientSigners { have, need });
+    }
+    let mut signers: Vec<u16> = other_signers
+        .into_iter()
+        .take(need - 1)
+        .chain([local_key.i])
+        .collect();
+    signers.sort_unstable();
+    Ok(signers)
+}
+
+/// Given a `LocalKey<Secp256k1>` (e.g with the secret share cleared/zerorized), a message,
+/// DER encoded threshold signature bytes and the hash function used for signing,
+/// returns an `Ok` result for a valid threshold signature for the message by the group public key of the `LocalKey<Secp256k1>`,
+/// or an appropriate `Err` result otherwise.
+///
+/// **NOTE:** Only the public parts of the `LocalKey<Secp256k1>` are used (see [`wamu_core::signing::verify_threshold_signature`]).
+pub fn verify_against_local_key(
+    local_key: &LocalKey<Secp256k1>,
+    message: &[u8],
+    signature: &[u8],
+    hash: MessageDigest,
+) -> Result<(), wamu_core::Error> {
+    let group_key = GroupKey::try_from(local_key)?;
+    wamu_core::signing::verify_threshold_signature(
+        message,
+        signature,
+        group_key.verifying_key(),
+        hash,
+    )
+}
+
+// Partial signature share as additional output.
+type AdditionalOutput = PartialSignature;
+
+/// An error returned when aggregating partial signature shares (see [`PartialSignature::aggregate`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregationError {
+    /// No partial signature shares were provided.
+    NoPartialSignatures,
+    /// The partial signature shares have different `r` values (i.e they're for different presignatures).
+    RMismatch,
+    /// More than one partial signature share was provided by the same party.
+    DuplicateParty { idx: u16 },
+    /// The aggregated signature is invalid (i.e `s` is zero).
+    InvalidSignature,
+}
+
+impl std::fmt::Display for AggregationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AggregationError::NoPartialSignatures => write!(f, "no partial signature shares"),
+            AggregationError::RMismatch => {
+                write!(f, "partial signature shares have different r values")
+            }
+            AggregationError::DuplicateParty { idx } => {
+                write!(f, "duplicate partial signature share from party {idx}")
+            }
+            AggregationError::InvalidSignature => write!(f, "invalid aggregated signature"),
+        }
+    }
+}
+
+impl std::error::Error for AggregationError {}
+
+/// A party's partial signature share (i.e `sigma_i = k_i * m + r * chi_i`) that can be aggregated externally.
+///
+/// The aggregated signature is (`r`, `s`), where `s` is the sum of the partial signature shares of all signing parties
+/// modulo the Secp256k1 group order (see [`aggregate`](Self::aggregate)).
+///
+/// Wire format (see [`to_bytes`](Self::to_bytes)): `idx` (2 bytes) || `r` (32 bytes) || `sigma_i` (32 bytes),
+/// with all integers encoded as big-endian unsigned integers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialSignature {
+    /// Party index.
+    pub idx: u16,
+    /// The x-coordinate of `R` (i.e the output of pre-signing).
+    pub r: [u8; 32],
+    /// The partial signature share.
+    pub sigma_i: [u8; 32],
+}
+
+impl PartialSignature {
+    /// Size of the wire format in bytes.
+    pub const SIZE: usize = 66;
+
+    /// Given a pre-signing output and a message digest, returns the partial signature share (if any).
+    fn new(output: &PresigningOutput<Secp256k1>, message_digest: &BigInt) -> Option<Self> {
+        let q = Scalar::<Secp256k1>::group_order();
+        // NOTE: The ECDSA `r` value is the x-coordinate of `R` reduced modulo the group order.
+        let r = BigInt::modulus(&output.R.x_coord()?, q);
+        let sigma_i = BigInt::mod_add(
+            &BigInt::mod_mul(&output.k_i, message_digest, q),
+            &BigInt::mod_mul(&r, &output.chi_i, q),
+            q,
+        );
+        Some(Self {
+            idx: output.i,
+            r: to_be_bytes_32(&r)?,
+            sigma_i: to_be_bytes_32(&sigma_i)?,
+        })
+    }
+
+    /// Returns the wire format of the partial signature share.
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0u8; Self::SIZE];
+        bytes[..2].copy_from_slice(&self.idx.to_be_bytes());
+        bytes[2..34].copy_from_slice(&self.r);
+        bytes[34..].copy_from_slice(&self.sigma_i);
+        bytes
+    }
+
+    /// Given partial signature shares from all signing parties,
+    /// returns the aggregated signature as (`r`, `s`) (i.e as 32 byte big-endian unsigned integers)
+    /// or an appropriate [`AggregationError`] (e.g if there are no partial signature shares or they have different `r` values).
+    pub fn aggregate(
+        partial_signatures: &[Self],
+    ) -> Result<([u8; 32], [u8; 32]), AggregationError> {
+        let r = partial_signatures
+            .first()
+            .ok_or(AggregationError::NoPartialSignatures)?
+            .r;
+        if partial_signatures.iter().any(|it| it.r != r) {
+            return Err(AggregationError::RMismatch);
+        }
+        let mut parties = HashSet::with_capacity(partial_signatures.len());
+        if let Some(duplicate) = partial_signatures.iter().find(|it| !parties.insert(it.idx)) {
+            return Err(AggregationError::DuplicateParty { idx: duplicate.idx });
+        }
+        let q = Scalar::<Secp256k1>::group_order();
+        let s = partial_signatures.iter().fold(BigInt::from(0), |acc, it| {
+            BigInt::mod_add(&acc, &BigInt::from_bytes(&it.sigma_i), q)
+        });
+        if s == BigInt::from(0) {
+            return Err(AggregationError::InvalidSignature);
+        }
+        Ok((
+            r,
+            to_be_bytes_32(&s).ok_or(AggregationError::InvalidSignature)?,
+        ))
+    }
+}
+
+impl TryFrom<&[u8]> for PartialSignature {
+    type Error = wamu_core::Error;
+
+    /// Decodes a partial signature share from its wire format.
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        if value.len() != Self::SIZE {
+            return Err(wamu_core::Error::Encoding);
+        }
+        let mut r = [0u8; 32];
+        r.copy_from_slice(&value[2..34]);
+        let mut sigma_i = [0u8; 32];
+        sigma_i.copy_from_slice(&value[34..]);
+        Ok(Self {
+            idx: u16::from_be_bytes([value[0], value[1]]),
+            r,
+            sigma_i,
+        })
+    }
+}
+
+/// A persist-able output of pre-signing (i.e a pre-signing output and transcript for a given `l` in the CGGMP20 paper)
+/// that can be used to sign a single message later (see [`SigningOptions::from_presignature`]).
+///
+/// **NOTE:** Presignatures include secret material (i.e `k_i` and `chi_i`), so they must be stored securely.
+/// Presignatures can't be cloned (i.e signing takes ownership of the presignature),
+/// and the secret material is zerorized when they're dropped.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawPresignature")
+)]
+pub struct Presignature {
+    /// The format version (see [`FORMAT_VERSION`]).
+    format_version: u16,
+    /// l in the CGGMP20 paper.
+    pre_signing_output_idx: usize,
+    /// The pre-signing output.
+    output: PresigningOutput<Secp256k1>,
+    /// The pre-signing transcript.
+    transcript: PresigningTranscript<Secp256k1>,
+}
+
+impl Presignature {
+    /// Given a pre-signing output and transcript (i.e the output of [`AugmentedPreSigning`])
+    /// and the associated `l` in the CGGMP20 paper, returns a presignature.
+    pub fn new(
+        output: PresigningOutput<Secp256k1>,
+        transcript: PresigningTranscript<Secp256k1>,
+        pre_signing_output_idx: usize,
+    ) -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            pre_signing_output_idx,
+            output,
+            transcript,
+        }
+    }
+
+    /// Returns the format version.
+    pub fn format_version(&self) -> u16 {
+        self.format_version
+    }
+
+    /// Returns the indices of the parties that computed the presignature (in ascending order)
+    /// (i.e the only set of signing parties that can use the presignature).
+    pub fn presigners(&self) -> Vec<u16> {
+        let mut presigners = self.output.ssid.P.clone();
+        presigners.sort_unstable();
+        presigners
+    }
+
+    /// Returns a unique identifier for the presignature
+    /// i.e SHA256(`l` (8 bytes) || `idx` (2 bytes) || `R` (compressed SEC1 format)).
+    pub fn id(&self) -> [u8; 32] {
+        use sha2::Digest;
+        let mut hasher = sha2::Sha256::new();
+        hasher.update((self.pre_signing_output_idx as u64).to_be_bytes());
+        hasher.update(self.output.i.to_be_bytes());
+        hasher.update(self.output.R.to_bytes(true));
+        hasher.finalize().into()
+    }
+}
+
+impl Zeroize for Presignature {
+    fn zeroize(&mut self) {
+        self.output.k_i.zeroize();
+        self.output.chi_i.zeroize();
+        self.output.ssid.clear_secret_share();
+    }
+}
+
+impl Drop for Presignature {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+// The secret material is zerorized when it's dropped.
+impl ZeroizeOnDrop for Presignature {}
+
+/// Unvalidated presignature (i.e as deserialized).
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawPresignature {
+    // Presignatures serialized before format versions were introduced have no format version.
+    #[serde(default)]
+    format_version: u16,
+    pre_signing_output_idx: usize,
+    output: PresigningOutput<Secp256k1>,
+    transcript: PresigningTranscript<Secp256k1>,
+}
+
+// The layout of legacy presignatures is otherwise unchanged.
+#[cfg(feature = "serde")]
+impl crate::format_version::Migrate for RawPresignature {
+    fn format_version(&self) -> u16 {
+        self.format_version
+    }
+
+    fn format_version_mut(&mut self) -> &mut u16 {
+        &mut self.format_version
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawPresignature> for Presignature {
+    type Error = crate::format_version::FormatVersionError;
+
+    /// Migrates deserialized presignatures in known older format versions and rejects unknown format versions.
+    fn try_from(raw: RawPresignature) -> Result<Self, Self::Error> {
+        let raw = crate::format_version::upgrade(raw)?;
+        Ok(Self {
+            format_version: raw.format_version,
+            pre_signing_output_idx: raw.pre_signing_output_idx,
+            output: raw.output,
+            transcript: raw.transcript,
+        })
+    }
+}
+
+/// A log of consumed presignatures (i.e presignatures that have already been used to sign a message).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawConsumedPresignatures")
+)]
+pub struct ConsumedPresignatures {
+    /// The format version (see [`FORMAT_VERSION`]).
+    format_version: u16,
+    /// Identifiers of consumed presignatures (see [`Presignature::id`]).
+    ids: HashSet<[u8; 32]>,
+}
+
+impl Default for ConsumedPresignatures {
+    fn default() -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            ids: HashSet::new(),
+        }
+    }
+}
+
+/// Unvalidated log of consumed presignatures (i.e as deserialized).
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawConsumedPresignatures {
+    // Logs serialized before format versions were introduced have no format version.
+    #[serde(default)]
+    format_version: u16,
+    ids: HashSet<[u8; 32]>,
+}
+
+// The layout of legacy logs is otherwise unchanged.
+#[cfg(feature = "serde")]
+impl crate::format_version::Migrate for RawConsumedPresignatures {
+    fn format_version(&self) -> u16 {
+        self.format_version
+    }
+
+    fn format_version_mut(&mut self) -> &mut u16 {
+        &mut self.format_version
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawConsumedPresignatures> for ConsumedPresignatures {
+    type Error = crate::format_version::FormatVersionError;
+
+    /// Migrates deserialized logs in known older format versions and rejects unknown format versions.
+    fn try_from(raw: RawConsumedPresignatures) -> Result<Self, Self::Error> {
+        let raw = crate::format_version::upgrade(raw)?;
+        Ok(Self {
+            format_version: raw.format_version,
+            ids: raw.ids,
+        })
+    }
+}
+
+impl ConsumedPresignatures {
+    /// Returns the format version.
+    pub fn format_version(&self) -> u16 {
+        self.format_version
+    }
+
+    /// Returns true if the presignature has already been used to sign a message.
+    pub fn contains(&self, presignature: &Presignature) -> bool {
+        self.contains_id(&presignature.id())
+    }
+
+    /// Returns true if the presignature identifier has already been used to sign a message.
+    fn contains_id(&self, id: &[u8; 32]) -> bool {
+        self.ids.contains(id)
+    }
+}
+
+/// Given the SSID of a (pre-)signing party, returns the indices of the (pre-)signing parties in ascending order
+/// for a well-formed party set or an appropriate error otherwise
+/// (e.g `InsufficientSigners` for fewer than `threshold + 1` parties,
+/// since the protocol would otherwise wait for messages from parties that will never send them).
+fn validate_party_set<T: IsCritical>(ssid: &SSID<Secp256k1>) -> Result<Vec<u16>, Error<T>> {
+    // The party set must not include duplicate parties.
+    let parties = sorted_party_indices(&ssid.P)?;
+
+    // The party itself must be included in the party set.
+    if !parties.contains(&ssid.X.i) {
+        return Err(Error::BadPartyIndex);
+    }
+
+    // The party set must include at least `threshold + 1` parties.
+    let need = wamu_core::quorum_size(ssid.X.t);
+    let have = parties.len();
+    if have < need {
+        return Err(Error::InsufficientSigners { have, need });
+    }
+
+    Ok(parties)
+}
+
+/// Given a list of party indices, returns the party indices in ascending order,
+/// or a `DuplicateParty` error if any party index appears more than once (i.e a malformed party set).
+fn sorted_party_indices(indices: &[u16]) -> Result<Vec<u16>, wamu_core::Error> {
+    let mut sorted = indices.to_vec();
+    sorted.sort_unstable();
+    if sorted.windows(2).any(|pair| pair[0] == pair[1]) {
+        Err(wamu_core::Error::DuplicateParty)
+    } else {
+        Ok(sorted)
+    }
+}
+
+/// Given a non-negative integer, returns its 32 byte big-endian representation (if it fits).
+fn to_be_bytes_32(value: &BigInt) -> Option<[u8; 32]> {
+    let bytes = value.to_bytes();
+    (bytes.len() <= 32).then(|| {
+        let mut padded = [0u8; 32];
+        padded[32 - bytes.len()..].copy_from_slice(&bytes);
+        padded
+    })
+}
+
+impl<'a, I: IdentityProvider> PartyIdentity for AugmentedSigning<'a, I> {
+    fn party_id(&self) -> PartyId {
+        PartyId::from_verifying_key(&self.identity_provider.verifying_key())
+    }
+}
+
+impl<'a, I: IdentityProvider> RoundLabel for AugmentedSigning<'a, I> {
+    fn round_label(&self) -> String {
+        format!("Signing Round {}", self.state_machine.current_round())
+    }
+}
 
 // Implements `StateMachine` trait for `AugmentedSigning`.
 impl_state_machine_for_augmented_state_machine!(
@@ -172,7 +1026,13 @@ pub struct AugmentedPreSigning<'a, I: IdentityProvider> {
     /// The decentralized identity provider of the party.
     identity_provider: &'a I,
     /// Verifying keys for other the parties.
-    verified_parties: &'a [VerifyingKey],
+    verified_parties: PartyList<'a>,
+    /// Index of the pre-signing output (i.e l in the CGGMP20 paper).
+    pre_signing_output_idx: usize,
+    /// Consecutive augmentation verification failures for each party.
+    augmentation_failures: AugmentationFailures,
+    /// Ceremony-wide deadline (if any).
+    deadline: Option<Instant>,
 }
 
 impl<'a, I: IdentityProvider> AugmentedPreSigning<'a, I> {
@@ -182,7 +1042,7 @@ impl<'a, I: IdentityProvider> AugmentedPreSigning<'a, I> {
         sub_share: &SubShare,
         identity_provider: &'a I,
         verified_parties: &'a [VerifyingKey],
-        mut ssid: SSID<Secp256k1>,
+        ssid: SSID<Secp256k1>,
         secrets: PreSigningSecrets,
         aux_ring_pedersen_s_values: HashMap<u16, BigInt>,
         aux_ring_pedersen_t_values: HashMap<u16, BigInt>,
@@ -190,57 +1050,218 @@ impl<'a, I: IdentityProvider> AugmentedPreSigning<'a, I> {
         // l in the CGGMP20 paper.
         pre_signing_output_idx: usize,
     ) -> Result<Self, Error<<PreSigning as StateMachine>::Err>> {
-        // Reconstructs secret share.
-        let secret_share = wamu_core::share_split_reconstruct::reconstruct(
+        Self::init(
             signing_share,
             sub_share,
             identity_provider,
-        )?;
-        // Sets the reconstructed secret share.
-        ssid.X.keys_linear.x_i = Scalar::<Secp256k1>::from_bytes(&secret_share.to_be_bytes())
-            .map_err(|_| Error::Core(wamu_core::Error::Encoding))?;
-
-        // Initializes state machine.
-        let mut aug_signing = Self {
-            state_machine: PreSigning::new(
-                ssid,
-                secrets,
-                aux_ring_pedersen_s_values,
-                aux_ring_pedersen_t_values,
-                aux_ring_pedersen_n_hat_values,
-                pre_signing_output_idx,
-            )?,
-            message_queue: Vec::new(),
-            identity_provider,
-            verified_parties,
-        };
-
-        // Retrieves messages from immediate state transitions (if any) and augments them.
-        aug_signing.update_augmented_message_queue()?;
-
-        // Returns augmented state machine.
-        Ok(aug_signing)
+            verified_parties.into(),
+            ssid,
+            secrets,
+            aux_ring_pedersen_s_values,
+            aux_ring_pedersen_t_values,
+            aux_ring_pedersen_n_hat_values,
+            pre_signing_output_idx,
+        )
     }
-}
+
+    /// Initializes party for the augmented pre-signing protocol with a shared (i.e reference counted) list of verifying keys,
+    /// so that the party owns its set of verified parties (e.g for parties held by long-lived async tasks).
+    pub fn new_shared(
+        signing_share: &SigningShare,
+        sub_share: &SubShare,
+        identity_provider: &'a I,
+        verified_parties: Arc<[VerifyingKey]>,
+        ssid: SSID<Secp256k1>,
+        secrets: PreSigningSecrets,
+        aux_ring_pedersen_s_values: HashMap<u16, BigInt>,
+        aux_ring_pedersen_t_values: HashMap<u16, BigInt>,
+        aux_ring_pedersen_n_hat_values: HashMap<u16, BigInt>,
+        // l in the CGGMP20 paper.
+        pre_signing_output_idx: usize,
+    ) -> Result<Self, Error<<PreSigning as StateMachine>::Err>> {
+        Self::init(
+            signing_share,
+            sub_share,
+            identity_provider,
+            verified_parties.into(),
+            ssid,
+            secrets,
+            aux_ring_pedersen_s_values,
+            aux_ring_pedersen_t_values,
+            aux_ring_pedersen_n_hat_values,
+            pre_signing_output_idx,
+        )
+    }
+
+    /// Initializes party for the augmented pre-signing protocol with either borrowed or shared verifying keys.
+    fn init(
+        signing_share: &SigningShare,
+        sub_share: &SubShare,
+        identity_provider: &'a I,
+        verified_parties: PartyList<'a>,
+        ssid: SSID<Secp256k1>,
+        secrets: PreSigningSecrets,
+        aux_ring_pedersen_s_values: HashMap<u16, BigInt>,
+        aux_ring_pedersen_t_values: HashMap<u16, BigInt>,
+        aux_ring_pedersen_n_hat_values: HashMap<u16, BigInt>,
+        // l in the CGGMP20 paper.
+        pre_signing_output_idx: usize,
+    ) -> Result<Self, Error<<PreSigning as StateMachine>::Err>> {
+        // Verified parties must be provided (i.e an empty list means no other party is authorized).
+        if verified_parties.is_empty() {
+            return Err(Error::Core(wamu_core::Error::NoVerifiedParties));
+        }
+
+        // A threshold of zero means that any single party can sign, so it's never allowed.
+        if ssid.X.t == 0 {
+            return Err(Error::Core(wamu_core::Error::InvalidThreshold));
+        }
+
+        // The pre-signing party set must be well-formed.
+        validate_party_set(&ssid)?;
+
+        // Reconstructs and sets the secret share (which is zerorized on both success and error paths).
+        let mut ssid = TransientSecretShare(ssid);
+        ssid.0.X.keys_linear.x_i = augmented_state_machine::reconstruct_secret_scalar(
+            signing_share,
+            sub_share,
+            identity_provider,
+        )?;
+
+        // Initializes state machine.
+        let mut aug_signing = Self {
+            state_machine: PreSigning::new(
+                ssid.handoff(),
+                secrets,
+                aux_ring_pedersen_s_values,
+                aux_ring_pedersen_t_values,
+                aux_ring_pedersen_n_hat_values,
+                pre_signing_output_idx,
+            )?,
+            message_queue: Vec::new(),
+            identity_provider,
+            verified_parties,
+            pre_signing_output_idx,
+            augmentation_failures: AugmentationFailures::default(),
+            deadline: None,
+        };
+
+        // Retrieves messages from immediate state transitions (if any) and augments them.
+        aug_signing.update_augmented_message_queue()?;
+
+        // Returns augmented state machine.
+        Ok(aug_signing)
+    }
+
+    /// Returns the commitment to the set of parties of the party.
+    fn party_set_hash(&self) -> [u8; 32] {
+        augmented_state_machine::party_set_hash(
+            &self.identity_provider.verifying_key(),
+            &self.verified_parties,
+        )
+    }
+
+    /// Given the index of the sender, returns the parameters signed by the sender
+    /// (i.e the sender index and the index of the pre-signing output).
+    fn parameter_hash(&self, sender: u16) -> Vec<u8> {
+        let mut parameters = Vec::with_capacity(2 + 8);
+        parameters.extend_from_slice(&sender.to_be_bytes());
+        parameters.extend_from_slice(&(self.pre_signing_output_idx as u64).to_be_bytes());
+        wamu_core::utils::domain_separated_message_bytes(PRE_SIGNING_PARAMETERS_TAG, &parameters)
+    }
+}
 
 impl<'a, I: IdentityProvider> AugmentedStateMachine for AugmentedPreSigning<'a, I> {
     type StateMachineType = PreSigning;
-    type AdditionalParams = ();
+    type AdditionalParams = AdditionalParams;
     type AdditionalOutput = ();
 
     // Implements all required `AugmentedStateMachine` methods.
-    impl_required_augmented_state_machine_methods!(state_machine, message_queue);
+    impl_required_augmented_state_machine_methods!(
+        state_machine,
+        message_queue,
+        augmentation_failures,
+        deadline
+    );
+
+    fn pre_handle_incoming(
+        &mut self,
+        msg: &Msg<
+            AugmentedType<
+                <Self::StateMachineType as StateMachine>::MessageBody,
+                Self::AdditionalParams,
+            >,
+        >,
+    ) -> Result<(), Error<<Self::StateMachineType as StateMachine>::Err>> {
+        match msg.body.extra.as_ref() {
+            // Verifies that signer is an expected party/signatory and the signature is valid.
+            Some(params) => {
+                wamu_core::wrappers::verify_request_with_signature(
+                    &IdentityAuthParams::signed_bytes(
+                        &self.parameter_hash(msg.sender),
+                        &params.party_set_hash,
+                    ),
+                    &params.verifying_key,
+                    &params.verifying_signature,
+                    &*self.verified_parties,
+                )?;
+                // Verifies that the (authenticated) sender committed to the same set of parties.
+                params.verify_party_set(msg.sender, &self.party_set_hash())
+            }
+            // Returns an error if expected additional parameters are missing.
+            None => Err(Error::MissingParams {
+                bad_actors: vec![msg.sender as usize],
+            }),
+        }
+    }
+
+    fn augment_outgoing_message(
+        &self,
+        sender: u16,
+        _: &<Self::StateMachineType as StateMachine>::MessageBody,
+    ) -> Result<Option<Self::AdditionalParams>, Error<<Self::StateMachineType as StateMachine>::Err>>
+    {
+        // Adds additional parameters to all messages
+        // (i.e so that every message is attributable to a verified party with the same set of parties).
+        let party_set_hash = self.party_set_hash();
+        let (verifying_key, verifying_signature) =
+            wamu_core::wrappers::initiate_request_with_signature(
+                &IdentityAuthParams::signed_bytes(&self.parameter_hash(sender), &party_set_hash),
+                self.identity_provider,
+            );
+        Ok(Some(IdentityAuthParams {
+            verifying_key,
+            verifying_signature,
+            party_set_hash,
+            current_threshold: None,
+        }))
+    }
+}
+
+// Identity authentication params.
+type AdditionalParams = IdentityAuthParams;
+
+// No additional output.
+type PreSigningAdditionalOutput = ();
+
+impl<'a, I: IdentityProvider> PartyIdentity for AugmentedPreSigning<'a, I> {
+    fn party_id(&self) -> PartyId {
+        PartyId::from_verifying_key(&self.identity_provider.verifying_key())
+    }
 }
 
-// No additional params.
-type AdditionalParams = ();
+impl<'a, I: IdentityProvider> RoundLabel for AugmentedPreSigning<'a, I> {
+    fn round_label(&self) -> String {
+        format!("Pre-signing Round {}", self.state_machine.current_round())
+    }
+}
 
 // Implements `StateMachine` trait for `AugmentedSigning`.
 impl_state_machine_for_augmented_state_machine!(
     AugmentedPreSigning,
     PreSigning,
     AdditionalParams,
-    AdditionalOutput
+    PreSigningAdditionalOutput
 );
 
 // Implement `Debug` trait for `AugmentedSigning` for test simulations.
@@ -264,17 +1285,39 @@ pub mod tests {
     use crate::augmented_state_machine::SubShareOutput;
     use cggmp_threshold_ecdsa::sign::SigningOutput;
     use cggmp_threshold_ecdsa::utilities::sha2::Sha256;
-    use curv::arithmetic::traits::{Modulo, One, Samplable};
+    use curv::arithmetic::traits::{Modulo, One, Samplable, Zero};
     use curv::arithmetic::Integer;
     use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
     use curv::elliptic::curves::{Point, Scalar};
     use fs_dkr::ring_pedersen_proof::RingPedersenStatement;
     use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen::LocalKey;
     use round_based::dev::Simulation;
+    use round_based::IsCritical;
     use wamu_core::test_utils::MockECDSAIdentityProvider;
 
     use super::*;
+    use crate::augmented_state_machine::GroupKey;
     use crate::keygen::tests::simulate_keygen;
+    use crate::keygen::AugmentedKeyGen;
+    use crate::test_utils::{ChaosConfig, ChaosError, ChaosRouter};
+    use wamu_core::crypto::{EllipticCurve, MessageDigest, SignatureAlgorithm, SignatureEncoding};
+
+    /// Returns the DER encoded signature (with "low S" normalization) for a signing output.
+    pub fn to_der_signature(output: &SigningOutput<Secp256k1>) -> wamu_core::crypto::Signature {
+        let signature = k256::ecdsa::Signature::from_scalars(
+            to_be_bytes_32(&output.r).unwrap(),
+            to_be_bytes_32(&output.sigma).unwrap(),
+        )
+        .unwrap();
+        let signature = signature.normalize_s().unwrap_or(signature);
+        wamu_core::crypto::Signature {
+            sig: signature.to_der().as_bytes().to_vec(),
+            algo: SignatureAlgorithm::ECDSA,
+            curve: EllipticCurve::Secp256k1,
+            hash: MessageDigest::SHA256,
+            enc: SignatureEncoding::DER,
+        }
+    }
 
     pub fn simulate_sign(
         keys_and_pre_signing_output: Vec<(
@@ -308,11 +1351,12 @@ pub mod tests {
                     identity_provider,
                     &verifying_keys,
                     message,
+                    Vec::new(),
                     ssid.clone(),
-                    pre_signing_data.clone(),
-                    pre_signing_output_idx,
+                    SigningOptions::new(pre_signing_data.clone(), pre_signing_output_idx),
                 )
-                .unwrap(),
+                .unwrap()
+                .with_partial_signature_export(),
             );
         }
 
@@ -335,7 +1379,7 @@ pub mod tests {
     ) -> Vec<
         AugmentedType<
             Option<(PresigningOutput<Secp256k1>, PresigningTranscript<Secp256k1>)>,
-            AdditionalOutput,
+            PreSigningAdditionalOutput,
         >,
     > {
         // Creates simulation.
@@ -637,15 +1681,1100 @@ pub mod tests {
         (keys, identity_providers, results)
     }
 
-    // All parties (2/2 signing).
     #[test]
-    fn sign_all_parties_works() {
-        generate_parties_and_simulate_signing(1, 2, 2);
+    fn group_key_works() {
+        // Runs signing simulation.
+        let (keys, _, results) = generate_parties_and_simulate_signing(1, 2, 2);
+
+        // Extracts group key from `LocalKey<Secp256k1>`.
+        let group_key = keys[0].group_key().unwrap();
+        assert_eq!(group_key, GroupKey::try_from(&keys[1].base).unwrap());
+
+        // Verifies signature (encoded in DER format) against group key.
+        assert!(wamu_core::crypto::verify_signature(
+            group_key.verifying_key(),
+            b"Hello, world!",
+            &to_der_signature(results[0].base.as_ref().unwrap()),
+        )
+        .is_ok());
     }
 
-    // Threshold signing (subset of parties) - (3/4 signing).
     #[test]
-    fn sign_threshold_works() {
-        generate_parties_and_simulate_signing(2, 4, 3);
+    fn verify_threshold_signature_works() {
+        // Runs signing simulation.
+        let (keys, _, results) = generate_parties_and_simulate_signing(1, 2, 2);
+        let group_key = keys[0].group_key().unwrap();
+
+        // Encodes signature in DER format (i.e without "low S" normalization).
+        let (r, s) = results[0]
+            .base
+            .as_ref()
+            .map(|it| (it.r.clone(), it.sigma.clone()))
+            .unwrap();
+        let signature = k256::ecdsa::Signature::from_scalars(
+            to_be_bytes_32(&r).unwrap(),
+            to_be_bytes_32(&s).unwrap(),
+        )
+        .unwrap();
+
+        // Verifies signature against group key using only `wamu-core`.
+        assert!(wamu_core::signing::verify_threshold_signature(
+            b"Hello, world!",
+            signature.to_der().as_bytes(),
+            group_key.verifying_key(),
+            MessageDigest::SHA256,
+        )
+        .is_ok());
+        assert!(wamu_core::signing::verify_threshold_signature(
+            b"Goodbye, world!",
+            signature.to_der().as_bytes(),
+            group_key.verifying_key(),
+            MessageDigest::SHA256,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn verify_against_local_key_works() {
+        // Runs signing simulation.
+        let (keys, _, results) = generate_parties_and_simulate_signing(1, 3, 2);
+
+        // Encodes signature in DER format.
+        let (r, s) = results[0]
+            .base
+            .as_ref()
+            .map(|it| (it.r.clone(), it.sigma.clone()))
+            .unwrap();
+        let signature = k256::ecdsa::Signature::from_scalars(
+            to_be_bytes_32(&r).unwrap(),
+            to_be_bytes_32(&s).unwrap(),
+        )
+        .unwrap();
+        let signature_bytes = signature.to_der();
+
+        // Generates a `LocalKey<Secp256k1>` for a different group public key.
+        let (other_keys, _) = simulate_keygen(1, 2);
+
+        for (local_key, message, expected_result) in [
+            // The `LocalKey<Secp256k1>` of any party and the signed message should be ok.
+            (&keys[0].base, &b"Hello, world!"[..], true),
+            (&keys[2].base, &b"Hello, world!"[..], true),
+            // A different message should fail.
+            (&keys[0].base, &b"Goodbye, world!"[..], false),
+            // A different group public key should fail.
+            (&other_keys[0].base, &b"Hello, world!"[..], false),
+        ] {
+            // Verifies that the secret share is cleared/zerorized.
+            assert_eq!(local_key.keys_linear.x_i, Scalar::<Secp256k1>::zero());
+
+            // Verifies expected result.
+            assert_eq!(
+                verify_against_local_key(
+                    local_key,
+                    message,
+                    signature_bytes.as_bytes(),
+                    MessageDigest::SHA256,
+                )
+                .is_ok(),
+                expected_result
+            );
+        }
+    }
+
+    #[test]
+    fn two_party_keygen_and_signing_works() {
+        // Creates identity providers and a list of verifying keys for both parties.
+        let identity_providers: Vec<MockECDSAIdentityProvider> = (0..2)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Runs key gen simulation with the two-party helper.
+        let mut simulation = Simulation::new();
+        for (idx, identity_provider) in identity_providers.iter().enumerate() {
+            simulation.add_party(
+                AugmentedKeyGen::two_party(
+                    identity_provider,
+                    &verifying_keys,
+                    wamu_core::party_index(idx).unwrap(),
+                )
+                .unwrap(),
+            );
+        }
+        let keys = simulation.run().unwrap();
+        for key in &keys {
+            // Verifies threshold and number of parties.
+            assert_eq!((key.base.t, key.base.n), (1, 2));
+        }
+        let group_key = keys[0].group_key().unwrap();
+
+        // Runs pre-signing simulation.
+        let pre_signing_output_idx = 1; // l in the CGGMP20 paper.
+        let pre_sign_inputs = generate_pre_sign_input(&keys, &identity_providers, 2);
+        let ssids: Vec<SSID<Secp256k1>> = pre_sign_inputs
+            .iter()
+            .map(|(_, _, _, ssid, ..)| ssid.clone())
+            .collect();
+        let pre_sign_results = simulate_pre_sign(pre_sign_inputs, pre_signing_output_idx);
+
+        // Runs signing simulation with the two-party helper.
+        let message = b"Hello, world!";
+        let mut simulation = Simulation::new();
+        for result in pre_sign_results {
+            let (output, transcript) = result.base.unwrap();
+            let idx = output.i as usize - 1;
+            let (signing_share, sub_share) = keys[idx].extra.as_ref().unwrap();
+            simulation.add_party(
+                sign_two_party(
+                    signing_share,
+                    sub_share,
+                    &identity_providers[idx],
+                    &verifying_keys,
+                    message,
+                    Vec::new(),
+                    ssids[idx].clone(),
+                    SigningOptions::new(
+                        HashMap::from([(pre_signing_output_idx as u16, (output, transcript))]),
+                        pre_signing_output_idx,
+                    ),
+                )
+                .unwrap(),
+            );
+        }
+        let results = simulation.run().unwrap();
+
+        // Verifies signature against group key.
+        let (r, s) = results[0]
+            .base
+            .as_ref()
+            .map(|it| (it.r.clone(), it.sigma.clone()))
+            .unwrap();
+        let signature = k256::ecdsa::Signature::from_scalars(
+            to_be_bytes_32(&r).unwrap(),
+            to_be_bytes_32(&s).unwrap(),
+        )
+        .unwrap();
+        assert!(wamu_core::signing::verify_threshold_signature(
+            message,
+            signature.to_der().as_bytes(),
+            group_key.verifying_key(),
+            MessageDigest::SHA256,
+        )
+        .is_ok());
+
+        // Verifies that the two-party signing helper rejects signing parties other than both parties
+        // (i.e the party set is validated instead of overridden).
+        for (signing_share, sub_share, identity_provider, ssid, ..) in
+            generate_pre_sign_input(&keys, &identity_providers, 2)
+        {
+            let init_with_parties = |parties: Vec<u16>| {
+                let mut ssid = ssid.clone();
+                ssid.P = parties;
+                sign_two_party(
+                    signing_share,
+                    sub_share,
+                    identity_provider,
+                    &verifying_keys,
+                    message,
+                    Vec::new(),
+                    ssid,
+                    SigningOptions::new(HashMap::new(), pre_signing_output_idx),
+                )
+            };
+            assert!(matches!(
+                init_with_parties(vec![ssid.X.i]),
+                Err(Error::InsufficientSigners { have: 1, need: 2 })
+            ));
+            assert!(matches!(
+                init_with_parties(vec![1, 3]),
+                Err(Error::BadPartyIndex)
+            ));
+            assert!(matches!(
+                init_with_parties(vec![1, 2, 2]),
+                Err(Error::Core(wamu_core::Error::DuplicateParty))
+            ));
+        }
+
+        // Verifies that the two-party signing helper rejects keys that aren't 2-of-2 keys.
+        let (keys, identity_providers) = simulate_keygen(1, 3);
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        for (signing_share, sub_share, identity_provider, ssid, ..) in
+            generate_pre_sign_input(&keys, &identity_providers, 2)
+        {
+            assert!(matches!(
+                sign_two_party(
+                    signing_share,
+                    sub_share,
+                    identity_provider,
+                    &verifying_keys,
+                    message,
+                    Vec::new(),
+                    ssid,
+                    SigningOptions::new(HashMap::new(), pre_signing_output_idx),
+                ),
+                Err(Error::NotTwoPartyKey)
+            ));
+        }
+    }
+
+    #[test]
+    fn select_signers_works() {
+        // Runs key gen simulation (i.e 3 signers are required).
+        let (keys, _) = simulate_keygen(2, 5);
+        let local_key = &keys[0].base;
+
+        for (available, expected_signers) in [
+            // Available parties including the party itself.
+            (vec![5, 3, 1, 4], vec![1, 3, 4]),
+            // Available parties excluding the party itself.
+            (vec![5, 4], vec![1, 4, 5]),
+            // Duplicate indices are ignored.
+            (vec![2, 2, 3], vec![1, 2, 3]),
+        ] {
+            // Verifies expected signing party set.
+            assert_eq!(
+                select_signers(&available, local_key).unwrap(),
+                expected_signers
+            );
+        }
+
+        for (available, expected_have) in [(vec![2], 2), (vec![2, 2, 1], 2), (vec![], 1)] {
+            // Verifies that insufficient available parties fail.
+            assert!(matches!(
+                select_signers(&available, local_key),
+                Err(Error::InsufficientSigners { have, need: 3 }) if have == expected_have
+            ));
+        }
+
+        for available in [vec![0, 2, 3], vec![2, 3, 6]] {
+            // Verifies that invalid party indices fail.
+            assert!(matches!(
+                select_signers(&available, local_key),
+                Err(Error::BadPartyIndex)
+            ));
+        }
+    }
+
+    #[test]
+    fn partial_signature_aggregation_works() {
+        // Runs signing simulation.
+        let (_, _, results) = generate_parties_and_simulate_signing(2, 4, 3);
+
+        // Extracts the aggregated signature from the results.
+        let (r, s) = results[0]
+            .base
+            .as_ref()
+            .map(|it| (it.r.clone(), it.sigma.clone()))
+            .unwrap();
+
+        // Exports partial signature shares in wire format and decodes them.
+        let partial_signatures: Vec<PartialSignature> = results
+            .iter()
+            .map(|it| {
+                PartialSignature::try_from(it.extra.as_ref().unwrap().to_bytes().as_slice())
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(partial_signatures.len(), 3);
+
+        // Verifies that external aggregation reproduces the state machine's signature.
+        assert_eq!(
+            PartialSignature::aggregate(&partial_signatures).unwrap(),
+            (to_be_bytes_32(&r).unwrap(), to_be_bytes_32(&s).unwrap())
+        );
+        // Verifies that aggregating a subset of the partial signature shares doesn't.
+        assert_ne!(
+            PartialSignature::aggregate(&partial_signatures[..2]).unwrap(),
+            (to_be_bytes_32(&r).unwrap(), to_be_bytes_32(&s).unwrap())
+        );
+
+        // Verifies that invalid sets of partial signature shares are rejected.
+        let mut mismatched_r = partial_signatures.clone();
+        mismatched_r[1].r = [1; 32];
+        let mut duplicate = partial_signatures.clone();
+        duplicate[1] = duplicate[0].clone();
+        let duplicate_idx = duplicate[0].idx;
+        for (partial_signatures, expected_error) in [
+            (Vec::new(), AggregationError::NoPartialSignatures),
+            (mismatched_r, AggregationError::RMismatch),
+            (
+                duplicate,
+                AggregationError::DuplicateParty { idx: duplicate_idx },
+            ),
+        ] {
+            assert_eq!(
+                PartialSignature::aggregate(&partial_signatures),
+                Err(expected_error)
+            );
+        }
+    }
+
+    #[test]
+    fn partial_signature_export_is_opt_in() {
+        // Runs key gen and pre-signing simulations.
+        let (threshold, n_parties) = (1, 2);
+        let (keys, identity_providers) = simulate_keygen(threshold, n_parties);
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let pre_signing_output_idx = 1; // l in the CGGMP20 paper.
+        let pre_sign_inputs = generate_pre_sign_input(&keys, &identity_providers, n_parties);
+        let ssids: Vec<SSID<Secp256k1>> = pre_sign_inputs
+            .iter()
+            .map(|(_, _, _, ssid, ..)| ssid.clone())
+            .collect();
+        let pre_sign_results = simulate_pre_sign(pre_sign_inputs, pre_signing_output_idx);
+        let (output, transcript) = pre_sign_results[0].base.clone().unwrap();
+        let idx = output.i as usize - 1;
+        let (signing_share, sub_share) = keys[idx].extra.as_ref().unwrap();
+
+        for export_partial_signature in [false, true] {
+            let mut party = AugmentedSigning::new(
+                signing_share,
+                sub_share,
+                &identity_providers[idx],
+                &verifying_keys,
+                b"Hello, world!",
+                Vec::new(),
+                ssids[idx].clone(),
+                SigningOptions::new(
+                    HashMap::from([(
+                        pre_signing_output_idx as u16,
+                        (output.clone(), transcript.clone()),
+                    )]),
+                    pre_signing_output_idx,
+                ),
+            )
+            .unwrap();
+            if export_partial_signature {
+                party = party.with_partial_signature_export();
+            }
+
+            // Verifies that the partial signature share is only exported if enabled.
+            assert_eq!(
+                party.partial_signature().is_some(),
+                export_partial_signature
+            );
+        }
+    }
+
+    #[test]
+    fn zero_threshold_fails() {
+        // Runs key gen simulation and generates pre-signing inputs.
+        let (keys, identity_providers) = simulate_keygen(1, 2);
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let pre_signing_output_idx = 1; // l in the CGGMP20 paper.
+
+        for (
+            signing_share,
+            sub_share,
+            identity_provider,
+            mut ssid,
+            secrets,
+            aux_ring_pedersen_s_values,
+            aux_ring_pedersen_t_values,
+            aux_ring_pedersen_n_hat_values,
+        ) in generate_pre_sign_input(&keys, &identity_providers, 2)
+        {
+            // Sets a threshold of zero.
+            ssid.X.t = 0;
+
+            // Verifies that a threshold of zero is rejected by both pre-signing and signing.
+            assert!(matches!(
+                AugmentedPreSigning::new(
+                    signing_share,
+                    sub_share,
+                    identity_provider,
+                    &verifying_keys,
+                    ssid.clone(),
+                    secrets,
+                    aux_ring_pedersen_s_values,
+                    aux_ring_pedersen_t_values,
+                    aux_ring_pedersen_n_hat_values,
+                    pre_signing_output_idx,
+                ),
+                Err(Error::Core(wamu_core::Error::InvalidThreshold))
+            ));
+            assert!(matches!(
+                AugmentedSigning::new(
+                    signing_share,
+                    sub_share,
+                    identity_provider,
+                    &verifying_keys,
+                    b"Hello, world!",
+                    Vec::new(),
+                    ssid,
+                    SigningOptions::new(HashMap::new(), pre_signing_output_idx),
+                ),
+                Err(Error::Core(wamu_core::Error::InvalidThreshold))
+            ));
+        }
+    }
+
+    #[test]
+    fn sign_mismatched_context_fails() {
+        // Runs key gen and pre-signing simulations.
+        let (threshold, n_parties) = (1, 2);
+        let (keys, identity_providers) = simulate_keygen(threshold, n_parties);
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let pre_signing_output_idx = 1; // l in the CGGMP20 paper.
+        let pre_sign_inputs = generate_pre_sign_input(&keys, &identity_providers, n_parties);
+        let ssids: Vec<SSID<Secp256k1>> = pre_sign_inputs
+            .iter()
+            .map(|(_, _, _, ssid, ..)| ssid.clone())
+            .collect();
+        let pre_sign_results = simulate_pre_sign(pre_sign_inputs, pre_signing_output_idx);
+
+        // Adds parties with different application-level contexts (e.g different chain ids) to the router.
+        let mut router = ChaosRouter::new(ChaosConfig::reliable(1)).unwrap();
+        for result in pre_sign_results {
+            let (output, transcript) = result.base.unwrap();
+            let idx = output.i as usize - 1;
+            let (signing_share, sub_share) = keys[idx].extra.as_ref().unwrap();
+            router.add_party(
+                AugmentedSigning::new(
+                    signing_share,
+                    sub_share,
+                    &identity_providers[idx],
+                    &verifying_keys,
+                    b"Hello, world!",
+                    format!("chain-id:{idx}").into_bytes(),
+                    ssids[idx].clone(),
+                    SigningOptions::new(
+                        HashMap::from([(pre_signing_output_idx as u16, (output, transcript))]),
+                        pre_signing_output_idx,
+                    ),
+                )
+                .unwrap(),
+            );
+        }
+
+        // Verifies that the session can't complete (i.e messages from mismatched parties are rejected).
+        assert!(matches!(router.run(), Err(ChaosError::Stalled { .. })));
+    }
+
+    #[test]
+    fn sign_mismatched_message_commitment_fails() {
+        // Runs key gen and pre-signing simulations.
+        let (threshold, n_parties) = (1, 2);
+        let (keys, identity_providers) = simulate_keygen(threshold, n_parties);
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let pre_signing_output_idx = 1; // l in the CGGMP20 paper.
+        let pre_sign_inputs = generate_pre_sign_input(&keys, &identity_providers, n_parties);
+        let ssids: Vec<SSID<Secp256k1>> = pre_sign_inputs
+            .iter()
+            .map(|(_, _, _, ssid, ..)| ssid.clone())
+            .collect();
+        let pre_sign_results = simulate_pre_sign(pre_sign_inputs, pre_signing_output_idx);
+
+        // Parties agree on a message commitment out-of-band, but the second party holds a different local message.
+        let commitment = message_commitment(b"Hello, world!");
+        let messages: [&[u8]; 2] = [b"Hello, world!", b"Goodbye, world!"];
+
+        let mut router = ChaosRouter::new(ChaosConfig::reliable(1)).unwrap();
+        for result in pre_sign_results {
+            let (output, transcript) = result.base.unwrap();
+            let idx = output.i as usize - 1;
+            let (signing_share, sub_share) = keys[idx].extra.as_ref().unwrap();
+            let presigning_data =
+                HashMap::from([(pre_signing_output_idx as u16, (output, transcript))]);
+
+            // Verifies that only the party whose local message matches the commitment can sign.
+            let result = AugmentedSigning::new(
+                signing_share,
+                sub_share,
+                &identity_providers[idx],
+                &verifying_keys,
+                messages[idx],
+                Vec::new(),
+                ssids[idx].clone(),
+                SigningOptions::new(presigning_data.clone(), pre_signing_output_idx)
+                    .with_message_commitment(commitment),
+            );
+            if idx == 0 {
+                router.add_party(result.unwrap());
+            } else {
+                assert!(matches!(result, Err(Error::MessageCommitmentMismatch)));
+                // Adds the mismatched party without verifying the commitment (e.g a faulty party).
+                router.add_party(
+                    AugmentedSigning::new(
+                        signing_share,
+                        sub_share,
+                        &identity_providers[idx],
+                        &verifying_keys,
+                        messages[idx],
+                        Vec::new(),
+                        ssids[idx].clone(),
+                        SigningOptions::new(presigning_data, pre_signing_output_idx),
+                    )
+                    .unwrap(),
+                );
+            }
+        }
+
+        // Verifies that the session can't complete (i.e messages from mismatched parties are rejected).
+        assert!(matches!(router.run(), Err(ChaosError::Stalled { .. })));
+    }
+
+    #[test]
+    fn pre_sign_party_set_mismatch_fails() {
+        // Runs key gen simulation and generates pre-signing inputs for 2 of 3 parties.
+        let (threshold, n_parties, n_participants) = (1, 3, 2);
+        let (keys, identity_providers) = simulate_keygen(threshold, n_parties);
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let pre_signing_output_idx = 1; // l in the CGGMP20 paper.
+        let pre_sign_inputs = generate_pre_sign_input(&keys, &identity_providers, n_participants);
+
+        // Sets a divergent set of parties for the second party (i.e the non-participating party is replaced by an outsider).
+        let divergent_verifying_keys = vec![
+            verifying_keys[0].clone(),
+            verifying_keys[1].clone(),
+            MockECDSAIdentityProvider::generate().verifying_key(),
+        ];
+        let party_sets = [&verifying_keys, &divergent_verifying_keys];
+
+        // Initializes pre-signing parties.
+        let mut parties: Vec<AugmentedPreSigning<MockECDSAIdentityProvider>> = pre_sign_inputs
+            .into_iter()
+            .zip(party_sets)
+            .map(
+                |(
+                    (
+                        signing_share,
+                        sub_share,
+                        identity_provider,
+                        ssid,
+                        secrets,
+                        aux_ring_pedersen_n_hat_values,
+                        aux_ring_pedersen_s_values,
+                        aux_ring_pedersen_t_values,
+                    ),
+                    party_set,
+                )| {
+                    AugmentedPreSigning::new(
+                        signing_share,
+                        sub_share,
+                        identity_provider,
+                        party_set,
+                        ssid,
+                        secrets,
+                        aux_ring_pedersen_s_values,
+                        aux_ring_pedersen_t_values,
+                        aux_ring_pedersen_n_hat_values,
+                        pre_signing_output_idx,
+                    )
+                    .unwrap()
+                },
+            )
+            .collect();
+        for party in parties.iter_mut() {
+            if party.wants_to_proceed() {
+                party.proceed().unwrap();
+            }
+        }
+        let msg = parties[0].message_queue().first().unwrap().clone();
+
+        // A party set hash that doesn't match the signature is rejected before it's compared.
+        let mut tampered_msg = msg.clone();
+        tampered_msg.body.extra.as_mut().unwrap().party_set_hash = [0; 32];
+        assert!(matches!(
+            parties[1].handle_incoming(tampered_msg),
+            Err(Error::RejectedAugmentation { party: 1, reason })
+                if matches!(
+                    *reason,
+                    Error::Core(wamu_core::Error::Crypto(
+                        wamu_core::CryptoError::SignatureVerificationFailed
+                    ))
+                )
+        ));
+
+        // Parties that disagree on the set of parties reject each other's messages.
+        assert!(matches!(
+            parties[1].handle_incoming(msg),
+            Err(Error::PartySetMismatch { party: 1 })
+        ));
+    }
+
+    // All parties (2/2 signing).
+    #[test]
+    fn sign_all_parties_works() {
+        generate_parties_and_simulate_signing(1, 2, 2);
+    }
+
+    // Threshold signing (subset of parties) - (3/4 signing).
+    #[test]
+    fn sign_threshold_works() {
+        generate_parties_and_simulate_signing(2, 4, 3);
+    }
+
+    #[test]
+    fn unsupported_signature_scheme_fails() {
+        // Runs keygen simulation.
+        let (threshold, n_parties) = (1, 2);
+        let (keys, identity_providers) = simulate_keygen(threshold, n_parties);
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let pre_sign_inputs = generate_pre_sign_input(&keys, &identity_providers, n_parties);
+        let (signing_share, sub_share, identity_provider, ssid, ..) = &pre_sign_inputs[0];
+
+        // Verifies that BIP-340 Schnorr signing is refused.
+        let result = AugmentedSigning::new(
+            signing_share,
+            sub_share,
+            *identity_provider,
+            &verifying_keys,
+            b"Hello, world!",
+            Vec::new(),
+            ssid.clone(),
+            SigningOptions::new(HashMap::new(), 1).with_scheme(SignatureScheme::Schnorr),
+        );
+        assert!(matches!(
+            result,
+            Err(Error::Core(wamu_core::Error::Crypto(
+                wamu_core::CryptoError::UnsupportedScheme
+            )))
+        ));
+    }
+
+    /// A 2-of-3 key with a presignature computed by the first 2 parties (e.g for testing signing party set checks).
+    struct PresignatureFixture {
+        keys: Vec<AugmentedType<LocalKey<Secp256k1>, SubShareOutput>>,
+        identity_providers: Vec<MockECDSAIdentityProvider>,
+        verifying_keys: Vec<VerifyingKey>,
+        // SSID, presigning output and transcript of the signing party.
+        ssid: SSID<Secp256k1>,
+        output: PresigningOutput<Secp256k1>,
+        transcript: PresigningTranscript<Secp256k1>,
+        // Position of the signing party in the lists of keys and identity providers.
+        idx: usize,
+        // l in the CGGMP20 paper.
+        pre_signing_output_idx: usize,
+    }
+
+    impl PresignatureFixture {
+        /// Runs key gen simulation (2-of-3) and pre-signing simulation for the first 2 parties.
+        fn new() -> Self {
+            let (threshold, n_parties, n_participants) = (1, 3, 2);
+            let (keys, identity_providers) = simulate_keygen(threshold, n_parties);
+            let verifying_keys: Vec<VerifyingKey> = identity_providers
+                .iter()
+                .map(IdentityProvider::verifying_key)
+                .collect();
+            let pre_signing_output_idx = 1; // l in the CGGMP20 paper.
+            let pre_sign_inputs =
+                generate_pre_sign_input(&keys, &identity_providers, n_participants);
+            let ssids: Vec<SSID<Secp256k1>> = pre_sign_inputs
+                .iter()
+                .map(|(_, _, _, ssid, ..)| ssid.clone())
+                .collect();
+            let pre_sign_results = simulate_pre_sign(pre_sign_inputs, pre_signing_output_idx);
+            let (output, transcript) = pre_sign_results[0].base.clone().unwrap();
+            let idx = output.i as usize - 1;
+            Self {
+                ssid: ssids[idx].clone(),
+                keys,
+                identity_providers,
+                verifying_keys,
+                output,
+                transcript,
+                idx,
+                pre_signing_output_idx,
+            }
+        }
+
+        /// Returns the index of the signing party.
+        fn party_idx(&self) -> u16 {
+            self.idx as u16 + 1
+        }
+
+        /// Initializes signing with the presignature for the given signing parties
+        /// (and presigning parties, if they should differ from the actual presigning parties).
+        fn init_signing(
+            &self,
+            signers: Vec<u16>,
+            presigners_option: Option<Vec<u16>>,
+        ) -> Result<
+            AugmentedSigning<'_, MockECDSAIdentityProvider>,
+            Error<<Signing as StateMachine>::Err>,
+        > {
+            // Sets the signing and presigning parties.
+            let mut ssid = self.ssid.clone();
+            ssid.P = signers;
+            let mut output = self.output.clone();
+            if let Some(presigners) = presigners_option {
+                output.ssid.P = presigners;
+            }
+
+            // Initializes signing with the presignature.
+            let (signing_share, sub_share) = self.keys[self.idx].extra.as_ref().unwrap();
+            AugmentedSigning::new(
+                signing_share,
+                sub_share,
+                &self.identity_providers[self.idx],
+                &self.verifying_keys,
+                b"Hello, world!",
+                Vec::new(),
+                ssid,
+                SigningOptions::new(
+                    HashMap::from([(
+                        self.pre_signing_output_idx as u16,
+                        (output, self.transcript.clone()),
+                    )]),
+                    self.pre_signing_output_idx,
+                ),
+            )
+        }
+    }
+
+    #[test]
+    fn sign_with_presignature_from_different_parties_fails() {
+        // Generates a presignature for the first 2 parties.
+        let fixture = PresignatureFixture::new();
+
+        for (signers, expected_mismatch) in [
+            // Same parties as the presigning parties (in any order) should be ok.
+            (vec![2, 1], None),
+            // A different subset of parties should fail.
+            (vec![1, 3], Some((vec![1, 2], vec![1, 3]))),
+            // A superset of the presigning parties should fail.
+            (vec![1, 2, 3], Some((vec![1, 2], vec![1, 2, 3]))),
+        ] {
+            // Initializes signing with the presignature.
+            let result = fixture.init_signing(signers, None);
+
+            // Verifies expected result.
+            match expected_mismatch {
+                None => assert!(result.is_ok()),
+                Some((expected_presigners, expected_signers)) => assert!(matches!(
+                    result,
+                    Err(Error::PresignerSetMismatch { presigners, signers })
+                        if presigners == expected_presigners && signers == expected_signers
+                )),
+            }
+        }
+
+        // Verifies the presigning parties of the presignature.
+        assert_eq!(
+            Presignature::new(
+                fixture.output.clone(),
+                fixture.transcript.clone(),
+                fixture.pre_signing_output_idx
+            )
+            .presigners(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn sign_with_insufficient_signers_fails() {
+        // Generates a presignature for the first 2 parties.
+        let fixture = PresignatureFixture::new();
+
+        for (signers, expected_have) in [
+            // A correctly-sized signing party set should be ok.
+            (vec![1, 2], None),
+            // Too few signing parties should fail.
+            (vec![fixture.party_idx()], Some(1)),
+        ] {
+            // Initializes signing with the presignature.
+            let result = fixture.init_signing(signers, None);
+
+            // Verifies expected result.
+            match expected_have {
+                None => assert!(result.is_ok()),
+                Some(expected_have) => assert!(matches!(
+                    result,
+                    Err(Error::InsufficientSigners { have, need: 2 }) if have == expected_have
+                )),
+            }
+        }
+    }
+
+    #[test]
+    fn sign_with_duplicate_signers_fails() {
+        // Generates a presignature for the first 2 parties.
+        let fixture = PresignatureFixture::new();
+
+        for (signers, presigners) in [
+            // Duplicate signing parties (i.e that would otherwise be too few signing parties).
+            (vec![fixture.party_idx(), fixture.party_idx()], vec![1, 2]),
+            // Duplicate signing parties (i.e that would otherwise match the presigning parties).
+            (vec![1, 2, 2], vec![1, 2]),
+            // Duplicate presigning parties.
+            (vec![1, 2], vec![1, 2, 2]),
+        ] {
+            // Verifies that the malformed party set is rejected (i.e instead of collapsing the duplicates).
+            assert!(matches!(
+                fixture.init_signing(signers, Some(presigners)),
+                Err(Error::Core(wamu_core::Error::DuplicateParty))
+            ));
+        }
+    }
+
+    #[test]
+    fn sign_without_own_party_index_fails() {
+        // Generates a presignature for the first 2 parties.
+        let fixture = PresignatureFixture::new();
+
+        // Verifies that a signing party set that doesn't include the party itself is rejected.
+        let other_parties: Vec<u16> = (1..=3).filter(|idx| *idx != fixture.party_idx()).collect();
+        assert!(matches!(
+            fixture.init_signing(other_parties, None),
+            Err(Error::BadPartyIndex)
+        ));
+    }
+
+    #[test]
+    fn pre_sign_with_malformed_party_set_fails() {
+        // Runs key gen simulation (2-of-3).
+        let (keys, identity_providers) = simulate_keygen(1, 3);
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let pre_signing_output_idx = 1; // l in the CGGMP20 paper.
+
+        for (parties, expected_error) in [
+            // Too few pre-signing parties should fail.
+            (vec![1], Error::InsufficientSigners { have: 1, need: 2 }),
+            // A pre-signing party set that doesn't include the party itself should fail.
+            (vec![2, 3], Error::BadPartyIndex),
+            // Duplicate pre-signing parties should fail.
+            (vec![1, 2, 2], Error::Core(wamu_core::Error::DuplicateParty)),
+        ] {
+            // Creates pre-signing input for the first party and sets the pre-signing parties.
+            let (
+                signing_share,
+                sub_share,
+                identity_provider,
+                mut ssid,
+                secrets,
+                aux_ring_pedersen_n_hat_values,
+                aux_ring_pedersen_s_values,
+                aux_ring_pedersen_t_values,
+            ) = generate_pre_sign_input(&keys, &identity_providers, 1)
+                .pop()
+                .unwrap();
+            ssid.P = parties;
+
+            // Verifies that the malformed party set is rejected up front
+            // (i.e instead of waiting for messages from parties that will never send them).
+            let result = AugmentedPreSigning::new(
+                signing_share,
+                sub_share,
+                identity_provider,
+                &verifying_keys,
+                ssid,
+                secrets,
+                aux_ring_pedersen_s_values,
+                aux_ring_pedersen_t_values,
+                aux_ring_pedersen_n_hat_values,
+                pre_signing_output_idx,
+            );
+            assert_eq!(
+                result.err().as_ref().map(std::mem::discriminant),
+                Some(std::mem::discriminant(&expected_error))
+            );
+        }
+    }
+
+    #[test]
+    fn sign_with_out_of_range_pre_signing_output_idx_fails() {
+        // Runs key gen simulation.
+        let (keys, identity_providers) = simulate_keygen(1, 2);
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        for (signing_share, sub_share, identity_provider, ssid, ..) in
+            generate_pre_sign_input(&keys, &identity_providers, 2)
+        {
+            // Verifies that a pre-signing output index that doesn't fit in a `u16` is rejected (i.e not truncated).
+            assert!(matches!(
+                AugmentedSigning::new(
+                    signing_share,
+                    sub_share,
+                    identity_provider,
+                    &verifying_keys,
+                    b"Hello, world!",
+                    Vec::new(),
+                    ssid,
+                    SigningOptions::new(HashMap::new(), usize::from(u16::MAX) + 1),
+                ),
+                Err(Error::Core(wamu_core::Error::TooManyParties))
+            ));
+        }
+    }
+
+    #[test]
+    fn pre_sign_and_sign_with_no_verified_parties_fails() {
+        // Runs key gen simulation.
+        let (keys, identity_providers) = simulate_keygen(1, 2);
+        let pre_signing_output_idx = 1; // l in the CGGMP20 paper.
+
+        for (
+            signing_share,
+            sub_share,
+            identity_provider,
+            ssid,
+            secrets,
+            aux_ring_pedersen_n_hat_values,
+            aux_ring_pedersen_s_values,
+            aux_ring_pedersen_t_values,
+        ) in generate_pre_sign_input(&keys, &identity_providers, 2)
+        {
+            // Verifies that an empty list of verified parties is rejected by pre-signing and signing.
+            assert!(matches!(
+                AugmentedPreSigning::new(
+                    signing_share,
+                    sub_share,
+                    identity_provider,
+                    &[],
+                    ssid.clone(),
+                    secrets,
+                    aux_ring_pedersen_s_values,
+                    aux_ring_pedersen_t_values,
+                    aux_ring_pedersen_n_hat_values,
+                    pre_signing_output_idx,
+                ),
+                Err(Error::Core(wamu_core::Error::NoVerifiedParties))
+            ));
+            assert!(matches!(
+                AugmentedSigning::new(
+                    signing_share,
+                    sub_share,
+                    identity_provider,
+                    &[] as &[VerifyingKey],
+                    b"Hello, world!",
+                    Vec::new(),
+                    ssid,
+                    SigningOptions::new(HashMap::new(), pre_signing_output_idx),
+                ),
+                Err(Error::Core(wamu_core::Error::NoVerifiedParties))
+            ));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn sign_from_persisted_presignature_works() {
+        // Runs keygen simulation.
+        let (threshold, n_parties) = (1, 2);
+        let (keys, identity_providers) = simulate_keygen(threshold, n_parties);
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Runs pre-signing simulation (e.g on networked devices).
+        let pre_signing_output_idx = 1; // l in the CGGMP20 paper.
+        let pre_sign_inputs = generate_pre_sign_input(&keys, &identity_providers, n_parties);
+        let ssids: Vec<SSID<Secp256k1>> = pre_sign_inputs
+            .iter()
+            .map(|(_, _, _, ssid, ..)| ssid.clone())
+            .collect();
+        let pre_sign_results = simulate_pre_sign(pre_sign_inputs, pre_signing_output_idx);
+
+        // Persists presignatures.
+        let persisted: Vec<String> = pre_sign_results
+            .into_iter()
+            .map(|it| {
+                let (output, transcript) = it.base.unwrap();
+                serde_json::to_string(&Presignature::new(
+                    output,
+                    transcript,
+                    pre_signing_output_idx,
+                ))
+                .unwrap()
+            })
+            .collect();
+
+        // Restores presignatures and signs later (e.g on air-gapped devices) with only the message.
+        let message = b"Hello, world!";
+        let mut consumed: Vec<ConsumedPresignatures> = (0..n_parties)
+            .map(|_| ConsumedPresignatures::default())
+            .collect();
+        let mut simulation = Simulation::new();
+        for (serialized, consumed_presignatures) in persisted.iter().zip(consumed.iter_mut()) {
+            let presignature: Presignature = serde_json::from_str(serialized).unwrap();
+            let idx = presignature.output.i as usize - 1;
+            let (signing_share, sub_share) = keys[idx].extra.as_ref().unwrap();
+            simulation.add_party(
+                AugmentedSigning::new(
+                    signing_share,
+                    sub_share,
+                    &identity_providers[idx],
+                    &verifying_keys,
+                    message,
+                    Vec::new(),
+                    ssids[idx].clone(),
+                    SigningOptions::from_presignature(presignature, consumed_presignatures),
+                )
+                .unwrap(),
+            );
+        }
+        let results = simulation.run().unwrap();
+        assert!(results.iter().all(|it| it.base.is_some()));
+
+        // Verifies that reusing a persisted presignature is refused.
+        let presignature: Presignature = serde_json::from_str(&persisted[0]).unwrap();
+        let idx = presignature.output.i as usize - 1;
+        assert!(consumed[idx].contains(&presignature));
+        let (signing_share, sub_share) = keys[idx].extra.as_ref().unwrap();
+        let result = AugmentedSigning::new(
+            signing_share,
+            sub_share,
+            &identity_providers[idx],
+            &verifying_keys,
+            b"Another message",
+            Vec::new(),
+            ssids[idx].clone(),
+            SigningOptions::from_presignature(presignature, &mut consumed[idx]),
+        );
+        assert!(matches!(result, Err(Error::PresignatureReused)));
+    }
+
+    #[test]
+    fn presignature_is_zeroized() {
+        // Runs keygen and pre-signing simulations.
+        let (keys, identity_providers) = simulate_keygen(1, 2);
+        let pre_signing_output_idx = 1; // l in the CGGMP20 paper.
+        let pre_sign_inputs = generate_pre_sign_input(&keys, &identity_providers, 2);
+        let pre_sign_results = simulate_pre_sign(pre_sign_inputs, pre_signing_output_idx);
+        let (output, transcript) = pre_sign_results.into_iter().next().unwrap().base.unwrap();
+        let mut presignature = Presignature::new(output, transcript, pre_signing_output_idx);
+        let id = presignature.id();
+
+        // Verifies that the secret material (but not the identifier) is zerorized.
+        presignature.zeroize();
+        assert_eq!(presignature.output.k_i, BigInt::zero());
+        assert_eq!(presignature.output.chi_i, BigInt::zero());
+        assert_eq!(
+            presignature.output.ssid.X.keys_linear.x_i,
+            Scalar::<Secp256k1>::zero()
+        );
+        assert_eq!(presignature.id(), id);
     }
 }
@@ -0,0 +1,457 @@
+//! Test utilities.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use round_based::{IsCritical, Msg, StateMachine};
+
+/// Network fault configuration for a [`ChaosRouter`].
+///
+/// **NOTE:** All probabilities must be in the range `[0, 1]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChaosConfig {
+    /// Seed for the router's random number generator (i.e the same seed reproduces the same faults).
+    pub seed: u64,
+    /// Probability that a message is dropped.
+    pub drop_probability: f64,
+    /// Probability that a message is delivered twice.
+    pub duplicate_probability: f64,
+    /// Probability that a message is delayed by between 1 and `max_delay` delivery rounds.
+    pub delay_probability: f64,
+    /// Maximum number of delivery rounds a message can be delayed by.
+    pub max_delay: usize,
+    /// Whether or not messages that are ready for delivery in the same delivery round are shuffled.
+    pub reorder: bool,
+}
+
+impl ChaosConfig {
+    /// Returns a configuration for a reliable network (i.e no faults) with the given seed.
+    pub fn reliable(seed: u64) -> Self {
+        Self {
+            seed,
+            drop_probability: 0.0,
+            duplicate_probability: 0.0,
+            delay_probability: 0.0,
+            max_delay: 0,
+            reorder: false,
+        }
+    }
+}
+
+/// A [`ChaosRouter`] error.
+#[derive(Debug)]
+pub enum ChaosError<E> {
+    /// The network fault configuration is invalid (e.g a probability outside the range `[0, 1]`).
+    InvalidConfig { reason: &'static str },
+    /// A party returned an error.
+    Party { idx: u16, error: E },
+    /// No party can make progress (e.g because required messages were dropped).
+    ///
+    /// Includes the current round of each party.
+    Stalled { rounds: Vec<u16> },
+}
+
+/// An in-memory message bus that drives [`StateMachine`](StateMachine)s over a simulated unreliable network
+/// that can drop, delay, duplicate and reorder messages.
+pub struct ChaosRouter<P: StateMachine> {
+    /// The parties.
+    parties: Vec<P>,
+    /// The network fault configuration.
+    config: ChaosConfig,
+    /// The seeded random number generator.
+    rng: StdRng,
+    /// In-flight messages and the number of delivery rounds remaining before they're delivered.
+    in_flight: Vec<(usize, Msg<P::MessageBody>)>,
+}
+
+impl<P> ChaosRouter<P>
+where
+    P: StateMachine,
+    P::MessageBody: Clone,
+{
+    /// Initializes a router with the given network fault configuration,
+    /// or returns an `InvalidConfig` error if any probability is outside the range `[0, 1]`.
+    pub fn new(config: ChaosConfig) -> Result<Self, ChaosError<P::Err>> {
+        // All probabilities must be in the range `[0, 1]` (i.e `NaN` is also rejected).
+        for (probability, reason) in [
+            (
+                config.drop_probability,
+                "drop probability must be in the range [0, 1]",
+            ),
+            (
+                config.duplicate_probability,
+                "duplicate probability must be in the range [0, 1]",
+            ),
+            (
+                config.delay_probability,
+                "delay probability must be in the range [0, 1]",
+            ),
+        ] {
+            if !(0.0..=1.0).contains(&probability) {
+                return Err(ChaosError::InvalidConfig { reason });
+            }
+        }
+
+        Ok(Self {
+            parties: Vec::new(),
+            config,
+            rng: StdRng::seed_from_u64(config.seed),
+            in_flight: Vec::new(),
+        })
+    }
+
+    /// Adds a party to the router.
+    pub fn add_party(&mut self, party: P) -> &mut Self {
+        self.parties.push(party);
+        self
+    }
+
+    /// Returns the parties (e.g to inspect their state after the protocol fails).
+    pub fn into_parties(self) -> Vec<P> {
+        self.parties
+    }
+
+    /// Runs the protocol until all parties are finished and returns their output,
+    /// or returns an error if a party fails or the protocol can't make any more progress.
+    pub fn run(&mut self) -> Result<Vec<P::Output>, ChaosError<P::Err>> {
+        self.run_with_inspector(|_| {})
+    }
+
+    /// Same as [`run`](Self::run) but calls `inspect` on each party after it proceeds
+    /// (e.g to observe the current round of each party).
+    pub fn run_with_inspector(
+        &mut self,
+        inspect: impl FnMut(&P),
+    ) -> Result<Vec<P::Output>, ChaosError<P::Err>> {
+        self.run_until_finished_with_inspector(inspect)?;
+        let mut outputs = Vec::with_capacity(self.parties.len());
+        for party in self.parties.iter_mut() {
+            let idx = party.party_ind();
+            match party.pick_output() {
+                Some(Ok(output)) => outputs.push(output),
+                Some(Err(error)) => return Err(ChaosError::Party { idx, error }),
+                None => unreachable!("finished parties always have output"),
+            }
+        }
+        Ok(outputs)
+    }
+
+    /// Same as [`run`](Self::run) but leaves the output with the parties
+    /// (e.g to pick the output with [`into_parties`](Self::into_parties) and a protocol specific method).
+    pub fn run_until_finished(&mut self) -> Result<(), ChaosError<P::Err>> {
+        self.run_until_finished_with_inspector(|_| {})
+    }
+
+    /// Runs the protocol until all parties are finished (without picking their output),
+    /// calling `inspect` on each party after it proceeds.
+    fn run_until_finished_with_inspector(
+        &mut self,
+        mut inspect: impl FnMut(&P),
+    ) -> Result<(), ChaosError<P::Err>> {
+        loop {
+            let mut made_progress = false;
+
+            // Proceeds for parties that are ready to do so and collects outgoing messages.
+            let mut outgoing = Vec::new();
+            for party in self.parties.iter_mut() {
+                if party.wants_to_proceed() {
+                    party.proceed().map_err(|error| ChaosError::Party {
+                        idx: party.party_ind(),
+                        error,
+                    })?;
+                    inspect(party);
+                    made_progress = true;
+                }
+                outgoing.append(party.message_queue());
+            }
+
+            // Sends outgoing messages over the unreliable network.
+            for msg in outgoing {
+                made_progress = true;
+                self.send(msg);
+            }
+
+            // Delivers messages whose delay has elapsed.
+            let (mut ready, delayed): (Vec<_>, Vec<_>) =
+                self.in_flight.drain(..).partition(|(delay, _)| *delay == 0);
+            self.in_flight = delayed
+                .into_iter()
+                .map(|(delay, msg)| (delay - 1, msg))
+                .collect();
+            if self.config.reorder {
+                ready.shuffle(&mut self.rng);
+            }
+            for (_, msg) in ready {
+                made_progress = true;
+                for party in self.parties.iter_mut() {
+                    let idx = party.party_ind();
+                    let is_recipient =
+                        idx != msg.sender && (msg.receiver.is_none() || msg.receiver == Some(idx));
+                    if is_recipient && !party.is_finished() {
+                        // Non-critical errors (e.g from duplicate messages) are skipped.
+                        if let Err(error) = party.handle_incoming(msg.clone()) {
+                            if error.is_critical() {
+                                return Err(ChaosError::Party { idx, error });
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Returns if all parties are finished.
+            if self.parties.iter().all(|party| party.is_finished()) {
+                return Ok(());
+            }
+
+            // Returns an error if no party can make any more progress.
+            if !made_progress && self.in_flight.is_empty() {
+                return Err(ChaosError::Stalled {
+                    rounds: self
+                        .parties
+                        .iter()
+                        .map(|party| party.current_round())
+                        .collect(),
+                });
+            }
+        }
+    }
+
+    /// Sends a message over the unreliable network.
+    fn send(&mut self, msg: Msg<P::MessageBody>) {
+        if self.rng.gen_bool(self.config.drop_probability) {
+            return;
+        }
+        let copies = if self.rng.gen_bool(self.config.duplicate_probability) {
+            2
+        } else {
+            1
+        };
+        for _ in 0..copies {
+            let delay =
+                if self.config.max_delay > 0 && self.rng.gen_bool(self.config.delay_probability) {
+                    self.rng.gen_range(1..=self.config.max_delay)
+                } else {
+                    0
+                };
+            self.in_flight.push((delay, msg.clone()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keygen::AugmentedKeyGen;
+    use wamu_core::crypto::VerifyingKey;
+    use wamu_core::test_utils::MockECDSAIdentityProvider;
+    use wamu_core::IdentityProvider;
+
+    /// A single round mock protocol where each party broadcasts its index
+    /// and finishes after receiving the index of every other party.
+    struct MockParty {
+        idx: u16,
+        n_parties: u16,
+        received: Vec<u16>,
+        message_queue: Vec<Msg<u16>>,
+        sent: bool,
+    }
+
+    #[derive(Debug)]
+    enum MockError {
+        DuplicateMessage,
+        TimeoutReached,
+    }
+
+    impl IsCritical for MockError {
+        fn is_critical(&self) -> bool {
+            // Duplicate messages are not critical errors.
+            !matches!(self, MockError::DuplicateMessage)
+        }
+    }
+
+    impl StateMachine for MockParty {
+        type MessageBody = u16;
+        type Err = MockError;
+        type Output = Vec<u16>;
+
+        fn handle_incoming(&mut self, msg: Msg<Self::MessageBody>) -> Result<(), Self::Err> {
+            if self.received.contains(&msg.body) {
+                return Err(MockError::DuplicateMessage);
+            }
+            self.received.push(msg.body);
+            Ok(())
+        }
+
+        fn message_queue(&mut self) -> &mut Vec<Msg<Self::MessageBody>> {
+            &mut self.message_queue
+        }
+
+        fn wants_to_proceed(&self) -> bool {
+            !self.sent
+        }
+
+        fn proceed(&mut self) -> Result<(), Self::Err> {
+            self.message_queue.push(Msg {
+                sender: self.idx,
+                receiver: None,
+                body: self.idx,
+            });
+            self.sent = true;
+            Ok(())
+        }
+
+        fn round_timeout(&self) -> Option<std::time::Duration> {
+            None
+        }
+
+        fn round_timeout_reached(&mut self) -> Self::Err {
+            MockError::TimeoutReached
+        }
+
+        fn is_finished(&self) -> bool {
+            self.sent && self.received.len() == self.n_parties as usize - 1
+        }
+
+        fn pick_output(&mut self) -> Option<Result<Self::Output, Self::Err>> {
+            self.is_finished().then(|| {
+                let mut received = self.received.clone();
+                received.sort();
+                Ok(received)
+            })
+        }
+
+        fn current_round(&self) -> u16 {
+            1
+        }
+
+        fn total_rounds(&self) -> Option<u16> {
+            Some(1)
+        }
+
+        fn party_ind(&self) -> u16 {
+            self.idx
+        }
+
+        fn parties(&self) -> u16 {
+            self.n_parties
+        }
+    }
+
+    #[test]
+    fn chaos_router_skips_duplicate_messages() {
+        // Sets number of parties.
+        let n_parties = 4;
+
+        // Light reordering where every message is sent twice.
+        let config = ChaosConfig {
+            duplicate_probability: 1.0,
+            delay_probability: 0.5,
+            max_delay: 2,
+            reorder: true,
+            ..ChaosConfig::reliable(3)
+        };
+
+        // Adds parties to the router.
+        let mut router = ChaosRouter::new(config).unwrap();
+        for idx in 1..=n_parties {
+            router.add_party(MockParty {
+                idx,
+                n_parties,
+                received: Vec::new(),
+                message_queue: Vec::new(),
+                sent: false,
+            });
+        }
+
+        // Verifies that the protocol completes (i.e duplicates are skipped)
+        // and that each party received the index of every other party exactly once.
+        let outputs = router.run().unwrap();
+        assert_eq!(outputs.len(), n_parties as usize);
+        for (output, idx) in outputs.into_iter().zip(1..=n_parties) {
+            let expected: Vec<u16> = (1..=n_parties).filter(|it| *it != idx).collect();
+            assert_eq!(output, expected);
+        }
+    }
+
+    #[test]
+    fn chaos_router_rejects_invalid_probabilities() {
+        for (drop_probability, duplicate_probability, delay_probability, is_valid) in [
+            // Probabilities in the range `[0, 1]` should be ok.
+            (0.0, 0.5, 1.0, true),
+            // Probabilities outside the range `[0, 1]` should fail.
+            (-0.1, 0.0, 0.0, false),
+            (0.0, 1.1, 0.0, false),
+            (0.0, 0.0, f64::NAN, false),
+        ] {
+            let config = ChaosConfig {
+                drop_probability,
+                duplicate_probability,
+                delay_probability,
+                max_delay: 1,
+                ..ChaosConfig::reliable(1)
+            };
+
+            // Verifies expected result.
+            let result = ChaosRouter::<MockParty>::new(config);
+            if is_valid {
+                assert!(result.is_ok());
+            } else {
+                assert!(matches!(result, Err(ChaosError::InvalidConfig { .. })));
+            }
+        }
+    }
+
+    #[test]
+    fn chaos_router_works() {
+        // Sets threshold and number of parties.
+        let threshold = 1;
+        let n_parties = 3;
+
+        // Creates identity providers and a list of verifying keys for all parties.
+        let identity_providers: Vec<MockECDSAIdentityProvider> = (1..=n_parties)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verifying_keys: Vec<VerifyingKey> = identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Generates test cases for light reordering (i.e delays and shuffles without drops) and heavy drops.
+        // NOTE: Duplicate messages are covered by `chaos_router_skips_duplicate_messages`.
+        let light_reordering = ChaosConfig {
+            delay_probability: 0.2,
+            max_delay: 1,
+            reorder: true,
+            ..ChaosConfig::reliable(1)
+        };
+        let heavy_drops = ChaosConfig {
+            drop_probability: 0.9,
+            ..ChaosConfig::reliable(2)
+        };
+
+        for (config, should_complete) in [(light_reordering, true), (heavy_drops, false)] {
+            // Adds parties to the router.
+            let mut router = ChaosRouter::new(config).unwrap();
+            for (idx, identity_provider) in identity_providers.iter().enumerate() {
+                router.add_party(
+                    AugmentedKeyGen::new(
+                        identity_provider,
+                        &verifying_keys,
+                        wamu_core::party_index(idx).unwrap(),
+                        threshold,
+                        n_parties,
+                    )
+                    .unwrap(),
+                );
+            }
+
+            // Runs the protocol and verifies the outcome.
+            let result = router.run();
+            if should_complete {
+                assert_eq!(result.unwrap().len(), n_parties as usize);
+            } else {
+                assert!(matches!(result, Err(ChaosError::Stalled { .. })));
+            }
+        }
+    }
+}
@@ -6,16 +6,16 @@ use curv::elliptic::curves::Secp256k1;
 use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen::LocalKey;
 use round_based::{Msg, StateMachine};
 use std::collections::HashMap;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use wamu_core::crypto::VerifyingKey;
-use wamu_core::{IdentityProvider, SigningShare, SubShare};
+use wamu_core::{Command, IdentityAuthedRequestError, IdentityProvider, SigningShare, SubShare};
 
 use crate::authorized_key_refresh::{AuthorizedKeyRefresh, Error, Message};
-use crate::key_refresh::AugmentedKeyRefresh;
+use crate::key_refresh::{AugmentedKeyRefresh, IndexedParties};
 use crate::quorum_approval;
 use crate::quorum_approval::QuorumApproval;
 
-const THRESHOLD_MODIFICATION: &str = "threshold-modification";
+const THRESHOLD_MODIFICATION: Command = Command::ThresholdModification;
 
 /// A [StateMachine](StateMachine) that implements [threshold modification as described by the Wamu protocol](https://wamu.tech/specification#threshold-modification).
 pub struct ThresholdModification<'a, I: IdentityProvider> {
@@ -53,6 +53,8 @@ pub struct ThresholdModification<'a, I: IdentityProvider> {
     refresh_state_machine: Option<AugmentedKeyRefresh<'a, I>>,
     /// Stores "out of order" messages.
     out_of_order_buffer: Vec<Msg<Message<'a, I, quorum_approval::Message>>>,
+    /// Ceremony-wide deadline (if any).
+    deadline: Option<Instant>,
 }
 
 impl<'a, I: IdentityProvider> ThresholdModification<'a, I> {
@@ -72,7 +74,31 @@ impl<'a, I: IdentityProvider> ThresholdModification<'a, I> {
         ThresholdModification<'a, I>,
         Error<'a, I, <QuorumApproval<'a, I> as StateMachine>::Err>,
     > {
-        // Initializes quorum approval state machine.
+        // Verified parties must be provided (i.e an empty list means no other party is authorized).
+        if verified_parties.is_empty() {
+            return Err(Error::Init(wamu_core::Error::NoVerifiedParties.into()));
+        }
+
+        // A threshold of zero means that any single party can sign, so it's never allowed.
+        if local_key.t == 0 || new_threshold == 0 {
+            return Err(Error::InvalidThreshold);
+        }
+
+        // The proposed threshold must be valid before any approvals are gathered
+        // (i.e `1 <= new_threshold <= n_parties/2` because FS-DKR requires an honest majority).
+        wamu_core::threshold_modification::validate_proposed_threshold(
+            new_threshold,
+            local_key.n,
+            true,
+        )
+        .map_err(|_| {
+            Error::Init(quorum_approval::Error::Identity(
+                IdentityAuthedRequestError::InvalidProposedThreshold,
+            ))
+        })?;
+
+        // Initializes quorum approval state machine
+        // (i.e the request and approvals are bound to the proposed threshold).
         let auth_state_machine = QuorumApproval::new(
             THRESHOLD_MODIFICATION,
             identity_provider,
@@ -82,7 +108,8 @@ impl<'a, I: IdentityProvider> ThresholdModification<'a, I> {
             local_key.n,
             is_initiator,
             false,
-        );
+        )
+        .with_new_threshold(new_threshold);
 
         // Initializes threshold modification state machine.
         let mut threshold_modification = Self {
@@ -102,6 +129,7 @@ impl<'a, I: IdentityProvider> ThresholdModification<'a, I> {
             auth_state_machine,
             refresh_state_machine: None,
             out_of_order_buffer: Vec::new(),
+            deadline: None,
         };
 
         // Retrieves messages from immediate state transitions (if any) and wraps them.
@@ -119,7 +147,8 @@ impl<'a, I: IdentityProvider> AuthorizedKeyRefresh<'a, I> for ThresholdModificat
         auth_state_machine,
         refresh_state_machine,
         message_queue,
-        out_of_order_buffer
+        out_of_order_buffer,
+        deadline
     );
 
     fn create_key_refresh(
@@ -133,7 +162,7 @@ impl<'a, I: IdentityProvider> AuthorizedKeyRefresh<'a, I> for ThresholdModificat
             Some(self.signing_share),
             Some(self.sub_share),
             self.identity_provider,
-            self.verified_parties,
+            IndexedParties::from_ordered(self.verified_parties),
             Some(self.local_key.clone()),
             None,
             self.old_to_new_map,
@@ -159,6 +188,7 @@ pub mod tests {
     use super::*;
     use crate::augmented_state_machine::{AugmentedType, SubShareOutput};
     use crate::keygen::tests::simulate_keygen;
+    use crate::test_utils::{ChaosConfig, ChaosError, ChaosRouter};
     use curv::elliptic::curves::Scalar;
     use round_based::dev::Simulation;
     use wamu_core::test_utils::MockECDSAIdentityProvider;
@@ -205,7 +235,12 @@ pub mod tests {
         }
 
         // Runs simulation and returns output.
-        simulation.run().unwrap()
+        simulation
+            .run()
+            .unwrap()
+            .into_iter()
+            .map(Into::into)
+            .collect()
     }
 
     pub fn generate_parties_and_simulate_threshold_modification(
@@ -243,7 +278,7 @@ pub mod tests {
         let mut current_to_new_idx_map = HashMap::new();
         for (i, key) in keys.iter().enumerate() {
             // Create party key config and index entry.
-            let idx = i as u16 + 1;
+            let idx = wamu_core::party_index(i).unwrap();
             let (signing_share, sub_share) = key.extra.as_ref().unwrap();
             let local_key = key.base.clone();
             current_to_new_idx_map.insert(local_key.i, idx);
@@ -292,4 +327,72 @@ pub mod tests {
     fn threshold_modification_works() {
         generate_parties_and_simulate_threshold_modification(1, 2, 4, 2);
     }
+
+    #[test]
+    fn threshold_modification_with_invalid_threshold_fails() {
+        // Runs key gen simulation.
+        let (keys, identity_providers) = simulate_keygen(1, 4);
+        let verifying_keys = wamu_core::collect_verifying_keys(&identity_providers);
+        let current_to_new_idx_map: HashMap<u16, u16> = (1..=4).map(|idx| (idx, idx)).collect();
+        let (signing_share, sub_share) = keys[0].extra.as_ref().unwrap();
+
+        // Verifies that out-of-range proposed thresholds are rejected (i.e FS-DKR requires an honest majority).
+        for new_threshold in [3, 4, 5] {
+            let result = ThresholdModification::new(
+                signing_share,
+                sub_share,
+                &identity_providers[0],
+                &verifying_keys,
+                keys[0].base.clone(),
+                new_threshold,
+                &current_to_new_idx_map,
+                true,
+            );
+            assert!(matches!(
+                result,
+                Err(Error::Init(quorum_approval::Error::Identity(
+                    IdentityAuthedRequestError::InvalidProposedThreshold
+                )))
+            ));
+        }
+    }
+
+    #[test]
+    fn threshold_modification_with_mismatched_threshold_fails() {
+        // Runs key gen simulation.
+        let (keys, identity_providers) = simulate_keygen(1, 4);
+        let verifying_keys = wamu_core::collect_verifying_keys(&identity_providers);
+        let current_to_new_idx_map: HashMap<u16, u16> = (1..=4).map(|idx| (idx, idx)).collect();
+
+        // Adds parties to the router with the first party as the initiator proposing a threshold of 2,
+        // and the third party expecting a threshold of 1.
+        let mut router = ChaosRouter::new(ChaosConfig::reliable(1)).unwrap();
+        for (i, key) in keys.iter().enumerate() {
+            let (signing_share, sub_share) = key.extra.as_ref().unwrap();
+            router.add_party(
+                ThresholdModification::new(
+                    signing_share,
+                    sub_share,
+                    &identity_providers[i],
+                    &verifying_keys,
+                    key.base.clone(),
+                    if i == 2 { 1 } else { 2 },
+                    &current_to_new_idx_map,
+                    i == 0,
+                )
+                .unwrap(),
+            );
+        }
+
+        // Verifies that the third party rejects the request for a different threshold.
+        assert!(matches!(
+            router.run(),
+            Err(ChaosError::Party {
+                idx: 3,
+                error: Error::Init(quorum_approval::Error::Identity(
+                    IdentityAuthedRequestError::InvalidProposedThreshold
+                )),
+            })
+        ));
+    }
 }
@@ -0,0 +1,364 @@
+//! Serializable public wallet state (i.e the group `LocalKey<Secp256k1>` with its secret share and Paillier secret key stripped).
+
+use curv::elliptic::curves::{Scalar, Secp256k1};
+use curv::BigInt;
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2020::state_machine::keygen::LocalKey;
+use serde::{Deserialize, Serialize};
+use wamu_core::crypto::VerifyingKey;
+
+use crate::augmented_state_machine::GroupKey;
+use crate::format_version::{self, FormatVersionError, Migrate, FORMAT_VERSION};
+
+/// The public parts of a wallet (i.e a `LocalKey<Secp256k1>` with both the secret share and the Paillier secret key
+/// (i.e the primes `p` and `q`) cleared/zerorized, and the group public key)
+/// that can be stored in a shared, unencrypted registry.
+///
+/// **NOTE:** The per-device secret material (i.e the "signing share", "sub-share" and Paillier secret key)
+/// must be stored separately, because the `LocalKey<Secp256k1>` of the public wallet state can't be used for signing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "RawPublicWalletState")]
+pub struct PublicWalletState {
+    /// The format version (see [`FORMAT_VERSION`]).
+    format_version: u16,
+    /// `LocalKey<Secp256k1>` with the secret share and Paillier secret key cleared/zerorized.
+    local_key: LocalKey<Secp256k1>,
+    /// The group public key.
+    #[serde(with = "group_key_bytes")]
+    group_key: VerifyingKey,
+    /// Verifying keys for the verified parties of the wallet in party index order (if any)
+    /// (see [`wamu_core::collect_verifying_keys`]).
+    verified_parties: Vec<VerifyingKey>,
+}
+
+impl PublicWalletState {
+    /// Given a `LocalKey<Secp256k1>` with the secret share cleared/zerorized (e.g the key output of key generation or key refresh),
+    /// returns the public wallet state (i.e with the Paillier secret key of the `LocalKey<Secp256k1>` stripped)
+    /// or an appropriate error if the `LocalKey<Secp256k1>` still holds a secret share.
+    pub fn new(mut local_key: LocalKey<Secp256k1>) -> Result<Self, PublicWalletStateError> {
+        // Refuses keys that still hold a secret share.
+        if local_key.keys_linear.x_i != Scalar::<Secp256k1>::zero() {
+            return Err(PublicWalletStateError::SecretShareNotCleared);
+        }
+        let group_key = VerifyingKey::from(GroupKey::try_from(&local_key)?);
+
+        // Strips the Paillier secret key (i.e the primes `p` and `q`).
+        local_key.paillier_dk.p = BigInt::from(0);
+        local_key.paillier_dk.q = BigInt::from(0);
+
+        Ok(Self {
+            format_version: FORMAT_VERSION,
+            local_key,
+            group_key,
+            verified_parties: Vec::new(),
+        })
+    }
+
+    /// Given verifying keys for all parties in party index order (e.g from [`wamu_core::collect_verifying_keys`]),
+    /// returns the public wallet state with the verified parties attached (i.e so that subsequent ceremonies use the same set of parties),
+    /// or an appropriate error if the number of verifying keys doesn't match the total number of parties.
+    pub fn with_verified_parties(
+        mut self,
+        verified_parties: Vec<VerifyingKey>,
+    ) -> Result<Self, PublicWalletStateError> {
+        if verified_parties.len() != usize::from(self.local_key.n) {
+            return Err(PublicWalletStateError::ParametersMismatch);
+        }
+        self.verified_parties = verified_parties;
+        Ok(self)
+    }
+
+    /// Returns the format version.
+    pub fn format_version(&self) -> u16 {
+        self.format_version
+    }
+
+    /// Returns the `LocalKey<Secp256k1>` with the secret share and Paillier secret key cleared/zerorized.
+    pub fn local_key(&self) -> &LocalKey<Secp256k1> {
+        &self.local_key
+    }
+
+    /// Returns the threshold.
+    pub fn threshold(&self) -> u16 {
+        self.local_key.t
+    }
+
+    /// Returns the total number of parties.
+    pub fn n_parties(&self) -> u16 {
+        self.local_key.n
+    }
+
+    /// Returns the group public key as an ECDSA/Secp256k1 verifying key (in compressed SEC1 format).
+    pub fn group_key(&self) -> &VerifyingKey {
+        &self.group_key
+    }
+
+    /// Returns the verifying keys for the verified parties of the wallet in party index order
+    /// (i.e an empty list if none are attached, see [`with_verified_parties`](Self::with_verified_parties)).
+    pub fn verified_parties(&self) -> &[VerifyingKey] {
+        &self.verified_parties
+    }
+}
+
+/// A public wallet state error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicWalletStateError {
+    /// A wrapped error from `wamu-core`.
+    Core(wamu_core::Error),
+    /// The group public key doesn't match the group public key of the `LocalKey<Secp256k1>`.
+    GroupKeyMismatch,
+    /// The verified parties don't match the total number of parties of the `LocalKey<Secp256k1>`.
+    ParametersMismatch,
+    /// The `LocalKey<Secp256k1>` of deserialized state still holds a Paillier secret key.
+    PaillierKeyNotCleared,
+    /// The `LocalKey<Secp256k1>` still holds a secret share.
+    SecretShareNotCleared,
+    /// An unknown (e.g future) format version without a migration path to the current format version.
+    UnsupportedFormatVersion(u16),
+}
+
+impl From<wamu_core::Error> for PublicWalletStateError {
+    fn from(error: wamu_core::Error) -> Self {
+        Self::Core(error)
+    }
+}
+
+impl From<FormatVersionError> for PublicWalletStateError {
+    fn from(error: FormatVersionError) -> Self {
+        match error {
+            FormatVersionError::UnsupportedFormatVersion(format_version) => {
+                Self::UnsupportedFormatVersion(format_version)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for PublicWalletStateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// Unvalidated public wallet state (i.e as deserialized).
+#[derive(Deserialize)]
+struct RawPublicWalletState {
+    // State serialized before format versions were introduced has no format version.
+    #[serde(default)]
+    format_version: u16,
+    local_key: LocalKey<Secp256k1>,
+    #[serde(with = "group_key_bytes")]
+    group_key: VerifyingKey,
+    // State serialized without verified parties has no verified parties.
+    #[serde(default)]
+    verified_parties: Vec<VerifyingKey>,
+}
+
+impl TryFrom<RawPublicWalletState> for PublicWalletState {
+    type Error = PublicWalletStateError;
+
+    /// Validates deserialized public wallet state against the `LocalKey<Secp256k1>`.
+    fn try_from(raw: RawPublicWalletState) -> Result<Self, Self::Error> {
+        let raw = format_version::upgrade(raw)?;
+        // Refuses state that still holds a Paillier secret key (i.e it was never meant to be shared).
+        let zero = BigInt::from(0);
+        if raw.local_key.paillier_dk.p != zero || raw.local_key.paillier_dk.q != zero {
+            return Err(PublicWalletStateError::PaillierKeyNotCleared);
+        }
+        let state = Self::new(raw.local_key)?;
+        if state.group_key != raw.group_key {
+            return Err(PublicWalletStateError::GroupKeyMismatch);
+        }
+        if raw.verified_parties.is_empty() {
+            Ok(state)
+        } else {
+            state.with_verified_parties(raw.verified_parties)
+        }
+    }
+}
+
+// The layout of legacy public wallet state is otherwise unchanged
+// (i.e the redundant threshold and total number of parties of legacy state are ignored).
+impl Migrate for RawPublicWalletState {
+    fn format_version(&self) -> u16 {
+        self.format_version
+    }
+
+    fn format_version_mut(&mut self) -> &mut u16 {
+        &mut self.format_version
+    }
+}
+
+/// (De)serializes the group public key as compressed SEC1 bytes.
+mod group_key_bytes {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use wamu_core::crypto::{EllipticCurve, KeyEncoding, SignatureAlgorithm, VerifyingKey};
+
+    pub fn serialize<S: Serializer>(
+        verifying_key: &VerifyingKey,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        verifying_key.key.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<VerifyingKey, D::Error> {
+        Ok(VerifyingKey {
+            key: Vec::<u8>::deserialize(deserializer)?,
+            algo: SignatureAlgorithm::ECDSA,
+            curve: EllipticCurve::Secp256k1,
+            enc: KeyEncoding::SEC1,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keygen;
+    use wamu_core::IdentityProvider;
+
+    #[test]
+    fn public_wallet_state_works() {
+        // Runs keygen simulation.
+        let (threshold, n_parties) = (1, 3);
+        let (keys, identity_providers) = keygen::tests::simulate_keygen(threshold, n_parties);
+        let key = &keys[0];
+
+        // Restores the secret share of the `LocalKey<Secp256k1>`.
+        let (signing_share, sub_share) = key.extra.as_ref().unwrap();
+        let secret_share = wamu_core::share_split_reconstruct::reconstruct(
+            signing_share,
+            sub_share,
+            &identity_providers[0],
+        )
+        .unwrap();
+        let mut key_with_secret = key.base.clone();
+        key_with_secret.keys_linear.x_i =
+            Scalar::<Secp256k1>::from_bytes(&secret_share.to_be_bytes()).unwrap();
+
+        // Verifies that a `LocalKey<Secp256k1>` still holding a secret share is refused.
+        assert_eq!(
+            PublicWalletState::new(key_with_secret).unwrap_err(),
+            PublicWalletStateError::SecretShareNotCleared
+        );
+
+        // Verifies that a `LocalKey<Secp256k1>` with the secret share cleared/zerorized round-trips
+        // (with the Paillier secret key stripped).
+        let state = PublicWalletState::new(key.base.clone()).unwrap();
+        assert_ne!(key.base.paillier_dk.p, BigInt::from(0));
+        assert_eq!(state.local_key().paillier_dk.p, BigInt::from(0));
+        assert_eq!(state.local_key().paillier_dk.q, BigInt::from(0));
+        assert_eq!(state.threshold(), threshold);
+        assert_eq!(state.n_parties(), n_parties);
+        assert_eq!(
+            state.group_key(),
+            &VerifyingKey::from(key.group_key().unwrap())
+        );
+        let serialized = serde_json::to_string(&state).unwrap();
+        let deserialized: PublicWalletState = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.threshold(), threshold);
+        assert_eq!(deserialized.n_parties(), n_parties);
+        assert_eq!(deserialized.group_key(), state.group_key());
+        assert_eq!(deserialized.local_key().public_key(), key.base.public_key());
+        assert_eq!(serde_json::to_string(&deserialized).unwrap(), serialized);
+
+        // Verifies that tampered public wallet state is rejected.
+        let mut value: serde_json::Value = serde_json::from_str(&serialized).unwrap();
+        value["group_key"] =
+            serde_json::to_value(&identity_providers[0].verifying_key().key).unwrap();
+        assert!(serde_json::from_value::<PublicWalletState>(value).is_err());
+
+        // Verifies that public wallet state that still holds a Paillier secret key is rejected.
+        let mut value: serde_json::Value = serde_json::from_str(&serialized).unwrap();
+        value["local_key"]["paillier_dk"] = serde_json::to_value(&key.base.paillier_dk).unwrap();
+        assert_eq!(
+            serde_json::from_value::<PublicWalletState>(value)
+                .unwrap_err()
+                .to_string(),
+            PublicWalletStateError::PaillierKeyNotCleared.to_string()
+        );
+    }
+
+    #[test]
+    fn public_wallet_state_verified_parties_works() {
+        // Runs keygen simulation.
+        let (keys, identity_providers) = keygen::tests::simulate_keygen(1, 3);
+        let verifying_keys = wamu_core::collect_verifying_keys(&identity_providers);
+
+        // Verifies that a list of verifying keys that doesn't match the total number of parties is refused.
+        let state = PublicWalletState::new(keys[0].base.clone()).unwrap();
+        assert!(state.verified_parties().is_empty());
+        assert_eq!(
+            state
+                .clone()
+                .with_verified_parties(verifying_keys[..2].to_vec())
+                .unwrap_err(),
+            PublicWalletStateError::ParametersMismatch
+        );
+
+        // Verifies that the verified parties are persisted and reloaded in the same order.
+        let state = state.with_verified_parties(verifying_keys.clone()).unwrap();
+        let serialized = serde_json::to_string(&state).unwrap();
+        let deserialized: PublicWalletState = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.verified_parties(), verifying_keys.as_slice());
+        for (verifying_key, identity_provider) in deserialized
+            .verified_parties()
+            .iter()
+            .zip(&identity_providers)
+        {
+            assert_eq!(verifying_key, &identity_provider.verifying_key());
+        }
+
+        // Verifies that tampered verified parties are rejected.
+        let mut value: serde_json::Value = serde_json::from_str(&serialized).unwrap();
+        value["verified_parties"].as_array_mut().unwrap().pop();
+        assert!(serde_json::from_value::<PublicWalletState>(value).is_err());
+    }
+
+    #[test]
+    fn public_wallet_state_format_versions_work() {
+        // Runs keygen simulation.
+        let (keys, _) = keygen::tests::simulate_keygen(1, 2);
+        let state = PublicWalletState::new(keys[0].base.clone()).unwrap();
+        assert_eq!(state.format_version(), FORMAT_VERSION);
+        let value = serde_json::to_value(&state).unwrap();
+
+        for (format_version_option, expected_result) in [
+            // The current format version should load.
+            (Some(FORMAT_VERSION), Ok(FORMAT_VERSION)),
+            // Legacy state (i.e without a format version) should be migrated to the current format version.
+            (None, Ok(FORMAT_VERSION)),
+            // A future format version without a migration path should be rejected.
+            (
+                Some(FORMAT_VERSION + 1),
+                Err(PublicWalletStateError::UnsupportedFormatVersion(
+                    FORMAT_VERSION + 1,
+                )),
+            ),
+        ] {
+            // Sets (or removes) the format version.
+            let mut value = value.clone();
+            match format_version_option {
+                Some(format_version) => {
+                    value["format_version"] = serde_json::Value::from(format_version)
+                }
+                None => {
+                    value.as_object_mut().unwrap().remove("format_version");
+                }
+            }
+
+            // Verifies expected result.
+            let result = serde_json::from_value::<PublicWalletState>(value);
+            match expected_result {
+                Ok(expected_format_version) => {
+                    let deserialized = result.unwrap();
+                    assert_eq!(deserialized.format_version(), expected_format_version);
+                    assert_eq!(deserialized.group_key(), state.group_key());
+                }
+                Err(expected_error) => {
+                    assert_eq!(result.unwrap_err().to_string(), expected_error.to_string());
+                }
+            }
+        }
+    }
+}
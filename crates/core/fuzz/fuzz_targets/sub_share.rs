@@ -0,0 +1,56 @@
+//! Fuzzes "sub-share" construction, "sub-share" interpolation and share splitting and reconstruction.
+//!
+//! Run with `cargo +nightly fuzz run sub_share` from the `crates/core` directory.
+
+#![no_main]
+
+use crypto_bigint::U256;
+use libfuzzer_sys::fuzz_target;
+use wamu_core::test_utils::MockECDSAIdentityProvider;
+use wamu_core::{share_split_reconstruct, SecretShare, SubShare, SubShareInterpolator};
+
+fuzz_target!(|data: &[u8]| {
+    // Parses the input into 32 byte words.
+    let words: Vec<U256> = data
+        .chunks_exact(32)
+        .map(|chunk| U256::from_be_slice(chunk))
+        .collect();
+
+    // Constructs "sub-shares" from pairs of words (i.e fallible construction must never panic).
+    let sub_shares: Vec<SubShare> = words
+        .chunks_exact(2)
+        .filter_map(|pair| SubShare::new(pair[0], pair[1]).ok())
+        .collect();
+
+    // Interpolates all pairs of "sub-shares" (i.e fallible interpolation must never panic).
+    for point_a in &sub_shares {
+        for point_b in &sub_shares {
+            if let Ok(interpolator) = SubShareInterpolator::new(point_a, point_b) {
+                let _ = interpolator.secret();
+                for word in &words {
+                    let _ = interpolator.sub_share(*word);
+                }
+            }
+        }
+    }
+
+    // Splits and reconstructs the first word as a "secret share" (i.e valid secrets must round trip).
+    if let Some(secret) = words.first() {
+        let identity_provider = MockECDSAIdentityProvider::generate();
+        let secret_share = SecretShare::from(*secret);
+        if let Ok((signing_share, sub_share)) =
+            share_split_reconstruct::split(&secret_share, &identity_provider)
+        {
+            let reconstructed_secret_share = share_split_reconstruct::reconstruct(
+                &signing_share,
+                &sub_share,
+                &identity_provider,
+            )
+            .unwrap();
+            assert_eq!(
+                reconstructed_secret_share.to_be_bytes(),
+                secret_share.to_be_bytes()
+            );
+        }
+    }
+});
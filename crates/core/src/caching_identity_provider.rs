@@ -0,0 +1,228 @@
+//! Identity provider decorator that caches "message share" signatures (e.g for remote or HSM backed identity providers).
+
+use crypto_bigint::subtle::ConstantTimeEq;
+use std::sync::{Arc, Mutex, PoisonError};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::crypto::{Signature, VerifyingKey};
+use crate::IdentityProvider;
+
+/// The default maximum number of cached "message share" signatures.
+pub const DEFAULT_CACHE_CAPACITY: usize = 16;
+
+/// An identity provider that memoizes the "message share" signatures (i.e [`IdentityProvider::sign_message_share`] results)
+/// of an underlying identity provider keyed by the signed message (e.g the bytes of a "signing share").
+///
+/// **NOTE:** General signatures (i.e [`IdentityProvider::sign`] results used for identity challenges and requests) are never cached,
+/// and clones share the same cache, which is zeroized when the last clone is dropped or [`Self::clear`] is called.
+///
+/// **NOTE:** At most [`DEFAULT_CACHE_CAPACITY`] signatures are cached by default (see [`Self::with_capacity`]),
+/// and the oldest cached signatures are zeroized and evicted first.
+#[derive(Clone)]
+pub struct CachingIdentityProvider<I: IdentityProvider> {
+    inner: I,
+    capacity: usize,
+    cache: Arc<Mutex<Zeroizing<Vec<CachedMessageShareSignature>>>>,
+}
+
+/// A cached "message share" signature.
+#[derive(Zeroize, ZeroizeOnDrop)]
+struct CachedMessageShareSignature {
+    msg: Vec<u8>,
+    r: [u8; 32],
+    s: [u8; 32],
+}
+
+impl<I: IdentityProvider> CachingIdentityProvider<I> {
+    /// Given an identity provider, returns an identity provider that caches its "message share" signatures.
+    pub fn new(inner: I) -> Self {
+        Self {
+            inner,
+            capacity: DEFAULT_CACHE_CAPACITY,
+            cache: Arc::new(Mutex::new(Zeroizing::new(Vec::new()))),
+        }
+    }
+
+    /// Sets the maximum number of cached "message share" signatures (i.e a capacity of zero disables caching).
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Returns a reference to the underlying identity provider.
+    pub fn inner(&self) -> &I {
+        &self.inner
+    }
+
+    /// Zeroizes and removes all cached "message share" signatures.
+    pub fn clear(&self) {
+        self.cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .zeroize();
+    }
+}
+
+impl<I: IdentityProvider> std::fmt::Debug for CachingIdentityProvider<I> {
+    // Cached "message share" signatures are secret, so they're never formatted.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachingIdentityProvider")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I: IdentityProvider> IdentityProvider for CachingIdentityProvider<I> {
+    /// Returns the verifying key of the underlying identity provider.
+    fn verifying_key(&self) -> VerifyingKey {
+        self.inner.verifying_key()
+    }
+
+    /// Computes the signature of a message with the underlying identity provider (i.e never cached).
+    fn sign(&self, msg: &[u8]) -> Signature {
+        self.inner.sign(msg)
+    }
+
+    /// Returns the cached "message share" signature for the message (if any),
+    /// otherwise computes it with the underlying identity provider and caches it.
+    fn sign_message_share(&self, msg: &[u8]) -> ([u8; 32], [u8; 32]) {
+        // The lock is held while signing, so concurrent requests for the same message only sign once.
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        // Messages (e.g "signing shares") are secret, so they're compared in constant time.
+        if let Some(cached) = cache
+            .iter()
+            .find(|cached| bool::from(cached.msg.as_slice().ct_eq(msg)))
+        {
+            return (cached.r, cached.s);
+        }
+        let (r, s) = self.inner.sign_message_share(msg);
+        if self.capacity > 0 {
+            // Evicts the oldest cached signatures (which are zeroized when they're dropped) to stay within capacity.
+            let n_evicted = (cache.len() + 1).saturating_sub(self.capacity);
+            cache.drain(..n_evicted);
+            cache.push(CachedMessageShareSignature {
+                msg: msg.to_vec(),
+                r,
+                s,
+            });
+        }
+        (r, s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::share_split_reconstruct;
+    use crate::test_utils::MockECDSAIdentityProvider;
+    use crypto_bigint::U256;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// An identity provider that counts its "message share" signatures.
+    #[derive(Debug, Clone)]
+    struct CountingIdentityProvider {
+        inner: MockECDSAIdentityProvider,
+        message_share_signatures: Arc<AtomicUsize>,
+    }
+
+    impl IdentityProvider for CountingIdentityProvider {
+        fn verifying_key(&self) -> VerifyingKey {
+            self.inner.verifying_key()
+        }
+
+        fn sign(&self, msg: &[u8]) -> Signature {
+            self.inner.sign(msg)
+        }
+
+        fn sign_message_share(&self, msg: &[u8]) -> ([u8; 32], [u8; 32]) {
+            self.message_share_signatures.fetch_add(1, Ordering::SeqCst);
+            self.inner.sign_message_share(msg)
+        }
+    }
+
+    #[test]
+    fn caching_identity_provider_works() {
+        // Generates identity providers.
+        let message_share_signatures = Arc::new(AtomicUsize::new(0));
+        let counting_identity_provider = CountingIdentityProvider {
+            inner: MockECDSAIdentityProvider::generate(),
+            message_share_signatures: message_share_signatures.clone(),
+        };
+        let identity_provider = CachingIdentityProvider::new(counting_identity_provider.clone());
+
+        // Splits a "secret share" (i.e signs the "signing share" once).
+        let secret_share = U256::from_u64(42).into();
+        let (signing_share, sub_share) =
+            share_split_reconstruct::split(&secret_share, &identity_provider).unwrap();
+        assert_eq!(message_share_signatures.load(Ordering::SeqCst), 1);
+
+        // Verifies that reconstructing the same "secret share" multiple times (including with clones)
+        // doesn't invoke the underlying identity provider's `sign_message_share` again.
+        for provider in [&identity_provider, &identity_provider.clone()] {
+            let reconstructed_secret_share =
+                share_split_reconstruct::reconstruct(&signing_share, &sub_share, provider).unwrap();
+            assert_eq!(
+                reconstructed_secret_share.to_be_bytes(),
+                secret_share.to_be_bytes()
+            );
+        }
+        assert_eq!(message_share_signatures.load(Ordering::SeqCst), 1);
+
+        // Verifies that general signatures and verifying keys are delegated to the underlying identity provider.
+        let msg = b"Hello, world!";
+        assert_eq!(
+            identity_provider.sign(msg),
+            counting_identity_provider.sign(msg)
+        );
+        assert_eq!(
+            identity_provider.verifying_key(),
+            counting_identity_provider.verifying_key()
+        );
+
+        // Verifies that clearing the cache invokes the underlying identity provider again.
+        identity_provider.clear();
+        share_split_reconstruct::reconstruct(&signing_share, &sub_share, &identity_provider)
+            .unwrap();
+        assert_eq!(message_share_signatures.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn caching_identity_provider_capacity_works() {
+        let msgs: [&[u8]; 3] = [b"share 1", b"share 2", b"share 3"];
+
+        for (capacity, resigned_msg, expected_signatures) in [
+            // Only the oldest message is evicted when the capacity is exceeded.
+            (2, msgs[0], 4),
+            (2, msgs[2], 3),
+            // All messages fit in the cache.
+            (3, msgs[0], 3),
+            // Nothing is cached with a capacity of zero.
+            (0, msgs[2], 4),
+        ] {
+            // Generates a caching identity provider with the given capacity.
+            let message_share_signatures = Arc::new(AtomicUsize::new(0));
+            let identity_provider = CachingIdentityProvider::new(CountingIdentityProvider {
+                inner: MockECDSAIdentityProvider::generate(),
+                message_share_signatures: message_share_signatures.clone(),
+            })
+            .with_capacity(capacity);
+
+            // Signs all messages, and then signs one of them again.
+            let signatures: Vec<_> = msgs
+                .iter()
+                .map(|msg| identity_provider.sign_message_share(msg))
+                .collect();
+            let resigned_idx = msgs.iter().position(|msg| *msg == resigned_msg).unwrap();
+            assert_eq!(
+                identity_provider.sign_message_share(resigned_msg),
+                signatures[resigned_idx]
+            );
+
+            // Verifies the number of times the underlying identity provider's `sign_message_share` was invoked.
+            assert_eq!(
+                message_share_signatures.load(Ordering::SeqCst),
+                expected_signatures
+            );
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! Sub-protocol commands (i.e the commands of identity authenticated and quorum approved requests).
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::errors::UnknownCommandError;
+
+/// A sub-protocol command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Command {
+    /// Key generation.
+    KeyGeneration,
+    /// Signing.
+    Signing,
+    /// Share addition.
+    ShareAddition,
+    /// Share removal.
+    ShareRemoval,
+    /// Threshold modification.
+    ThresholdModification,
+    /// Membership change (i.e combined share addition, share removal and/or threshold modification).
+    MembershipChange,
+    /// Identity rotation.
+    IdentityRotation,
+    /// Share recovery with a quorum of parties.
+    ShareRecoveryQuorum,
+    /// Quorum approval.
+    QuorumApproval,
+}
+
+impl Command {
+    /// All sub-protocol commands.
+    pub const ALL: [Command; 9] = [
+        Command::KeyGeneration,
+        Command::Signing,
+        Command::ShareAddition,
+        Command::ShareRemoval,
+        Command::ThresholdModification,
+        Command::MembershipChange,
+        Command::IdentityRotation,
+        Command::ShareRecoveryQuorum,
+        Command::QuorumApproval,
+    ];
+
+    /// Returns the command string (i.e the command of identity authenticated and quorum approved request payloads).
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Command::KeyGeneration => "key-generation",
+            Command::Signing => "signing",
+            Command::ShareAddition => "share-addition",
+            Command::ShareRemoval => "share-removal",
+            Command::ThresholdModification => "threshold-modification",
+            Command::MembershipChange => "membership-change",
+            Command::IdentityRotation => "identity-rotation",
+            Command::ShareRecoveryQuorum => "share-recovery-quorum",
+            Command::QuorumApproval => "quorum-approval",
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = UnknownCommandError;
+
+    /// Converts a command string into a `Command`.
+    fn from_str(command: &str) -> Result<Self, Self::Err> {
+        Command::ALL
+            .into_iter()
+            .find(|it| it.as_str() == command)
+            .ok_or(UnknownCommandError)
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_string_conversion_works() {
+        for command in Command::ALL {
+            // Verifies that the command string round trips.
+            assert_eq!(command.as_str().parse::<Command>(), Ok(command));
+            assert_eq!(command.to_string(), command.as_str());
+        }
+
+        // Verifies that unknown command strings are rejected.
+        for command in ["", "command", "Signing", "share-recovery"] {
+            assert_eq!(command.parse::<Command>(), Err(UnknownCommandError));
+        }
+    }
+}
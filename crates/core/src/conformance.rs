@@ -0,0 +1,164 @@
+//! Conformance tests against golden test vectors for the wire behavior of core sub-protocols
+//! (i.e share splitting and reconstruction, identity challenge message bytes and request signing bytes).
+//!
+//! Test vectors are JSON files in the `tests/vectors` directory (see `tests/vectors/README.md` for their provenance).
+
+use crypto_bigint::{Encoding, U256};
+use serde_json::Value;
+
+use crate::crypto::Random32Bytes;
+use crate::share::{SecretShare, SigningShare, SubShare};
+use crate::test_utils::{self, MockECDSAIdentityProvider};
+use crate::traits::IdentityProvider;
+use crate::{
+    identity_authed_request, identity_challenge, quorum_approved_request, share_split_reconstruct,
+    utils,
+};
+
+#[test]
+fn share_split_reconstruct_vectors_work() {
+    let vectors = parse_vectors(include_str!(
+        "../tests/vectors/share_split_reconstruct.json"
+    ));
+    for vector in as_array(&vectors["split"]) {
+        let identity_provider =
+            MockECDSAIdentityProvider::from_seed(hex_32_bytes(&vector["identity_seed"]));
+        let secret_share =
+            SecretShare::from(U256::from_be_bytes(hex_32_bytes(&vector["secret_share"])));
+        let signing_share =
+            SigningShare::from(Random32Bytes::from(hex_32_bytes(&vector["signing_share"])));
+        let expected_sub_share = sub_share(vector);
+
+        // Verifies "sub-share" splitting.
+        let sub_share = share_split_reconstruct::split_with_signing_share(
+            &secret_share,
+            &signing_share,
+            &identity_provider,
+        )
+        .unwrap();
+        assert!(sub_share == expected_sub_share);
+
+        // Verifies "secret share" reconstruction.
+        let reconstructed_secret_share = share_split_reconstruct::reconstruct(
+            &signing_share,
+            &expected_sub_share,
+            &identity_provider,
+        )
+        .unwrap();
+        assert_eq!(
+            reconstructed_secret_share.to_be_bytes(),
+            secret_share.to_be_bytes()
+        );
+    }
+
+    for vector in as_array(&vectors["split_deterministic"]) {
+        let identity_provider =
+            MockECDSAIdentityProvider::from_seed(hex_32_bytes(&vector["identity_seed"]));
+        let seed = hex_bytes(&vector["seed"]);
+
+        // Verifies deterministic share splitting.
+        let (signing_share, sub_share) =
+            share_split_reconstruct::split_deterministic(&seed, &identity_provider).unwrap();
+        assert_eq!(
+            signing_share.to_be_bytes(),
+            hex_32_bytes(&vector["signing_share"])
+        );
+        assert!(sub_share == self::sub_share(vector));
+
+        // Verifies "secret share" derivation and reconstruction.
+        let secret_share = share_split_reconstruct::derive_secret_share(&seed, &identity_provider);
+        assert_eq!(
+            secret_share.to_be_bytes(),
+            hex_32_bytes(&vector["secret_share"])
+        );
+        let reconstructed_secret_share =
+            share_split_reconstruct::reconstruct(&signing_share, &sub_share, &identity_provider)
+                .unwrap();
+        assert_eq!(
+            reconstructed_secret_share.to_be_bytes(),
+            secret_share.to_be_bytes()
+        );
+    }
+}
+
+#[test]
+fn identity_challenge_vectors_work() {
+    let vectors = parse_vectors(include_str!("../tests/vectors/identity_challenge.json"));
+    for vector in as_array(&vectors["challenge_message_bytes"]) {
+        let challenge_fragments: Vec<Random32Bytes> = as_array(&vector["challenge_fragments"])
+            .iter()
+            .map(|fragment| Random32Bytes::from(hex_32_bytes(fragment)))
+            .collect();
+
+        // Verifies identity challenge message bytes.
+        assert_eq!(
+            identity_challenge::challenge_message_bytes(&challenge_fragments),
+            hex_bytes(&vector["message_bytes"])
+        );
+    }
+}
+
+#[test]
+fn request_signing_vectors_work() {
+    let vectors = parse_vectors(include_str!("../tests/vectors/request_signing.json"));
+    for vector in as_array(&vectors["identity_authed_request"]) {
+        let identity_provider =
+            MockECDSAIdentityProvider::from_seed(hex_32_bytes(&vector["identity_seed"]));
+        let verifying_key = identity_provider.verifying_key();
+        let command = vector["command"].as_str().unwrap();
+        let timestamp = vector["timestamp"].as_u64().unwrap();
+        assert_eq!(verifying_key.key, hex_bytes(&vector["verifying_key"]));
+
+        // Verifies request signing bytes and sign-able message bytes.
+        let signing_bytes =
+            identity_authed_request::request_signing_bytes(command, timestamp, &verifying_key);
+        assert_eq!(signing_bytes, hex_bytes(&vector["signing_bytes"]));
+        assert_eq!(
+            utils::prefix_message_bytes(&signing_bytes),
+            hex_bytes(&vector["message_bytes"])
+        );
+    }
+
+    for vector in as_array(&vectors["command_approval"]) {
+        // Verifies command approval message bytes.
+        assert_eq!(
+            quorum_approved_request::command_approval_message_bytes(
+                &Random32Bytes::from(hex_32_bytes(&vector["challenge_fragment"])),
+                vector["command"].as_str().unwrap(),
+                vector["timestamp"].as_u64().unwrap(),
+                0,
+                None,
+            ),
+            hex_bytes(&vector["message_bytes"])
+        );
+    }
+}
+
+/// Given the contents of a JSON test vector file, returns the parsed test vectors.
+fn parse_vectors(json: &str) -> Value {
+    serde_json::from_str(json).unwrap()
+}
+
+/// Given a JSON array, returns its items.
+fn as_array(value: &Value) -> &Vec<Value> {
+    value.as_array().unwrap()
+}
+
+/// Given a JSON test vector with hex encoded `sub_share_x` and `sub_share_y` coordinates, returns the "sub-share".
+fn sub_share(vector: &Value) -> SubShare {
+    SubShare::new(
+        U256::from_be_bytes(hex_32_bytes(&vector["sub_share_x"])),
+        U256::from_be_bytes(hex_32_bytes(&vector["sub_share_y"])),
+    )
+    .unwrap()
+}
+
+/// Given a JSON hex string, returns its bytes.
+fn hex_bytes(value: &Value) -> Vec<u8> {
+    test_utils::hex_bytes(value.as_str().unwrap())
+}
+
+/// Given a JSON hex string of 32 bytes, returns its bytes.
+fn hex_32_bytes(value: &Value) -> [u8; 32] {
+    hex_bytes(value).try_into().unwrap()
+}
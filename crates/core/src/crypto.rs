@@ -2,6 +2,7 @@
 
 use crypto_bigint::modular::constant_mod::ResidueParams;
 use crypto_bigint::{impl_modulus, Encoding, NonZero, Random, RandomMod, U256};
+use sha2::{Digest, Sha256, Sha512};
 use std::fmt;
 use zeroize::Zeroize;
 
@@ -105,19 +106,113 @@ pub fn verify_signature(
                         match (verifying_key.enc, signature.enc) {
                             // Verifies DER encoded ECDSA/Secp256k1/SHA-256 signatures with SEC1 encoded verifying key.
                             (KeyEncoding::SEC1, SignatureEncoding::DER) => {
-                                // Deserialize verifying key.
-                                // `k256::ecdsa::VerifyingKey` uses `Secp256k1` and `SHA-256`.
-                                let ver_key =
-                                    k256::ecdsa::VerifyingKey::from_sec1_bytes(&verifying_key.key);
                                 // Deserialize signature.
                                 let sig = k256::ecdsa::Signature::from_der(&signature.sig)
-                                    .map_err(|_| CryptoError::InvalidSignature)?;
+                                    .map_err(|_| CryptoError::SignatureDecodeError)?;
+                                // Deserialize verifying key.
+                                // `k256::ecdsa::VerifyingKey` uses `Secp256k1` and `SHA-256`.
+                                let ver_key = sec1_verifying_key(&verifying_key.key)?;
                                 // Verify ECDSA/Secp256k1/SHA-256 signature.
                                 use k256::ecdsa::signature::Verifier;
                                 ver_key
-                                    .map_err(|_| CryptoError::InvalidVerifyingKey)?
                                     .verify(msg, &sig)
-                                    .map_err(|_| CryptoError::InvalidSignature)
+                                    .map_err(|_| CryptoError::SignatureVerificationFailed)
+                            }
+                            _ => Err(CryptoError::UnsupportedEncoding),
+                        }
+                    }
+                    // Verifies ECDSA/Secp256k1/Keccak256 signatures (e.g Ethereum `personal_sign` signatures).
+                    MessageDigest::Keccak256 => {
+                        // Matches verifying key and signature encoding.
+                        match (verifying_key.enc, signature.enc) {
+                            // Verifies DER encoded ECDSA/Secp256k1/Keccak256 signatures with SEC1 encoded verifying key.
+                            (KeyEncoding::SEC1, SignatureEncoding::DER) => {
+                                // Deserialize signature.
+                                let sig = k256::ecdsa::Signature::from_der(&signature.sig)
+                                    .map_err(|_| CryptoError::SignatureDecodeError)?;
+                                // Deserialize verifying key.
+                                let ver_key = sec1_verifying_key(&verifying_key.key)?;
+                                // Computes the Keccak256 digest of the message.
+                                use sha3::Digest;
+                                let digest = sha3::Keccak256::digest(msg);
+                                // Verify ECDSA/Secp256k1/Keccak256 signature.
+                                use k256::ecdsa::signature::hazmat::PrehashVerifier;
+                                ver_key
+                                    .verify_prehash(&digest, &sig)
+                                    .map_err(|_| CryptoError::SignatureVerificationFailed)
+                            }
+                            _ => Err(CryptoError::UnsupportedEncoding),
+                        }
+                    }
+                    _ => Err(CryptoError::UnsupportedDigest),
+                }
+            }
+            // Verifies Ed25519ph (i.e pre-hashed EdDSA/Curve25519) signatures.
+            // RFC 8032 encoded verifying key and signature and SHA-512 pre-hash.
+            (SignatureAlgorithm::Ed25519ph, EllipticCurve::Curve25519) => {
+                // Matches the message digest/hash function.
+                match signature.hash {
+                    // Verifies Ed25519ph/SHA-512 signatures.
+                    MessageDigest::SHA512 => {
+                        // Matches verifying key and signature encoding.
+                        match (verifying_key.enc, signature.enc) {
+                            // Verifies RFC 8032 encoded Ed25519ph/SHA-512 signatures with RFC 8032 encoded verifying key.
+                            (KeyEncoding::RFC8032, SignatureEncoding::RFC8032) => {
+                                // Deserialize verifying key.
+                                let ver_key = <[u8; 32]>::try_from(verifying_key.key.as_slice())
+                                    .ok()
+                                    .and_then(|bytes| {
+                                        ed25519_dalek::VerifyingKey::from_bytes(&bytes).ok()
+                                    })
+                                    .ok_or(CryptoError::InvalidVerifyingKey)?;
+                                // Deserialize signature.
+                                let sig = ed25519_dalek::Signature::from_slice(&signature.sig)
+                                    .map_err(|_| CryptoError::SignatureDecodeError)?;
+                                // Computes the SHA-512 pre-hash of the message.
+                                let mut prehash = Sha512::new();
+                                prehash.update(msg);
+                                // Verify Ed25519ph/SHA-512 signature (without a context).
+                                ver_key
+                                    .verify_prehashed(prehash, None, &sig)
+                                    .map_err(|_| CryptoError::SignatureVerificationFailed)
+                            }
+                            _ => Err(CryptoError::UnsupportedEncoding),
+                        }
+                    }
+                    _ => Err(CryptoError::UnsupportedDigest),
+                }
+            }
+            // Verifies Schnorr/Secp256k1 (i.e BIP-340) signatures.
+            // BIP-340 encoded (i.e x-only) verifying key and signature and (tagged) SHA-256 challenge hash.
+            (SignatureAlgorithm::Schnorr, EllipticCurve::Secp256k1) => {
+                // Matches the message digest/hash function.
+                match signature.hash {
+                    // Verifies BIP-340 signatures.
+                    // NOTE: BIP-340 signs the message as is (e.g a Taproot signature hash),
+                    // the SHA-256 digest is the tagged hash of the challenge.
+                    MessageDigest::SHA256 => {
+                        // Matches verifying key and signature encoding.
+                        match (verifying_key.enc, signature.enc) {
+                            // Verifies BIP-340 encoded Schnorr/Secp256k1 signatures with BIP-340 encoded verifying key.
+                            (KeyEncoding::BIP340, SignatureEncoding::BIP340) => {
+                                // Deserialize verifying key.
+                                let ver_key = <[u8; 32]>::try_from(verifying_key.key.as_slice())
+                                    .ok()
+                                    .and_then(|bytes| {
+                                        k256::schnorr::VerifyingKey::from_bytes(&bytes).ok()
+                                    })
+                                    .ok_or(CryptoError::InvalidVerifyingKey)?;
+                                // Deserialize signature.
+                                let sig = <[u8; 64]>::try_from(signature.sig.as_slice())
+                                    .ok()
+                                    .and_then(|bytes| {
+                                        k256::schnorr::Signature::try_from(bytes.as_slice()).ok()
+                                    })
+                                    .ok_or(CryptoError::SignatureDecodeError)?;
+                                // Verify Schnorr/Secp256k1 signature.
+                                ver_key
+                                    .verify_raw(msg, &sig)
+                                    .map_err(|_| CryptoError::SignatureVerificationFailed)
                             }
                             _ => Err(CryptoError::UnsupportedEncoding),
                         }
@@ -131,7 +226,10 @@ pub fn verify_signature(
 }
 
 /// A verifying key (e.g an ECDSA/secp256k1 public key).
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// **NOTE:** Verifying keys are ordered by the canonical (algorithm, curve, encoding, key bytes) tuple.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VerifyingKey {
     /// The verifying key as a sequence of bytes.
     pub key: Vec<u8>,
@@ -143,8 +241,186 @@ pub struct VerifyingKey {
     pub enc: KeyEncoding,
 }
 
+impl PartialOrd for VerifyingKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for VerifyingKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.algo, self.curve, self.enc, &self.key).cmp(&(
+            other.algo,
+            other.curve,
+            other.enc,
+            &other.key,
+        ))
+    }
+}
+
+impl VerifyingKey {
+    /// Given a target key encoding, returns the verifying key in the target encoding
+    /// (e.g SEC1 bytes for on-chain verification contracts or an EIP-55 address for display),
+    /// or an appropriate `Err` result for unsupported conversions and malformed verifying keys.
+    ///
+    /// **NOTE:** EIP-55 encoded verifying keys are the UTF-8 bytes of the checksummed `0x` prefixed address,
+    /// and SEC1 encoded verifying keys are normalized to the compressed form.
+    ///
+    /// **NOTE:** EIP-55 addresses are derived from a hash of the verifying key,
+    /// so EIP-55 to SEC1 conversions aren't possible and return an `UnsupportedEncoding` error.
+    pub fn reencode(&self, target: KeyEncoding) -> Result<VerifyingKey, CryptoError> {
+        if (self.algo, self.curve) != (SignatureAlgorithm::ECDSA, EllipticCurve::Secp256k1) {
+            return Err(CryptoError::UnsupportedScheme);
+        }
+        let key = match (self.enc, target) {
+            (KeyEncoding::SEC1, KeyEncoding::SEC1) => {
+                sec1_verifying_key(&self.key)?.to_sec1_bytes().to_vec()
+            }
+            (KeyEncoding::SEC1, KeyEncoding::EIP55) => {
+                eip55_address(&sec1_verifying_key(&self.key)?).into_bytes()
+            }
+            (KeyEncoding::EIP55, KeyEncoding::EIP55) => {
+                // Verifies the address checksum.
+                let address = std::str::from_utf8(&self.key)
+                    .ok()
+                    .and_then(|address| address.strip_prefix("0x"))
+                    .filter(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+                    .ok_or(CryptoError::InvalidVerifyingKey)?;
+                if eip55_checksum(&address.to_ascii_lowercase()) != address {
+                    return Err(CryptoError::InvalidVerifyingKey);
+                }
+                self.key.clone()
+            }
+            (KeyEncoding::EIP55, KeyEncoding::SEC1)
+            | (KeyEncoding::RFC8032 | KeyEncoding::BIP340, _)
+            | (_, KeyEncoding::RFC8032 | KeyEncoding::BIP340) => {
+                return Err(CryptoError::UnsupportedEncoding)
+            }
+        };
+        Ok(VerifyingKey {
+            key,
+            algo: self.algo,
+            curve: self.curve,
+            enc: target,
+        })
+    }
+
+    /// Given a human-readable prefix (e.g `cosmos`), returns the bech32 encoded address of the verifying key
+    /// (i.e the RIPEMD-160 digest of the SHA-256 digest of the compressed SEC1 verifying key as used by Cosmos SDK chains),
+    /// or an appropriate `Err` result for unsupported schemes and encodings, malformed verifying keys and invalid prefixes.
+    ///
+    /// **NOTE:** Bitcoin SegWit addresses additionally encode a witness version, so they aren't produced by this method.
+    ///
+    /// Ref: <https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki>.
+    pub fn to_bech32_address(&self, hrp: &str) -> Result<String, CryptoError> {
+        if (self.algo, self.curve) != (SignatureAlgorithm::ECDSA, EllipticCurve::Secp256k1) {
+            return Err(CryptoError::UnsupportedScheme);
+        }
+        // Addresses are derived from a hash of the verifying key, so only SEC1 encoded verifying keys are supported.
+        if self.enc != KeyEncoding::SEC1 {
+            return Err(CryptoError::UnsupportedEncoding);
+        }
+        let verifying_key = sec1_verifying_key(&self.key)?;
+        let digest = ripemd::Ripemd160::digest(Sha256::digest(verifying_key.to_sec1_bytes()));
+        let hrp = bech32::Hrp::parse(hrp).map_err(|_| CryptoError::UnsupportedEncoding)?;
+        bech32::encode::<bech32::Bech32>(hrp, &digest).map_err(|_| CryptoError::UnsupportedEncoding)
+    }
+}
+
+/// Given SEC1 encoded verifying key bytes, returns an ECDSA/Secp256k1 verifying key or an `InvalidVerifyingKey` error.
+fn sec1_verifying_key(bytes: &[u8]) -> Result<k256::ecdsa::VerifyingKey, CryptoError> {
+    k256::ecdsa::VerifyingKey::from_sec1_bytes(bytes).map_err(|_| CryptoError::InvalidVerifyingKey)
+}
+
+/// Given an ECDSA/Secp256k1 verifying key, returns its checksummed `0x` prefixed Ethereum address.
+///
+/// Ref: <https://eips.ethereum.org/EIPS/eip-55>.
+fn eip55_address(verifying_key: &k256::ecdsa::VerifyingKey) -> String {
+    // The address is the last 20 bytes of the Keccak256 digest of the uncompressed public key (without the `0x04` prefix).
+    let encoded_point = verifying_key.to_encoded_point(false);
+    let digest = sha3::Keccak256::digest(&encoded_point.as_bytes()[1..]);
+    let address: String = digest[12..]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("0x{}", eip55_checksum(&address))
+}
+
+/// Given a lowercase hex encoded address (without the `0x` prefix), returns the EIP-55 checksummed address
+/// (i.e each letter is uppercase if the corresponding nibble of the Keccak256 digest of the address is at least 8).
+fn eip55_checksum(address: &str) -> String {
+    let digest = sha3::Keccak256::digest(address.as_bytes());
+    address
+        .chars()
+        .enumerate()
+        .map(|(idx, c)| {
+            let nibble = (digest[idx / 2] >> if idx % 2 == 0 { 4 } else { 0 }) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Domain tag for deriving [`PartyId`]s from verifying keys.
+const PARTY_ID_TAG: &[u8] = b"wamu:party-id";
+
+/// A stable party identifier derived from the party's long-term verifying key
+/// (e.g for referencing a party in audit logs and transcripts across ceremonies).
+///
+/// **NOTE:** Unlike party indices, party identifiers are not reassigned by key refresh, share addition or share removal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PartyId([u8; 32]);
+
+impl PartyId {
+    /// Given a verifying key, returns the SHA-256 hash of its domain separated
+    /// canonical (algorithm, curve, encoding, key bytes) encoding as a party identifier.
+    pub fn from_verifying_key(verifying_key: &VerifyingKey) -> Self {
+        let mut encoded_key = Vec::with_capacity(3 + 8 + verifying_key.key.len());
+        encoded_key.extend_from_slice(&[
+            verifying_key.algo as u8,
+            verifying_key.curve as u8,
+            verifying_key.enc as u8,
+        ]);
+        encoded_key.extend_from_slice(&(verifying_key.key.len() as u64).to_be_bytes());
+        encoded_key.extend_from_slice(&verifying_key.key);
+        Self(
+            Sha256::digest(crate::utils::domain_separated_message_bytes(
+                PARTY_ID_TAG,
+                &encoded_key,
+            ))
+            .into(),
+        )
+    }
+
+    /// Returns the 32 bytes representation of the party identifier.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0
+    }
+}
+
+impl From<&VerifyingKey> for PartyId {
+    /// Converts a verifying key into a `PartyId` representation.
+    fn from(verifying_key: &VerifyingKey) -> Self {
+        Self::from_verifying_key(verifying_key)
+    }
+}
+
+impl fmt::Display for PartyId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Formats the party identifier as lowercase hex.
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
 /// A signature (e.g a ECDSA/secp256k1/SHA-256 signature).
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Signature {
     /// The signature as a sequence of bytes.
     pub sig: Vec<u8>,
@@ -158,18 +434,80 @@ pub struct Signature {
     pub enc: SignatureEncoding,
 }
 
+impl Signature {
+    /// Given DER encoded ECDSA signature bytes, the elliptic curve and the hash function used for signing,
+    /// returns a DER encoded ECDSA signature or an appropriate `Err` result for malformed signature bytes.
+    pub fn from_der_bytes(
+        bytes: &[u8],
+        curve: EllipticCurve,
+        hash: MessageDigest,
+    ) -> Result<Self, CryptoError> {
+        match curve {
+            EllipticCurve::Secp256k1 => {
+                // Verifies that the signature bytes decode.
+                k256::ecdsa::Signature::from_der(bytes)
+                    .map_err(|_| CryptoError::SignatureDecodeError)?;
+                Ok(Self {
+                    sig: bytes.to_vec(),
+                    algo: SignatureAlgorithm::ECDSA,
+                    curve,
+                    hash,
+                    enc: SignatureEncoding::DER,
+                })
+            }
+            EllipticCurve::Curve25519 => Err(CryptoError::UnsupportedScheme),
+        }
+    }
+
+    /// Given the big-endian encoded `r` and `s` scalars of an ECDSA signature,
+    /// the elliptic curve and the hash function used for signing,
+    /// returns a DER encoded ECDSA signature or an appropriate `Err` result for invalid scalars.
+    pub fn from_raw(
+        r: &[u8; 32],
+        s: &[u8; 32],
+        curve: EllipticCurve,
+        hash: MessageDigest,
+    ) -> Result<Self, CryptoError> {
+        match curve {
+            EllipticCurve::Secp256k1 => {
+                // Scalars must be non-zero and less than the curve order.
+                let sig = k256::ecdsa::Signature::from_scalars(*r, *s)
+                    .map_err(|_| CryptoError::SignatureDecodeError)?;
+                Ok(Self {
+                    sig: sig.to_der().as_bytes().to_vec(),
+                    algo: SignatureAlgorithm::ECDSA,
+                    curve,
+                    hash,
+                    enc: SignatureEncoding::DER,
+                })
+            }
+            EllipticCurve::Curve25519 => Err(CryptoError::UnsupportedScheme),
+        }
+    }
+}
+
 /// A signature algorithm.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum SignatureAlgorithm {
     /// Ref: <https://en.wikipedia.org/wiki/Elliptic_Curve_Digital_Signature_Algorithm>.
     ECDSA,
     /// Ref: <https://en.wikipedia.org/wiki/EdDSA>.
     EdDSA,
+    /// Pre-hashed EdDSA over Curve25519 (i.e hash-then-sign with a SHA-512 pre-hash).
+    ///
+    /// Ref: <https://datatracker.ietf.org/doc/html/rfc8032#section-5.1>.
+    Ed25519ph,
+    /// Schnorr signatures over Secp256k1 as specified by BIP-340 (e.g for Bitcoin Taproot).
+    ///
+    /// Ref: <https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki>.
+    Schnorr,
 }
 
 /// An elliptic curve.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EllipticCurve {
     /// Ref: <https://www.secg.org/sec2-v2.pdf>.
     Secp256k1,
@@ -179,27 +517,566 @@ pub enum EllipticCurve {
 
 /// A cryptographic message digest/hash function.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MessageDigest {
     /// Ref: <https://en.wikipedia.org/wiki/SHA-2>.
     SHA256,
     /// Ref: <https://en.wikipedia.org/wiki/SHA-3>.
     Keccak256,
+    /// Ref: <https://en.wikipedia.org/wiki/SHA-2>.
+    SHA512,
 }
 
 /// A key encoding format.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum KeyEncoding {
     /// Ref: <https://www.secg.org/sec1-v2.pdf>.
     SEC1,
     /// Ref: <https://eips.ethereum.org/EIPS/eip-55>.
     EIP55,
+    /// Ref: <https://datatracker.ietf.org/doc/html/rfc8032#section-5.1.2>.
+    RFC8032,
+    /// 32 byte x-only public keys.
+    ///
+    /// Ref: <https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki#public-key-generation>.
+    BIP340,
 }
 
 /// A signature encoding format.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SignatureEncoding {
     /// Ref: <https://en.wikipedia.org/wiki/X.690#DER_encoding>.
     DER,
     /// Ref: <https://ethereum.org/en/developers/docs/data-structures-and-encoding/rlp/>.
     RLP,
+    /// Ref: <https://datatracker.ietf.org/doc/html/rfc8032#section-5.1.6>.
+    RFC8032,
+    /// 64 byte signatures (i.e the x-coordinate of `R` followed by `s`).
+    ///
+    /// Ref: <https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki#default-signing>.
+    BIP340,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockECDSAIdentityProvider;
+    use crate::traits::IdentityProvider;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::{BTreeSet, HashSet};
+    use std::hash::{Hash, Hasher};
+
+    #[test]
+    fn party_id_works() {
+        // Generates verifying keys.
+        let verifying_key = MockECDSAIdentityProvider::generate().verifying_key();
+        let other_verifying_key = MockECDSAIdentityProvider::generate().verifying_key();
+        // Same key bytes with a different encoding.
+        let re_encoded_verifying_key = VerifyingKey {
+            enc: KeyEncoding::EIP55,
+            ..verifying_key.clone()
+        };
+
+        // Verifies that party identifiers are deterministic.
+        let party_id = PartyId::from_verifying_key(&verifying_key);
+        assert_eq!(party_id, PartyId::from(&verifying_key.clone()));
+        assert_eq!(party_id.to_string().len(), 64);
+
+        // Verifies that party identifiers are distinct for distinct verifying keys.
+        for other_key in [other_verifying_key, re_encoded_verifying_key] {
+            assert_ne!(party_id, PartyId::from_verifying_key(&other_key));
+        }
+    }
+
+    #[test]
+    fn verifying_key_hash_and_ordering_works() {
+        // Generates verifying keys.
+        let verifying_key = MockECDSAIdentityProvider::generate().verifying_key();
+        let other_verifying_key = MockECDSAIdentityProvider::generate().verifying_key();
+        // Same key bytes with a different encoding.
+        let re_encoded_verifying_key = VerifyingKey {
+            enc: KeyEncoding::EIP55,
+            ..verifying_key.clone()
+        };
+
+        // Verifies that equal keys hash equal.
+        let hash = |key: &VerifyingKey| {
+            let mut hasher = DefaultHasher::new();
+            key.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&verifying_key), hash(&verifying_key.clone()));
+        let set: HashSet<VerifyingKey> = [
+            verifying_key.clone(),
+            verifying_key.clone(),
+            other_verifying_key.clone(),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(set.len(), 2);
+
+        // Verifies that ordering is total and consistent with equality.
+        let keys = [
+            verifying_key.clone(),
+            other_verifying_key.clone(),
+            re_encoded_verifying_key.clone(),
+        ];
+        for a in &keys {
+            assert_eq!(a.cmp(a), std::cmp::Ordering::Equal);
+            for b in &keys {
+                assert_eq!(a.cmp(b), b.cmp(a).reverse());
+                assert_eq!(a.cmp(b) == std::cmp::Ordering::Equal, a == b);
+            }
+        }
+
+        // Verifies that ordering is by the canonical (algorithm, curve, encoding, key bytes) tuple.
+        assert!(verifying_key < re_encoded_verifying_key);
+        assert!(other_verifying_key < re_encoded_verifying_key);
+
+        // Verifies that sorting is deterministic regardless of input order.
+        let sorted: Vec<VerifyingKey> = keys
+            .iter()
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let reverse_sorted: Vec<VerifyingKey> = keys
+            .iter()
+            .rev()
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        assert_eq!(sorted, reverse_sorted);
+        assert_eq!(sorted.len(), 3);
+    }
+
+    #[test]
+    fn verifying_key_reencode_works() {
+        // Verifying key for the secret key `1` and its known Ethereum address.
+        let secret_key = k256::ecdsa::SigningKey::from_slice(&U256::ONE.to_be_bytes()).unwrap();
+        let encoded_point = k256::ecdsa::VerifyingKey::from(&secret_key).to_encoded_point(false);
+        let expected_address = "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf";
+
+        for sec1_bytes in [
+            // Uncompressed SEC1 encoding.
+            encoded_point.as_bytes().to_vec(),
+            // Compressed SEC1 encoding.
+            k256::ecdsa::VerifyingKey::from(&secret_key)
+                .to_sec1_bytes()
+                .to_vec(),
+        ] {
+            let verifying_key = VerifyingKey {
+                key: sec1_bytes,
+                algo: SignatureAlgorithm::ECDSA,
+                curve: EllipticCurve::Secp256k1,
+                enc: KeyEncoding::SEC1,
+            };
+
+            // Verifies SEC1 -> EIP-55 conversion.
+            let eip55_key = verifying_key.reencode(KeyEncoding::EIP55).unwrap();
+            assert_eq!(eip55_key.key, expected_address.as_bytes());
+            assert_eq!(eip55_key.enc, KeyEncoding::EIP55);
+            assert_eq!(
+                (eip55_key.algo, eip55_key.curve),
+                (verifying_key.algo, verifying_key.curve)
+            );
+
+            // Verifies that re-encoding in the same encoding preserves the key identity
+            // (i.e SEC1 keys are normalized to the compressed form).
+            let sec1_key = verifying_key.reencode(KeyEncoding::SEC1).unwrap();
+            assert_eq!(sec1_key.key.len(), 33);
+            assert_eq!(
+                sec1_key.reencode(KeyEncoding::SEC1).unwrap(),
+                sec1_key.clone()
+            );
+            assert_eq!(sec1_key.reencode(KeyEncoding::EIP55).unwrap(), eip55_key);
+            assert_eq!(eip55_key.reencode(KeyEncoding::EIP55).unwrap(), eip55_key);
+
+            // Verifies that EIP-55 -> SEC1 conversion is rejected (i.e addresses are derived from a hash of the key).
+            assert_eq!(
+                eip55_key.reencode(KeyEncoding::SEC1),
+                Err(CryptoError::UnsupportedEncoding)
+            );
+        }
+
+        // Verifies EIP-55 checksum validation.
+        let eip55_key = |address: &str| VerifyingKey {
+            key: address.as_bytes().to_vec(),
+            algo: SignatureAlgorithm::ECDSA,
+            curve: EllipticCurve::Secp256k1,
+            enc: KeyEncoding::EIP55,
+        };
+        for (address, expected_result) in [
+            // Ref: <https://eips.ethereum.org/EIPS/eip-55#test-cases>.
+            ("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed", true),
+            ("0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359", true),
+            // Bad checksum.
+            ("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed", false),
+            // Missing prefix.
+            ("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed", false),
+            // Bad length.
+            ("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA", false),
+        ] {
+            let result = eip55_key(address).reencode(KeyEncoding::EIP55);
+            if expected_result {
+                assert_eq!(result, Ok(eip55_key(address)));
+            } else {
+                assert_eq!(result, Err(CryptoError::InvalidVerifyingKey));
+            }
+        }
+
+        // Verifies that malformed SEC1 keys and unsupported schemes are rejected.
+        let malformed_key = VerifyingKey {
+            // Invalid SEC1 tag.
+            key: vec![0x05; 33],
+            enc: KeyEncoding::SEC1,
+            ..eip55_key(expected_address)
+        };
+        assert_eq!(
+            malformed_key.reencode(KeyEncoding::EIP55),
+            Err(CryptoError::InvalidVerifyingKey)
+        );
+        assert_eq!(
+            VerifyingKey {
+                curve: EllipticCurve::Curve25519,
+                ..malformed_key
+            }
+            .reencode(KeyEncoding::EIP55),
+            Err(CryptoError::UnsupportedScheme)
+        );
+    }
+
+    #[test]
+    fn verifying_key_to_bech32_address_works() {
+        // Verifying key for the secret key `1` (i.e RIPEMD-160(SHA-256(key)) = `751e76e8199196d454941c45d1b3a323f1433bd6`).
+        let secret_key = k256::ecdsa::SigningKey::from_slice(&U256::ONE.to_be_bytes()).unwrap();
+        let verifying_key = VerifyingKey {
+            key: k256::ecdsa::VerifyingKey::from(&secret_key)
+                .to_encoded_point(false)
+                .as_bytes()
+                .to_vec(),
+            algo: SignatureAlgorithm::ECDSA,
+            curve: EllipticCurve::Secp256k1,
+            enc: KeyEncoding::SEC1,
+        };
+
+        for (hrp, expected_result) in [
+            (
+                "cosmos",
+                Ok("cosmos1w508d6qejxtdg4y5r3zarvary0c5xw7k6ah60c".to_string()),
+            ),
+            (
+                "osmo",
+                Ok("osmo1w508d6qejxtdg4y5r3zarvary0c5xw7kjxy2e2".to_string()),
+            ),
+            // Invalid human-readable prefixes.
+            ("", Err(CryptoError::UnsupportedEncoding)),
+            ("cosmos 1", Err(CryptoError::UnsupportedEncoding)),
+        ] {
+            // Verifies expected result.
+            assert_eq!(verifying_key.to_bech32_address(hrp), expected_result);
+        }
+
+        // Verifies that the address doesn't depend on the SEC1 form of the verifying key.
+        let compressed_key = verifying_key.reencode(KeyEncoding::SEC1).unwrap();
+        assert_eq!(
+            compressed_key.to_bech32_address("cosmos"),
+            verifying_key.to_bech32_address("cosmos")
+        );
+
+        // Verifies that unsupported schemes and encodings are rejected.
+        for (unsupported_key, expected_error) in [
+            (
+                VerifyingKey {
+                    curve: EllipticCurve::Curve25519,
+                    ..verifying_key.clone()
+                },
+                CryptoError::UnsupportedScheme,
+            ),
+            (
+                verifying_key.reencode(KeyEncoding::EIP55).unwrap(),
+                CryptoError::UnsupportedEncoding,
+            ),
+        ] {
+            assert_eq!(
+                unsupported_key.to_bech32_address("cosmos"),
+                Err(expected_error)
+            );
+        }
+    }
+
+    #[test]
+    fn verify_signature_decode_and_verification_failures_work() {
+        // Generates identity provider and a signature of the message.
+        let identity_provider = MockECDSAIdentityProvider::generate();
+        let verifying_key = identity_provider.verifying_key();
+        let message = b"Hello, world!";
+        let signature = identity_provider.sign(message);
+
+        for (signature_to_verify, expected_result) in [
+            // A valid signature should be ok.
+            (signature.clone(), Ok(())),
+            // Malformed DER bytes should fail to decode.
+            (
+                Signature {
+                    sig: vec![0x30, 0x01, 0x00],
+                    ..signature.clone()
+                },
+                Err(CryptoError::SignatureDecodeError),
+            ),
+            // Empty signature bytes should fail to decode.
+            (
+                Signature {
+                    sig: Vec::new(),
+                    ..signature.clone()
+                },
+                Err(CryptoError::SignatureDecodeError),
+            ),
+            // A well-formed signature of a different message should fail verification.
+            (
+                identity_provider.sign(b"Another message"),
+                Err(CryptoError::SignatureVerificationFailed),
+            ),
+        ] {
+            // Verifies expected result.
+            assert_eq!(
+                verify_signature(&verifying_key, message, &signature_to_verify),
+                expected_result
+            );
+        }
+    }
+
+    #[test]
+    fn verify_bip340_signature_works() {
+        // Known BIP-340 test vector (index 1).
+        // Ref: <https://github.com/bitcoin/bips/blob/master/bip-0340/test-vectors.csv>.
+        let verifying_key = VerifyingKey {
+            key: U256::from_be_hex(
+                "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            )
+            .to_be_bytes()
+            .to_vec(),
+            algo: SignatureAlgorithm::Schnorr,
+            curve: EllipticCurve::Secp256k1,
+            enc: KeyEncoding::BIP340,
+        };
+        let message =
+            U256::from_be_hex("243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89")
+                .to_be_bytes();
+        let r =
+            U256::from_be_hex("6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE3341");
+        let s =
+            U256::from_be_hex("8906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A");
+        let signature = Signature {
+            sig: [r.to_be_bytes(), s.to_be_bytes()].concat(),
+            algo: SignatureAlgorithm::Schnorr,
+            curve: EllipticCurve::Secp256k1,
+            hash: MessageDigest::SHA256,
+            enc: SignatureEncoding::BIP340,
+        };
+
+        for (verifying_key_to_use, message_to_verify, signature_to_verify, expected_result) in [
+            // The known signature should be ok.
+            (
+                verifying_key.clone(),
+                message.to_vec(),
+                signature.clone(),
+                Ok(()),
+            ),
+            // A different message should fail verification.
+            (
+                verifying_key.clone(),
+                b"Hello, world!".to_vec(),
+                signature.clone(),
+                Err(CryptoError::SignatureVerificationFailed),
+            ),
+            // Truncated signature bytes should fail to decode.
+            (
+                verifying_key.clone(),
+                message.to_vec(),
+                Signature {
+                    sig: signature.sig[1..].to_vec(),
+                    ..signature.clone()
+                },
+                Err(CryptoError::SignatureDecodeError),
+            ),
+            // A verifying key that's not an x-only public key should fail.
+            (
+                VerifyingKey {
+                    key: [&[0x02], verifying_key.key.as_slice()].concat(),
+                    ..verifying_key.clone()
+                },
+                message.to_vec(),
+                signature.clone(),
+                Err(CryptoError::InvalidVerifyingKey),
+            ),
+            // A DER encoded signature is unsupported.
+            (
+                verifying_key.clone(),
+                message.to_vec(),
+                Signature {
+                    enc: SignatureEncoding::DER,
+                    ..signature.clone()
+                },
+                Err(CryptoError::UnsupportedEncoding),
+            ),
+        ] {
+            // Verifies expected result.
+            assert_eq!(
+                verify_signature(
+                    &verifying_key_to_use,
+                    &message_to_verify,
+                    &signature_to_verify
+                ),
+                expected_result
+            );
+        }
+    }
+
+    #[test]
+    fn verify_ed25519ph_signature_works() {
+        // Generates Ed25519 signing key and its RFC 8032 encoded verifying key.
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7; 32]);
+        let verifying_key = VerifyingKey {
+            key: signing_key.verifying_key().to_bytes().to_vec(),
+            algo: SignatureAlgorithm::Ed25519ph,
+            curve: EllipticCurve::Curve25519,
+            enc: KeyEncoding::RFC8032,
+        };
+        let message = b"Hello, world!";
+
+        // Generates a pre-hashed (i.e Ed25519ph) and a non-pre-hashed (i.e pure Ed25519) signature of the message.
+        let mut prehash = Sha512::new();
+        prehash.update(message);
+        let prehashed_sig = signing_key.sign_prehashed(prehash, None).unwrap();
+        let pure_sig = ed25519_dalek::Signer::sign(&signing_key, message);
+        let signature = Signature {
+            sig: prehashed_sig.to_bytes().to_vec(),
+            algo: SignatureAlgorithm::Ed25519ph,
+            curve: EllipticCurve::Curve25519,
+            hash: MessageDigest::SHA512,
+            enc: SignatureEncoding::RFC8032,
+        };
+
+        for (signature_to_verify, expected_result) in [
+            // A pre-hashed signature should be ok.
+            (signature.clone(), Ok(())),
+            // A non-pre-hashed signature should fail verification.
+            (
+                Signature {
+                    sig: pure_sig.to_bytes().to_vec(),
+                    ..signature.clone()
+                },
+                Err(CryptoError::SignatureVerificationFailed),
+            ),
+            // Truncated signature bytes should fail to decode.
+            (
+                Signature {
+                    sig: signature.sig[1..].to_vec(),
+                    ..signature.clone()
+                },
+                Err(CryptoError::SignatureDecodeError),
+            ),
+            // A digest other than SHA-512 is unsupported.
+            (
+                Signature {
+                    hash: MessageDigest::SHA256,
+                    ..signature.clone()
+                },
+                Err(CryptoError::UnsupportedDigest),
+            ),
+        ] {
+            // Verifies expected result.
+            assert_eq!(
+                verify_signature(&verifying_key, message, &signature_to_verify),
+                expected_result
+            );
+        }
+    }
+
+    #[test]
+    fn signature_construction_works() {
+        // Generates identity provider and a signature of the message.
+        let identity_provider = MockECDSAIdentityProvider::generate();
+        let verifying_key = identity_provider.verifying_key();
+        let message = b"Hello, world!";
+        let signature = identity_provider.sign(message);
+        let (r, s) = k256::ecdsa::Signature::from_der(&signature.sig)
+            .unwrap()
+            .split_bytes();
+        let (r, s): ([u8; 32], [u8; 32]) = (r.into(), s.into());
+
+        for (constructed_signature, expected_result) in [
+            // Valid DER bytes.
+            (
+                Signature::from_der_bytes(
+                    &signature.sig,
+                    EllipticCurve::Secp256k1,
+                    MessageDigest::SHA256,
+                ),
+                Ok(()),
+            ),
+            // Valid raw scalars.
+            (
+                Signature::from_raw(&r, &s, EllipticCurve::Secp256k1, MessageDigest::SHA256),
+                Ok(()),
+            ),
+            // Malformed DER bytes.
+            (
+                Signature::from_der_bytes(
+                    &signature.sig[1..],
+                    EllipticCurve::Secp256k1,
+                    MessageDigest::SHA256,
+                ),
+                Err(CryptoError::SignatureDecodeError),
+            ),
+            // Empty DER bytes.
+            (
+                Signature::from_der_bytes(&[], EllipticCurve::Secp256k1, MessageDigest::SHA256),
+                Err(CryptoError::SignatureDecodeError),
+            ),
+            // Zero `r` scalar.
+            (
+                Signature::from_raw(
+                    &[0; 32],
+                    &s,
+                    EllipticCurve::Secp256k1,
+                    MessageDigest::SHA256,
+                ),
+                Err(CryptoError::SignatureDecodeError),
+            ),
+            // `s` scalar that overflows the curve order.
+            (
+                Signature::from_raw(
+                    &r,
+                    &[0xff; 32],
+                    EllipticCurve::Secp256k1,
+                    MessageDigest::SHA256,
+                ),
+                Err(CryptoError::SignatureDecodeError),
+            ),
+            // Unsupported curve.
+            (
+                Signature::from_der_bytes(
+                    &signature.sig,
+                    EllipticCurve::Curve25519,
+                    MessageDigest::SHA256,
+                ),
+                Err(CryptoError::UnsupportedScheme),
+            ),
+        ] {
+            // Verifies expected result.
+            assert_eq!(
+                constructed_signature.and_then(|sig| verify_signature(
+                    &verifying_key,
+                    message,
+                    &sig
+                )),
+                expected_result
+            );
+        }
+    }
 }
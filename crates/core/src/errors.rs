@@ -7,8 +7,30 @@ pub enum Error {
     Arithmetic(ArithmeticError),
     /// A cryptography error.
     Crypto(CryptoError),
+    /// An invalid identity challenge response signature from the current identity (e.g for identity rotation).
+    CurrentSignatureInvalid,
+    /// A party that appears more than once (e.g duplicate identity rotation responses).
+    DuplicateParty,
     /// Encoding error.
     Encoding,
+    /// Identity challenge response signatures for a different set of challenge fragments
+    /// (i.e neither the current nor the new identity's signature is valid for the expected challenge fragments).
+    FragmentMismatch,
+    /// A group identity rotation that was aborted because at least one party's identity rotation failed.
+    GroupRotationAborted,
+    /// A group identity rotation that is missing verified identity rotation challenge responses from some parties.
+    GroupRotationIncomplete,
+    /// An identity provider whose verifying key doesn't match the expected verifying key
+    /// (e.g reconstructing a "secret share" with the wrong device/identity).
+    IdentityMismatch,
+    /// An invalid threshold (i.e not in the range `1 <= threshold < n_parties`).
+    InvalidThreshold,
+    /// An invalid identity challenge response signature from the new identity (e.g for identity rotation).
+    NewSignatureInvalid,
+    /// An empty list of verified parties (i.e no other party is authorized).
+    NoVerifiedParties,
+    /// A number of parties or party index that doesn't fit in a `u16` (i.e more than 65535 parties).
+    TooManyParties,
     /// A signature from an unauthorized party.
     UnauthorizedParty,
 }
@@ -16,6 +38,9 @@ pub enum Error {
 /// An arithmetic error.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ArithmeticError {
+    /// Two points share the same x-coordinate
+    /// (e.g interpolating a line through two "sub-shares" with the same index).
+    DuplicateXCoordinate,
     /// The provided value is larger than the modulus
     /// (e.g values larger than the curve order for elliptic curve operations).
     ModulusOverflow,
@@ -36,12 +61,16 @@ impl From<CryptoError> for Error {
 /// A low-level cryptography error.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CryptoError {
-    /// An invalid signature for the message.
-    InvalidSignature,
+    /// A commitment that doesn't match the revealed value.
+    InvalidCommitment,
     /// An invalid verifying key.
     InvalidVerifyingKey,
     /// A signature algorithm and/or elliptic curve mismatch between the verifying key and signature.
     SchemeMismatch,
+    /// A malformed signature (i.e signature bytes that can't be decoded for the signature encoding).
+    SignatureDecodeError,
+    /// A well-formed signature that isn't valid for the message and verifying key.
+    SignatureVerificationFailed,
     /// An unsupported cryptographic scheme algorithm (e.g unsupported combination of signature algorithm and elliptic curve).
     UnsupportedScheme,
     /// An unsupported hash function.
@@ -61,6 +90,20 @@ pub enum IdentityAuthedRequestError {
     InvalidTimestamp,
     /// A request with either an invalid signature or an unauthorized signer.
     Unauthorized(Error),
+    /// A request with an unknown command (see [`Command`](crate::Command)).
+    UnknownCommand,
+    /// A request that proposes an invalid threshold (e.g a threshold modification request).
+    InvalidProposedThreshold,
+}
+
+/// An error for parsing an unknown command string (see [`Command`](crate::Command)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownCommandError;
+
+impl From<UnknownCommandError> for IdentityAuthedRequestError {
+    fn from(_: UnknownCommandError) -> Self {
+        IdentityAuthedRequestError::UnknownCommand
+    }
 }
 
 /// Implements `From<Error>` and `From<CryptoError>` for the error type.
@@ -84,10 +127,10 @@ macro_rules! impl_from_error {
 impl_from_error!(IdentityAuthedRequestError);
 
 /// An identity authenticated request verification error.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum QuorumApprovedRequestError {
-    /// Not enough approvals to form a quorum.
-    InsufficientApprovals,
+    /// Not enough approvals to form a quorum (with details about the quorum decision).
+    InsufficientApprovals(QuorumDecision),
     /// A request with either an invalid signature or an unauthorized signer.
     Unauthorized(Error),
 }
@@ -95,6 +138,34 @@ pub enum QuorumApprovedRequestError {
 // Implements `From<Error>` and `From<CryptoError>` for `QuorumApprovedRequestError`.
 impl_from_error!(QuorumApprovedRequestError);
 
+/// Details about a quorum decision (e.g for displaying why a quorum approved request wasn't authorized).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuorumDecision {
+    /// The number of distinct valid approvals (excluding the initiator's implicit approval)
+    /// or the summed weight of the initiator and distinct valid approvers for weighted thresholds.
+    pub valid_approvals: u64,
+    /// Positions of rejected approvals in the list of command approval payloads and the reason for rejecting them
+    /// (in ascending order of position).
+    pub invalid_approvals: Vec<(usize, ApprovalRejection)>,
+    /// The required number of approvals (excluding the initiator's implicit approval)
+    /// or the weight threshold for weighted thresholds.
+    pub required: u64,
+}
+
+/// A reason for rejecting a command approval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalRejection {
+    /// An approval with either an invalid signature or an unauthorized signer.
+    Unauthorized(Error),
+    /// An approval from a party or approver group whose approval has already been counted
+    /// (including the initiator's implicit approval).
+    Duplicate,
+    /// An approval that wasn't acknowledged by the initiator's challenge response.
+    Unacknowledged,
+    /// An approval that isn't valid yet (i.e its `valid_after` timestamp is in the future).
+    Timelocked { valid_after: u64 },
+}
+
 /// A share backup or recovery error.
 #[derive(Debug)]
 pub enum ShareBackupRecoveryError {
@@ -104,6 +175,8 @@ pub enum ShareBackupRecoveryError {
     InvalidSubShare,
     /// An encryption/decryption error.
     EncryptionError(aes_gcm::Error),
+    /// An unknown or unsupported cipher suite tag.
+    UnsupportedCipher(u8),
 }
 
 impl From<aes_gcm::Error> for ShareBackupRecoveryError {
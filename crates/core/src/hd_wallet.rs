@@ -0,0 +1,108 @@
+//! Identity provider for [BIP-32](https://github.com/bitcoin/bips/blob/master/bip-0032.mediawiki) hierarchical deterministic (HD) wallets.
+
+use bip32::XPrv;
+use k256::ecdsa::{signature::Signer, SigningKey};
+
+pub use bip32::DerivationPath;
+
+use crate::crypto::{
+    EllipticCurve, KeyEncoding, MessageDigest, Signature, SignatureAlgorithm, SignatureEncoding,
+    VerifyingKey,
+};
+use crate::IdentityProvider;
+
+/// An ECDSA/Secp256k1/SHA-256 based identity provider whose signing key is derived from a BIP-32 seed along a derivation path.
+#[derive(Debug, Clone)]
+pub struct Bip32IdentityProvider {
+    secret: SigningKey,
+}
+
+impl Bip32IdentityProvider {
+    /// Given a BIP-32 seed and a derivation path, returns an identity provider for the derived child key.
+    ///
+    /// **NOTE:** Deriving along the same path from the same seed always returns the same identity.
+    pub fn new(seed: impl AsRef<[u8]>, path: &DerivationPath) -> Result<Self, bip32::Error> {
+        let xprv = XPrv::derive_from_path(seed, path)?;
+        Ok(Self {
+            secret: xprv.private_key().clone(),
+        })
+    }
+}
+
+impl IdentityProvider for Bip32IdentityProvider {
+    /// Computes and serializes the ECDSA/Secp256k1 verifying key (in SEC1 format).
+    fn verifying_key(&self) -> VerifyingKey {
+        VerifyingKey {
+            // `k256::ecdsa::SigningKey` uses `Secp256k1` and `SHA-256`.
+            key: k256::ecdsa::VerifyingKey::from(&self.secret)
+                .to_sec1_bytes()
+                .to_vec(),
+            algo: SignatureAlgorithm::ECDSA,
+            curve: EllipticCurve::Secp256k1,
+            enc: KeyEncoding::SEC1,
+        }
+    }
+
+    /// Computes and serializes (in DER format) the ECDSA/Secp256k1/SHA-256 signature of a message.
+    fn sign(&self, msg: &[u8]) -> Signature {
+        // `k256::ecdsa::SigningKey` uses `Secp256k1` and `SHA-256`.
+        let signature: k256::ecdsa::Signature = self.secret.sign(msg);
+        Signature {
+            sig: signature.to_der().as_bytes().to_vec(),
+            algo: SignatureAlgorithm::ECDSA,
+            curve: EllipticCurve::Secp256k1,
+            hash: MessageDigest::SHA256,
+            enc: SignatureEncoding::DER,
+        }
+    }
+
+    /// Computes the ECDSA/Secp256k1/SHA-256 signature for a message and returns (`r`, `s`) as (`[u8; 32]`, `[u8; 32]`).
+    fn sign_message_share(&self, msg: &[u8]) -> ([u8; 32], [u8; 32]) {
+        // `k256::ecdsa::SigningKey` uses `Secp256k1` and `SHA-256`.
+        let signature: k256::ecdsa::Signature = self.secret.sign(msg);
+        let (r, s) = signature.split_bytes();
+        (r.into(), s.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto;
+
+    #[test]
+    fn bip32_identity_provider_works() {
+        // Seed, derivation paths and message to sign.
+        let seed = [7u8; 64];
+        let path: DerivationPath = "m/44'/60'/0'/0/0".parse().unwrap();
+        let other_path: DerivationPath = "m/44'/60'/0'/0/1".parse().unwrap();
+        let msg = b"Hello, world!";
+
+        // Derives identity providers.
+        let identity_provider = Bip32IdentityProvider::new(seed, &path).unwrap();
+        let identity_provider_same_path = Bip32IdentityProvider::new(seed, &path).unwrap();
+        let identity_provider_other_path = Bip32IdentityProvider::new(seed, &other_path).unwrap();
+
+        // Verifies that derivation is deterministic.
+        assert_eq!(
+            identity_provider.verifying_key(),
+            identity_provider_same_path.verifying_key()
+        );
+        assert_ne!(
+            identity_provider.verifying_key(),
+            identity_provider_other_path.verifying_key()
+        );
+        assert_eq!(
+            identity_provider.sign_message_share(msg),
+            identity_provider_same_path.sign_message_share(msg)
+        );
+
+        // Signing.
+        let signature = identity_provider.sign(msg);
+
+        // Verifying.
+        assert!(
+            crypto::verify_signature(&identity_provider.verifying_key(), msg, &signature).is_ok()
+        );
+    }
+}
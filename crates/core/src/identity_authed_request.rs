@@ -2,10 +2,11 @@
 //!
 //! Ref: <https://wamu.tech/specification#identity-authed-request>.
 
+use crate::command::Command;
 use crate::crypto::VerifyingKey;
 use crate::errors::{Error, IdentityAuthedRequestError};
 use crate::payloads::IdentityAuthedRequestPayload;
-use crate::traits::IdentityProvider;
+use crate::traits::{IdentityProvider, VerifiedParties};
 use crate::{crypto, utils};
 
 /// How long a request remains valid.
@@ -14,19 +15,72 @@ const EXPIRY_TIMEOUT: u64 = 60 * 60; // 1 hour.
 /// How far in the future a request is allowed to be (e.g due to out of sync clocks between parties).
 const FUTURE_TIMESTAMP_TOLERANCE: u64 = 5 * 60; // 5 minutes.
 
-/// Given a "command" and an identity provider, returns the payload for initiating an identity authenticated request.
+/// Version of the request signing bytes format (i.e the output of [`request_signing_bytes`]).
+pub const REQUEST_SIGNING_BYTES_VERSION: u8 = 1;
+
+/// Given a sub-protocol command and an identity provider, returns the payload for initiating an identity authenticated request.
 ///
 /// Ref: <https://wamu.tech/specification#identity-authed-request-initiation>.
 pub fn initiate(
-    command: &'static str,
+    command: Command,
+    identity_provider: &impl IdentityProvider,
+) -> IdentityAuthedRequestPayload {
+    initiate_with_prefix(command, identity_provider, utils::prefix_message_bytes)
+}
+
+/// Given a sub-protocol command, an identity provider and a message prefixing strategy
+/// (e.g [`utils::eth_personal_sign_bytes`] for identity providers that sign with Ethereum `personal_sign`),
+/// returns the payload for initiating an identity authenticated request.
+///
+/// Ref: <https://wamu.tech/specification#identity-authed-request-initiation>.
+pub fn initiate_with_prefix(
+    command: Command,
+    identity_provider: &impl IdentityProvider,
+    prefix_message_bytes: fn(&[u8]) -> Vec<u8>,
+) -> IdentityAuthedRequestPayload {
+    initiate_with_message_bytes(command, identity_provider, |timestamp, verifying_key| {
+        command_message_bytes(
+            command.as_str(),
+            timestamp,
+            verifying_key,
+            prefix_message_bytes,
+        )
+    })
+}
+
+/// Given a sub-protocol command, an application-wide domain (e.g `"com.example.wallet"`) and an identity provider,
+/// returns the payload for initiating an identity authenticated request namespaced to the domain
+/// (i.e requests from one application can't be replayed to another application that uses a different domain).
+///
+/// **NOTE:** The domain isn't part of the payload, so it must be configured identically by all parties
+/// (see [`verify_with_domain`]).
+///
+/// Ref: <https://wamu.tech/specification#identity-authed-request-initiation>.
+pub fn initiate_with_domain(
+    command: Command,
+    domain: &str,
     identity_provider: &impl IdentityProvider,
+) -> IdentityAuthedRequestPayload {
+    initiate_with_message_bytes(command, identity_provider, |timestamp, verifying_key| {
+        domain_command_message_bytes(domain, command.as_str(), timestamp, verifying_key)
+    })
+}
+
+/// Given a sub-protocol command, an identity provider and a function that returns the sign-able message bytes
+/// for a timestamp and the verifying key of the initiator,
+/// returns the payload for initiating an identity authenticated request.
+fn initiate_with_message_bytes(
+    command: Command,
+    identity_provider: &impl IdentityProvider,
+    message_bytes: impl Fn(u64, &VerifyingKey) -> Vec<u8>,
 ) -> IdentityAuthedRequestPayload {
     let timestamp = utils::unix_timestamp();
-    let signature = identity_provider.sign(&command_message_bytes(command, timestamp));
+    let verifying_key = identity_provider.verifying_key();
+    let signature = identity_provider.sign(&message_bytes(timestamp, &verifying_key));
 
     IdentityAuthedRequestPayload {
-        command,
-        verifying_key: identity_provider.verifying_key(),
+        command: command.as_str(),
+        verifying_key,
         timestamp,
         signature,
     }
@@ -38,9 +92,68 @@ pub fn initiate(
 /// Ref: <https://wamu.tech/specification#identity-authed-request-verification>.
 pub fn verify(
     request: &IdentityAuthedRequestPayload,
-    verified_parties: &[VerifyingKey],
+    verified_parties: &(impl VerifiedParties + ?Sized),
+) -> Result<(), IdentityAuthedRequestError> {
+    verify_with_prefix(request, verified_parties, utils::prefix_message_bytes)
+}
+
+/// Given an identity authenticated request payload, a list of verifying keys for the other parties
+/// and a message prefixing strategy (e.g [`utils::eth_personal_sign_bytes`] for Ethereum `personal_sign` signatures),
+/// returns an ok result for a valid request or an appropriate error result for an invalid request.
+///
+/// **NOTE:** The message prefixing strategy must match the one used to initiate the request.
+///
+/// Ref: <https://wamu.tech/specification#identity-authed-request-verification>.
+pub fn verify_with_prefix(
+    request: &IdentityAuthedRequestPayload,
+    verified_parties: &(impl VerifiedParties + ?Sized),
+    prefix_message_bytes: fn(&[u8]) -> Vec<u8>,
 ) -> Result<(), IdentityAuthedRequestError> {
-    if !verified_parties.contains(&request.verifying_key) {
+    verify_with_message_bytes(request, verified_parties, |request| {
+        command_message_bytes(
+            request.command,
+            request.timestamp,
+            &request.verifying_key,
+            prefix_message_bytes,
+        )
+    })
+}
+
+/// Given an identity authenticated request payload, an application-wide domain (e.g `"com.example.wallet"`)
+/// and a list of verifying keys for the other parties,
+/// returns an ok result for a valid request namespaced to the domain or an appropriate error result for an invalid request
+/// (e.g a request initiated for a different domain).
+///
+/// **NOTE:** The domain must match the one used to initiate the request (see [`initiate_with_domain`]).
+///
+/// Ref: <https://wamu.tech/specification#identity-authed-request-verification>.
+pub fn verify_with_domain(
+    request: &IdentityAuthedRequestPayload,
+    domain: &str,
+    verified_parties: &(impl VerifiedParties + ?Sized),
+) -> Result<(), IdentityAuthedRequestError> {
+    verify_with_message_bytes(request, verified_parties, |request| {
+        domain_command_message_bytes(
+            domain,
+            request.command,
+            request.timestamp,
+            &request.verifying_key,
+        )
+    })
+}
+
+/// Given an identity authenticated request payload, a list of verifying keys for the other parties
+/// and a function that returns the sign-able message bytes for the request,
+/// returns an ok result for a valid request or an appropriate error result for an invalid request.
+fn verify_with_message_bytes(
+    request: &IdentityAuthedRequestPayload,
+    verified_parties: &(impl VerifiedParties + ?Sized),
+    message_bytes: impl Fn(&IdentityAuthedRequestPayload) -> Vec<u8>,
+) -> Result<(), IdentityAuthedRequestError> {
+    // Command must be a known sub-protocol command.
+    request.command.parse::<Command>()?;
+
+    if !verified_parties.contains_party(&request.verifying_key) {
         // Sender must be a verified party.
         Err(IdentityAuthedRequestError::Unauthorized(
             Error::UnauthorizedParty,
@@ -55,20 +168,64 @@ pub fn verify(
         // Command signature must be valid.
         Ok(crypto::verify_signature(
             &request.verifying_key,
-            &command_message_bytes(request.command, request.timestamp),
+            &message_bytes(request),
             &request.signature,
         )?)
     }
 }
 
-/// Returns sign-able message bytes for the command and timestamp.
-fn command_message_bytes(command: &str, timestamp: u64) -> Vec<u8> {
-    utils::prefix_message_bytes(format!("{}{}", command, timestamp).as_bytes())
+/// Given a "command", a timestamp and the verifying key of the initiator,
+/// returns the canonical request signing bytes
+/// i.e `version || command length || command || timestamp || verifying key length || verifying key`,
+/// where the version is 1 byte, lengths are 8 byte big-endian unsigned integers
+/// and the timestamp is an 8 byte big-endian unsigned integer.
+///
+/// **NOTE:** These bytes are prefixed (e.g with [`utils::prefix_message_bytes`]) before signing.
+///
+/// Ref: <https://wamu.tech/specification#identity-authed-request-initiation>.
+pub fn request_signing_bytes(
+    command: &str,
+    timestamp: u64,
+    verifying_key: &VerifyingKey,
+) -> Vec<u8> {
+    let mut result = Vec::with_capacity(1 + 8 + command.len() + 8 + 8 + verifying_key.key.len());
+    result.push(REQUEST_SIGNING_BYTES_VERSION);
+    result.extend_from_slice(&(command.len() as u64).to_be_bytes());
+    result.extend_from_slice(command.as_bytes());
+    result.extend_from_slice(&timestamp.to_be_bytes());
+    result.extend_from_slice(&(verifying_key.key.len() as u64).to_be_bytes());
+    result.extend_from_slice(&verifying_key.key);
+    result
+}
+
+/// Returns sign-able message bytes for the command, timestamp and verifying key given a message prefixing strategy.
+fn command_message_bytes(
+    command: &str,
+    timestamp: u64,
+    verifying_key: &VerifyingKey,
+    prefix_message_bytes: fn(&[u8]) -> Vec<u8>,
+) -> Vec<u8> {
+    prefix_message_bytes(&request_signing_bytes(command, timestamp, verifying_key))
+}
+
+/// Returns sign-able message bytes for the command, timestamp and verifying key namespaced to an application-wide domain
+/// (i.e the length-prefixed domain is prepended to the request signing bytes, see [`utils::domain_separated_message_bytes`]).
+fn domain_command_message_bytes(
+    domain: &str,
+    command: &str,
+    timestamp: u64,
+    verifying_key: &VerifyingKey,
+) -> Vec<u8> {
+    utils::prefix_message_bytes(&utils::domain_separated_message_bytes(
+        domain.as_bytes(),
+        &request_signing_bytes(command, timestamp, verifying_key),
+    ))
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::crypto::{EllipticCurve, KeyEncoding, SignatureAlgorithm};
     use crate::errors::CryptoError;
     use crate::test_utils::MockECDSAIdentityProvider;
 
@@ -78,7 +235,7 @@ mod test {
         let identity_provider = MockECDSAIdentityProvider::generate();
 
         // Generates identity authenticated request payload.
-        let payload = initiate("command", &identity_provider);
+        let payload = initiate(Command::Signing, &identity_provider);
 
         for (verified_parties, timestamp_modification, signature_modification, expected_result) in [
             // Valid request from a verified party should be ok.
@@ -112,7 +269,7 @@ mod test {
                 None,
                 Some(identity_provider.sign(b"Hello, world!")),
                 Err(IdentityAuthedRequestError::Unauthorized(Error::Crypto(
-                    CryptoError::InvalidSignature,
+                    CryptoError::SignatureVerificationFailed,
                 ))),
             ),
         ] {
@@ -136,4 +293,192 @@ mod test {
             assert_eq!(result, expected_result);
         }
     }
+
+    #[test]
+    fn identity_authed_request_with_unknown_command_fails() {
+        // Generates identity provider.
+        let identity_provider = MockECDSAIdentityProvider::generate();
+        let verified_parties = [identity_provider.verifying_key()];
+
+        for (command, expected_result) in [
+            // Request with a known command should be ok.
+            (Command::ShareAddition.as_str(), Ok(())),
+            // Request with an unknown command should fail (even with a valid signature).
+            ("command", Err(IdentityAuthedRequestError::UnknownCommand)),
+            (
+                "share-addition ",
+                Err(IdentityAuthedRequestError::UnknownCommand),
+            ),
+        ] {
+            // Generates identity authenticated request payload with a valid signature
+            // (i.e manually, because initiators only accept known commands).
+            let timestamp = utils::unix_timestamp();
+            let verifying_key = identity_provider.verifying_key();
+            let signature = identity_provider.sign(&command_message_bytes(
+                command,
+                timestamp,
+                &verifying_key,
+                utils::prefix_message_bytes,
+            ));
+            let payload = IdentityAuthedRequestPayload {
+                command,
+                verifying_key,
+                timestamp,
+                signature,
+            };
+
+            // Verifies identity authenticated request payload.
+            assert_eq!(verify(&payload, &verified_parties), expected_result);
+        }
+    }
+
+    #[test]
+    fn identity_authed_request_with_prefix_works() {
+        // Generates identity provider.
+        let identity_provider = MockECDSAIdentityProvider::generate();
+        let verified_parties = [identity_provider.verifying_key()];
+
+        // Generates identity authenticated request payload using Ethereum `personal_sign` message prefixing.
+        let payload = initiate_with_prefix(
+            Command::Signing,
+            &identity_provider,
+            utils::eth_personal_sign_bytes,
+        );
+
+        // Verifies identity authenticated request payload with matching and mismatched message prefixing.
+        assert_eq!(
+            verify_with_prefix(&payload, &verified_parties, utils::eth_personal_sign_bytes),
+            Ok(())
+        );
+        assert_eq!(
+            verify(&payload, &verified_parties),
+            Err(IdentityAuthedRequestError::Unauthorized(Error::Crypto(
+                CryptoError::SignatureVerificationFailed,
+            )))
+        );
+    }
+
+    #[test]
+    fn identity_authed_request_with_domain_works() {
+        // Generates identity provider.
+        let identity_provider = MockECDSAIdentityProvider::generate();
+        let verified_parties = [identity_provider.verifying_key()];
+
+        // Generates identity authenticated request payload for domain A.
+        let payload = initiate_with_domain(Command::Signing, "com.example.a", &identity_provider);
+
+        for (domain, expected_result) in [
+            // Matching domain should be ok.
+            (Some("com.example.a"), Ok(())),
+            // Different domain should fail.
+            (
+                Some("com.example.b"),
+                Err(IdentityAuthedRequestError::Unauthorized(Error::Crypto(
+                    CryptoError::SignatureVerificationFailed,
+                ))),
+            ),
+            // No domain should fail.
+            (
+                None,
+                Err(IdentityAuthedRequestError::Unauthorized(Error::Crypto(
+                    CryptoError::SignatureVerificationFailed,
+                ))),
+            ),
+        ] {
+            // Verifies identity authenticated request payload.
+            let result = match domain {
+                Some(domain) => verify_with_domain(&payload, domain, &verified_parties),
+                None => verify(&payload, &verified_parties),
+            };
+
+            // Verifies expected result.
+            assert_eq!(result, expected_result);
+        }
+
+        // Verifies that a request without a domain fails verification under a domain.
+        assert_eq!(
+            verify_with_domain(
+                &initiate(Command::Signing, &identity_provider),
+                "com.example.a",
+                &verified_parties
+            ),
+            Err(IdentityAuthedRequestError::Unauthorized(Error::Crypto(
+                CryptoError::SignatureVerificationFailed,
+            )))
+        );
+    }
+
+    #[test]
+    fn identity_authed_request_batch_verification_with_index_works() {
+        // Generates identity providers for verified and unverified parties.
+        let verified_identity_providers: Vec<MockECDSAIdentityProvider> = (0..200)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let unverified_identity_providers: Vec<MockECDSAIdentityProvider> = (0..20)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verified_parties: Vec<VerifyingKey> = verified_identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Builds the verified party index once for the whole batch.
+        let verified_party_index = crate::utils::VerifiedPartyIndex::new(&verified_parties);
+
+        // Generates a batch of identity authenticated request payloads from verified and unverified parties.
+        let batch: Vec<IdentityAuthedRequestPayload> = verified_identity_providers
+            .iter()
+            .chain(unverified_identity_providers.iter())
+            .map(|identity_provider| initiate(Command::Signing, identity_provider))
+            .collect();
+
+        // Verifies that the index and the linear scan produce identical results for the whole batch.
+        let linear_results: Vec<_> = batch
+            .iter()
+            .map(|payload| verify(payload, &verified_parties))
+            .collect();
+        let indexed_results: Vec<_> = batch
+            .iter()
+            .map(|payload| verify(payload, &verified_party_index))
+            .collect();
+        assert_eq!(linear_results, indexed_results);
+        assert_eq!(
+            indexed_results
+                .iter()
+                .filter(|result| result.is_ok())
+                .count(),
+            verified_identity_providers.len()
+        );
+    }
+
+    #[test]
+    fn request_signing_bytes_golden_vector_works() {
+        // Fixed input.
+        let verifying_key = VerifyingKey {
+            key: vec![0x02, 0xab, 0xcd],
+            algo: SignatureAlgorithm::ECDSA,
+            curve: EllipticCurve::Secp256k1,
+            enc: KeyEncoding::SEC1,
+        };
+        let timestamp = 0x0102030405060708;
+
+        // Verifies the byte layout.
+        assert_eq!(
+            request_signing_bytes("command", timestamp, &verifying_key),
+            [
+                // Version.
+                &[REQUEST_SIGNING_BYTES_VERSION][..],
+                // Command length and command.
+                &[0, 0, 0, 0, 0, 0, 0, 7],
+                b"command",
+                // Timestamp.
+                &[1, 2, 3, 4, 5, 6, 7, 8],
+                // Verifying key length and verifying key.
+                &[0, 0, 0, 0, 0, 0, 0, 3],
+                &[0x02, 0xab, 0xcd],
+            ]
+            .concat()
+        );
+        assert_eq!(REQUEST_SIGNING_BYTES_VERSION, 1);
+    }
 }
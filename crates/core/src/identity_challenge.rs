@@ -2,11 +2,37 @@
 //!
 //! Ref: <https://wamu.tech/specification#identity-challenge>.
 
-use crate::crypto::{Random32Bytes, Signature, VerifyingKey};
+use crypto_bigint::U256;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+use crate::crypto::{PartyId, Random32Bytes, Signature, VerifyingKey};
 use crate::errors::CryptoError;
 use crate::traits::IdentityProvider;
 use crate::{crypto, utils};
 
+/// Domain tag for identity challenge fragment commitments.
+const FRAGMENT_COMMITMENT_TAG: &[u8] = b"wamu:identity-challenge:fragment-commitment";
+/// Domain tag for identity challenges with challenge fragments attributed to the verifiers that contributed them.
+const ATTRIBUTED_CHALLENGE_TAG: &[u8] = b"wamu:identity-challenge:attribution";
+
+/// A commitment to an identity challenge fragment (i.e a SHA-256 hash of the domain separated challenge fragment).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Commitment([u8; 32]);
+
+impl Commitment {
+    /// Returns the commitment as bytes.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0
+    }
+}
+
+impl From<[u8; 32]> for Commitment {
+    fn from(value: [u8; 32]) -> Self {
+        Self(value)
+    }
+}
+
 /// Returns a challenge fragment for initiating an identity challenge.
 ///
 /// Ref: <https://wamu.tech/specification#identity-challenge-initiation>.
@@ -14,6 +40,39 @@ pub fn initiate() -> Random32Bytes {
     Random32Bytes::generate()
 }
 
+/// Returns a challenge fragment for initiating an identity challenge along with a commitment to the challenge fragment
+/// (i.e for a commit-reveal identity challenge where each verifier publishes their commitment before revealing their challenge fragment,
+/// so that no verifier can choose their challenge fragment after seeing the others).
+pub fn initiate_committed() -> (Random32Bytes, Commitment) {
+    let challenge_fragment = initiate();
+    let commitment = fragment_commitment(&challenge_fragment);
+    (challenge_fragment, commitment)
+}
+
+/// Given a revealed challenge fragment and a previously published commitment,
+/// returns an `Ok` result if the challenge fragment matches the commitment, or an appropriate `Err` result otherwise.
+pub fn verify_fragment_commitment(
+    challenge_fragment: &Random32Bytes,
+    commitment: &Commitment,
+) -> Result<(), CryptoError> {
+    if &fragment_commitment(challenge_fragment) == commitment {
+        Ok(())
+    } else {
+        Err(CryptoError::InvalidCommitment)
+    }
+}
+
+/// Returns the commitment to a challenge fragment.
+fn fragment_commitment(challenge_fragment: &Random32Bytes) -> Commitment {
+    Commitment(
+        Sha256::digest(utils::domain_separated_message_bytes(
+            FRAGMENT_COMMITMENT_TAG,
+            &challenge_fragment.to_be_bytes(),
+        ))
+        .into(),
+    )
+}
+
 /// Given a list of identity challenge fragments and an identity provider, returns the response signature for an identity challenge.
 ///
 /// Ref: <https://wamu.tech/specification#identity-challenge-response>.
@@ -41,8 +100,67 @@ pub fn verify(
     )
 }
 
+/// Given the identity challenge fragments attributed to the verifiers that contributed them and an identity provider,
+/// returns the response signature for an identity challenge that binds each challenge fragment to its verifier.
+///
+/// **NOTE:** The response is only valid for [`verify_with_attribution`] (i.e not for [`verify`]).
+pub fn respond_with_attribution(
+    fragment_attribution: &HashMap<VerifyingKey, U256>,
+    identity_provider: &impl IdentityProvider,
+) -> Signature {
+    identity_provider.sign(&attributed_challenge_message_bytes(fragment_attribution))
+}
+
+/// Given an identity challenge response signature, the identity challenge fragments attributed to the verifiers that contributed them and
+/// a verifying key for challenged party,
+/// returns an `Ok` result if the identity challenge response signature is valid for exactly the attributed challenge fragments,
+/// or an appropriate `Err` result otherwise.
+///
+/// **NOTE:** The signed message binds each challenge fragment to the verifier that contributed it
+/// (e.g so that a verifier can later prove which challenge fragment it contributed for dispute resolution),
+/// so the same challenge fragments attributed to different verifiers are rejected.
+pub fn verify_with_attribution(
+    signature: &Signature,
+    fragment_attribution: &HashMap<VerifyingKey, U256>,
+    verifying_key: &VerifyingKey,
+) -> Result<(), CryptoError> {
+    crypto::verify_signature(
+        verifying_key,
+        &attributed_challenge_message_bytes(fragment_attribution),
+        signature,
+    )
+}
+
+/// Returns sign-able message bytes for the identity challenge fragments attributed to the verifiers that contributed them
+/// (i.e the party identifier of each verifier followed by its challenge fragment, in order of party identifier).
+pub(crate) fn attributed_challenge_message_bytes(
+    fragment_attribution: &HashMap<VerifyingKey, U256>,
+) -> Vec<u8> {
+    // Sort the attributed challenge fragments so that we always get the same challenge regardless of order of receiving challenges.
+    let mut sorted_attribution: Vec<([u8; 32], Random32Bytes)> = fragment_attribution
+        .iter()
+        .map(|(verifier, fragment)| {
+            (
+                PartyId::from_verifying_key(verifier).to_bytes(),
+                Random32Bytes::from(*fragment),
+            )
+        })
+        .collect();
+    sorted_attribution.sort();
+    utils::domain_separated_message_bytes(
+        ATTRIBUTED_CHALLENGE_TAG,
+        &sorted_attribution
+            .iter()
+            .fold(Vec::<u8>::new(), |mut acc, (party_id, fragment)| {
+                acc.extend_from_slice(party_id);
+                acc.extend_from_slice(&fragment.to_be_bytes());
+                acc
+            }),
+    )
+}
+
 /// Returns sign-able message bytes for the identity challenge fragments.
-fn challenge_message_bytes(challenge_fragments: &[Random32Bytes]) -> Vec<u8> {
+pub(crate) fn challenge_message_bytes(challenge_fragments: &[Random32Bytes]) -> Vec<u8> {
     // Sort the challenge fragments so that we always get the same challenge regardless of order of receiving challenges.
     let mut sorted_challenge_fragments = challenge_fragments.to_owned();
     sorted_challenge_fragments.sort();
@@ -82,7 +200,7 @@ mod tests {
                 &MockECDSAIdentityProvider::generate(),
                 &challenge_fragments,
                 &challenge_fragments,
-                Err(CryptoError::InvalidSignature),
+                Err(CryptoError::SignatureVerificationFailed),
             ),
             // Response signing the wrong challenge fragments should be rejected.
             (
@@ -91,7 +209,7 @@ mod tests {
                     .map(|n| Random32Bytes::from(U256::from(n)))
                     .collect(),
                 &challenge_fragments,
-                Err(CryptoError::InvalidSignature),
+                Err(CryptoError::SignatureVerificationFailed),
             ),
         ] {
             // Generates an identity challenge response using the "actual signer" and "signing challenge fragments" for this test case.
@@ -108,4 +226,102 @@ mod tests {
             assert_eq!(result, expected_result);
         }
     }
+
+    #[test]
+    fn identity_challenge_attribution_works() {
+        // Generates identity provider.
+        let identity_provider = MockECDSAIdentityProvider::generate();
+
+        // Generates identity challenge fragments attributed to the verifiers that contributed them.
+        let verifiers: Vec<VerifyingKey> = (0..3)
+            .map(|_| MockECDSAIdentityProvider::generate().verifying_key())
+            .collect();
+        let fragment_attribution: HashMap<VerifyingKey, U256> = verifiers
+            .iter()
+            .map(|verifier| (verifier.clone(), initiate().as_u256()))
+            .collect();
+        let signature = respond_with_attribution(&fragment_attribution, &identity_provider);
+
+        // Generates altered attributions.
+        let mut swapped = fragment_attribution.clone();
+        swapped.insert(verifiers[0].clone(), fragment_attribution[&verifiers[1]]);
+        swapped.insert(verifiers[1].clone(), fragment_attribution[&verifiers[0]]);
+        let mut misattributed = fragment_attribution.clone();
+        misattributed.insert(verifiers[0].clone(), initiate().as_u256());
+        let mut missing = fragment_attribution.clone();
+        missing.remove(&verifiers[0]);
+        let mut extra = fragment_attribution.clone();
+        extra.insert(
+            MockECDSAIdentityProvider::generate().verifying_key(),
+            initiate().as_u256(),
+        );
+
+        for (attribution, expected_result) in [
+            // The attributed challenge fragments should be accepted.
+            (&fragment_attribution, Ok(())),
+            // The same challenge fragments attributed to different verifiers should be rejected.
+            (&swapped, Err(CryptoError::SignatureVerificationFailed)),
+            // A verifier attributed a different challenge fragment should be rejected.
+            (
+                &misattributed,
+                Err(CryptoError::SignatureVerificationFailed),
+            ),
+            // A missing verifier should be rejected.
+            (&missing, Err(CryptoError::SignatureVerificationFailed)),
+            // An extra verifier should be rejected.
+            (&extra, Err(CryptoError::SignatureVerificationFailed)),
+        ] {
+            // Verifies expected result.
+            assert_eq!(
+                verify_with_attribution(
+                    &signature,
+                    attribution,
+                    &identity_provider.verifying_key()
+                ),
+                expected_result
+            );
+        }
+
+        // Verifies that an attributed response isn't valid for the unattributed challenge fragments and vice versa.
+        let challenge_fragments: Vec<Random32Bytes> = fragment_attribution
+            .values()
+            .copied()
+            .map(Random32Bytes::from)
+            .collect();
+        assert_eq!(
+            verify(
+                &signature,
+                &challenge_fragments,
+                &identity_provider.verifying_key()
+            ),
+            Err(CryptoError::SignatureVerificationFailed)
+        );
+        assert_eq!(
+            verify_with_attribution(
+                &respond(&challenge_fragments, &identity_provider),
+                &fragment_attribution,
+                &identity_provider.verifying_key()
+            ),
+            Err(CryptoError::SignatureVerificationFailed)
+        );
+    }
+
+    #[test]
+    fn identity_challenge_fragment_commitment_works() {
+        // Generates a committed identity challenge fragment.
+        let (challenge_fragment, commitment) = initiate_committed();
+
+        for (revealed_fragment, expected_result) in [
+            // Valid reveal should be accepted.
+            (challenge_fragment, Ok(())),
+            // Mismatched reveal should be rejected.
+            (initiate(), Err(CryptoError::InvalidCommitment)),
+        ] {
+            // Verifies expected result.
+            assert_eq!(
+                verify_fragment_commitment(&revealed_fragment, &commitment),
+                expected_result
+            );
+        }
+    }
 }
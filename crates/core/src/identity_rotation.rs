@@ -2,32 +2,45 @@
 //!
 //! Ref: <https://wamu.tech/specification#identity-rotation>.
 
+use crypto_bigint::U256;
+use std::collections::HashMap;
+
+use crate::command::Command;
 use crate::crypto::{Random32Bytes, VerifyingKey};
-use crate::errors::{Error, IdentityAuthedRequestError};
-use crate::payloads::{IdentityAuthedRequestPayload, IdentityRotationChallengeResponsePayload};
+use crate::errors::{CryptoError, Error, IdentityAuthedRequestError};
+use crate::payloads::{
+    ChallengeInitiation, IdentityAuthedRequestPayload, IdentityRotationChallengeResponsePayload,
+};
 use crate::share::{SigningShare, SubShare};
 use crate::traits::IdentityProvider;
 use crate::{identity_authed_request, identity_challenge, share_split_reconstruct, wrappers};
 
-const IDENTITY_ROTATION: &str = "identity-rotation";
+const IDENTITY_ROTATION: &str = Command::IdentityRotation.as_str();
 
 /// Given an identity provider, returns the payload for initiating an identity rotation request.
 pub fn initiate(identity_provider: &impl IdentityProvider) -> IdentityAuthedRequestPayload {
-    identity_authed_request::initiate(IDENTITY_ROTATION, identity_provider)
+    identity_authed_request::initiate(Command::IdentityRotation, identity_provider)
 }
 
 /// Given an identity rotation request payload and a list of verifying keys for the other parties,
-/// returns an ok result with a challenge fragment for initiating an identity challenge for a valid request
+/// returns an ok result with a challenge initiation record (i.e a challenge fragment for initiating an identity challenge
+/// and the requester, command and timestamp of the request) for a valid request
 /// or an appropriate error result for an invalid request.
 pub fn verify_request_and_initiate_challenge(
     request: &IdentityAuthedRequestPayload,
     verified_parties: &[VerifyingKey],
-) -> Result<Random32Bytes, IdentityAuthedRequestError> {
-    wrappers::verify_identity_authed_request_and_initiate_challenge(
+) -> Result<ChallengeInitiation, IdentityAuthedRequestError> {
+    let fragment = wrappers::verify_identity_authed_request_and_initiate_challenge(
         IDENTITY_ROTATION,
         request,
         verified_parties,
-    )
+    )?;
+    Ok(ChallengeInitiation {
+        fragment,
+        requester: request.verifying_key.clone(),
+        command: request.command.to_string(),
+        timestamp: request.timestamp,
+    })
 }
 
 /// Given a list of identity challenge fragments, the current identity provider and the new identity provider,
@@ -48,31 +61,115 @@ pub fn challenge_response(
     }
 }
 
+/// Given the identity challenge fragments attributed to the verifiers that contributed them,
+/// the current identity provider and the new identity provider,
+/// returns the identity rotation challenge response payload that includes the new verifying key and
+/// challenge response signatures (that bind each challenge fragment to its verifier) from both the current and the new identity providers.
+pub fn challenge_response_with_attribution(
+    fragment_attribution: &HashMap<VerifyingKey, U256>,
+    current_identity_provider: &impl IdentityProvider,
+    new_identity_provider: &impl IdentityProvider,
+) -> IdentityRotationChallengeResponsePayload {
+    IdentityRotationChallengeResponsePayload {
+        new_verifying_key: new_identity_provider.verifying_key(),
+        current_signature: identity_challenge::respond_with_attribution(
+            fragment_attribution,
+            current_identity_provider,
+        ),
+        new_signature: identity_challenge::respond_with_attribution(
+            fragment_attribution,
+            new_identity_provider,
+        ),
+    }
+}
+
 /// Given an identity rotation challenge response, a list of identity challenge fragments and
 /// a verifying key for challenged party,
-/// returns an `Ok` result for valid identity rotation challenge response signature, or an appropriate `Err` result otherwise.
+/// returns an `Ok` result for valid identity rotation challenge response signatures, or an appropriate `Err` result otherwise
+/// (i.e `CurrentSignatureInvalid` or `NewSignatureInvalid` if only one of the signatures is invalid,
+/// or `FragmentMismatch` if both signatures are invalid).
+///
+/// **NOTE:** If neither signature is valid for the challenge fragments,
+/// the most likely cause is that the challenged party signed a different set of challenge fragments.
 pub fn verify_challenge_response(
     response: &IdentityRotationChallengeResponsePayload,
     challenge_fragments: &[Random32Bytes],
     verifying_key: &VerifyingKey,
 ) -> Result<(), Error> {
-    // Verifies current identity.
-    identity_challenge::verify(
-        &response.current_signature,
-        challenge_fragments,
-        verifying_key,
-    )?;
-    // Verifies new identity.
-    Ok(identity_challenge::verify(
-        &response.new_signature,
-        challenge_fragments,
-        &response.new_verifying_key,
-    )?)
+    challenge_response_outcome(
+        // Verifies current identity.
+        identity_challenge::verify(
+            &response.current_signature,
+            challenge_fragments,
+            verifying_key,
+        ),
+        // Verifies new identity.
+        identity_challenge::verify(
+            &response.new_signature,
+            challenge_fragments,
+            &response.new_verifying_key,
+        ),
+    )
+}
+
+/// Given an identity rotation challenge response, the identity challenge fragments attributed to the verifiers that contributed them and
+/// a verifying key for challenged party,
+/// returns an `Ok` result if the identity rotation challenge response signatures are valid for exactly the attributed challenge fragments,
+/// or an appropriate `Err` result otherwise (see [`verify_challenge_response`]).
+///
+/// **NOTE:** The response must be generated by [`challenge_response_with_attribution`],
+/// so that each challenge fragment is bound to a specific verifier (e.g so that a verifier can later prove which challenge fragment it contributed for dispute resolution).
+pub fn verify_challenge_response_with_attribution(
+    response: &IdentityRotationChallengeResponsePayload,
+    fragment_attribution: &HashMap<VerifyingKey, U256>,
+    verifying_key: &VerifyingKey,
+) -> Result<(), Error> {
+    challenge_response_outcome(
+        // Verifies current identity.
+        identity_challenge::verify_with_attribution(
+            &response.current_signature,
+            fragment_attribution,
+            verifying_key,
+        ),
+        // Verifies new identity.
+        identity_challenge::verify_with_attribution(
+            &response.new_signature,
+            fragment_attribution,
+            &response.new_verifying_key,
+        ),
+    )
+}
+
+/// Given the verification results for the current and new identity challenge response signatures,
+/// returns the outcome of the identity rotation challenge response verification (see [`verify_challenge_response`]).
+fn challenge_response_outcome(
+    current_result: Result<(), CryptoError>,
+    new_result: Result<(), CryptoError>,
+) -> Result<(), Error> {
+    match (current_result, new_result) {
+        (Ok(()), Ok(())) => Ok(()),
+        (
+            Err(CryptoError::SignatureVerificationFailed),
+            Err(CryptoError::SignatureVerificationFailed),
+        ) => Err(Error::FragmentMismatch),
+        (Err(CryptoError::SignatureVerificationFailed), _) => Err(Error::CurrentSignatureInvalid),
+        (Err(error), _) => Err(Error::Crypto(error)),
+        (Ok(()), Err(CryptoError::SignatureVerificationFailed)) => Err(Error::NewSignatureInvalid),
+        (Ok(()), Err(error)) => Err(Error::Crypto(error)),
+    }
 }
 
 /// Given the current "signing share", "sub-share" and identity provider, and the new identity provider,
 /// returns an `Ok` result wrapping the new "signing share" and "sub-share" associated with the new identity provider,
 /// that can be used to reconstruct the current "secret share" given the new identity provider, or an appropriate `Err` result.
+///
+/// **NOTE:** Rotation without reconstructing the "secret share" (e.g by only recomputing "sub-share" a under the new identity
+/// and re-deriving "sub-share" b) is not possible without exposing the "secret share".
+/// The "secret share" is the constant term of the line through "sub-share" a and "sub-share" b,
+/// so a new "sub-share" b must lie on the line through the "secret share" and the new "sub-share" a,
+/// but any function of the current "sub-share" a and "sub-share" b that computes it
+/// already holds enough information to interpolate the "secret share" (i.e the "secret share" is still materialized, just implicitly).
+/// Keeping the current "sub-share" b instead would change the "secret share".
 pub fn rotate_signing_and_sub_share(
     signing_share: &SigningShare,
     sub_share_b: &SubShare,
@@ -87,11 +184,110 @@ pub fn rotate_signing_and_sub_share(
     share_split_reconstruct::split(&secret_share, new_identity_provider)
 }
 
+/// A coordinated identity rotation for the whole group (e.g when the underlying identity system migrates),
+/// that collects identity rotation challenge responses from all parties and either commits the new set of verifying keys
+/// (only if all parties prove control of both their current and new identities) or aborts,
+/// so the group never ends up half-rotated.
+#[derive(Debug, Clone)]
+pub struct GroupIdentityRotation {
+    /// Current verifying keys for all parties.
+    current_parties: Vec<VerifyingKey>,
+    /// Identity challenge fragments.
+    challenge_fragments: Vec<Random32Bytes>,
+    /// New verifying keys for parties with verified identity rotation challenge responses
+    /// (in the same order as the current verifying keys).
+    new_parties: Vec<Option<VerifyingKey>>,
+    /// Whether or not the group identity rotation was aborted.
+    is_aborted: bool,
+}
+
+impl GroupIdentityRotation {
+    /// Given the current verifying keys for all parties and a list of identity challenge fragments,
+    /// returns a group identity rotation.
+    pub fn new(current_parties: &[VerifyingKey], challenge_fragments: &[Random32Bytes]) -> Self {
+        Self {
+            current_parties: current_parties.to_vec(),
+            challenge_fragments: challenge_fragments.to_vec(),
+            new_parties: vec![None; current_parties.len()],
+            is_aborted: false,
+        }
+    }
+
+    /// Given the current verifying key of a party and its identity rotation challenge response,
+    /// returns an `Ok` result if the challenge response is valid, or an appropriate `Err` result otherwise.
+    ///
+    /// **NOTE:** An invalid challenge response from a party aborts the whole group identity rotation,
+    /// and so does a new verifying key that was already claimed by another party (i.e a `DuplicateParty` error),
+    /// while a second response from the same party is rejected with a `DuplicateParty` error (i.e responses are never overwritten).
+    pub fn add_response(
+        &mut self,
+        current_verifying_key: &VerifyingKey,
+        response: &IdentityRotationChallengeResponsePayload,
+    ) -> Result<(), Error> {
+        if self.is_aborted {
+            return Err(Error::GroupRotationAborted);
+        }
+        // Responses are only accepted from current parties.
+        let position = self
+            .current_parties
+            .iter()
+            .position(|verifying_key| verifying_key == current_verifying_key)
+            .ok_or(Error::UnauthorizedParty)?;
+        // Each party can only respond once.
+        if self.new_parties[position].is_some() {
+            return Err(Error::DuplicateParty);
+        }
+        // Aborts the group identity rotation if the party fails to prove control of both identities.
+        if let Err(error) =
+            verify_challenge_response(response, &self.challenge_fragments, current_verifying_key)
+        {
+            self.is_aborted = true;
+            return Err(error);
+        }
+        // Aborts the group identity rotation if the new verifying key was already claimed by another party.
+        if self
+            .new_parties
+            .iter()
+            .flatten()
+            .any(|verifying_key| verifying_key == &response.new_verifying_key)
+        {
+            self.is_aborted = true;
+            return Err(Error::DuplicateParty);
+        }
+        self.new_parties[position] = Some(response.new_verifying_key.clone());
+        Ok(())
+    }
+
+    /// Returns true if the group identity rotation was aborted.
+    pub fn is_aborted(&self) -> bool {
+        self.is_aborted
+    }
+
+    /// Returns true if verified identity rotation challenge responses have been collected from all parties.
+    pub fn is_complete(&self) -> bool {
+        self.new_parties.iter().all(Option::is_some)
+    }
+
+    /// Returns an `Ok` result with the new verifying keys for all parties (in the same order as the current verifying keys)
+    /// if all parties successfully rotated their identities, or an appropriate `Err` result otherwise
+    /// (i.e `GroupRotationAborted` if any party's identity rotation failed,
+    /// or `GroupRotationIncomplete` if some parties haven't responded yet).
+    pub fn commit(self) -> Result<Vec<VerifyingKey>, Error> {
+        if self.is_aborted {
+            Err(Error::GroupRotationAborted)
+        } else {
+            self.new_parties
+                .into_iter()
+                .collect::<Option<Vec<VerifyingKey>>>()
+                .ok_or(Error::GroupRotationIncomplete)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::crypto::Random32Bytes;
-    use crate::errors::CryptoError;
     use crate::share::SecretShare;
     use crate::test_utils::MockECDSAIdentityProvider;
     use crypto_bigint::U256;
@@ -115,7 +311,7 @@ mod tests {
         let init_payload = initiate(&current_identity_provider);
 
         // Verifies identity rotation request and initiates challenge.
-        let init_results: Vec<Result<Random32Bytes, IdentityAuthedRequestError>> = (0..5)
+        let init_results: Vec<Result<ChallengeInitiation, IdentityAuthedRequestError>> = (0..5)
             .map(|_| {
                 verify_request_and_initiate_challenge(
                     &init_payload,
@@ -127,14 +323,31 @@ mod tests {
         // Verifies expected result.
         assert!(!init_results.iter().any(|result| result.is_err()));
 
-        // Unwrap challenge fragments.
-        let challenge_fragments: Vec<Random32Bytes> = init_results
+        // Unwrap challenge initiation records.
+        let challenge_initiations: Vec<ChallengeInitiation> = init_results
             .into_iter()
             .map(|result| result.unwrap())
             .collect();
 
+        // Verifies that the challenge initiation metadata matches the request.
+        for challenge_initiation in &challenge_initiations {
+            assert_eq!(
+                challenge_initiation.requester,
+                current_identity_provider.verifying_key()
+            );
+            assert_eq!(challenge_initiation.command, IDENTITY_ROTATION);
+            assert_eq!(challenge_initiation.timestamp, init_payload.timestamp);
+        }
+
+        // Extracts challenge fragments.
+        let challenge_fragments: Vec<Random32Bytes> = challenge_initiations
+            .iter()
+            .map(|challenge_initiation| challenge_initiation.fragment)
+            .collect();
+
         for (
             actual_current_signer,
+            actual_new_signer,
             fragments_to_sign,
             fragments_to_verify,
             expected_challenge_result,
@@ -142,33 +355,43 @@ mod tests {
             // Valid challenge response should be accepted.
             (
                 &current_identity_provider,
+                &new_identity_provider,
                 &challenge_fragments,
                 &challenge_fragments,
                 Ok(()),
             ),
-            // Challenge response from the wrong signer should be rejected.
+            // Challenge response from the wrong current signer should be rejected.
+            (
+                &MockECDSAIdentityProvider::generate(),
+                &new_identity_provider,
+                &challenge_fragments,
+                &challenge_fragments,
+                Err(Error::CurrentSignatureInvalid),
+            ),
+            // Challenge response from the wrong new signer should be rejected.
             (
+                &current_identity_provider,
                 &MockECDSAIdentityProvider::generate(),
                 &challenge_fragments,
                 &challenge_fragments,
-                Err(Error::Crypto(CryptoError::InvalidSignature)),
+                Err(Error::NewSignatureInvalid),
             ),
             // Challenge response signing the wrong challenge fragments should be rejected.
             (
                 &current_identity_provider,
+                &new_identity_provider,
                 &(0..3u8)
                     .map(|n| Random32Bytes::from(U256::from(n)))
                     .collect(),
                 &challenge_fragments,
-                Err(Error::Crypto(CryptoError::InvalidSignature)),
+                Err(Error::FragmentMismatch),
             ),
         ] {
-            // Generates identity rotation challenge response using the "actual signer" and "signing challenge fragments" for this test case.
-            let challenge_payload = challenge_response(
-                fragments_to_sign,
-                actual_current_signer,
-                &new_identity_provider,
-            );
+            // Generates identity rotation challenge response using the "actual signers" and "signing challenge fragments" for this test case.
+            let mut challenge_payload =
+                challenge_response(fragments_to_sign, actual_current_signer, actual_new_signer);
+            // Sets the new verifying key to that of the expected new identity provider.
+            challenge_payload.new_verifying_key = new_identity_provider.verifying_key();
 
             // Verifies identity rotation challenge response using the challenged identity provider and "verification challenge fragments" for this test case.
             let challenge_result = verify_challenge_response(
@@ -204,4 +427,239 @@ mod tests {
             &secret_share.to_be_bytes()
         );
     }
+
+    #[test]
+    fn identity_rotation_fragment_attribution_works() {
+        // Generates current and new identity providers for the rotating party.
+        let current_identity_provider = MockECDSAIdentityProvider::generate();
+        let new_identity_provider = MockECDSAIdentityProvider::generate();
+
+        // Generates identity challenge fragments attributed to the verifiers that contributed them.
+        let verifiers: Vec<VerifyingKey> = (0..3)
+            .map(|_| MockECDSAIdentityProvider::generate().verifying_key())
+            .collect();
+        let fragment_attribution: HashMap<VerifyingKey, U256> = verifiers
+            .iter()
+            .map(|verifier| (verifier.clone(), identity_challenge::initiate().as_u256()))
+            .collect();
+
+        // Generates the identity rotation challenge response for the attributed challenge fragments.
+        let response = challenge_response_with_attribution(
+            &fragment_attribution,
+            &current_identity_provider,
+            &new_identity_provider,
+        );
+
+        // Generates altered attributions.
+        let mut swapped = fragment_attribution.clone();
+        swapped.insert(verifiers[0].clone(), fragment_attribution[&verifiers[1]]);
+        swapped.insert(verifiers[1].clone(), fragment_attribution[&verifiers[0]]);
+        let mut misattributed = fragment_attribution.clone();
+        misattributed.insert(
+            verifiers[0].clone(),
+            identity_challenge::initiate().as_u256(),
+        );
+        let mut missing = fragment_attribution.clone();
+        missing.remove(&verifiers[0]);
+        let mut extra = fragment_attribution.clone();
+        extra.insert(
+            MockECDSAIdentityProvider::generate().verifying_key(),
+            identity_challenge::initiate().as_u256(),
+        );
+
+        for (attribution, expected_result) in [
+            // The attributed challenge fragments should be accepted.
+            (&fragment_attribution, Ok(())),
+            // The same challenge fragments attributed to different verifiers should be rejected.
+            (&swapped, Err(Error::FragmentMismatch)),
+            // A verifier attributed a different challenge fragment should be rejected.
+            (&misattributed, Err(Error::FragmentMismatch)),
+            // A missing verifier should be rejected.
+            (&missing, Err(Error::FragmentMismatch)),
+            // An extra verifier should be rejected.
+            (&extra, Err(Error::FragmentMismatch)),
+        ] {
+            // Verifies expected result.
+            assert_eq!(
+                verify_challenge_response_with_attribution(
+                    &response,
+                    attribution,
+                    &current_identity_provider.verifying_key()
+                ),
+                expected_result
+            );
+        }
+
+        // Verifies that the attribution is consistent with the identity challenge response signature for the current identity.
+        assert_eq!(
+            identity_challenge::verify_with_attribution(
+                &response.current_signature,
+                &fragment_attribution,
+                &current_identity_provider.verifying_key()
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn group_identity_rotation_works() {
+        // Generates current and new identity providers for all parties.
+        let current_identity_providers: Vec<MockECDSAIdentityProvider> = (0..3)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let new_identity_providers: Vec<MockECDSAIdentityProvider> = (0..3)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let current_parties: Vec<VerifyingKey> = current_identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+        let new_parties: Vec<VerifyingKey> = new_identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Generates identity challenge fragments.
+        let challenge_fragments: Vec<Random32Bytes> =
+            (0..3).map(|_| identity_challenge::initiate()).collect();
+
+        for (failing_party, expected_result) in [
+            // All parties successfully rotating their identities should commit the new set of verifying keys.
+            (None, Ok(new_parties.clone())),
+            // A single party failing to rotate its identity should abort the whole group identity rotation.
+            (Some(1), Err(Error::GroupRotationAborted)),
+        ] {
+            let mut group_rotation =
+                GroupIdentityRotation::new(&current_parties, &challenge_fragments);
+
+            // Verifies that the group identity rotation can't be committed before all parties respond.
+            assert_eq!(
+                group_rotation.clone().commit(),
+                Err(Error::GroupRotationIncomplete)
+            );
+
+            for (idx, (current_identity_provider, new_identity_provider)) in
+                current_identity_providers
+                    .iter()
+                    .zip(new_identity_providers.iter())
+                    .enumerate()
+            {
+                // Generates the identity rotation challenge response (signed by the wrong new identity for the failing party).
+                let wrong_new_identity_provider = MockECDSAIdentityProvider::generate();
+                let response = if failing_party == Some(idx) {
+                    IdentityRotationChallengeResponsePayload {
+                        new_verifying_key: new_identity_provider.verifying_key(),
+                        ..challenge_response(
+                            &challenge_fragments,
+                            current_identity_provider,
+                            &wrong_new_identity_provider,
+                        )
+                    }
+                } else {
+                    challenge_response(
+                        &challenge_fragments,
+                        current_identity_provider,
+                        new_identity_provider,
+                    )
+                };
+
+                // Adds the identity rotation challenge response.
+                let result = group_rotation
+                    .add_response(&current_identity_provider.verifying_key(), &response);
+                match failing_party {
+                    Some(failing_idx) if idx == failing_idx => {
+                        assert_eq!(result, Err(Error::NewSignatureInvalid))
+                    }
+                    Some(failing_idx) if idx > failing_idx => {
+                        assert_eq!(result, Err(Error::GroupRotationAborted))
+                    }
+                    _ => assert_eq!(result, Ok(())),
+                }
+            }
+
+            // Verifies that responses from unknown parties are rejected.
+            if failing_party.is_none() {
+                let unknown_identity_provider = MockECDSAIdentityProvider::generate();
+                assert_eq!(
+                    group_rotation.clone().add_response(
+                        &unknown_identity_provider.verifying_key(),
+                        &challenge_response(
+                            &challenge_fragments,
+                            &unknown_identity_provider,
+                            &new_identity_providers[0],
+                        ),
+                    ),
+                    Err(Error::UnauthorizedParty)
+                );
+                assert!(group_rotation.is_complete());
+            }
+
+            // Verifies expected result.
+            assert_eq!(group_rotation.is_aborted(), failing_party.is_some());
+            assert_eq!(group_rotation.commit(), expected_result);
+        }
+    }
+
+    #[test]
+    fn group_identity_rotation_rejects_duplicate_responses() {
+        // Generates current and new identity providers for all parties.
+        let current_identity_providers: Vec<MockECDSAIdentityProvider> = (0..3)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let new_identity_providers: Vec<MockECDSAIdentityProvider> = (0..3)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let current_parties: Vec<VerifyingKey> = current_identity_providers
+            .iter()
+            .map(IdentityProvider::verifying_key)
+            .collect();
+
+        // Generates identity challenge fragments.
+        let challenge_fragments: Vec<Random32Bytes> =
+            (0..3).map(|_| identity_challenge::initiate()).collect();
+
+        // Adds a verified identity rotation challenge response for party 1.
+        let mut group_rotation = GroupIdentityRotation::new(&current_parties, &challenge_fragments);
+        assert_eq!(
+            group_rotation.add_response(
+                &current_parties[0],
+                &challenge_response(
+                    &challenge_fragments,
+                    &current_identity_providers[0],
+                    &new_identity_providers[0],
+                ),
+            ),
+            Ok(())
+        );
+
+        // Verifies that a second response from party 1 is rejected without overwriting the first one
+        // (i.e without aborting the group identity rotation).
+        assert_eq!(
+            group_rotation.add_response(
+                &current_parties[0],
+                &challenge_response(
+                    &challenge_fragments,
+                    &current_identity_providers[0],
+                    &new_identity_providers[1],
+                ),
+            ),
+            Err(Error::DuplicateParty)
+        );
+        assert!(!group_rotation.is_aborted());
+
+        // Verifies that a new verifying key that was already claimed by party 1 aborts the group identity rotation.
+        assert_eq!(
+            group_rotation.add_response(
+                &current_parties[1],
+                &challenge_response(
+                    &challenge_fragments,
+                    &current_identity_providers[1],
+                    &new_identity_providers[0],
+                ),
+            ),
+            Err(Error::DuplicateParty)
+        );
+        assert!(group_rotation.is_aborted());
+        assert_eq!(group_rotation.commit(), Err(Error::GroupRotationAborted));
+    }
 }
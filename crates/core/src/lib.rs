@@ -3,20 +3,33 @@
 #![feature(doc_cfg)]
 
 pub use self::{
+    command::Command,
     errors::{
-        CryptoError, Error, IdentityAuthedRequestError, QuorumApprovedRequestError,
-        ShareBackupRecoveryError,
+        ApprovalRejection, CryptoError, Error, IdentityAuthedRequestError,
+        QuorumApprovedRequestError, QuorumDecision, ShareBackupRecoveryError, UnknownCommandError,
     },
     payloads::{
-        CommandApprovalPayload, EncryptedShareBackup, IdentityAuthedRequestPayload,
-        IdentityRotationChallengeResponsePayload, QuorumApprovedChallengeResponsePayload,
+        ChallengeInitiation, CommandApprovalPayload, EncryptedShareBackup,
+        IdentityAuthedRequestPayload, IdentityRotationChallengeResponsePayload,
+        QuorumApprovedChallengeResponsePayload, RequestInfo, ThresholdModificationRequestPayload,
     },
     share::{SecretShare, SigningShare, SubShare},
-    traits::IdentityProvider,
+    traits::{IdentityProvider, VerifiedParties},
+    utils::{
+        collect_verifying_keys, party_count, party_index, quorum_size, validate_threshold,
+        VerifiedPartyIndex,
+    },
 };
 
+pub mod caching_identity_provider;
+mod command;
+#[cfg(test)]
+mod conformance;
 pub mod crypto;
 mod errors;
+#[cfg(feature = "bip32")]
+#[doc(cfg(feature = "bip32"))]
+pub mod hd_wallet;
 pub mod identity_authed_request;
 pub mod identity_challenge;
 pub mod identity_rotation;
@@ -25,6 +38,8 @@ pub mod quorum_approved_request;
 mod share;
 pub mod share_recovery_backup;
 pub mod share_split_reconstruct;
+pub mod signing;
+pub mod threshold_modification;
 mod traits;
 pub mod utils;
 pub mod wrappers;
@@ -32,3 +47,7 @@ pub mod wrappers;
 #[cfg(any(test, feature = "dev"))]
 #[doc(cfg(feature = "dev"))]
 pub mod test_utils;
+
+#[cfg(feature = "fuzzing")]
+#[doc(cfg(feature = "fuzzing"))]
+pub use self::share::SubShareInterpolator;
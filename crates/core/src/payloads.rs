@@ -15,6 +15,41 @@ pub struct IdentityAuthedRequestPayload {
     pub signature: Signature,
 }
 
+/// A threshold modification request payload.
+#[derive(Debug, Clone)]
+pub struct ThresholdModificationRequestPayload {
+    /// The identity authenticated request (whose signature is bound to the proposed threshold).
+    pub request: IdentityAuthedRequestPayload,
+    /// The proposed new threshold.
+    pub new_threshold: u16,
+}
+
+/// A record of a verified identity authenticated request for which an identity challenge was initiated
+/// (i.e the challenge fragment and who/what was authorized to initiate the challenge).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChallengeInitiation {
+    /// The identity challenge fragment.
+    pub fragment: Random32Bytes,
+    /// The verifying key of the requesting party.
+    pub requester: VerifyingKey,
+    /// The command of the request.
+    pub command: String,
+    /// The UTC timestamp at which the request was initiated.
+    pub timestamp: u64,
+}
+
+/// The metadata of a verified identity authenticated request
+/// (e.g for deciding whether or not to participate in the requested sub-protocol).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestInfo {
+    /// The verifying key of the requesting party.
+    pub requester: VerifyingKey,
+    /// The command of the request.
+    pub command: String,
+    /// The UTC timestamp at which the request was initiated.
+    pub timestamp: u64,
+}
+
 /// An identity rotation challenge response payload.
 #[derive(Debug, Clone)]
 pub struct IdentityRotationChallengeResponsePayload {
@@ -33,7 +68,10 @@ pub struct CommandApprovalPayload {
     pub challenge_fragment: Random32Bytes,
     /// The verifying key of the approving party.
     pub verifying_key: VerifyingKey,
-    /// A signature of the identity challenge fragment by the approving party.
+    /// The unix timestamp (in seconds) after which the approval becomes valid (e.g for a mandatory cooldown),
+    /// or zero for an approval that's valid immediately.
+    pub valid_after: u64,
+    /// A signature of the identity challenge fragment (and the `valid_after` timestamp if any) by the approving party.
     pub signature: Signature,
 }
 
@@ -46,7 +84,7 @@ pub struct QuorumApprovedChallengeResponsePayload {
     pub approving_quorum: Vec<VerifyingKey>,
 }
 
-/// An encrypted share backup (i.e an encrypted "signing share" and "sub-share", and a random nonce).
+/// An encrypted share backup (i.e an encrypted "signing share" and "sub-share", a random nonce and a cipher suite tag).
 pub struct EncryptedShareBackup {
     /// An encrypted "signing share".
     pub signing_share: Vec<u8>,
@@ -54,4 +92,6 @@ pub struct EncryptedShareBackup {
     pub sub_share: (Vec<u8>, Vec<u8>),
     /// The encryption/decryption nonce.
     pub nonce: Vec<u8>,
+    /// The tag of the cipher suite used for encryption (see [`BackupCipher`](crate::share_recovery_backup::BackupCipher)).
+    pub cipher: u8,
 }
@@ -2,22 +2,42 @@
 //!
 //! Ref: <https://wamu.tech/specification#quorum-approved-request>.
 
+use std::collections::HashMap;
+
+use crate::command::Command;
 use crate::crypto::{Random32Bytes, VerifyingKey};
-use crate::errors::{IdentityAuthedRequestError, QuorumApprovedRequestError};
+use crate::errors::{
+    ApprovalRejection, Error, IdentityAuthedRequestError, QuorumApprovedRequestError,
+    QuorumDecision,
+};
 use crate::payloads::{
     CommandApprovalPayload, IdentityAuthedRequestPayload, QuorumApprovedChallengeResponsePayload,
 };
-use crate::traits::IdentityProvider;
+use crate::traits::{IdentityProvider, VerifiedParties};
 use crate::{crypto, identity_authed_request, identity_challenge, utils, wrappers};
 
-/// Given a "command" and an identity provider, returns the payload for initiating an quorum approved request.
+/// Domain tag for time-locked command approvals.
+const TIMELOCKED_COMMAND_APPROVAL_TAG: &[u8] = b"wamu:timelocked-command-approval";
+
+/// Given a sub-protocol command and an identity provider, returns the payload for initiating an quorum approved request.
 pub fn initiate(
-    command: &'static str,
+    command: Command,
     identity_provider: &impl IdentityProvider,
 ) -> IdentityAuthedRequestPayload {
     identity_authed_request::initiate(command, identity_provider)
 }
 
+/// Given a sub-protocol command, an application-wide domain (e.g `"com.example.wallet"`) and an identity provider,
+/// returns the payload for initiating a quorum approved request namespaced to the domain
+/// (see [`identity_authed_request::initiate_with_domain`]).
+pub fn initiate_with_domain(
+    command: Command,
+    domain: &str,
+    identity_provider: &impl IdentityProvider,
+) -> IdentityAuthedRequestPayload {
+    identity_authed_request::initiate_with_domain(command, domain, identity_provider)
+}
+
 /// Given a "command" a quorum approved request initialization payload, an identity provider and a list of verifying keys for the other parties,
 /// returns an ok result with a "command" approval payload for initiating an identity challenge and approval acknowledgement for a valid request
 /// or an appropriate error result for an invalid request.
@@ -26,44 +46,266 @@ pub fn verify_request_and_initiate_challenge(
     request: &IdentityAuthedRequestPayload,
     identity_provider: &impl IdentityProvider,
     verified_parties: &[VerifyingKey],
+) -> Result<CommandApprovalPayload, IdentityAuthedRequestError> {
+    verify_request_and_initiate_challenge_with_timelock(
+        command,
+        request,
+        identity_provider,
+        verified_parties,
+        0,
+    )
+}
+
+/// Same as [`verify_request_and_initiate_challenge`] but for a request namespaced to
+/// an application-wide domain (see [`initiate_with_domain`]),
+/// where the returned "command" approval payload is also bound to the domain
+/// (see [`ApprovalPolicy::with_domain`]).
+///
+/// **NOTE:** The domain must match the one used to initiate the request.
+pub fn verify_request_and_initiate_challenge_with_domain(
+    command: &str,
+    domain: &str,
+    request: &IdentityAuthedRequestPayload,
+    identity_provider: &impl IdentityProvider,
+    verified_parties: &[VerifyingKey],
+) -> Result<CommandApprovalPayload, IdentityAuthedRequestError> {
+    let challenge_fragment =
+        wrappers::verify_identity_authed_request_and_initiate_challenge_with_domain(
+            command,
+            domain,
+            request,
+            verified_parties,
+        )?;
+    Ok(approve(
+        challenge_fragment,
+        request,
+        identity_provider,
+        0,
+        Some(domain),
+    ))
+}
+
+/// Same as [`verify_request_and_initiate_challenge`] but returns a time-locked "command" approval payload
+/// that only becomes valid after the given unix timestamp (in seconds) e.g to give other parties time to veto a high-value action.
+///
+/// **NOTE:** The `valid_after` timestamp is signed by the approving party, so it can't be removed or changed,
+/// and a `valid_after` timestamp of zero returns an approval that's valid immediately.
+pub fn verify_request_and_initiate_challenge_with_timelock(
+    command: &str,
+    request: &IdentityAuthedRequestPayload,
+    identity_provider: &impl IdentityProvider,
+    verified_parties: &[VerifyingKey],
+    valid_after: u64,
 ) -> Result<CommandApprovalPayload, IdentityAuthedRequestError> {
     let challenge_fragment = wrappers::verify_identity_authed_request_and_initiate_challenge(
         command,
         request,
         verified_parties,
     )?;
+    Ok(approve(
+        challenge_fragment,
+        request,
+        identity_provider,
+        valid_after,
+        None,
+    ))
+}
+
+/// Given a challenge fragment, a (verified) quorum approved request initialization payload, an identity provider,
+/// a `valid_after` timestamp and the application-wide domain of the request (if any),
+/// returns a "command" approval payload (i.e the approval signature is also bound to the domain).
+pub(crate) fn approve(
+    challenge_fragment: Random32Bytes,
+    request: &IdentityAuthedRequestPayload,
+    identity_provider: &impl IdentityProvider,
+    valid_after: u64,
+    domain: Option<&str>,
+) -> CommandApprovalPayload {
     let signature = identity_provider.sign(&command_approval_message_bytes(
         &challenge_fragment,
         request.command,
         request.timestamp,
+        valid_after,
+        domain,
     ));
-    Ok(CommandApprovalPayload {
+    CommandApprovalPayload {
         challenge_fragment,
         verifying_key: identity_provider.verifying_key(),
+        valid_after,
         signature,
-    })
+    }
+}
+
+/// A policy for counting "command" approvals toward a quorum
+/// (i.e a quorum size or a weighted threshold, and optionally approver groups, the application-wide domain of the request
+/// and an injected current unix timestamp for time-locked approvals).
+///
+/// **NOTE:** All options can be combined (e.g a weighted threshold for a request namespaced to an application-wide domain).
+#[derive(Debug, Clone)]
+pub struct ApprovalPolicy<'a> {
+    /// The quorum size or weighted threshold.
+    threshold: ApprovalThreshold<'a>,
+    /// The application-wide domain of the request (if any).
+    domain: Option<&'a str>,
+    /// Verified parties partitioned into approver groups.
+    approver_groups: &'a [Vec<VerifyingKey>],
+    /// The current unix timestamp (in seconds) if injected (e.g from a trusted clock).
+    now: Option<u64>,
+}
+
+/// The number (or summed weight) of approvals required by an [`ApprovalPolicy`].
+#[derive(Debug, Clone)]
+enum ApprovalThreshold<'a> {
+    /// Quorum size (including the initiator's implicit approval).
+    QuorumSize(usize),
+    /// Weights of the parties and the weight threshold.
+    Weighted {
+        weights: &'a HashMap<VerifyingKey, u64>,
+        weight_threshold: u64,
+    },
+}
+
+impl<'a> ApprovalPolicy<'a> {
+    /// Given a quorum size (including the initiator's implicit approval),
+    /// returns a policy where each distinct valid approval counts once toward the quorum.
+    pub fn new(quorum_size: usize) -> Self {
+        Self::with_threshold(ApprovalThreshold::QuorumSize(quorum_size))
+    }
+
+    /// Given the weights of the parties and a weight threshold (e.g stake-weighted approvals),
+    /// returns a policy where the summed weight of distinct valid approvers must be at least the weight threshold.
+    ///
+    /// **NOTE:** The initiator's weight counts toward the threshold since the initiator is an implicit approval,
+    /// while approvals from parties without a weight contribute zero.
+    pub fn weighted(weights: &'a HashMap<VerifyingKey, u64>, weight_threshold: u64) -> Self {
+        Self::with_threshold(ApprovalThreshold::Weighted {
+            weights,
+            weight_threshold,
+        })
+    }
+
+    /// Sets the application-wide domain of the request (and "command" approvals)
+    /// (see [`verify_request_and_initiate_challenge_with_domain`]).
+    pub fn with_domain(mut self, domain: &'a str) -> Self {
+        self.domain = Some(domain);
+        self
+    }
+
+    /// Sets approver groups (e.g parties in the same security domain or with the same custodian),
+    /// where at most one approval per group counts toward the quorum.
+    ///
+    /// **NOTE:** Parties that aren't in any group each count individually,
+    /// while approvals from the initiator's group never count since the initiator is an implicit approval.
+    pub fn with_approver_groups(mut self, approver_groups: &'a [Vec<VerifyingKey>]) -> Self {
+        self.approver_groups = approver_groups;
+        self
+    }
+
+    /// Sets the current unix timestamp (in seconds) (e.g from a trusted clock),
+    /// where time-locked approvals whose `valid_after` timestamp is after `now` don't count toward the quorum.
+    ///
+    /// **NOTE:** Defaults to the current unix timestamp of the system clock.
+    pub fn with_timestamp(mut self, now: u64) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// Given a quorum size or weighted threshold, returns a policy without any other options.
+    fn with_threshold(threshold: ApprovalThreshold<'a>) -> Self {
+        Self {
+            threshold,
+            domain: None,
+            approver_groups: &[],
+            now: None,
+        }
+    }
+
+    /// Returns the current unix timestamp (in seconds).
+    fn timestamp(&self) -> u64 {
+        self.now.unwrap_or_else(utils::unix_timestamp)
+    }
+
+    /// Returns the required number of approvals (excluding the initiator's implicit approval) or the weight threshold.
+    fn required(&self) -> u64 {
+        match self.threshold {
+            ApprovalThreshold::QuorumSize(quorum_size) => quorum_size.saturating_sub(1) as u64,
+            ApprovalThreshold::Weighted {
+                weight_threshold, ..
+            } => weight_threshold,
+        }
+    }
+
+    /// Given the verifying key of the initiator and the verifying keys of distinct valid approvers,
+    /// returns the number of approvals (excluding the initiator's implicit approval)
+    /// or the summed weight of the initiator and approvers.
+    fn progress<'b>(
+        &self,
+        initiator_verifying_key: &'b VerifyingKey,
+        approvers: impl ExactSizeIterator<Item = &'b VerifyingKey>,
+    ) -> u64 {
+        match self.threshold {
+            ApprovalThreshold::QuorumSize(_) => approvers.len() as u64,
+            ApprovalThreshold::Weighted { weights, .. } => approvers
+                .chain([initiator_verifying_key])
+                .fold(0u64, |total, verifying_key| {
+                    total.saturating_add(weights.get(verifying_key).copied().unwrap_or(0))
+                }),
+        }
+    }
+
+    /// Given a verifying key, returns the position of its approver group (if any).
+    fn group_idx(&self, verifying_key: &VerifyingKey) -> Option<usize> {
+        self.approver_groups
+            .iter()
+            .position(|group| group.contains(verifying_key))
+    }
 }
 
 /// Given a list of command approval payloads, an identity provider, a quorum approved request initialization payload,
-/// a quorum size and a list of verifying keys for the other parties,
+/// a list of verifying keys for the other parties and an approval policy,
 /// returns an ok result with a quorum approved challenge response payload
 /// or an appropriate error result for an invalid request.
+///
+/// **NOTE:** Time-locked approvals count toward the quorum (i.e the injected current unix timestamp isn't used),
+/// so that the challenge response becomes valid once they're valid (see [`verify_challenge_response`]).
 pub fn challenge_response(
     approvals: &[CommandApprovalPayload],
     identity_provider: &impl IdentityProvider,
     request: &IdentityAuthedRequestPayload,
-    quorum_size: usize,
-    verified_parties: &[VerifyingKey],
+    verified_parties: &(impl VerifiedParties + ?Sized),
+    policy: &ApprovalPolicy,
 ) -> Result<QuorumApprovedChallengeResponsePayload, QuorumApprovedRequestError> {
-    // quorum_size - 1 because of implicit approval from initiator.
-    let valid_approvals = verify_approvals(approvals, request, quorum_size - 1, verified_parties)?;
+    let initiator_verifying_key = identity_provider.verifying_key();
+    let mut invalid_approvals = Vec::new();
+    let valid_approvals = filter_distinct_group_approvals(
+        filter_distinct_valid_approvals(
+            index_approvals(approvals),
+            request,
+            verified_parties,
+            &initiator_verifying_key,
+            policy,
+            &mut invalid_approvals,
+        ),
+        &initiator_verifying_key,
+        policy,
+        &mut invalid_approvals,
+    );
+    verify_quorum(
+        &valid_approvals,
+        invalid_approvals,
+        &initiator_verifying_key,
+        policy,
+    )?;
     let approving_quorum = valid_approvals
         .iter()
-        .map(|approval| approval.verifying_key.clone())
+        .map(|(_, approval)| approval.verifying_key.clone())
         .collect();
     Ok(QuorumApprovedChallengeResponsePayload {
         signature: identity_challenge::respond(
-            &extract_challenge_fragments(&valid_approvals).collect::<Vec<Random32Bytes>>(),
+            &valid_approvals
+                .iter()
+                .map(|(_, approval)| approval.challenge_fragment)
+                .collect::<Vec<Random32Bytes>>(),
             identity_provider,
         ),
         approving_quorum,
@@ -72,102 +314,511 @@ pub fn challenge_response(
 
 /// Given a quorum approved challenge response payload, a list of command approval payloads,
 /// a verifying key for challenged party, a quorum approved request initialization payload,
-/// a quorum size and a list of verifying keys for the other parties,
+/// a list of verifying keys for the other parties and an approval policy,
 /// returns an `Ok` result for valid quorum approved challenge response, or an appropriate `Err` result otherwise.
+///
+/// **NOTE:** Time-locked approvals whose `valid_after` timestamp is after the current unix timestamp
+/// (see [`ApprovalPolicy::with_timestamp`]) don't count toward the quorum.
 pub fn verify_challenge_response(
     response: &QuorumApprovedChallengeResponsePayload,
     approvals: &[CommandApprovalPayload],
     verifying_key: &VerifyingKey,
     request: &IdentityAuthedRequestPayload,
-    quorum_size: usize,
-    verified_parties: &[VerifyingKey],
+    verified_parties: &(impl VerifiedParties + ?Sized),
+    policy: &ApprovalPolicy,
 ) -> Result<(), QuorumApprovedRequestError> {
-    let initiator_acknowledged_approvals: Vec<CommandApprovalPayload> = approvals
-        .iter()
-        .filter(|approval| response.approving_quorum.contains(&approval.verifying_key))
-        .cloned()
-        .collect();
-    verify_approvals(
-        &initiator_acknowledged_approvals,
+    let (initiator_acknowledged_approvals, mut invalid_approvals) =
+        partition_acknowledged_approvals(response, approvals);
+    let valid_approvals = filter_distinct_valid_approvals(
+        initiator_acknowledged_approvals,
         request,
-        // quorum_size - 1 because of implicit approval from initiator.
-        quorum_size - 1,
         verified_parties,
+        verifying_key,
+        policy,
+        &mut invalid_approvals,
+    );
+    // The initiator signs the challenge fragments of the distinct valid approvals it acknowledged
+    // (i.e repeat approvals from the same party aren't part of the signed challenge).
+    let initiator_acknowledged_fragments: Vec<Random32Bytes> = valid_approvals
+        .iter()
+        .map(|(_, approval)| approval.challenge_fragment)
+        .collect();
+    // Time-locked approvals are part of the signed challenge, but they don't count toward the quorum.
+    let unlocked_approvals = filter_unlocked_approvals(
+        filter_distinct_group_approvals(
+            valid_approvals,
+            verifying_key,
+            policy,
+            &mut invalid_approvals,
+        ),
+        policy.timestamp(),
+        &mut invalid_approvals,
+    );
+    verify_quorum(
+        &unlocked_approvals,
+        invalid_approvals,
+        verifying_key,
+        policy,
     )?;
     Ok(identity_challenge::verify(
         &response.signature,
-        &extract_challenge_fragments(&initiator_acknowledged_approvals)
-            .collect::<Vec<Random32Bytes>>(),
+        &initiator_acknowledged_fragments,
         verifying_key,
     )?)
 }
 
-/// Given a list of command approval payloads, a quorum approved request initialization payload,
-/// a quorum size and a list of verifying keys for the other parties,
-/// returns an ok result with a list of valid command approval payloads if there are enough valid command approvals
-/// to form a quorum or an appropriate error result otherwise.
-fn verify_approvals(
-    approvals: &[CommandApprovalPayload],
+/// Command approval payloads with their positions in a list of command approval payloads.
+type IndexedApprovals = Vec<(usize, CommandApprovalPayload)>;
+
+/// Given a command approval payload, a quorum approved request initialization payload and a list of verifying keys for the other parties,
+/// returns an `Ok` result if the command approval is from a verified party and has a valid signature, or an appropriate `Err` result otherwise.
+pub fn verify_approval(
+    approval: &CommandApprovalPayload,
     request: &IdentityAuthedRequestPayload,
-    quorum_size: usize,
     verified_parties: &[VerifyingKey],
-) -> Result<Vec<CommandApprovalPayload>, QuorumApprovedRequestError> {
-    let valid_approvals = filter_valid_approvals(approvals, request, verified_parties);
-    if valid_approvals.len() < quorum_size {
-        Err(QuorumApprovedRequestError::InsufficientApprovals)
+) -> Result<(), QuorumApprovedRequestError> {
+    Ok(check_approval(approval, request, None, verified_parties)?)
+}
+
+/// Same as [`verify_approval`] but for a request (and "command" approval) namespaced to an application-wide domain
+/// (see [`verify_request_and_initiate_challenge_with_domain`]).
+pub fn verify_approval_with_domain(
+    approval: &CommandApprovalPayload,
+    request: &IdentityAuthedRequestPayload,
+    domain: &str,
+    verified_parties: &[VerifyingKey],
+) -> Result<(), QuorumApprovedRequestError> {
+    Ok(check_approval(
+        approval,
+        request,
+        Some(domain),
+        verified_parties,
+    )?)
+}
+
+/// Given two lists of command approval payloads (e.g collected by different coordinators),
+/// a quorum approved request initialization payload and a list of verifying keys for the other parties,
+/// returns a merged list of valid command approval payloads with at most one approval per approving party.
+///
+/// **NOTE:** Invalid approvals are dropped, and for each approving party, only its first valid approval
+/// (i.e from `a` before `b`, in order) is kept.
+pub fn merge_approvals(
+    a: &[CommandApprovalPayload],
+    b: &[CommandApprovalPayload],
+    request: &IdentityAuthedRequestPayload,
+    verified_parties: &[VerifyingKey],
+) -> Vec<CommandApprovalPayload> {
+    let mut merged_approvals: Vec<CommandApprovalPayload> = Vec::with_capacity(a.len() + b.len());
+    for approval in a.iter().chain(b) {
+        if !merged_approvals
+            .iter()
+            .any(|merged_approval| merged_approval.verifying_key == approval.verifying_key)
+            && check_approval(approval, request, None, verified_parties).is_ok()
+        {
+            merged_approvals.push(approval.clone());
+        }
+    }
+    merged_approvals
+}
+
+/// A stateful collector of command approvals for a quorum approved request
+/// (e.g for approvals that arrive one at a time), that validates each approval as it arrives,
+/// keeps at most one approval per approving party (and approver group) and reports when a quorum is met.
+#[derive(Debug, Clone)]
+pub struct ApprovalCollector<'a> {
+    /// Quorum approved request initialization payload.
+    request: &'a IdentityAuthedRequestPayload,
+    /// Verifying keys for the other parties.
+    verified_parties: &'a [VerifyingKey],
+    /// Approval policy.
+    policy: ApprovalPolicy<'a>,
+    /// Distinct valid command approval payloads (in order of arrival).
+    approvals: Vec<CommandApprovalPayload>,
+}
+
+/// The state of an [`ApprovalCollector`] after adding a command approval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectorState {
+    /// Not enough distinct valid approvals to form a quorum yet
+    /// (with the number of distinct valid approvals and the required number of approvals,
+    /// both excluding the initiator's implicit approval, or the summed weight and the weight threshold).
+    Pending { valid_approvals: u64, required: u64 },
+    /// Enough distinct valid approvals to form a quorum.
+    QuorumMet,
+    /// The command approval was rejected (i.e it doesn't count toward the quorum).
+    Rejected(ApprovalRejection),
+}
+
+impl<'a> ApprovalCollector<'a> {
+    /// Given a quorum approved request initialization payload, a list of verifying keys for the other parties
+    /// and an approval policy, returns an empty approval collector.
+    pub fn new(
+        request: &'a IdentityAuthedRequestPayload,
+        verified_parties: &'a [VerifyingKey],
+        policy: ApprovalPolicy<'a>,
+    ) -> Self {
+        Self {
+            request,
+            verified_parties,
+            policy,
+            approvals: Vec::new(),
+        }
+    }
+
+    /// Given a command approval payload, validates and adds it to the collected approvals,
+    /// and returns the state of the collector.
+    ///
+    /// **NOTE:** Once a quorum is met, further approvals are ignored (i.e `QuorumMet` is returned without adding them).
+    ///
+    /// **NOTE:** Time-locked approvals whose `valid_after` timestamp is after the current unix timestamp
+    /// (see [`ApprovalPolicy::with_timestamp`]) are rejected (i.e they should be added again once they're valid).
+    pub fn add(&mut self, approval: CommandApprovalPayload) -> CollectorState {
+        if self.is_quorum_met() {
+            return CollectorState::QuorumMet;
+        }
+        if let Err(error) = check_approval(
+            &approval,
+            self.request,
+            self.policy.domain,
+            self.verified_parties,
+        ) {
+            return CollectorState::Rejected(ApprovalRejection::Unauthorized(error));
+        }
+        // Time-locked approvals don't count until they're valid.
+        if self.policy.timestamp() < approval.valid_after {
+            return CollectorState::Rejected(ApprovalRejection::Timelocked {
+                valid_after: approval.valid_after,
+            });
+        }
+        // Approvals from the initiator (i.e an implicit approval), parties that have already approved
+        // or their approver groups don't count.
+        let approval_group = self.policy.group_idx(&approval.verifying_key);
+        let is_duplicate = |verifying_key: &VerifyingKey| {
+            *verifying_key == approval.verifying_key
+                || approval_group.is_some()
+                    && self.policy.group_idx(verifying_key) == approval_group
+        };
+        if is_duplicate(&self.request.verifying_key)
+            || self
+                .approvals
+                .iter()
+                .any(|collected| is_duplicate(&collected.verifying_key))
+        {
+            return CollectorState::Rejected(ApprovalRejection::Duplicate);
+        }
+        self.approvals.push(approval);
+        self.state()
+    }
+
+    /// Returns the state of the collector.
+    pub fn state(&self) -> CollectorState {
+        if self.is_quorum_met() {
+            CollectorState::QuorumMet
+        } else {
+            CollectorState::Pending {
+                valid_approvals: self.progress(),
+                required: self.policy.required(),
+            }
+        }
+    }
+
+    /// Returns true if enough distinct valid approvals have been collected to form a quorum.
+    pub fn is_quorum_met(&self) -> bool {
+        self.progress() >= self.policy.required()
+    }
+
+    /// Returns the collected distinct valid command approval payloads (in order of arrival).
+    pub fn approvals(&self) -> &[CommandApprovalPayload] {
+        &self.approvals
+    }
+
+    /// Given the identity provider of the initiator,
+    /// returns an ok result with a quorum approved challenge response payload for the collected approvals
+    /// or an appropriate error result if the quorum isn't met (see [`challenge_response`]).
+    pub fn challenge_response(
+        &self,
+        identity_provider: &impl IdentityProvider,
+    ) -> Result<QuorumApprovedChallengeResponsePayload, QuorumApprovedRequestError> {
+        challenge_response(
+            &self.approvals,
+            identity_provider,
+            self.request,
+            self.verified_parties,
+            &self.policy,
+        )
+    }
+
+    /// Returns the number (or summed weight) of the collected approvals.
+    fn progress(&self) -> u64 {
+        self.policy.progress(
+            &self.request.verifying_key,
+            self.approvals
+                .iter()
+                .map(|approval| &approval.verifying_key),
+        )
+    }
+}
+
+/// Same as [`verify_approval`] but with the application-wide domain of the request (if any)
+/// and returns the underlying error for an invalid command approval.
+fn check_approval(
+    approval: &CommandApprovalPayload,
+    request: &IdentityAuthedRequestPayload,
+    domain: Option<&str>,
+    verified_parties: &(impl VerifiedParties + ?Sized),
+) -> Result<(), Error> {
+    if !verified_parties.contains_party(&approval.verifying_key) {
+        return Err(Error::UnauthorizedParty);
+    }
+    Ok(crypto::verify_signature(
+        &approval.verifying_key,
+        &command_approval_message_bytes(
+            &approval.challenge_fragment,
+            request.command,
+            request.timestamp,
+            approval.valid_after,
+            domain,
+        ),
+        &approval.signature,
+    )?)
+}
+
+/// Given a list of command approval payloads (with their positions), a quorum approved request initialization payload,
+/// a list of verifying keys for the other parties, the verifying key of the initiator, an approval policy
+/// and a list of rejected approvals, returns the valid command approval payloads (with their positions)
+/// with at most one approval per party and no approvals from the initiator, and records the rejected ones.
+///
+/// **NOTE:** Only the first valid approval from each party counts (i.e repeat approvals are rejected as duplicates).
+fn filter_distinct_valid_approvals(
+    approvals: IndexedApprovals,
+    request: &IdentityAuthedRequestPayload,
+    verified_parties: &(impl VerifiedParties + ?Sized),
+    initiator_verifying_key: &VerifyingKey,
+    policy: &ApprovalPolicy,
+    invalid_approvals: &mut Vec<(usize, ApprovalRejection)>,
+) -> IndexedApprovals {
+    filter_distinct_party_approvals(
+        filter_valid_approvals(
+            approvals,
+            request,
+            policy.domain,
+            verified_parties,
+            invalid_approvals,
+        ),
+        initiator_verifying_key,
+        invalid_approvals,
+    )
+}
+
+/// Given a list of valid command approval payloads (with their positions), a list of rejected approvals,
+/// the verifying key of the initiator and an approval policy,
+/// returns an `Ok` result if the approvals form a quorum or an appropriate `Err` result otherwise.
+fn verify_quorum(
+    approvals: &IndexedApprovals,
+    invalid_approvals: Vec<(usize, ApprovalRejection)>,
+    initiator_verifying_key: &VerifyingKey,
+    policy: &ApprovalPolicy,
+) -> Result<(), QuorumApprovedRequestError> {
+    let progress = policy.progress(
+        initiator_verifying_key,
+        approvals
+            .iter()
+            .map(|(_, approval)| &approval.verifying_key),
+    );
+    let required = policy.required();
+    if progress < required {
+        Err(insufficient_approvals(
+            progress,
+            invalid_approvals,
+            required,
+        ))
     } else {
-        Ok(valid_approvals)
+        Ok(())
     }
 }
 
-/// Given a list of command approval payloads, a quorum approved request initialization payload
-/// and a list of verifying keys for the other parties, returns a list of valid command approval payloads.
-fn filter_valid_approvals(
+/// Given the number (or summed weight) of valid approvals, a list of rejected approvals
+/// and the required number of approvals (or weight threshold), returns an insufficient approvals error.
+fn insufficient_approvals(
+    valid_approvals: u64,
+    mut invalid_approvals: Vec<(usize, ApprovalRejection)>,
+    required: u64,
+) -> QuorumApprovedRequestError {
+    invalid_approvals.sort_by_key(|(idx, _)| *idx);
+    QuorumApprovedRequestError::InsufficientApprovals(QuorumDecision {
+        valid_approvals,
+        invalid_approvals,
+        required,
+    })
+}
+
+/// Given a list of command approval payloads, returns the command approval payloads with their positions in the list.
+fn index_approvals(approvals: &[CommandApprovalPayload]) -> IndexedApprovals {
+    approvals.iter().cloned().enumerate().collect()
+}
+
+/// Given a quorum approved challenge response payload and a list of command approval payloads,
+/// returns the command approval payloads acknowledged by the initiator (with their positions in the list)
+/// and rejections for the unacknowledged command approval payloads.
+fn partition_acknowledged_approvals(
+    response: &QuorumApprovedChallengeResponsePayload,
     approvals: &[CommandApprovalPayload],
+) -> (IndexedApprovals, Vec<(usize, ApprovalRejection)>) {
+    let (acknowledged_approvals, unacknowledged_approvals): (Vec<_>, Vec<_>) =
+        index_approvals(approvals)
+            .into_iter()
+            .partition(|(_, approval)| response.approving_quorum.contains(&approval.verifying_key));
+    (
+        acknowledged_approvals,
+        unacknowledged_approvals
+            .into_iter()
+            .map(|(idx, _)| (idx, ApprovalRejection::Unacknowledged))
+            .collect(),
+    )
+}
+
+/// Given a list of command approval payloads (with their positions), a quorum approved request initialization payload
+/// (and its application-wide domain, if any), a list of verifying keys for the other parties and a list of rejected approvals,
+/// returns a list of valid command approval payloads (with their positions) and records the rejected ones.
+fn filter_valid_approvals(
+    approvals: IndexedApprovals,
     request: &IdentityAuthedRequestPayload,
-    verified_parties: &[VerifyingKey],
-) -> Vec<CommandApprovalPayload> {
+    domain: Option<&str>,
+    verified_parties: &(impl VerifiedParties + ?Sized),
+    invalid_approvals: &mut Vec<(usize, ApprovalRejection)>,
+) -> IndexedApprovals {
     approvals
-        .iter()
-        .filter(|approval| {
-            verified_parties.contains(&approval.verifying_key)
-                && crypto::verify_signature(
-                    &approval.verifying_key,
-                    &command_approval_message_bytes(
-                        &approval.challenge_fragment,
-                        request.command,
-                        request.timestamp,
-                    ),
-                    &approval.signature,
-                )
-                .is_ok()
+        .into_iter()
+        .filter(|(idx, approval)| {
+            match check_approval(approval, request, domain, verified_parties) {
+                Ok(()) => true,
+                Err(error) => {
+                    invalid_approvals.push((*idx, ApprovalRejection::Unauthorized(error)));
+                    false
+                }
+            }
+        })
+        .collect()
+}
+
+/// Given a list of valid command approval payloads (with their positions), the current unix timestamp
+/// and a list of rejected approvals, returns the command approval payloads (with their positions)
+/// that aren't time-locked (i.e whose `valid_after` timestamp isn't after the current unix timestamp)
+/// and records the time-locked ones.
+fn filter_unlocked_approvals(
+    approvals: IndexedApprovals,
+    now: u64,
+    invalid_approvals: &mut Vec<(usize, ApprovalRejection)>,
+) -> IndexedApprovals {
+    approvals
+        .into_iter()
+        .filter(|(idx, approval)| {
+            if now < approval.valid_after {
+                invalid_approvals.push((
+                    *idx,
+                    ApprovalRejection::Timelocked {
+                        valid_after: approval.valid_after,
+                    },
+                ));
+                false
+            } else {
+                true
+            }
+        })
+        .collect()
+}
+
+/// Given a list of command approval payloads (with their positions), the verifying key of the initiator,
+/// an approval policy (i.e with approver groups) and a list of rejected approvals,
+/// returns a list of command approval payloads (with their positions) with at most one approval per group
+/// and no approvals from the initiator's group, and records the rejected ones.
+fn filter_distinct_group_approvals(
+    approvals: IndexedApprovals,
+    initiator_verifying_key: &VerifyingKey,
+    policy: &ApprovalPolicy,
+    invalid_approvals: &mut Vec<(usize, ApprovalRejection)>,
+) -> IndexedApprovals {
+    let mut counted_groups: Vec<usize> = policy
+        .group_idx(initiator_verifying_key)
+        .into_iter()
+        .collect();
+    approvals
+        .into_iter()
+        .filter(
+            |(idx, approval)| match policy.group_idx(&approval.verifying_key) {
+                // Only the first approval from each group counts.
+                Some(group) if counted_groups.contains(&group) => {
+                    invalid_approvals.push((*idx, ApprovalRejection::Duplicate));
+                    false
+                }
+                Some(group) => {
+                    counted_groups.push(group);
+                    true
+                }
+                // Parties that aren't in any group count individually.
+                None => true,
+            },
+        )
+        .collect()
+}
+
+/// Given a list of command approval payloads (with their positions), the verifying key of the initiator
+/// and a list of rejected approvals, returns a list of command approval payloads (with their positions)
+/// with at most one approval per party and no approvals from the initiator, and records the rejected ones.
+fn filter_distinct_party_approvals(
+    approvals: IndexedApprovals,
+    initiator_verifying_key: &VerifyingKey,
+    invalid_approvals: &mut Vec<(usize, ApprovalRejection)>,
+) -> IndexedApprovals {
+    let mut counted_parties = vec![initiator_verifying_key.clone()];
+    approvals
+        .into_iter()
+        .filter(|(idx, approval)| {
+            // Only the first approval from each party counts.
+            if counted_parties.contains(&approval.verifying_key) {
+                invalid_approvals.push((*idx, ApprovalRejection::Duplicate));
+                false
+            } else {
+                counted_parties.push(approval.verifying_key.clone());
+                true
+            }
         })
-        .cloned()
         .collect()
 }
 
 /// Returns sign-able message bytes for the command approval.
-fn command_approval_message_bytes(
+///
+/// **NOTE:** Time-locked approvals (i.e with a non-zero `valid_after` timestamp) are domain separated
+/// with the `valid_after` timestamp appended as an 8 byte big-endian unsigned integer,
+/// while approvals for a request namespaced to an application-wide domain are also domain separated with that domain.
+pub(crate) fn command_approval_message_bytes(
     challenge_fragment: &Random32Bytes,
     command: &str,
     timestamp: u64,
+    valid_after: u64,
+    domain: Option<&str>,
 ) -> Vec<u8> {
-    utils::prefix_message_bytes(
-        format!("{}{}{}", challenge_fragment, command, timestamp).as_bytes(),
-    )
-}
-
-/// Given a list of command approval payloads and an identity provider, returns a list of wrapped challenge fragments.
-fn extract_challenge_fragments(
-    approvals: &[CommandApprovalPayload],
-) -> impl Iterator<Item = Random32Bytes> + '_ {
-    approvals.iter().map(|item| item.challenge_fragment)
+    let mut approval_bytes = format!("{}{}{}", challenge_fragment, command, timestamp).into_bytes();
+    if valid_after != 0 {
+        approval_bytes = utils::domain_separated_message_bytes(
+            TIMELOCKED_COMMAND_APPROVAL_TAG,
+            &[approval_bytes.as_slice(), &valid_after.to_be_bytes()].concat(),
+        );
+    }
+    match domain {
+        Some(domain) => utils::prefix_message_bytes(&utils::domain_separated_message_bytes(
+            domain.as_bytes(),
+            &approval_bytes,
+        )),
+        None => utils::prefix_message_bytes(&approval_bytes),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::errors::{CryptoError, Error};
+    use crate::command::Command;
+    use crate::errors::CryptoError;
     use crate::test_utils::MockECDSAIdentityProvider;
     use crypto_bigint::U256;
 
@@ -192,7 +843,7 @@ mod tests {
             .collect();
 
         // Sets the command.
-        let command = "command";
+        let command = Command::Signing;
 
         // Generates quorum approved request initialization payload.
         let init_payload = initiate(command, &initiator_identity_provider);
@@ -203,7 +854,7 @@ mod tests {
                 .iter()
                 .map(|identity_provider| {
                     verify_request_and_initiate_challenge(
-                        command,
+                        command.as_str(),
                         &init_payload,
                         identity_provider,
                         &verified_parties,
@@ -245,7 +896,7 @@ mod tests {
                 &approvals,
                 quorum_size,
                 Err(QuorumApprovedRequestError::Unauthorized(Error::Crypto(
-                    CryptoError::InvalidSignature,
+                    CryptoError::SignatureVerificationFailed,
                 ))),
             ),
             // Challenge response signing an insufficient number of approvals should be rejected.
@@ -253,7 +904,16 @@ mod tests {
                 &initiator_identity_provider,
                 &approvals[0..3].to_vec(), // initiator + 3 approvals is an insufficient quorum.
                 4, // Allows initiator to successfully sign only 3 approvals (i.e quorum_size - 1).
-                Err(QuorumApprovedRequestError::InsufficientApprovals),
+                Err(QuorumApprovedRequestError::InsufficientApprovals(
+                    QuorumDecision {
+                        valid_approvals: 3,
+                        invalid_approvals: vec![
+                            (3, ApprovalRejection::Unacknowledged),
+                            (4, ApprovalRejection::Unacknowledged),
+                        ],
+                        required: 4,
+                    },
+                )),
             ),
             // Challenge response signing the wrong challenge fragments should be rejected.
             (
@@ -266,17 +926,20 @@ mod tests {
                             &challenge_fragment,
                             init_payload.command,
                             init_payload.timestamp,
+                            0,
+                            None,
                         ));
                         CommandApprovalPayload {
                             challenge_fragment,
                             verifying_key: identity_provider.verifying_key(),
+                            valid_after: 0,
                             signature,
                         }
                     })
                     .collect(),
                 quorum_size,
                 Err(QuorumApprovedRequestError::Unauthorized(Error::Crypto(
-                    CryptoError::InvalidSignature,
+                    CryptoError::SignatureVerificationFailed,
                 ))),
             ),
         ] {
@@ -285,8 +948,8 @@ mod tests {
                 approvals_to_sign,
                 actual_current_signer,
                 &init_payload,
-                quorum_size_to_sign,
                 &verified_parties,
+                &ApprovalPolicy::new(quorum_size_to_sign),
             );
 
             // Verifies expected challenge response result.
@@ -301,12 +964,993 @@ mod tests {
                 &approvals,
                 &initiator_identity_provider.verifying_key(),
                 &init_payload,
-                quorum_size,
                 &verified_parties,
+                &ApprovalPolicy::new(quorum_size),
             );
 
             // Verifies expected result.
             assert_eq!(challenge_result, expected_challenge_result);
         }
     }
+
+    #[test]
+    fn quorum_approved_request_with_domain_works() {
+        // Generates identity providers for the initiator and an approver.
+        let initiator_identity_provider = MockECDSAIdentityProvider::generate();
+        let approver_identity_provider = MockECDSAIdentityProvider::generate();
+        let verified_parties = [
+            initiator_identity_provider.verifying_key(),
+            approver_identity_provider.verifying_key(),
+        ];
+
+        // Generates quorum approved request initialization payload namespaced to a domain.
+        let command = Command::Signing;
+        let init_payload =
+            initiate_with_domain(command, "com.example.wallet", &initiator_identity_provider);
+
+        for (domain, expected_result) in [
+            // Request for the same domain should be approved.
+            ("com.example.wallet", Ok(())),
+            // Request for a different domain should be rejected.
+            (
+                "com.example.other",
+                Err(IdentityAuthedRequestError::Unauthorized(Error::Crypto(
+                    CryptoError::SignatureVerificationFailed,
+                ))),
+            ),
+        ] {
+            // Verifies quorum approved request and initiates challenge.
+            let result = verify_request_and_initiate_challenge_with_domain(
+                command.as_str(),
+                domain,
+                &init_payload,
+                &approver_identity_provider,
+                &verified_parties,
+            );
+
+            // Verifies expected result.
+            assert_eq!(result.map(|_| ()), expected_result);
+        }
+
+        // Request without a domain should be rejected when a domain is configured.
+        assert!(verify_request_and_initiate_challenge_with_domain(
+            command.as_str(),
+            "com.example.wallet",
+            &initiate(command, &initiator_identity_provider),
+            &approver_identity_provider,
+            &verified_parties,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn quorum_approved_request_with_approver_groups_works() {
+        // Generates identity providers for the initiator and all other parties.
+        let initiator_identity_provider = MockECDSAIdentityProvider::generate();
+        let approver_identity_providers: Vec<MockECDSAIdentityProvider> = (0..4)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+
+        // Sets quorum.
+        let quorum_size = 4;
+
+        // Creates a list of verifying keys for all parties.
+        let approver_verifying_keys: Vec<VerifyingKey> = approver_identity_providers
+            .iter()
+            .map(|identity_provider| identity_provider.verifying_key())
+            .collect();
+        let verified_parties: Vec<VerifyingKey> = approver_verifying_keys
+            .iter()
+            .cloned()
+            .chain([initiator_identity_provider.verifying_key()])
+            .collect();
+
+        // Groups the first 2 approvers (i.e their approvals only count once).
+        let approver_groups = vec![approver_verifying_keys[0..2].to_vec()];
+
+        // Generates quorum approved request initialization payload and approvals.
+        let command = Command::Signing;
+        let init_payload = initiate(command, &initiator_identity_provider);
+        let approvals: Vec<CommandApprovalPayload> = approver_identity_providers
+            .iter()
+            .map(|identity_provider| {
+                verify_request_and_initiate_challenge(
+                    command.as_str(),
+                    &init_payload,
+                    identity_provider,
+                    &verified_parties,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        for (approvals_to_sign, expected_response_result, expected_result) in [
+            // initiator + 3 approvals from distinct groups is a valid quorum.
+            (approvals.clone(), Ok(()), Ok(())),
+            // initiator + 3 approvals with 2 from the same group is an insufficient quorum.
+            (
+                approvals[0..3].to_vec(),
+                Err(QuorumApprovedRequestError::InsufficientApprovals(
+                    QuorumDecision {
+                        valid_approvals: 2,
+                        invalid_approvals: vec![(1, ApprovalRejection::Duplicate)],
+                        required: 3,
+                    },
+                )),
+                Err(QuorumApprovedRequestError::InsufficientApprovals(
+                    QuorumDecision {
+                        valid_approvals: 2,
+                        invalid_approvals: vec![
+                            (1, ApprovalRejection::Duplicate),
+                            (3, ApprovalRejection::Unacknowledged),
+                        ],
+                        required: 3,
+                    },
+                )),
+            ),
+        ] {
+            // Generates quorum approved challenge response with approver groups.
+            let challenge_response_result = challenge_response(
+                &approvals_to_sign,
+                &initiator_identity_provider,
+                &init_payload,
+                &verified_parties,
+                &ApprovalPolicy::new(quorum_size).with_approver_groups(&approver_groups),
+            );
+
+            // Verifies expected result.
+            assert_eq!(
+                challenge_response_result.clone().map(|_| ()),
+                expected_response_result
+            );
+
+            // Verifies that the approving quorum includes at most one approval per group.
+            if let Ok(challenge_payload) = challenge_response_result {
+                assert_eq!(challenge_payload.approving_quorum.len(), quorum_size - 1);
+            }
+
+            // Verifies quorum approved challenge response (generated without approver groups) with approver groups.
+            let challenge_payload = challenge_response(
+                &approvals_to_sign,
+                &initiator_identity_provider,
+                &init_payload,
+                &verified_parties,
+                &ApprovalPolicy::new(quorum_size),
+            )
+            .unwrap();
+            let challenge_result = verify_challenge_response(
+                &challenge_payload,
+                &approvals,
+                &initiator_identity_provider.verifying_key(),
+                &init_payload,
+                &verified_parties,
+                &ApprovalPolicy::new(quorum_size).with_approver_groups(&approver_groups),
+            );
+
+            // Verifies expected result.
+            assert_eq!(challenge_result, expected_result);
+        }
+    }
+
+    #[test]
+    fn quorum_approved_request_with_weights_works() {
+        // Generates identity providers for the initiator and all other parties.
+        let initiator_identity_provider = MockECDSAIdentityProvider::generate();
+        let approver_identity_providers: Vec<MockECDSAIdentityProvider> = (0..5)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+
+        // Creates a list of verifying keys for all parties.
+        let approver_verifying_keys: Vec<VerifyingKey> = approver_identity_providers
+            .iter()
+            .map(|identity_provider| identity_provider.verifying_key())
+            .collect();
+        let verified_parties: Vec<VerifyingKey> = approver_verifying_keys
+            .iter()
+            .cloned()
+            .chain([initiator_identity_provider.verifying_key()])
+            .collect();
+
+        // Assigns a high weight to the first approver, low weights to the next 3 approvers
+        // and no weight to the last approver.
+        let weights: HashMap<VerifyingKey, u64> = [
+            (initiator_identity_provider.verifying_key(), 1),
+            (approver_verifying_keys[0].clone(), 10),
+            (approver_verifying_keys[1].clone(), 2),
+            (approver_verifying_keys[2].clone(), 2),
+            (approver_verifying_keys[3].clone(), 2),
+        ]
+        .into_iter()
+        .collect();
+        let weight_threshold = 7;
+
+        // Generates quorum approved request initialization payload and approvals.
+        let command = Command::Signing;
+        let init_payload = initiate(command, &initiator_identity_provider);
+        let approvals: Vec<CommandApprovalPayload> = approver_identity_providers
+            .iter()
+            .map(|identity_provider| {
+                verify_request_and_initiate_challenge(
+                    command.as_str(),
+                    &init_payload,
+                    identity_provider,
+                    &verified_parties,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        let insufficient_weight = |invalid_approvals| {
+            Err(QuorumApprovedRequestError::InsufficientApprovals(
+                QuorumDecision {
+                    valid_approvals: 5,
+                    invalid_approvals,
+                    required: weight_threshold,
+                },
+            ))
+        };
+        for (approvals_to_sign, expected_response_result, expected_result) in [
+            // A single high weight approver meets the threshold (i.e 1 + 10).
+            (vec![approvals[0].clone()], Ok(()), Ok(())),
+            // Several low weight approvers meet the threshold (i.e 1 + 2 + 2 + 2).
+            (approvals[1..4].to_vec(), Ok(()), Ok(())),
+            // Too few low weight approvers and an approver without a weight don't meet the threshold (i.e 1 + 2 + 2 + 0).
+            (
+                approvals[2..5].to_vec(),
+                insufficient_weight(Vec::new()),
+                insufficient_weight(vec![
+                    (0, ApprovalRejection::Unacknowledged),
+                    (1, ApprovalRejection::Unacknowledged),
+                ]),
+            ),
+            // Duplicate approvals only count once (i.e 1 + 2 + 2).
+            (
+                vec![
+                    approvals[1].clone(),
+                    approvals[2].clone(),
+                    approvals[2].clone(),
+                ],
+                insufficient_weight(vec![(2, ApprovalRejection::Duplicate)]),
+                insufficient_weight(vec![
+                    (0, ApprovalRejection::Unacknowledged),
+                    (3, ApprovalRejection::Unacknowledged),
+                    (4, ApprovalRejection::Unacknowledged),
+                ]),
+            ),
+        ] {
+            // Generates quorum approved challenge response with weights.
+            let challenge_response_result = challenge_response(
+                &approvals_to_sign,
+                &initiator_identity_provider,
+                &init_payload,
+                &verified_parties,
+                &ApprovalPolicy::weighted(&weights, weight_threshold),
+            );
+
+            // Verifies expected result.
+            assert_eq!(
+                challenge_response_result.clone().map(|_| ()),
+                expected_response_result
+            );
+
+            // Verifies quorum approved challenge response (generated without weights) with weights.
+            let challenge_payload = challenge_response(
+                &approvals_to_sign,
+                &initiator_identity_provider,
+                &init_payload,
+                &verified_parties,
+                &ApprovalPolicy::new(1),
+            )
+            .unwrap();
+            let challenge_result = verify_challenge_response(
+                &challenge_payload,
+                &approvals,
+                &initiator_identity_provider.verifying_key(),
+                &init_payload,
+                &verified_parties,
+                &ApprovalPolicy::weighted(&weights, weight_threshold),
+            );
+
+            // Verifies expected result.
+            assert_eq!(challenge_result, expected_result);
+        }
+    }
+
+    #[test]
+    fn quorum_approved_request_with_weights_and_domain_works() {
+        // Generates identity providers for the initiator and all other parties.
+        let initiator_identity_provider = MockECDSAIdentityProvider::generate();
+        let approver_identity_providers: Vec<MockECDSAIdentityProvider> = (0..2)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+
+        // Creates a list of verifying keys for all parties.
+        let verified_parties: Vec<VerifyingKey> = approver_identity_providers
+            .iter()
+            .map(|identity_provider| identity_provider.verifying_key())
+            .chain([initiator_identity_provider.verifying_key()])
+            .collect();
+
+        // Assigns weights such that the initiator and both approvers are required (i.e 1 + 2 + 2).
+        let weights: HashMap<VerifyingKey, u64> = [
+            (initiator_identity_provider.verifying_key(), 1),
+            (verified_parties[0].clone(), 2),
+            (verified_parties[1].clone(), 2),
+        ]
+        .into_iter()
+        .collect();
+        let weight_threshold = 5;
+
+        // Generates quorum approved request initialization payload namespaced to a domain and approvals.
+        let command = Command::Signing;
+        let domain = "com.example.wallet";
+        let init_payload = initiate_with_domain(command, domain, &initiator_identity_provider);
+        let approvals: Vec<CommandApprovalPayload> = approver_identity_providers
+            .iter()
+            .map(|identity_provider| {
+                verify_request_and_initiate_challenge_with_domain(
+                    command.as_str(),
+                    domain,
+                    &init_payload,
+                    identity_provider,
+                    &verified_parties,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        // Generates quorum approved challenge response with weights and the domain.
+        let policy = ApprovalPolicy::weighted(&weights, weight_threshold).with_domain(domain);
+        let challenge_payload = challenge_response(
+            &approvals,
+            &initiator_identity_provider,
+            &init_payload,
+            &verified_parties,
+            &policy,
+        )
+        .unwrap();
+
+        let wrong_domain_rejection = ApprovalRejection::Unauthorized(Error::Crypto(
+            CryptoError::SignatureVerificationFailed,
+        ));
+        for (policy, expected_result) in [
+            // Weighted approvals for the same domain meet the threshold.
+            (policy.clone(), Ok(())),
+            // Weighted approvals without the domain are rejected.
+            (
+                ApprovalPolicy::weighted(&weights, weight_threshold),
+                Err(QuorumApprovedRequestError::InsufficientApprovals(
+                    QuorumDecision {
+                        valid_approvals: 1,
+                        invalid_approvals: vec![
+                            (0, wrong_domain_rejection),
+                            (1, wrong_domain_rejection),
+                        ],
+                        required: weight_threshold,
+                    },
+                )),
+            ),
+            // Weighted approvals for a different domain are rejected.
+            (
+                ApprovalPolicy::weighted(&weights, weight_threshold)
+                    .with_domain("com.example.other"),
+                Err(QuorumApprovedRequestError::InsufficientApprovals(
+                    QuorumDecision {
+                        valid_approvals: 1,
+                        invalid_approvals: vec![
+                            (0, wrong_domain_rejection),
+                            (1, wrong_domain_rejection),
+                        ],
+                        required: weight_threshold,
+                    },
+                )),
+            ),
+        ] {
+            // Verifies expected result.
+            assert_eq!(
+                verify_challenge_response(
+                    &challenge_payload,
+                    &approvals,
+                    &initiator_identity_provider.verifying_key(),
+                    &init_payload,
+                    &verified_parties,
+                    &policy,
+                ),
+                expected_result
+            );
+        }
+    }
+
+    #[test]
+    fn repeat_approvals_only_count_once() {
+        // Generates identity providers for the initiator and all other parties.
+        let initiator_identity_provider = MockECDSAIdentityProvider::generate();
+        let approver_identity_providers: Vec<MockECDSAIdentityProvider> = (0..3)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+
+        // Sets quorum.
+        let quorum_size = 4;
+
+        // Creates a list of verifying keys for all parties.
+        let verified_parties: Vec<VerifyingKey> = approver_identity_providers
+            .iter()
+            .map(|identity_provider| identity_provider.verifying_key())
+            .chain([initiator_identity_provider.verifying_key()])
+            .collect();
+
+        // Generates quorum approved request initialization payload and approvals
+        // (the second approver submits twice i.e with a different challenge fragment each time,
+        // and the initiator submits an approval too).
+        let command = Command::Signing;
+        let init_payload = initiate(command, &initiator_identity_provider);
+        let approvals: Vec<CommandApprovalPayload> = [
+            &approver_identity_providers[0],
+            &approver_identity_providers[1],
+            &approver_identity_providers[1],
+            &initiator_identity_provider,
+        ]
+        .into_iter()
+        .map(|identity_provider| {
+            verify_request_and_initiate_challenge(
+                command.as_str(),
+                &init_payload,
+                identity_provider,
+                &verified_parties,
+            )
+            .unwrap()
+        })
+        .collect();
+        // An approver group with only the last approver and equal weights for all parties.
+        let approver_groups = vec![vec![verified_parties[2].clone()]];
+        let weights: HashMap<VerifyingKey, u64> = verified_parties
+            .iter()
+            .map(|verifying_key| (verifying_key.clone(), 1))
+            .collect();
+
+        for (policy, valid_approvals, required) in [
+            // Quorum size.
+            (ApprovalPolicy::new(quorum_size), 2, 3),
+            // Quorum size with approver groups.
+            (
+                ApprovalPolicy::new(quorum_size).with_approver_groups(&approver_groups),
+                2,
+                3,
+            ),
+            // Weighted threshold (i.e the initiator's weight counts toward the threshold).
+            (ApprovalPolicy::weighted(&weights, quorum_size as u64), 3, 4),
+        ] {
+            let insufficient_approvals = |invalid_approvals| {
+                Err(QuorumApprovedRequestError::InsufficientApprovals(
+                    QuorumDecision {
+                        valid_approvals,
+                        invalid_approvals,
+                        required,
+                    },
+                ))
+            };
+
+            // Verifies that the repeat approvals don't count toward the quorum.
+            assert_eq!(
+                challenge_response(
+                    &approvals,
+                    &initiator_identity_provider,
+                    &init_payload,
+                    &verified_parties,
+                    &policy,
+                )
+                .map(|_| ()),
+                insufficient_approvals(vec![
+                    (2, ApprovalRejection::Duplicate),
+                    (3, ApprovalRejection::Duplicate),
+                ])
+            );
+
+            // Verifies that a challenge response (generated by an initiator that only requires 2 approvals)
+            // is rejected because the repeat approvals don't count toward the quorum.
+            let challenge_payload = challenge_response(
+                &approvals,
+                &initiator_identity_provider,
+                &init_payload,
+                &verified_parties,
+                &ApprovalPolicy::new(quorum_size - 1),
+            )
+            .unwrap();
+            assert_eq!(challenge_payload.approving_quorum.len(), 2);
+            assert_eq!(
+                verify_challenge_response(
+                    &challenge_payload,
+                    &approvals,
+                    &initiator_identity_provider.verifying_key(),
+                    &init_payload,
+                    &verified_parties,
+                    &policy,
+                ),
+                insufficient_approvals(vec![
+                    (2, ApprovalRejection::Duplicate),
+                    (3, ApprovalRejection::Unacknowledged),
+                ])
+            );
+
+            // Verifies that the challenge response is still valid for a quorum that the distinct approvals meet
+            // (i.e the repeat approval isn't part of the signed challenge).
+            assert_eq!(
+                verify_challenge_response(
+                    &challenge_payload,
+                    &approvals,
+                    &initiator_identity_provider.verifying_key(),
+                    &init_payload,
+                    &verified_parties,
+                    &ApprovalPolicy::new(quorum_size - 1),
+                ),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn quorum_decision_works() {
+        // Generates identity providers for the initiator, all other parties and an unauthorized party.
+        let initiator_identity_provider = MockECDSAIdentityProvider::generate();
+        let approver_identity_providers: Vec<MockECDSAIdentityProvider> = (0..4)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let unauthorized_identity_provider = MockECDSAIdentityProvider::generate();
+
+        // Sets quorum.
+        let quorum_size = 5;
+
+        // Creates a list of verifying keys for all parties.
+        let verified_parties: Vec<VerifyingKey> = approver_identity_providers
+            .iter()
+            .map(|identity_provider| identity_provider.verifying_key())
+            .chain([initiator_identity_provider.verifying_key()])
+            .collect();
+
+        // Generates quorum approved request initialization payload and approvals.
+        let command = Command::Signing;
+        let init_payload = initiate(command, &initiator_identity_provider);
+        let approvals: Vec<CommandApprovalPayload> = approver_identity_providers
+            .iter()
+            .chain([&unauthorized_identity_provider])
+            .map(|identity_provider| {
+                verify_request_and_initiate_challenge(
+                    command.as_str(),
+                    &init_payload,
+                    identity_provider,
+                    &verified_parties,
+                )
+                .unwrap()
+            })
+            .collect();
+        // Approvals with invalid signatures.
+        let invalid_signature_approvals: Vec<CommandApprovalPayload> = approver_identity_providers
+            .iter()
+            .zip(&approvals)
+            .map(|(identity_provider, approval)| CommandApprovalPayload {
+                signature: identity_provider.sign(b"Another message"),
+                ..approval.clone()
+            })
+            .collect();
+
+        // A near-miss quorum (i.e initiator + 3 valid approvals and an approval with an invalid signature).
+        let near_miss_approvals = [
+            approvals[0].clone(),
+            approvals[1].clone(),
+            approvals[2].clone(),
+            invalid_signature_approvals[3].clone(),
+        ];
+        let near_miss_decision = QuorumDecision {
+            valid_approvals: 3,
+            invalid_approvals: vec![(
+                3,
+                ApprovalRejection::Unauthorized(Error::Crypto(
+                    CryptoError::SignatureVerificationFailed,
+                )),
+            )],
+            required: 4,
+        };
+
+        for (approvals_to_sign, expected_result) in [
+            // A near-miss quorum.
+            (
+                near_miss_approvals.to_vec(),
+                Err(QuorumApprovedRequestError::InsufficientApprovals(
+                    near_miss_decision,
+                )),
+            ),
+            // All invalid approvals (i.e an approval from an unauthorized party and approvals with invalid signatures).
+            (
+                vec![
+                    approvals[4].clone(),
+                    invalid_signature_approvals[0].clone(),
+                    invalid_signature_approvals[1].clone(),
+                ],
+                Err(QuorumApprovedRequestError::InsufficientApprovals(
+                    QuorumDecision {
+                        valid_approvals: 0,
+                        invalid_approvals: vec![
+                            (0, ApprovalRejection::Unauthorized(Error::UnauthorizedParty)),
+                            (
+                                1,
+                                ApprovalRejection::Unauthorized(Error::Crypto(
+                                    CryptoError::SignatureVerificationFailed,
+                                )),
+                            ),
+                            (
+                                2,
+                                ApprovalRejection::Unauthorized(Error::Crypto(
+                                    CryptoError::SignatureVerificationFailed,
+                                )),
+                            ),
+                        ],
+                        required: 4,
+                    },
+                )),
+            ),
+        ] {
+            // Generates quorum approved challenge response.
+            let challenge_response_result = challenge_response(
+                &approvals_to_sign,
+                &initiator_identity_provider,
+                &init_payload,
+                &verified_parties,
+                &ApprovalPolicy::new(quorum_size),
+            );
+
+            // Verifies expected result.
+            assert_eq!(challenge_response_result.map(|_| ()), expected_result);
+        }
+
+        // Verifies that a near-miss quorum decision is reported when verifying a challenge response
+        // (i.e generated by an initiator that only requires 3 valid approvals, so the approval with an invalid signature isn't acknowledged).
+        let challenge_payload = challenge_response(
+            &near_miss_approvals,
+            &initiator_identity_provider,
+            &init_payload,
+            &verified_parties,
+            &ApprovalPolicy::new(quorum_size - 1),
+        )
+        .unwrap();
+        assert_eq!(
+            verify_challenge_response(
+                &challenge_payload,
+                &near_miss_approvals,
+                &initiator_identity_provider.verifying_key(),
+                &init_payload,
+                &verified_parties,
+                &ApprovalPolicy::new(quorum_size),
+            ),
+            Err(QuorumApprovedRequestError::InsufficientApprovals(
+                QuorumDecision {
+                    valid_approvals: 3,
+                    invalid_approvals: vec![(3, ApprovalRejection::Unacknowledged)],
+                    required: 4,
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn merge_approvals_works() {
+        // Generates identity providers for the initiator, all other parties and an unauthorized party.
+        let initiator_identity_provider = MockECDSAIdentityProvider::generate();
+        let approver_identity_providers: Vec<MockECDSAIdentityProvider> = (0..5)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let unauthorized_identity_provider = MockECDSAIdentityProvider::generate();
+
+        // Sets quorum.
+        let quorum_size = 6;
+
+        // Creates a list of verifying keys for all parties.
+        let verified_parties: Vec<VerifyingKey> = approver_identity_providers
+            .iter()
+            .map(|identity_provider| identity_provider.verifying_key())
+            .chain([initiator_identity_provider.verifying_key()])
+            .collect();
+
+        // Generates quorum approved request initialization payload and approvals.
+        let command = Command::Signing;
+        let init_payload = initiate(command, &initiator_identity_provider);
+        let approvals: Vec<CommandApprovalPayload> = approver_identity_providers
+            .iter()
+            .chain([&unauthorized_identity_provider])
+            .map(|identity_provider| {
+                verify_request_and_initiate_challenge(
+                    command.as_str(),
+                    &init_payload,
+                    identity_provider,
+                    &verified_parties,
+                )
+                .unwrap()
+            })
+            .collect();
+        // An approval with an invalid signature.
+        let invalid_signature_approval = CommandApprovalPayload {
+            signature: approver_identity_providers[3].sign(b"Another message"),
+            ..approvals[3].clone()
+        };
+
+        // Overlapping collections of approvals (e.g from different coordinators).
+        let approvals_a = [
+            approvals[0].clone(),
+            approvals[1].clone(),
+            approvals[2].clone(),
+            invalid_signature_approval,
+        ];
+        let approvals_b = [
+            approvals[1].clone(),
+            approvals[2].clone(),
+            approvals[3].clone(),
+            approvals[4].clone(),
+            approvals[5].clone(), // Unauthorized party.
+        ];
+
+        // Merges approvals.
+        let merged_approvals =
+            merge_approvals(&approvals_a, &approvals_b, &init_payload, &verified_parties);
+
+        // Verifies that only the first valid approval from each verified party remains.
+        assert_eq!(merged_approvals.len(), 5);
+        for (idx, (approval, expected_approval)) in
+            merged_approvals.iter().zip(&approvals).enumerate()
+        {
+            assert_eq!(approval.verifying_key, expected_approval.verifying_key);
+            assert_eq!(approval.signature, expected_approval.signature);
+            assert!(!merged_approvals[idx + 1..]
+                .iter()
+                .any(|other_approval| other_approval.verifying_key == approval.verifying_key));
+        }
+
+        // Verifies that the merged approvals form a quorum.
+        let challenge_payload = challenge_response(
+            &merged_approvals,
+            &initiator_identity_provider,
+            &init_payload,
+            &verified_parties,
+            &ApprovalPolicy::new(quorum_size),
+        )
+        .unwrap();
+        assert!(verify_challenge_response(
+            &challenge_payload,
+            &merged_approvals,
+            &initiator_identity_provider.verifying_key(),
+            &init_payload,
+            &verified_parties,
+            &ApprovalPolicy::new(quorum_size),
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn approval_collector_works() {
+        // Generates identity providers for the initiator, all other parties and an unauthorized party.
+        let initiator_identity_provider = MockECDSAIdentityProvider::generate();
+        let approver_identity_providers: Vec<MockECDSAIdentityProvider> = (0..4)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let unauthorized_identity_provider = MockECDSAIdentityProvider::generate();
+
+        // Sets quorum.
+        let quorum_size = 4;
+
+        // Creates a list of verifying keys for all parties.
+        let verified_parties: Vec<VerifyingKey> = approver_identity_providers
+            .iter()
+            .map(|identity_provider| identity_provider.verifying_key())
+            .chain([initiator_identity_provider.verifying_key()])
+            .collect();
+
+        // Generates quorum approved request initialization payload and approvals.
+        let command = Command::Signing;
+        let init_payload = initiate(command, &initiator_identity_provider);
+        let approvals: Vec<CommandApprovalPayload> = approver_identity_providers
+            .iter()
+            .chain([
+                &unauthorized_identity_provider,
+                &initiator_identity_provider,
+            ])
+            .map(|identity_provider| {
+                verify_request_and_initiate_challenge(
+                    command.as_str(),
+                    &init_payload,
+                    identity_provider,
+                    &verified_parties,
+                )
+                .unwrap()
+            })
+            .collect();
+        // An approval with an invalid signature.
+        let invalid_signature_approval = CommandApprovalPayload {
+            signature: approver_identity_providers[0].sign(b"Another message"),
+            ..approvals[0].clone()
+        };
+        // A time-locked approval.
+        let timelocked_approval = verify_request_and_initiate_challenge_with_timelock(
+            command.as_str(),
+            &init_payload,
+            &approver_identity_providers[1],
+            &verified_parties,
+            1_000,
+        )
+        .unwrap();
+
+        // Initializes the approval collector (with a current unix timestamp that's the `valid_after` timestamp of the time-locked approval).
+        let policy = ApprovalPolicy::new(quorum_size).with_timestamp(1_000);
+        let mut collector =
+            ApprovalCollector::new(&init_payload, &verified_parties, policy.clone());
+        assert_eq!(
+            collector.state(),
+            CollectorState::Pending {
+                valid_approvals: 0,
+                required: 3
+            }
+        );
+
+        // Verifies that a challenge response can't be generated before the quorum is met.
+        assert!(matches!(
+            collector.challenge_response(&initiator_identity_provider),
+            Err(QuorumApprovedRequestError::InsufficientApprovals(_))
+        ));
+
+        for (approval, expected_state) in [
+            // Approval with an invalid signature.
+            (
+                invalid_signature_approval,
+                CollectorState::Rejected(ApprovalRejection::Unauthorized(Error::Crypto(
+                    CryptoError::SignatureVerificationFailed,
+                ))),
+            ),
+            // First valid approval.
+            (
+                approvals[0].clone(),
+                CollectorState::Pending {
+                    valid_approvals: 1,
+                    required: 3,
+                },
+            ),
+            // Duplicate approval.
+            (
+                approvals[0].clone(),
+                CollectorState::Rejected(ApprovalRejection::Duplicate),
+            ),
+            // Approval from an unauthorized party.
+            (
+                approvals[4].clone(),
+                CollectorState::Rejected(ApprovalRejection::Unauthorized(Error::UnauthorizedParty)),
+            ),
+            // Approval from the initiator (i.e duplicate of the implicit approval).
+            (
+                approvals[5].clone(),
+                CollectorState::Rejected(ApprovalRejection::Duplicate),
+            ),
+            // Time-locked approval that's valid now.
+            (
+                timelocked_approval.clone(),
+                CollectorState::Pending {
+                    valid_approvals: 2,
+                    required: 3,
+                },
+            ),
+            // Third valid approval meets the quorum.
+            (approvals[2].clone(), CollectorState::QuorumMet),
+            // Further approvals are ignored.
+            (approvals[3].clone(), CollectorState::QuorumMet),
+        ] {
+            // Verifies expected state.
+            assert_eq!(collector.add(approval), expected_state);
+        }
+        assert_eq!(collector.approvals().len(), 3);
+
+        // Verifies that a time-locked approval is rejected before its `valid_after` timestamp.
+        let mut early_collector = ApprovalCollector::new(
+            &init_payload,
+            &verified_parties,
+            ApprovalPolicy::new(quorum_size).with_timestamp(999),
+        );
+        assert_eq!(
+            early_collector.add(timelocked_approval),
+            CollectorState::Rejected(ApprovalRejection::Timelocked { valid_after: 1_000 })
+        );
+
+        // Generates and verifies quorum approved challenge response.
+        let challenge_payload = collector
+            .challenge_response(&initiator_identity_provider)
+            .unwrap();
+        assert!(verify_challenge_response(
+            &challenge_payload,
+            collector.approvals(),
+            &initiator_identity_provider.verifying_key(),
+            &init_payload,
+            &verified_parties,
+            &policy,
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn time_locked_approvals_work() {
+        // Generates identity providers for the initiator and all other parties.
+        let initiator_identity_provider = MockECDSAIdentityProvider::generate();
+        let approver_identity_providers: Vec<MockECDSAIdentityProvider> = (0..3)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+
+        // Sets quorum.
+        let quorum_size = 4;
+
+        // Creates a list of verifying keys for all parties.
+        let verified_parties: Vec<VerifyingKey> = approver_identity_providers
+            .iter()
+            .map(|identity_provider| identity_provider.verifying_key())
+            .chain([initiator_identity_provider.verifying_key()])
+            .collect();
+
+        // Generates quorum approved request initialization payload and approvals (the last one is time-locked).
+        let command = Command::Signing;
+        let init_payload = initiate(command, &initiator_identity_provider);
+        let valid_after = init_payload.timestamp + 3600;
+        let approvals: Vec<CommandApprovalPayload> = approver_identity_providers
+            .iter()
+            .enumerate()
+            .map(|(idx, identity_provider)| {
+                verify_request_and_initiate_challenge_with_timelock(
+                    command.as_str(),
+                    &init_payload,
+                    identity_provider,
+                    &verified_parties,
+                    if idx == 2 { valid_after } else { 0 },
+                )
+                .unwrap()
+            })
+            .collect();
+
+        // Generates quorum approved challenge response.
+        let challenge_payload = challenge_response(
+            &approvals,
+            &initiator_identity_provider,
+            &init_payload,
+            &verified_parties,
+            &ApprovalPolicy::new(quorum_size),
+        )
+        .unwrap();
+
+        for (now, expected_result) in [
+            // Time-locked approval is rejected before its `valid_after` timestamp.
+            (
+                valid_after - 1,
+                Err(QuorumApprovedRequestError::InsufficientApprovals(
+                    QuorumDecision {
+                        valid_approvals: 2,
+                        invalid_approvals: vec![(2, ApprovalRejection::Timelocked { valid_after })],
+                        required: 3,
+                    },
+                )),
+            ),
+            // Time-locked approval is accepted at and after its `valid_after` timestamp.
+            (valid_after, Ok(())),
+            (valid_after + 1, Ok(())),
+        ] {
+            // Verifies expected result.
+            assert_eq!(
+                verify_challenge_response(
+                    &challenge_payload,
+                    &approvals,
+                    &initiator_identity_provider.verifying_key(),
+                    &init_payload,
+                    &verified_parties,
+                    &ApprovalPolicy::new(quorum_size).with_timestamp(now),
+                ),
+                expected_result
+            );
+        }
+
+        // Verifies that removing the time-lock invalidates the approval's signature.
+        let unlocked_approval = CommandApprovalPayload {
+            valid_after: 0,
+            ..approvals[2].clone()
+        };
+        assert!(verify_approval(&unlocked_approval, &init_payload, &verified_parties).is_err());
+    }
 }
@@ -1,6 +1,7 @@
 //! Secret share and "sub-share" types, abstractions and utilities.
 
 use crypto_bigint::modular::constant_mod::ResidueParams;
+use crypto_bigint::subtle::ConstantTimeEq;
 use crypto_bigint::{const_residue, U256};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
@@ -86,7 +87,9 @@ impl TryFrom<&[u8]> for SigningShare {
 /// A "sub-share" as defined by the Wamu protocol.
 ///
 /// Ref: <https://wamu.tech/specification#share-splitting-and-reconstruction>.
-#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+///
+/// **NOTE:** "Sub-shares" are ordered by `x` (i.e the index) then `y`.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Zeroize, ZeroizeOnDrop)]
 pub struct SubShare {
     x: U256,
     y: U256,
@@ -94,6 +97,8 @@ pub struct SubShare {
 
 impl SubShare {
     /// Initializes a new "sub-share".
+    ///
+    /// Returns an error if either coordinate is not less than the order of the `Secp256k1` curve.
     pub fn new(x: U256, y: U256) -> Result<Self, ArithmeticError> {
         // `x` or `y` coordinates must be less than the order of the `Secp256k1` curve.
         if x < Secp256k1Order::MODULUS && y < Secp256k1Order::MODULUS {
@@ -108,6 +113,11 @@ impl SubShare {
         self.x
     }
 
+    /// Returns the index (i.e the `x` coordinate) of the "sub-share".
+    pub fn index(&self) -> U256 {
+        self.x
+    }
+
     /// Returns the `y` coordinate of the "sub-share".
     pub fn y(&self) -> U256 {
         self.y
@@ -119,6 +129,12 @@ impl SubShare {
     }
 }
 
+/// Interpolates a line (a polynomial of degree 1) through 2 "sub-shares".
+///
+/// **NOTE:** All arithmetic (i.e subtraction, multiplication and modular inversion) is performed on
+/// `crypto-bigint` Montgomery form residues whose operations are constant-time (i.e timing doesn't depend on the values),
+/// and "sub-share" x-coordinates are compared with [`ConstantTimeEq`], so the only branch in interpolation is
+/// on whether the "sub-shares" have the same x-coordinate (i.e an invalid input).
 #[derive(Zeroize, ZeroizeOnDrop)]
 pub struct SubShareInterpolator {
     gradient: U256,
@@ -126,10 +142,17 @@ pub struct SubShareInterpolator {
 }
 
 impl SubShareInterpolator {
-    /// Given 2 "sub-shares" A and B, returns a "sub-share" interpolator.
+    /// Given 2 "sub-shares" A and B, returns a "sub-share" interpolator
+    /// or an error if A and B have the same x-coordinate (i.e no unique line passes through both points).
     ///
     /// i.e a line (a polynomial of degree 1) such that A and B are both points on the line.
-    pub fn new(point_a: &SubShare, point_b: &SubShare) -> Self {
+    pub fn new(point_a: &SubShare, point_b: &SubShare) -> Result<Self, ArithmeticError> {
+        // dx must be non-zero (i.e invertible mod q).
+        // NOTE: x-coordinates are compared in constant-time.
+        if bool::from(point_a.x.ct_eq(&point_b.x)) {
+            return Err(ArithmeticError::DuplicateXCoordinate);
+        }
+
         // dy/dx (mod q) is equivalent to dy * i where i is the modular multiplicative inverse of dx such that dx * i  ≡ 1 (mod q).
         // Ref: <http://en.wikipedia.org/wiki/Modular_multiplicative_inverse#Computation>.
         // NOTE: Since q is prime, gcd(dx, q) = 1, so a modular multiplicative inverse always exists and
@@ -141,16 +164,18 @@ impl SubShareInterpolator {
         let y_2 = point_b.y;
         let dy = const_residue!(y_1, Secp256k1Order) - const_residue!(y_2, Secp256k1Order);
         let dx = const_residue!(x_1, Secp256k1Order) - const_residue!(x_2, Secp256k1Order);
+        // NOTE: `Residue::invert` is constant-time (i.e it always runs a fixed number of iterations),
+        // and dx is always invertible because x-coordinates are distinct and less than q.
         let gradient = dy * dx.invert().0;
 
         // From y = mx + c (mod q), we compute the intercept c = y - mx (mod q).
         let intercept_mod =
             const_residue!(y_1, Secp256k1Order) - (gradient * const_residue!(x_1, Secp256k1Order));
 
-        Self {
+        Ok(Self {
             gradient: gradient.retrieve(),
             intercept: intercept_mod.retrieve(),
-        }
+        })
     }
 
     /// Returns "secret share" for given "sub-shares".
@@ -159,6 +184,8 @@ impl SubShareInterpolator {
     }
 
     /// Returns a unique "sub-share" for the index.
+    ///
+    /// Returns an error if the index is zero or is not less than the order of the `Secp256k1` curve.
     pub fn sub_share(&self, idx: U256) -> Result<SubShare, ArithmeticError> {
         // The "index" should be:
         // - less than the order of the `Secp256k1` curve.
@@ -184,6 +211,7 @@ impl SubShareInterpolator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crypto_bigint::Encoding;
 
     #[test]
     fn sub_share_interpolator_works() {
@@ -196,7 +224,8 @@ mod tests {
         let sub_share_1 = SubShare::new(U256::ONE, U256::from(2u8)).unwrap();
 
         // Initializes the "sub-share" interpolator for share splitting with "sub-shares" at index 0 and 1.
-        let split_sub_share_interpolator = SubShareInterpolator::new(&sub_share_0, &sub_share_1);
+        let split_sub_share_interpolator =
+            SubShareInterpolator::new(&sub_share_0, &sub_share_1).unwrap();
 
         // The "sub-share" at index 2 is (2, 3),i.e when x = 2, y = 3.
         let sub_share_2 = SubShare::new(U256::from(2u8), U256::from(3u8)).unwrap();
@@ -212,9 +241,247 @@ mod tests {
 
         // Initializes the "sub-share" interpolator for share reconstruction with "sub-shares" at index 1 and 2.
         let reconstruct_sub_share_interpolator =
-            SubShareInterpolator::new(&sub_share_1, &sub_share_2);
+            SubShareInterpolator::new(&sub_share_1, &sub_share_2).unwrap();
 
         // Verify that the "sub-share" interpolator returns the right "secret share".
         assert_eq!(&reconstruct_sub_share_interpolator.secret(), &secret_share);
     }
+
+    #[test]
+    fn sub_share_interpolator_matches_variable_time_path() {
+        use k256::elliptic_curve::ops::Invert;
+        use k256::elliptic_curve::{Field, PrimeField};
+
+        let to_u256 = |scalar: &k256::Scalar| U256::from_be_slice(&scalar.to_bytes());
+        for _ in 0..32 {
+            // Generates 2 random "sub-shares".
+            let (x_1, y_1, x_2, y_2) = (
+                k256::Scalar::random(&mut rand::thread_rng()),
+                k256::Scalar::random(&mut rand::thread_rng()),
+                k256::Scalar::random(&mut rand::thread_rng()),
+                k256::Scalar::random(&mut rand::thread_rng()),
+            );
+            let sub_share_1 = SubShare::new(to_u256(&x_1), to_u256(&y_1)).unwrap();
+            let sub_share_2 = SubShare::new(to_u256(&x_2), to_u256(&y_2)).unwrap();
+
+            // Computes the intercept using variable-time inversion (i.e the reference path).
+            let gradient = (y_1 - y_2) * (x_1 - x_2).invert_vartime().unwrap();
+            let intercept = y_1 - gradient * x_1;
+
+            // Verifies that the constant-time path returns the same "secret share".
+            let interpolator = SubShareInterpolator::new(&sub_share_1, &sub_share_2).unwrap();
+            assert_eq!(interpolator.secret(), to_u256(&intercept));
+            assert_eq!(
+                k256::Scalar::from_repr(interpolator.secret().to_be_bytes().into()).unwrap(),
+                intercept
+            );
+        }
+    }
+
+    #[test]
+    fn sub_share_bounds_checks_work() {
+        // Generates test cases for "sub-share" coordinates.
+        for (x, y, expected_result) in [
+            // Coordinates less than the modulus should be ok.
+            (
+                U256::ONE,
+                Secp256k1Order::MODULUS.wrapping_sub(&U256::ONE),
+                Ok(()),
+            ),
+            // An `x` coordinate equal to the modulus should fail.
+            (
+                Secp256k1Order::MODULUS,
+                U256::ONE,
+                Err(ArithmeticError::ModulusOverflow),
+            ),
+            // A `y` coordinate greater than the modulus should fail.
+            (U256::ONE, U256::MAX, Err(ArithmeticError::ModulusOverflow)),
+        ] {
+            // Verifies expected result.
+            assert_eq!(SubShare::new(x, y).map(|_| ()), expected_result);
+        }
+
+        // Initializes a "sub-share" interpolator.
+        let interpolator = SubShareInterpolator::new(
+            &SubShare::new(U256::ZERO, U256::ONE).unwrap(),
+            &SubShare::new(U256::ONE, U256::from(2u8)).unwrap(),
+        )
+        .unwrap();
+
+        // Generates test cases for "sub-share" indices.
+        for (idx, expected_result) in [
+            // An index less than the modulus should be ok.
+            (Secp256k1Order::MODULUS.wrapping_sub(&U256::ONE), Ok(())),
+            // The zero index (i.e the "secret share") should fail.
+            (U256::ZERO, Err(ArithmeticError::ModulusOverflow)),
+            // An index equal to the modulus should fail.
+            (
+                Secp256k1Order::MODULUS,
+                Err(ArithmeticError::ModulusOverflow),
+            ),
+        ] {
+            // Verifies expected result.
+            assert_eq!(interpolator.sub_share(idx).map(|_| ()), expected_result);
+        }
+    }
+
+    #[test]
+    fn sub_share_interpolator_duplicate_x_coordinate_fails() {
+        // Generates test cases for pairs of "sub-shares".
+        for (sub_share_a, sub_share_b, expected_result) in [
+            // "Sub-shares" with distinct x-coordinates should be ok.
+            (
+                SubShare::new(U256::ONE, U256::from(2u8)).unwrap(),
+                SubShare::new(U256::from(2u8), U256::from(3u8)).unwrap(),
+                Ok(()),
+            ),
+            // "Sub-shares" with the same x-coordinate but different y-coordinates should fail.
+            (
+                SubShare::new(U256::ONE, U256::from(2u8)).unwrap(),
+                SubShare::new(U256::ONE, U256::from(3u8)).unwrap(),
+                Err(ArithmeticError::DuplicateXCoordinate),
+            ),
+            // Identical "sub-shares" should fail.
+            (
+                SubShare::new(U256::ONE, U256::from(2u8)).unwrap(),
+                SubShare::new(U256::ONE, U256::from(2u8)).unwrap(),
+                Err(ArithmeticError::DuplicateXCoordinate),
+            ),
+        ] {
+            // Verifies expected result.
+            assert_eq!(
+                SubShareInterpolator::new(&sub_share_a, &sub_share_b).map(|_| ()),
+                expected_result
+            );
+        }
+    }
+
+    #[test]
+    fn share_from_invalid_slice_fails() {
+        for (slice, expected_ok) in [
+            // 32 bytes should be ok.
+            (&[1u8; 32][..], true),
+            // Fewer than 32 bytes should fail.
+            (&[1u8; 31][..], false),
+            // More than 32 bytes should fail.
+            (&[1u8; 33][..], false),
+            // An empty slice should fail.
+            (&[][..], false),
+        ] {
+            // Verifies expected result for "secret shares" and "signing shares".
+            let expected_result = if expected_ok {
+                Ok(())
+            } else {
+                Err(Error::Encoding)
+            };
+            assert_eq!(SecretShare::try_from(slice).map(|_| ()), expected_result);
+            assert_eq!(SigningShare::try_from(slice).map(|_| ()), expected_result);
+        }
+    }
+
+    #[test]
+    fn sub_share_ordering_detects_duplicate_indices() {
+        // Generates "sub-shares" from multiple custodians (in no particular order),
+        // where 2 custodians hold different "sub-shares" with the same index.
+        let mut sub_shares = [
+            SubShare::new(U256::from(3u8), U256::from(1u8)).unwrap(),
+            SubShare::new(U256::ONE, U256::from(5u8)).unwrap(),
+            SubShare::new(U256::from(3u8), U256::from(7u8)).unwrap(),
+            SubShare::new(U256::from(2u8), U256::from(2u8)).unwrap(),
+        ];
+
+        // Verifies that "sub-shares" are ordered by index then `y` coordinate.
+        sub_shares.sort();
+        assert_eq!(
+            sub_shares
+                .iter()
+                .map(SubShare::as_tuple)
+                .collect::<Vec<(U256, U256)>>(),
+            vec![
+                (U256::ONE, U256::from(5u8)),
+                (U256::from(2u8), U256::from(2u8)),
+                (U256::from(3u8), U256::ONE),
+                (U256::from(3u8), U256::from(7u8)),
+            ]
+        );
+
+        // Verifies that the "sub-shares" with the same index are detected as conflicting.
+        let conflicting_indices: Vec<U256> = sub_shares
+            .windows(2)
+            .filter(|pair| pair[0].index() == pair[1].index())
+            .map(|pair| pair[0].index())
+            .collect();
+        assert_eq!(conflicting_indices, vec![U256::from(3u8)]);
+        assert_eq!(
+            SubShareInterpolator::new(&sub_shares[2], &sub_shares[3]).map(|_| ()),
+            Err(ArithmeticError::DuplicateXCoordinate)
+        );
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn sub_share_construction_never_panics(
+            x in proptest::prelude::any::<[u8; 32]>(),
+            y in proptest::prelude::any::<[u8; 32]>(),
+        ) {
+            // Verifies that only coordinates less than the modulus are accepted.
+            let (x, y) = (U256::from_be_bytes(x), U256::from_be_bytes(y));
+            let expected_result = if x < Secp256k1Order::MODULUS && y < Secp256k1Order::MODULUS {
+                Ok(())
+            } else {
+                Err(ArithmeticError::ModulusOverflow)
+            };
+            proptest::prop_assert_eq!(SubShare::new(x, y).map(|_| ()), expected_result);
+        }
+
+        #[test]
+        fn sub_share_interpolation_never_panics(
+            points in proptest::collection::vec(proptest::prelude::any::<([u8; 32], [u8; 32])>(), 2..8),
+            idx in proptest::prelude::any::<[u8; 32]>(),
+        ) {
+            // Reduces coordinates modulo the order of the `Secp256k1` curve
+            // and includes a duplicate x-coordinate.
+            let mut sub_shares: Vec<SubShare> = points
+                .iter()
+                .map(|(x, y)| {
+                    let (x, y) = (U256::from_be_bytes(*x), U256::from_be_bytes(*y));
+                    SubShare::new(
+                        const_residue!(x, Secp256k1Order).retrieve(),
+                        const_residue!(y, Secp256k1Order).retrieve(),
+                    )
+                    .unwrap()
+                })
+                .collect();
+            sub_shares.push(SubShare::new(sub_shares[0].x(), U256::ONE).unwrap());
+
+            for point_a in &sub_shares {
+                for point_b in &sub_shares {
+                    match SubShareInterpolator::new(point_a, point_b) {
+                        // Verifies that the line passes through both "sub-shares"
+                        // and that indices out of range are rejected.
+                        Ok(interpolator) => {
+                            for point in [point_a, point_b] {
+                                if point.x() != U256::ZERO {
+                                    proptest::prop_assert_eq!(
+                                        interpolator.sub_share(point.x()).unwrap().y(),
+                                        point.y()
+                                    );
+                                }
+                            }
+                            let idx = U256::from_be_bytes(idx);
+                            proptest::prop_assert_eq!(
+                                interpolator.sub_share(idx).is_ok(),
+                                idx != U256::ZERO && idx < Secp256k1Order::MODULUS
+                            );
+                        }
+                        // Verifies that only duplicate x-coordinates are rejected.
+                        Err(error) => {
+                            proptest::prop_assert_eq!(error, ArithmeticError::DuplicateXCoordinate);
+                            proptest::prop_assert_eq!(point_a.x(), point_b.x());
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
@@ -2,84 +2,225 @@
 //!
 //! Ref: <https://wamu.tech/specification#share-recovery-backup>.
 //!
-//! [HKDF (HMAC-based Extract-and-Expand Key Derivation Function)](https://tools.ietf.org/html/rfc5869) and
-//! [AES-GCM (Advanced Encryption Standard Galois/Counter Mode)](https://en.wikipedia.org/wiki/Galois/Counter_Mode)
-//! are the key derivation function and symmetric encryption algorithm used respectively.
-
-use aes_gcm::aead::consts::U12;
-use aes_gcm::aes::Aes256;
-use aes_gcm::{
-    aead::{Aead, AeadCore, KeyInit},
-    Aes256Gcm, AesGcm,
-};
+//! The symmetric encryption algorithm and key derivation function are selected by a [`BackupCipher`] suite i.e
+//! [AES-GCM (Advanced Encryption Standard Galois/Counter Mode)](https://en.wikipedia.org/wiki/Galois/Counter_Mode) with
+//! [HKDF (HMAC-based Extract-and-Expand Key Derivation Function)](https://tools.ietf.org/html/rfc5869) (the default) or
+//! [PBKDF2 (Password-Based Key Derivation Function 2)](https://datatracker.ietf.org/doc/html/rfc8018#section-5.2), or
+//! [XChaCha20-Poly1305](https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-xchacha) with
+//! [Argon2id](https://datatracker.ietf.org/doc/html/rfc9106).
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, Payload};
+use aes_gcm::Aes256Gcm;
+use argon2::Argon2;
+use chacha20poly1305::XChaCha20Poly1305;
 use crypto_bigint::{Encoding, U256};
 use hkdf::Hkdf;
 use sha2::Sha256;
 
+use crate::crypto::VerifyingKey;
 use crate::errors::ShareBackupRecoveryError;
 use crate::payloads::EncryptedShareBackup;
 use crate::share::{SigningShare, SubShare};
 use crate::traits::IdentityProvider;
 
-/// Given an entropy seed (i.e typically a standardized phrase), "signing share", "sub-share" and identity provider,
+/// Salt for the PBKDF2 and Argon2id key derivation functions.
+///
+/// **NOTE:** The key derivation input is a signature from the identity provider (i.e not a low entropy password),
+/// so a fixed domain separation salt is sufficient.
+const KDF_SALT: &[u8] = b"wamu-share-recovery-backup";
+
+/// Domain tag for the associated data that binds an encrypted share backup to a wallet (i.e a group verifying key).
+const WALLET_BINDING_TAG: &[u8] = b"wamu:share-backup-wallet";
+
+/// Number of PBKDF2-HMAC-SHA256 iterations.
+const PBKDF2_ROUNDS: u32 = 100_000;
+
+/// A cipher suite (i.e a symmetric encryption algorithm and a key derivation function) for encrypted share backups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackupCipher {
+    /// AES-256-GCM encryption with HKDF-SHA256 key derivation.
+    #[default]
+    Aes256GcmHkdfSha256,
+    /// AES-256-GCM encryption with PBKDF2-HMAC-SHA256 key derivation (i.e only FIPS-approved primitives).
+    Aes256GcmPbkdf2Sha256,
+    /// XChaCha20-Poly1305 encryption with Argon2id key derivation.
+    XChaCha20Poly1305Argon2id,
+}
+
+impl BackupCipher {
+    /// Returns the tag that identifies the cipher suite in an encrypted share backup.
+    pub fn tag(&self) -> u8 {
+        match self {
+            BackupCipher::Aes256GcmHkdfSha256 => 0,
+            BackupCipher::Aes256GcmPbkdf2Sha256 => 1,
+            BackupCipher::XChaCha20Poly1305Argon2id => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for BackupCipher {
+    type Error = ShareBackupRecoveryError;
+
+    /// Converts a cipher suite tag into a `BackupCipher`.
+    fn try_from(tag: u8) -> Result<Self, Self::Error> {
+        match tag {
+            0 => Ok(BackupCipher::Aes256GcmHkdfSha256),
+            1 => Ok(BackupCipher::Aes256GcmPbkdf2Sha256),
+            2 => Ok(BackupCipher::XChaCha20Poly1305Argon2id),
+            _ => Err(ShareBackupRecoveryError::UnsupportedCipher(tag)),
+        }
+    }
+}
+
+/// Given an entropy seed (i.e typically a standardized phrase), "signing share", "sub-share",
+/// the group verifying key of the wallet and identity provider,
 /// returns an ok result including the encrypted share backup (i.e an encrypted "signing share" and "sub-share", and a random nonce)
-/// or an encryption error result.
+/// that's bound to the wallet or an encryption error result.
+///
+/// **NOTE:** Uses the default cipher suite (see [`backup_with_cipher`] for selecting a different cipher suite).
 ///
 /// Ref: <https://wamu.tech/specification#share-recovery-backup-encrypt>.
 pub fn backup(
     entropy_seed: &[u8],
     signing_share: &SigningShare,
     sub_share: &SubShare,
+    group_key: &VerifyingKey,
     identity_provider: &impl IdentityProvider,
 ) -> Result<EncryptedShareBackup, ShareBackupRecoveryError> {
-    // Generates nonce.
-    let nonce = Aes256Gcm::generate_nonce(&mut rand::thread_rng());
+    backup_with_cipher(
+        BackupCipher::default(),
+        entropy_seed,
+        signing_share,
+        sub_share,
+        group_key,
+        identity_provider,
+    )
+}
+
+/// Given a cipher suite, an entropy seed (i.e typically a standardized phrase), "signing share", "sub-share",
+/// the group verifying key of the wallet and identity provider,
+/// returns an ok result including the encrypted share backup (i.e an encrypted "signing share" and "sub-share", a random nonce
+/// and the cipher suite tag) that's bound to the wallet or an encryption error result.
+///
+/// **NOTE:** The group verifying key is authenticated as associated data (i.e it's not included in the encrypted share backup),
+/// so the encrypted share backup can only be recovered with the same group verifying key (see [`recover`]).
+///
+/// Ref: <https://wamu.tech/specification#share-recovery-backup-encrypt>.
+pub fn backup_with_cipher(
+    cipher_suite: BackupCipher,
+    entropy_seed: &[u8],
+    signing_share: &SigningShare,
+    sub_share: &SubShare,
+    group_key: &VerifyingKey,
+    identity_provider: &impl IdentityProvider,
+) -> Result<EncryptedShareBackup, ShareBackupRecoveryError> {
+    encrypt_backup(
+        cipher_suite,
+        entropy_seed,
+        signing_share,
+        sub_share,
+        &wallet_binding_bytes(group_key),
+        identity_provider,
+    )
+}
+
+/// Given a cipher suite, an entropy seed, "signing share", "sub-share", associated data and identity provider,
+/// returns an ok result including the encrypted share backup or an encryption error result.
+fn encrypt_backup(
+    cipher_suite: BackupCipher,
+    entropy_seed: &[u8],
+    signing_share: &SigningShare,
+    sub_share: &SubShare,
+    aad: &[u8],
+    identity_provider: &impl IdentityProvider,
+) -> Result<EncryptedShareBackup, ShareBackupRecoveryError> {
+    // Generates encryption cipher and nonce.
+    let cipher = generate_encryption_cipher(cipher_suite, entropy_seed, identity_provider);
+    let nonce = cipher.generate_nonce();
 
     // Encrypts the "signing share" and "sub-share".
-    let cipher = generate_encryption_cipher(entropy_seed, identity_provider);
-    let encrypted_signing_share = cipher.encrypt(&nonce, signing_share.to_be_bytes().as_ref())?;
+    let encrypted_signing_share =
+        cipher.encrypt(&nonce, signing_share.to_be_bytes().as_ref(), aad)?;
     let encrypted_sub_share = (
-        cipher.encrypt(&nonce, sub_share.x().to_be_bytes().as_ref())?,
-        cipher.encrypt(&nonce, sub_share.y().to_be_bytes().as_ref())?,
+        cipher.encrypt(&nonce, sub_share.x().to_be_bytes().as_ref(), aad)?,
+        cipher.encrypt(&nonce, sub_share.y().to_be_bytes().as_ref(), aad)?,
     );
 
     // Returns the encrypted share backup.
     Ok(EncryptedShareBackup {
         signing_share: encrypted_signing_share,
         sub_share: encrypted_sub_share,
-        nonce: nonce.to_vec(),
+        nonce,
+        cipher: cipher_suite.tag(),
     })
 }
 
 /// Given an entropy seed (i.e typically a standardized phrase), encrypted share backup
-/// (i.e an encrypted "signing share" and "sub-share", and a random nonce) and an identity provider,
-/// returns the decrypted "signing share" and "sub-share".
+/// (i.e an encrypted "signing share" and "sub-share", and a random nonce), the group verifying key of the wallet
+/// and an identity provider, returns the decrypted "signing share" and "sub-share".
+///
+/// **NOTE:** The cipher suite is selected based on the cipher suite tag of the encrypted share backup.
+/// Recovery fails with an [`EncryptionError`](ShareBackupRecoveryError::EncryptionError)
+/// (i.e an integrity check failure) if the group verifying key isn't the one the encrypted share backup is bound to.
 ///
 /// Ref: <https://wamu.tech/specification#share-recovery-backup-decrypt>.
 pub fn recover(
     entropy_seed: &[u8],
     encrypted_share_backup: &EncryptedShareBackup,
+    group_key: &VerifyingKey,
+    identity_provider: &impl IdentityProvider,
+) -> Result<(SigningShare, SubShare), ShareBackupRecoveryError> {
+    decrypt_backup(
+        entropy_seed,
+        encrypted_share_backup,
+        &wallet_binding_bytes(group_key),
+        identity_provider,
+    )
+}
+
+/// Given an entropy seed (i.e typically a standardized phrase), an encrypted share backup that isn't bound to a wallet
+/// and an identity provider, returns the decrypted "signing share" and "sub-share".
+///
+/// **NOTE:** Only for recovering legacy encrypted share backups (i.e created before backups were bound to a wallet),
+/// recovery of an encrypted share backup that's bound to a wallet always fails the integrity check (see [`recover`]).
+pub fn recover_unbound(
+    entropy_seed: &[u8],
+    encrypted_share_backup: &EncryptedShareBackup,
+    identity_provider: &impl IdentityProvider,
+) -> Result<(SigningShare, SubShare), ShareBackupRecoveryError> {
+    decrypt_backup(entropy_seed, encrypted_share_backup, &[], identity_provider)
+}
+
+/// Given an entropy seed, encrypted share backup, associated data and an identity provider,
+/// returns the decrypted "signing share" and "sub-share".
+fn decrypt_backup(
+    entropy_seed: &[u8],
+    encrypted_share_backup: &EncryptedShareBackup,
+    aad: &[u8],
     identity_provider: &impl IdentityProvider,
 ) -> Result<(SigningShare, SubShare), ShareBackupRecoveryError> {
-    // Generates nonce.
-    let nonce = aes_gcm::Nonce::from_slice(&encrypted_share_backup.nonce);
+    // Selects the cipher suite.
+    let cipher_suite = BackupCipher::try_from(encrypted_share_backup.cipher)?;
+
+    // Retrieves nonce.
+    let nonce = &encrypted_share_backup.nonce;
 
     // Decrypts the "signing share" and "sub-share".
-    let cipher = generate_encryption_cipher(entropy_seed, identity_provider);
+    let cipher = generate_encryption_cipher(cipher_suite, entropy_seed, identity_provider);
     let signing_share_bytes =
-        cipher.decrypt(nonce, encrypted_share_backup.signing_share.as_ref())?;
+        cipher.decrypt(nonce, encrypted_share_backup.signing_share.as_ref(), aad)?;
     let signing_share = SigningShare::try_from(signing_share_bytes.as_ref())
         .map_err(|_| ShareBackupRecoveryError::InvalidSigningShare)?;
     let sub_share = SubShare::new(
         U256::from_be_bytes(
             cipher
-                .decrypt(nonce, encrypted_share_backup.sub_share.0.as_ref())?
+                .decrypt(nonce, encrypted_share_backup.sub_share.0.as_ref(), aad)?
                 .try_into()
                 .map_err(|_| ShareBackupRecoveryError::InvalidSubShare)?,
         ),
         U256::from_be_bytes(
             cipher
-                .decrypt(nonce, encrypted_share_backup.sub_share.1.as_ref())?
+                .decrypt(nonce, encrypted_share_backup.sub_share.1.as_ref(), aad)?
                 .try_into()
                 .map_err(|_| ShareBackupRecoveryError::InvalidSubShare)?,
         ),
@@ -89,21 +230,95 @@ pub fn recover(
     Ok((signing_share, sub_share))
 }
 
-/// Given an entropy seed (i.e typically a standardized phrase) and an identity provider, returns an encryption cipher.
+/// Given a group verifying key, returns the domain separated canonical (algorithm, curve, encoding, key bytes) encoding
+/// of the group verifying key (i.e the associated data that binds an encrypted share backup to a wallet).
+fn wallet_binding_bytes(group_key: &VerifyingKey) -> Vec<u8> {
+    let mut encoded_key = Vec::with_capacity(3 + group_key.key.len());
+    encoded_key.extend_from_slice(&[
+        group_key.algo as u8,
+        group_key.curve as u8,
+        group_key.enc as u8,
+    ]);
+    encoded_key.extend_from_slice(&group_key.key);
+    crate::utils::domain_separated_message_bytes(WALLET_BINDING_TAG, &encoded_key)
+}
+
+/// An encryption cipher for a cipher suite.
+enum Cipher {
+    Aes256Gcm(Box<Aes256Gcm>),
+    XChaCha20Poly1305(XChaCha20Poly1305),
+}
+
+impl Cipher {
+    /// Generates a random nonce of the appropriate length for the cipher.
+    fn generate_nonce(&self) -> Vec<u8> {
+        let mut rng = rand::thread_rng();
+        match self {
+            Cipher::Aes256Gcm(_) => Aes256Gcm::generate_nonce(&mut rng).to_vec(),
+            Cipher::XChaCha20Poly1305(_) => XChaCha20Poly1305::generate_nonce(&mut rng).to_vec(),
+        }
+    }
+
+    /// Encrypts the plaintext with the nonce and authenticates the associated data.
+    fn encrypt(&self, nonce: &[u8], msg: &[u8], aad: &[u8]) -> Result<Vec<u8>, aes_gcm::Error> {
+        let payload = Payload { msg, aad };
+        match self {
+            Cipher::Aes256Gcm(cipher) => cipher.encrypt(aes_nonce(nonce)?, payload),
+            Cipher::XChaCha20Poly1305(cipher) => cipher.encrypt(xchacha_nonce(nonce)?, payload),
+        }
+    }
+
+    /// Decrypts the ciphertext with the nonce and verifies the associated data.
+    fn decrypt(&self, nonce: &[u8], msg: &[u8], aad: &[u8]) -> Result<Vec<u8>, aes_gcm::Error> {
+        let payload = Payload { msg, aad };
+        match self {
+            Cipher::Aes256Gcm(cipher) => cipher.decrypt(aes_nonce(nonce)?, payload),
+            Cipher::XChaCha20Poly1305(cipher) => cipher.decrypt(xchacha_nonce(nonce)?, payload),
+        }
+    }
+}
+
+/// Returns an AES-GCM nonce or an error for a nonce with an invalid length.
+fn aes_nonce(
+    nonce: &[u8],
+) -> Result<&aes_gcm::Nonce<<Aes256Gcm as AeadCore>::NonceSize>, aes_gcm::Error> {
+    (nonce.len() == 12)
+        .then(|| aes_gcm::Nonce::from_slice(nonce))
+        .ok_or(aes_gcm::Error)
+}
+
+/// Returns an XChaCha20-Poly1305 nonce or an error for a nonce with an invalid length.
+fn xchacha_nonce(nonce: &[u8]) -> Result<&chacha20poly1305::XNonce, aes_gcm::Error> {
+    (nonce.len() == 24)
+        .then(|| chacha20poly1305::XNonce::from_slice(nonce))
+        .ok_or(aes_gcm::Error)
+}
+
+/// Given a cipher suite, an entropy seed (i.e typically a standardized phrase) and an identity provider, returns an encryption cipher.
 fn generate_encryption_cipher(
+    cipher_suite: BackupCipher,
     entropy_seed: &[u8],
     identity_provider: &impl IdentityProvider,
-) -> AesGcm<Aes256, U12> {
+) -> Cipher {
     // Generates encryption key.
-    let key_bytes = generate_encryption_key(entropy_seed, identity_provider);
-    let key = aes_gcm::Key::<Aes256Gcm>::from_slice(&key_bytes);
+    let key_bytes = generate_encryption_key(cipher_suite, entropy_seed, identity_provider);
 
     // Generates and returns cipher.
-    Aes256Gcm::new(key)
+    match cipher_suite {
+        BackupCipher::Aes256GcmHkdfSha256 | BackupCipher::Aes256GcmPbkdf2Sha256 => {
+            let key = aes_gcm::Key::<Aes256Gcm>::from_slice(&key_bytes);
+            Cipher::Aes256Gcm(Box::new(Aes256Gcm::new(key)))
+        }
+        BackupCipher::XChaCha20Poly1305Argon2id => {
+            let key = chacha20poly1305::Key::from_slice(&key_bytes);
+            Cipher::XChaCha20Poly1305(XChaCha20Poly1305::new(key))
+        }
+    }
 }
 
-/// Given an entropy seed (i.e typically a standardized phrase) and an identity provider, returns a 256 bit encryption secret.
+/// Given a cipher suite, an entropy seed (i.e typically a standardized phrase) and an identity provider, returns a 256 bit encryption secret.
 fn generate_encryption_key(
+    cipher_suite: BackupCipher,
     entropy_seed: &[u8],
     identity_provider: &impl IdentityProvider,
 ) -> [u8; 32] {
@@ -112,9 +327,17 @@ fn generate_encryption_key(
 
     // Generates encryption key.
     let mut output_key = [0u8; 32];
-    Hkdf::<Sha256>::new(None, &entropy.sig)
-        .expand(&[], &mut output_key)
-        .expect("32 is a valid length for Sha256 to output");
+    match cipher_suite {
+        BackupCipher::Aes256GcmHkdfSha256 => Hkdf::<Sha256>::new(None, &entropy.sig)
+            .expand(&[], &mut output_key)
+            .expect("32 is a valid length for Sha256 to output"),
+        BackupCipher::Aes256GcmPbkdf2Sha256 => {
+            pbkdf2::pbkdf2_hmac::<Sha256>(&entropy.sig, KDF_SALT, PBKDF2_ROUNDS, &mut output_key)
+        }
+        BackupCipher::XChaCha20Poly1305Argon2id => Argon2::default()
+            .hash_password_into(&entropy.sig, KDF_SALT, &mut output_key)
+            .expect("default Argon2id parameters, salt and 32 byte output length are valid"),
+    }
 
     // Returns generated encryption key.
     output_key
@@ -143,8 +366,17 @@ mod tests {
         let (signing_share, sub_share) =
             share_split_reconstruct::split(&secret_share, &identity_provider).unwrap();
 
-        // Generates encryption share backup.
-        let backup_result = backup(entropy_seed, &signing_share, &sub_share, &identity_provider);
+        // Generates group verifying key for the wallet.
+        let group_key = MockECDSAIdentityProvider::generate().verifying_key();
+
+        // Generates encryption share backup (with the default cipher suite).
+        let backup_result = backup(
+            entropy_seed,
+            &signing_share,
+            &sub_share,
+            &group_key,
+            &identity_provider,
+        );
 
         // Verifies backup result.
         assert!(backup_result.is_ok());
@@ -153,7 +385,12 @@ mod tests {
         let encrypted_share_backup = backup_result.unwrap();
 
         // Generates encryption share backup.
-        let recover_result = recover(entropy_seed, &encrypted_share_backup, &identity_provider);
+        let recover_result = recover(
+            entropy_seed,
+            &encrypted_share_backup,
+            &group_key,
+            &identity_provider,
+        );
 
         // Verifies recover result.
         assert!(recover_result.is_ok());
@@ -170,30 +407,211 @@ mod tests {
     }
 
     #[test]
-    fn generate_encryption_key_works() {
+    fn share_recovery_with_encrypted_backup_cipher_suites_works() {
         // Generates identity provider.
         let identity_provider = MockECDSAIdentityProvider::generate();
 
         // Set entropy seed.
         let entropy_seed = b"Hello, world!";
 
-        // Generates encryption key.
-        let encryption_key = generate_encryption_key(entropy_seed, &identity_provider);
+        // Generates secret share.
+        let secret_share = SecretShare::from(Random32Bytes::generate_mod_q());
 
-        // Verifies that generated encryption key is deterministic based on the entropy seed and identity provider.
+        // Computes "signing share" and "sub-share".
+        let (signing_share, sub_share) =
+            share_split_reconstruct::split(&secret_share, &identity_provider).unwrap();
+
+        // Generates group verifying key for the wallet.
+        let group_key = MockECDSAIdentityProvider::generate().verifying_key();
+
+        for cipher_suite in [
+            BackupCipher::Aes256GcmHkdfSha256,
+            BackupCipher::Aes256GcmPbkdf2Sha256,
+            BackupCipher::XChaCha20Poly1305Argon2id,
+        ] {
+            // Generates encryption share backup with the cipher suite.
+            let encrypted_share_backup = backup_with_cipher(
+                cipher_suite,
+                entropy_seed,
+                &signing_share,
+                &sub_share,
+                &group_key,
+                &identity_provider,
+            )
+            .unwrap();
+
+            // Verifies that the cipher suite is recorded in the encrypted share backup.
+            assert_eq!(encrypted_share_backup.cipher, cipher_suite.tag());
+
+            // Recovers "signing share" and "sub-share" (i.e the cipher suite is selected automatically).
+            let (recovered_signing_share, recovered_sub_share) = recover(
+                entropy_seed,
+                &encrypted_share_backup,
+                &group_key,
+                &identity_provider,
+            )
+            .unwrap();
+
+            // Verifies recovered "signing share" and "sub-share".
+            assert_eq!(
+                &recovered_signing_share.to_be_bytes(),
+                &signing_share.to_be_bytes()
+            );
+            assert_eq!(recovered_sub_share.as_tuple(), sub_share.as_tuple());
+        }
+
+        // Verifies that an unknown cipher suite tag is rejected.
+        let mut encrypted_share_backup = backup(
+            entropy_seed,
+            &signing_share,
+            &sub_share,
+            &group_key,
+            &identity_provider,
+        )
+        .unwrap();
+        encrypted_share_backup.cipher = u8::MAX;
+        assert!(matches!(
+            recover(
+                entropy_seed,
+                &encrypted_share_backup,
+                &group_key,
+                &identity_provider
+            ),
+            Err(ShareBackupRecoveryError::UnsupportedCipher(u8::MAX))
+        ));
+    }
+
+    #[test]
+    fn share_recovery_with_wallet_bound_backup_works() {
+        // Generates identity provider.
+        let identity_provider = MockECDSAIdentityProvider::generate();
+
+        // Set entropy seed.
+        let entropy_seed = b"Hello, world!";
+
+        // Generates secret share.
+        let secret_share = SecretShare::from(Random32Bytes::generate_mod_q());
+
+        // Computes "signing share" and "sub-share".
+        let (signing_share, sub_share) =
+            share_split_reconstruct::split(&secret_share, &identity_provider).unwrap();
+
+        // Generates group verifying keys for the wallet and another wallet.
+        let group_key = MockECDSAIdentityProvider::generate().verifying_key();
+        let other_group_key = MockECDSAIdentityProvider::generate().verifying_key();
+
+        for cipher_suite in [
+            BackupCipher::Aes256GcmHkdfSha256,
+            BackupCipher::Aes256GcmPbkdf2Sha256,
+            BackupCipher::XChaCha20Poly1305Argon2id,
+        ] {
+            // Generates encryption share backup bound to the wallet.
+            let encrypted_share_backup = backup_with_cipher(
+                cipher_suite,
+                entropy_seed,
+                &signing_share,
+                &sub_share,
+                &group_key,
+                &identity_provider,
+            )
+            .unwrap();
+
+            // Verifies that recovery with the wrong group key (or without a group key) fails the integrity check.
+            assert!(matches!(
+                recover(
+                    entropy_seed,
+                    &encrypted_share_backup,
+                    &other_group_key,
+                    &identity_provider
+                ),
+                Err(ShareBackupRecoveryError::EncryptionError(_))
+            ));
+            assert!(matches!(
+                recover_unbound(entropy_seed, &encrypted_share_backup, &identity_provider),
+                Err(ShareBackupRecoveryError::EncryptionError(_))
+            ));
+
+            // Recovers "signing share" and "sub-share" with the right group key.
+            let (recovered_signing_share, recovered_sub_share) = recover(
+                entropy_seed,
+                &encrypted_share_backup,
+                &group_key,
+                &identity_provider,
+            )
+            .unwrap();
+
+            // Verifies recovered "signing share" and "sub-share".
+            assert_eq!(
+                &recovered_signing_share.to_be_bytes(),
+                &signing_share.to_be_bytes()
+            );
+            assert_eq!(recovered_sub_share.as_tuple(), sub_share.as_tuple());
+        }
+
+        // Verifies that legacy encrypted share backups (i.e not bound to a wallet) can only be recovered as unbound backups.
+        let legacy_share_backup = encrypt_backup(
+            BackupCipher::default(),
+            entropy_seed,
+            &signing_share,
+            &sub_share,
+            &[],
+            &identity_provider,
+        )
+        .unwrap();
+        assert!(matches!(
+            recover(
+                entropy_seed,
+                &legacy_share_backup,
+                &group_key,
+                &identity_provider
+            ),
+            Err(ShareBackupRecoveryError::EncryptionError(_))
+        ));
+        let (recovered_signing_share, recovered_sub_share) =
+            recover_unbound(entropy_seed, &legacy_share_backup, &identity_provider).unwrap();
         assert_eq!(
-            encryption_key,
-            generate_encryption_key(entropy_seed, &identity_provider)
+            &recovered_signing_share.to_be_bytes(),
+            &signing_share.to_be_bytes()
         );
+        assert_eq!(recovered_sub_share.as_tuple(), sub_share.as_tuple());
+    }
 
-        // Verifies that different inputs (entropy seed and identity provider) permutations produce different encryption keys.
-        assert_ne!(
-            encryption_key,
-            generate_encryption_key(entropy_seed, &MockECDSAIdentityProvider::generate())
-        );
-        assert_ne!(
-            encryption_key,
-            generate_encryption_key(b"Another phrase.", &identity_provider)
-        );
+    #[test]
+    fn generate_encryption_key_works() {
+        // Generates identity provider.
+        let identity_provider = MockECDSAIdentityProvider::generate();
+
+        // Set entropy seed.
+        let entropy_seed = b"Hello, world!";
+
+        for cipher_suite in [
+            BackupCipher::Aes256GcmHkdfSha256,
+            BackupCipher::Aes256GcmPbkdf2Sha256,
+            BackupCipher::XChaCha20Poly1305Argon2id,
+        ] {
+            // Generates encryption key.
+            let encryption_key =
+                generate_encryption_key(cipher_suite, entropy_seed, &identity_provider);
+
+            // Verifies that generated encryption key is deterministic based on the entropy seed and identity provider.
+            assert_eq!(
+                encryption_key,
+                generate_encryption_key(cipher_suite, entropy_seed, &identity_provider)
+            );
+
+            // Verifies that different inputs (entropy seed and identity provider) permutations produce different encryption keys.
+            assert_ne!(
+                encryption_key,
+                generate_encryption_key(
+                    cipher_suite,
+                    entropy_seed,
+                    &MockECDSAIdentityProvider::generate()
+                )
+            );
+            assert_ne!(
+                encryption_key,
+                generate_encryption_key(cipher_suite, b"Another phrase.", &identity_provider)
+            );
+        }
     }
 }
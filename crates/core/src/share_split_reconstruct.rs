@@ -2,11 +2,21 @@
 //!
 //! Ref: <https://wamu.tech/specification#share-splitting-and-reconstruction>.
 
-use crypto_bigint::{Encoding, U256};
+use crypto_bigint::modular::constant_mod::ResidueParams;
+use crypto_bigint::{const_residue, Encoding, U256};
+use sha2::{Digest, Sha256};
 
+use crate::crypto::{Random32Bytes, Secp256k1Order, VerifyingKey};
 use crate::errors::Error;
 use crate::share::{SecretShare, SigningShare, SubShare, SubShareInterpolator};
 use crate::traits::IdentityProvider;
+use crate::utils;
+
+/// Domain separation tag for deriving a "secret share" from a seed.
+const DETERMINISTIC_SECRET_SHARE_TAG: &[u8] = b"wamu:deterministic-secret-share";
+
+/// Domain separation tag for deriving a "signing share" from a seed.
+const DETERMINISTIC_SIGNING_SHARE_TAG: &[u8] = b"wamu:deterministic-signing-share";
 
 /// Given a "secret share" and an identity provider, returns "signing share" and "sub-share"
 /// that can be used to reconstruct the "secret share" given the same identity provider.
@@ -19,26 +29,59 @@ pub fn split(
     // Generates "signing share".
     let signing_share = SigningShare::generate();
 
-    // Computes "sub-share" a from "signing share".
-    let (r, s) = identity_provider.sign_message_share(&signing_share.to_be_bytes());
-    let sub_share_a = SubShare::new(U256::from_be_bytes(r), U256::from_be_bytes(s))?;
+    // Computes "sub-share" b.
+    let sub_share_b = split_with_signing_share(secret_share, &signing_share, identity_provider)?;
 
-    // Initializes the "sub-share" interpolator.
-    let sub_share_interpolator = SubShareInterpolator::new(
-        // The "secret share" is the constant term, so x = 0.
-        &SubShare::new(U256::ZERO, secret_share.as_u256())?,
-        &sub_share_a,
-    );
+    // Returns "signing share" and "sub-share" b.
+    Ok((signing_share, sub_share_b))
+}
+
+/// Given a seed and an identity provider, returns "signing share" and "sub-share" for a "secret share"
+/// derived from the identity provider's signature over the seed (see [`derive_secret_share`]),
+/// such that the same identity provider and seed always reproduce the same "signing share" and "sub-share"
+/// (e.g for disaster recovery).
+///
+/// **NOTE:** The "signing share" is also derived from the identity provider's signature over the seed
+/// (but with a different domain separation tag), so the identity provider must compute deterministic signatures
+/// (e.g ECDSA with RFC 6979 nonces).
+///
+/// Ref: <https://wamu.tech/specification#share-splitting>.
+pub fn split_deterministic(
+    seed: &[u8],
+    identity_provider: &impl IdentityProvider,
+) -> Result<(SigningShare, SubShare), Error> {
+    // Derives "secret share" and "signing share".
+    let secret_share = derive_secret_share(seed, identity_provider);
+    let signing_share = SigningShare::from(Random32Bytes::from(U256::from_be_bytes(derive_bytes(
+        DETERMINISTIC_SIGNING_SHARE_TAG,
+        seed,
+        identity_provider,
+    ))));
 
     // Computes "sub-share" b.
-    let sub_share_b = sub_share_interpolator.sub_share(U256::ONE)?;
+    let sub_share_b = split_with_signing_share(&secret_share, &signing_share, identity_provider)?;
 
     // Returns "signing share" and "sub-share" b.
     Ok((signing_share, sub_share_b))
 }
 
+/// Given a seed and an identity provider, returns a "secret share" derived from the identity provider's signature
+/// over the domain separated seed (reduced modulo the order of the `Secp256k1` curve).
+///
+/// **NOTE:** The identity provider must compute deterministic signatures (e.g ECDSA with RFC 6979 nonces).
+pub fn derive_secret_share(seed: &[u8], identity_provider: &impl IdentityProvider) -> SecretShare {
+    let secret = U256::from_be_bytes(derive_bytes(
+        DETERMINISTIC_SECRET_SHARE_TAG,
+        seed,
+        identity_provider,
+    ));
+    SecretShare::from(const_residue!(secret, Secp256k1Order).retrieve())
+}
+
 /// Returns "secret share" associated with "signing share", "sub-share" and identity provider.
 ///
+/// **NOTE:** Reconstruction arithmetic is constant-time (see [`SubShareInterpolator`]).
+///
 /// Ref: <https://wamu.tech/specification#share-reconstruction>.
 pub fn reconstruct(
     signing_share: &SigningShare,
@@ -46,16 +89,129 @@ pub fn reconstruct(
     identity_provider: &impl IdentityProvider,
 ) -> Result<SecretShare, Error> {
     // Computes "sub-share" a from "signing share".
-    let (r, s) = identity_provider.sign_message_share(&signing_share.to_be_bytes());
-    let sub_share_a = SubShare::new(U256::from_be_bytes(r), U256::from_be_bytes(s))?;
+    let sub_share_a = sub_share_a(signing_share, identity_provider)?;
+
+    // Initializes the "sub-share" interpolator.
+    let sub_share_interpolator = SubShareInterpolator::new(&sub_share_a, sub_share_b)?;
+
+    // Returns "secret share".
+    Ok(sub_share_interpolator.secret().into())
+}
+
+/// Returns "secret share" associated with "signing share", "sub-share" and identity provider
+/// after verifying that the identity provider's verifying key matches the expected verifying key,
+/// or an appropriate error otherwise.
+///
+/// **NOTE:** Unlike [`reconstruct`], this catches reconstruction with the wrong identity provider
+/// (which would otherwise silently return an invalid "secret share").
+///
+/// Ref: <https://wamu.tech/specification#share-reconstruction>.
+pub fn reconstruct_checked(
+    signing_share: &SigningShare,
+    sub_share_b: &SubShare,
+    identity_provider: &impl IdentityProvider,
+    expected_verifying_key: &VerifyingKey,
+) -> Result<SecretShare, Error> {
+    if &identity_provider.verifying_key() != expected_verifying_key {
+        return Err(Error::IdentityMismatch);
+    }
+    reconstruct(signing_share, sub_share_b, identity_provider)
+}
 
+/// Returns "secret share" associated with a precomputed "sub-share" a and "sub-share" b.
+///
+/// **NOTE:** Useful for reconstructing the "secret share" repeatedly (e.g for batched identity rotations)
+/// without requesting a signature from the identity provider each time.
+/// See [`sub_share_a`] for computing "sub-share" a.
+///
+/// **NOTE:** Returns an error if "sub-share" a and "sub-share" b have the same x-coordinate
+/// (e.g a corrupted or maliciously crafted "sub-share" b).
+///
+/// Ref: <https://wamu.tech/specification#share-reconstruction>.
+pub fn reconstruct_with_sub_share_a(
+    sub_share_a: &SubShare,
+    sub_share_b: &SubShare,
+) -> Result<SecretShare, Error> {
     // Initializes the "sub-share" interpolator.
-    let sub_share_interpolator = SubShareInterpolator::new(&sub_share_a, sub_share_b);
+    let sub_share_interpolator = SubShareInterpolator::new(sub_share_a, sub_share_b)?;
 
     // Returns "secret share".
     Ok(sub_share_interpolator.secret().into())
 }
 
+/// Given a "secret share", a caller-provided "signing share" and an identity provider,
+/// returns the matching "sub-share" that can be used to reconstruct the "secret share"
+/// given the same "signing share" and identity provider (e.g for restoring an exact prior state or migrating an existing secret).
+///
+/// **NOTE:** Unlike [`split`], no randomness is used, so the same inputs always produce the same "sub-share"
+/// (given an identity provider that computes deterministic signatures e.g ECDSA with RFC 6979 nonces).
+/// The "signing share" must be kept secret and should be uniformly random (e.g a "signing share" from a prior [`split`]).
+///
+/// Ref: <https://wamu.tech/specification#share-splitting>.
+pub fn split_with_signing_share(
+    secret_share: &SecretShare,
+    signing_share: &SigningShare,
+    identity_provider: &impl IdentityProvider,
+) -> Result<SubShare, Error> {
+    // Computes "sub-share" a from "signing share".
+    let sub_share_a = sub_share_a(signing_share, identity_provider)?;
+
+    // Initializes the "sub-share" interpolator.
+    let sub_share_interpolator = SubShareInterpolator::new(
+        // The "secret share" is the constant term, so x = 0.
+        &SubShare::new(U256::ZERO, secret_share.as_u256())?,
+        &sub_share_a,
+    )?;
+
+    // Returns "sub-share" b.
+    Ok(sub_share_interpolator.sub_share(U256::ONE)?)
+}
+
+/// Given the current "signing share", "sub-share" and identity provider,
+/// returns a fresh "signing share" and the matching new "sub-share" that reconstruct the same "secret share"
+/// given the same identity provider (e.g after the local storage holding the "signing share" is compromised).
+///
+/// **NOTE:** The "signing share" and "sub-share" lie on the same line through the "secret share",
+/// so the "sub-share" must be replaced as well (i.e the current "sub-share" and any backups of it are invalidated).
+///
+/// Ref: <https://wamu.tech/specification#share-splitting-and-reconstruction>.
+pub fn rotate_signing_share(
+    signing_share: &SigningShare,
+    sub_share_b: &SubShare,
+    identity_provider: &impl IdentityProvider,
+) -> Result<(SigningShare, SubShare), Error> {
+    // Reconstructs "secret share" from the current "signing share" and "sub-share".
+    let secret_share = reconstruct(signing_share, sub_share_b, identity_provider)?;
+
+    // Splits the "secret share" with a fresh "signing share".
+    split(&secret_share, identity_provider)
+}
+
+/// Returns a SHA-256 digest of the identity provider's signature over the domain separated seed.
+fn derive_bytes(tag: &[u8], seed: &[u8], identity_provider: &impl IdentityProvider) -> [u8; 32] {
+    let (r, s) =
+        identity_provider.sign_message_share(&utils::domain_separated_message_bytes(tag, seed));
+    Sha256::new()
+        .chain_update(r)
+        .chain_update(s)
+        .finalize()
+        .into()
+}
+
+/// Returns "sub-share" a associated with "signing share" and identity provider.
+///
+/// Ref: <https://wamu.tech/specification#share-reconstruction>.
+pub fn sub_share_a(
+    signing_share: &SigningShare,
+    identity_provider: &impl IdentityProvider,
+) -> Result<SubShare, Error> {
+    let (r, s) = identity_provider.sign_message_share(&signing_share.to_be_bytes());
+    Ok(SubShare::new(
+        U256::from_be_bytes(r),
+        U256::from_be_bytes(s),
+    )?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,4 +239,230 @@ mod tests {
             &secret_share.to_be_bytes()
         );
     }
+
+    #[test]
+    fn split_with_signing_share_works() {
+        // Generates secret share.
+        let secret_share = SecretShare::from(Random32Bytes::generate_mod_q());
+
+        // Generates identity provider.
+        let identity_provider = MockECDSAIdentityProvider::generate();
+
+        // Computes "signing share" and "sub-share".
+        let (signing_share, sub_share_b) = split(&secret_share, &identity_provider).unwrap();
+
+        // Restores the "sub-share" with the known "signing share".
+        let restored_sub_share_b =
+            split_with_signing_share(&secret_share, &signing_share, &identity_provider).unwrap();
+
+        // Verifies that the exact prior state is restored.
+        assert_eq!(restored_sub_share_b.as_tuple(), sub_share_b.as_tuple());
+
+        // Migrates the "secret share" with a chosen "signing share".
+        let chosen_signing_share = SigningShare::from(Random32Bytes::from([7u8; 32]));
+        let migrated_sub_share_b =
+            split_with_signing_share(&secret_share, &chosen_signing_share, &identity_provider)
+                .unwrap();
+
+        // Verifies that the "secret share" is reconstructed with the chosen "signing share".
+        let reconstructed_secret_share = reconstruct(
+            &chosen_signing_share,
+            &migrated_sub_share_b,
+            &identity_provider,
+        )
+        .unwrap();
+        assert_eq!(
+            reconstructed_secret_share.to_be_bytes(),
+            secret_share.to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn rotate_signing_share_works() {
+        // Generates secret share.
+        let secret_share = SecretShare::from(Random32Bytes::generate_mod_q());
+
+        // Generates identity provider.
+        let identity_provider = MockECDSAIdentityProvider::generate();
+
+        // Computes "signing share" and "sub-share".
+        let (signing_share, sub_share_b) = split(&secret_share, &identity_provider).unwrap();
+
+        // Rotates the "signing share".
+        let (new_signing_share, new_sub_share_b) =
+            rotate_signing_share(&signing_share, &sub_share_b, &identity_provider).unwrap();
+
+        // Verifies that the "signing share" and "sub-share" changed.
+        assert_ne!(new_signing_share.to_be_bytes(), signing_share.to_be_bytes());
+        assert_ne!(new_sub_share_b.as_tuple(), sub_share_b.as_tuple());
+
+        // Verifies that the new "signing share" and "sub-share" reconstruct the original "secret share".
+        let reconstructed_secret_share =
+            reconstruct(&new_signing_share, &new_sub_share_b, &identity_provider).unwrap();
+        assert_eq!(
+            reconstructed_secret_share.to_be_bytes(),
+            secret_share.to_be_bytes()
+        );
+
+        // Verifies that the old "signing share" no longer reconstructs the "secret share" with the new "sub-share".
+        let mixed_secret_share =
+            reconstruct(&signing_share, &new_sub_share_b, &identity_provider).unwrap();
+        assert_ne!(mixed_secret_share.to_be_bytes(), secret_share.to_be_bytes());
+    }
+
+    #[test]
+    fn deterministic_splitting_works() {
+        // Generates identity providers.
+        let identity_provider = MockECDSAIdentityProvider::generate();
+        let other_identity_provider = MockECDSAIdentityProvider::generate();
+
+        // Computes "signing share" and "sub-share" twice for the same identity provider and seed.
+        let seed = b"recovery seed";
+        let (signing_share, sub_share_b) = split_deterministic(seed, &identity_provider).unwrap();
+        let (signing_share_2, sub_share_b_2) =
+            split_deterministic(seed, &identity_provider).unwrap();
+
+        // Verifies that the same identity provider and seed reproduce the same shares.
+        assert_eq!(signing_share.to_be_bytes(), signing_share_2.to_be_bytes());
+        assert_eq!(sub_share_b.as_tuple(), sub_share_b_2.as_tuple());
+
+        // Verifies that the shares reconstruct the derived "secret share".
+        let reconstructed_secret_share =
+            reconstruct(&signing_share, &sub_share_b, &identity_provider).unwrap();
+        assert_eq!(
+            reconstructed_secret_share.to_be_bytes(),
+            derive_secret_share(seed, &identity_provider).to_be_bytes()
+        );
+
+        // Verifies that a different seed or identity provider derives different shares.
+        for (seed, identity_provider) in [
+            (&b"other seed"[..], &identity_provider),
+            (&seed[..], &other_identity_provider),
+        ] {
+            let (other_signing_share, other_sub_share_b) =
+                split_deterministic(seed, identity_provider).unwrap();
+            assert_ne!(
+                signing_share.to_be_bytes(),
+                other_signing_share.to_be_bytes()
+            );
+            assert_ne!(sub_share_b.as_tuple(), other_sub_share_b.as_tuple());
+        }
+    }
+
+    #[test]
+    fn checked_reconstruction_works() {
+        // Generates secret share.
+        let secret_share = SecretShare::from(Random32Bytes::generate_mod_q());
+
+        // Generates identity providers.
+        let identity_provider = MockECDSAIdentityProvider::generate();
+        let other_identity_provider = MockECDSAIdentityProvider::generate();
+
+        // Computes "signing share" and "sub-share".
+        let (signing_share, sub_share_b) = split(&secret_share, &identity_provider).unwrap();
+
+        for (provider, expected_verifying_key, expected_result) in [
+            // The expected identity provider should reconstruct the "secret share".
+            (
+                &identity_provider,
+                identity_provider.verifying_key(),
+                Ok(secret_share.to_be_bytes()),
+            ),
+            // A mismatched identity provider should fail.
+            (
+                &other_identity_provider,
+                identity_provider.verifying_key(),
+                Err(Error::IdentityMismatch),
+            ),
+            // The wrong expected verifying key should fail.
+            (
+                &identity_provider,
+                other_identity_provider.verifying_key(),
+                Err(Error::IdentityMismatch),
+            ),
+        ] {
+            // Verifies expected result.
+            let result = reconstruct_checked(
+                &signing_share,
+                &sub_share_b,
+                provider,
+                &expected_verifying_key,
+            );
+            assert_eq!(
+                result.map(|secret_share| secret_share.to_be_bytes()),
+                expected_result
+            );
+        }
+    }
+
+    #[test]
+    fn reconstruction_with_precomputed_sub_share_a_works() {
+        // Generates secret share.
+        let secret_share = SecretShare::from(Random32Bytes::generate_mod_q());
+
+        // Generates identity provider.
+        let identity_provider = MockECDSAIdentityProvider::generate();
+
+        // Computes "signing share" and "sub-share".
+        let (signing_share, sub_share_b) = split(&secret_share, &identity_provider).unwrap();
+
+        // Precomputes "sub-share" a.
+        let sub_share_a = sub_share_a(&signing_share, &identity_provider).unwrap();
+
+        // Reconstructs "secret share" using both the standard and precomputed paths.
+        let reconstructed_secret_share =
+            reconstruct(&signing_share, &sub_share_b, &identity_provider).unwrap();
+        let reconstructed_secret_share_precomputed =
+            reconstruct_with_sub_share_a(&sub_share_a, &sub_share_b).unwrap();
+
+        // Verifies that both paths yield the same "secret share".
+        assert_eq!(
+            &reconstructed_secret_share_precomputed.to_be_bytes(),
+            &reconstructed_secret_share.to_be_bytes()
+        );
+        assert_eq!(
+            &reconstructed_secret_share_precomputed.to_be_bytes(),
+            &secret_share.to_be_bytes()
+        );
+
+        // Verifies that a "sub-share" b with the same x-coordinate as "sub-share" a is rejected (i.e instead of panicking).
+        assert!(matches!(
+            reconstruct_with_sub_share_a(&sub_share_a, &sub_share_a),
+            Err(Error::Arithmetic(
+                crate::errors::ArithmeticError::DuplicateXCoordinate
+            ))
+        ));
+    }
+
+    proptest::proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(32))]
+
+        #[test]
+        fn share_splitting_and_reconstruction_round_trips(
+            secret in proptest::prelude::any::<[u8; 32]>(),
+        ) {
+            // Generates identity provider.
+            let identity_provider = MockECDSAIdentityProvider::generate();
+
+            // Verifies that secrets less than the modulus round trip, while larger secrets are rejected.
+            let secret_share = SecretShare::from(U256::from_be_bytes(secret));
+            match split(&secret_share, &identity_provider) {
+                Ok((signing_share, sub_share_b)) => {
+                    let reconstructed_secret_share =
+                        reconstruct(&signing_share, &sub_share_b, &identity_provider).unwrap();
+                    proptest::prop_assert_eq!(
+                        reconstructed_secret_share.to_be_bytes(),
+                        secret_share.to_be_bytes()
+                    );
+                }
+                Err(error) => {
+                    proptest::prop_assert!(secret_share.as_u256() >= Secp256k1Order::MODULUS);
+                    proptest::prop_assert!(matches!(
+                        error,
+                        Error::Arithmetic(crate::errors::ArithmeticError::ModulusOverflow)
+                    ));
+                }
+            }
+        }
+    }
 }
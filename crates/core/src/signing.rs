@@ -0,0 +1,120 @@
+//! Utilities for verifying threshold signatures (e.g by an auditing service that doesn't run the signing protocol).
+
+use crate::crypto::{
+    EllipticCurve, MessageDigest, Signature, SignatureAlgorithm, SignatureEncoding, VerifyingKey,
+};
+use crate::errors::{CryptoError, Error};
+
+/// Given a message, DER encoded threshold signature bytes, the group verifying key and the hash function used for signing,
+/// returns an `Ok` result for a valid threshold signature for the message, or an appropriate `Err` result otherwise.
+///
+/// **NOTE:** ECDSA/secp256k1 signatures are normalized to "low S" form before verification
+/// (i.e threshold ECDSA protocols don't necessarily output "low S" signatures).
+pub fn verify_threshold_signature(
+    message: &[u8],
+    signature_bytes: &[u8],
+    group_key: &VerifyingKey,
+    hash_function: MessageDigest,
+) -> Result<(), Error> {
+    let sig = match (group_key.algo, group_key.curve) {
+        // Normalizes ECDSA/secp256k1 signatures.
+        (SignatureAlgorithm::ECDSA, EllipticCurve::Secp256k1) => {
+            let signature = k256::ecdsa::Signature::from_der(signature_bytes)
+                .map_err(|_| CryptoError::SignatureDecodeError)?;
+            signature
+                .normalize_s()
+                .unwrap_or(signature)
+                .to_der()
+                .as_bytes()
+                .to_vec()
+        }
+        _ => signature_bytes.to_vec(),
+    };
+
+    Ok(crate::crypto::verify_signature(
+        group_key,
+        message,
+        &Signature {
+            sig,
+            algo: group_key.algo,
+            curve: group_key.curve,
+            hash: hash_function,
+            enc: SignatureEncoding::DER,
+        },
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::KeyEncoding;
+    use k256::ecdsa::signature::Signer;
+
+    #[test]
+    fn verify_threshold_signature_works() {
+        // Generates signing key and group verifying key.
+        let signing_key = k256::ecdsa::SigningKey::random(&mut rand::thread_rng());
+        let group_key = VerifyingKey {
+            key: signing_key
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec(),
+            algo: SignatureAlgorithm::ECDSA,
+            curve: EllipticCurve::Secp256k1,
+            enc: KeyEncoding::SEC1,
+        };
+
+        // Generates "low S" and "high S" signatures for the message.
+        let message = b"Hello, world!";
+        let signature: k256::ecdsa::Signature = signing_key.sign(message);
+        let (r, s) = signature.split_scalars();
+        let high_s_signature = k256::ecdsa::Signature::from_scalars(r, -*s).unwrap();
+        let signature_bytes = signature.to_der().as_bytes().to_vec();
+        let high_s_signature_bytes = high_s_signature.to_der().as_bytes().to_vec();
+
+        for (message, signature_bytes, hash_function, expected_result) in [
+            // Valid "low S" signature.
+            (
+                &message[..],
+                &signature_bytes[..],
+                MessageDigest::SHA256,
+                Ok(()),
+            ),
+            // Valid "high S" signature.
+            (
+                &message[..],
+                &high_s_signature_bytes[..],
+                MessageDigest::SHA256,
+                Ok(()),
+            ),
+            // Wrong message.
+            (
+                &b"Goodbye, world!"[..],
+                &signature_bytes[..],
+                MessageDigest::SHA256,
+                Err(Error::Crypto(CryptoError::SignatureVerificationFailed)),
+            ),
+            // Wrong hash function.
+            (
+                &message[..],
+                &signature_bytes[..],
+                MessageDigest::Keccak256,
+                Err(Error::Crypto(CryptoError::SignatureVerificationFailed)),
+            ),
+            // Malformed signature.
+            (
+                &message[..],
+                &signature_bytes[1..],
+                MessageDigest::SHA256,
+                Err(Error::Crypto(CryptoError::SignatureDecodeError)),
+            ),
+        ] {
+            // Verifies expected result.
+            assert_eq!(
+                verify_threshold_signature(message, signature_bytes, &group_key, hash_function),
+                expected_result
+            );
+        }
+    }
+}
@@ -1,6 +1,9 @@
 //! Test utilities.
 
 use k256::ecdsa::{signature::Signer, SigningKey};
+use k256::elliptic_curve::bigint::U256;
+use k256::elliptic_curve::ops::ReduceNonZero;
+use k256::NonZeroScalar;
 
 use crate::crypto::{
     EllipticCurve, KeyEncoding, MessageDigest, Signature, SignatureAlgorithm, SignatureEncoding,
@@ -23,6 +26,18 @@ impl MockECDSAIdentityProvider {
             secret: SigningKey::random(&mut rng),
         }
     }
+
+    /// Deterministically derives an ECDSA/Secp256k1/SHA-256 signing key from a seed.
+    ///
+    /// **NOTE:** Identity providers derived from the same seed produce identical verifying keys and signatures.
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        // Reduces the seed to a non-zero scalar modulo the order of the `Secp256k1` curve (i.e a valid signing key).
+        let scalar = <k256::Scalar as ReduceNonZero<U256>>::reduce_nonzero_bytes(&seed.into());
+        Self {
+            // The reduced scalar is never zero.
+            secret: SigningKey::from(NonZeroScalar::new(scalar).unwrap()),
+        }
+    }
 }
 
 impl IdentityProvider for MockECDSAIdentityProvider {
@@ -39,7 +54,7 @@ impl IdentityProvider for MockECDSAIdentityProvider {
         }
     }
 
-    /// Computes and serializes (in DER format) the ECDSA/Secp256k1/SHA-256 signature of a message .
+    /// Computes and serializes (in DER format) the ECDSA/Secp256k1/SHA-256 signature of a message.
     fn sign(&self, msg: &[u8]) -> Signature {
         // `k256::ecdsa::SigningKey` uses `Secp256k1` and `SHA-256`.
         let signature: k256::ecdsa::Signature = self.secret.sign(msg);
@@ -69,6 +84,16 @@ impl MockECDSAIdentityProvider {
     }
 }
 
+/// Given a hex string (without a `0x` prefix), returns its bytes (e.g for decoding test vectors).
+///
+/// **NOTE:** Panics if the hex string is invalid (i.e an odd length or a non-hex character).
+pub fn hex_bytes(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(&hex[idx..idx + 2], 16).unwrap())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,4 +115,50 @@ mod tests {
             crypto::verify_signature(&identity_provider.verifying_key(), msg, &signature).is_ok()
         );
     }
+
+    #[test]
+    fn identity_provider_from_seed_is_reproducible() {
+        // Message to sign.
+        let msg = b"Hello, world!";
+
+        // Derives identity providers from seeds.
+        let identity_provider = MockECDSAIdentityProvider::from_seed([1; 32]);
+        let identity_provider_same_seed = MockECDSAIdentityProvider::from_seed([1; 32]);
+        let identity_provider_other_seed = MockECDSAIdentityProvider::from_seed([2; 32]);
+
+        // Verifies that identity providers from the same seed are identical.
+        assert_eq!(
+            identity_provider.verifying_key(),
+            identity_provider_same_seed.verifying_key()
+        );
+        assert_eq!(
+            identity_provider.sign(msg),
+            identity_provider_same_seed.sign(msg)
+        );
+        assert_eq!(
+            identity_provider.export(),
+            identity_provider_same_seed.export()
+        );
+
+        // Verifies that identity providers from different seeds are different.
+        assert_ne!(
+            identity_provider.verifying_key(),
+            identity_provider_other_seed.verifying_key()
+        );
+
+        // Verifies that seeds that aren't valid signing keys (i.e zero) are still usable.
+        let identity_provider_zero_seed = MockECDSAIdentityProvider::from_seed([0; 32]);
+        assert!(crypto::verify_signature(
+            &identity_provider_zero_seed.verifying_key(),
+            msg,
+            &identity_provider_zero_seed.sign(msg)
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn hex_bytes_works() {
+        assert_eq!(hex_bytes(""), Vec::<u8>::new());
+        assert_eq!(hex_bytes("00ff10Ab"), vec![0x00, 0xff, 0x10, 0xab]);
+    }
 }
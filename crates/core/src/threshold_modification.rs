@@ -0,0 +1,318 @@
+//! Threshold modification request implementation.
+//!
+//! Ref: <https://wamu.tech/specification#threshold-modification>.
+
+use crate::command::Command;
+use crate::crypto::VerifyingKey;
+use crate::errors::{Error, IdentityAuthedRequestError, QuorumApprovedRequestError};
+use crate::payloads::{
+    CommandApprovalPayload, QuorumApprovedChallengeResponsePayload,
+    ThresholdModificationRequestPayload,
+};
+use crate::quorum_approved_request::ApprovalPolicy;
+use crate::traits::{IdentityProvider, VerifiedParties};
+use crate::{identity_authed_request, identity_challenge, quorum_approved_request, utils};
+
+const THRESHOLD_MODIFICATION: &str = Command::ThresholdModification.as_str();
+
+/// Given a proposed new threshold and an identity provider,
+/// returns the payload for initiating a threshold modification request
+/// (i.e the request signature is bound to the proposed threshold, see [`threshold_domain`]).
+pub fn initiate(
+    new_threshold: u16,
+    identity_provider: &impl IdentityProvider,
+) -> ThresholdModificationRequestPayload {
+    ThresholdModificationRequestPayload {
+        request: identity_authed_request::initiate_with_domain(
+            Command::ThresholdModification,
+            &threshold_domain(new_threshold),
+            identity_provider,
+        ),
+        new_threshold,
+    }
+}
+
+/// Given a proposed new threshold, the number of parties and
+/// whether or not the key refresh protocol requires an honest majority (e.g FS-DKR),
+/// returns an `Ok` result if `1 <= new_threshold < n_parties` (and `new_threshold <= n_parties / 2` for an honest majority),
+/// or an `InvalidThreshold` error otherwise.
+pub fn validate_proposed_threshold(
+    new_threshold: u16,
+    n_parties: u16,
+    requires_honest_majority: bool,
+) -> Result<(), Error> {
+    utils::validate_threshold(new_threshold, n_parties)?;
+    if requires_honest_majority && new_threshold > n_parties / 2 {
+        Err(Error::InvalidThreshold)
+    } else {
+        Ok(())
+    }
+}
+
+/// Given a threshold modification request payload, an identity provider, a list of verifying keys for the other parties,
+/// the number of parties and whether or not the key refresh protocol requires an honest majority (e.g FS-DKR),
+/// returns an ok result with a "command" approval payload for initiating an identity challenge and approval acknowledgement
+/// for a valid request or an appropriate error result for an invalid request
+/// (e.g `InvalidProposedThreshold` for an out-of-range proposed threshold).
+///
+/// **NOTE:** The proposed threshold is validated before the request signature, so no approval is ever gathered for it,
+/// and the approval signature is bound to the proposed threshold (see [`threshold_domain`]).
+pub fn verify_request_and_initiate_challenge(
+    payload: &ThresholdModificationRequestPayload,
+    identity_provider: &impl IdentityProvider,
+    verified_parties: &(impl VerifiedParties + ?Sized),
+    n_parties: u16,
+    requires_honest_majority: bool,
+) -> Result<CommandApprovalPayload, IdentityAuthedRequestError> {
+    verify_request_and_initiate_challenge_with_timelock(
+        payload,
+        identity_provider,
+        verified_parties,
+        n_parties,
+        requires_honest_majority,
+        0,
+    )
+}
+
+/// Same as [`verify_request_and_initiate_challenge`] but returns a time-locked "command" approval payload
+/// that only becomes valid after the given unix timestamp (in seconds)
+/// (see [`quorum_approved_request::verify_request_and_initiate_challenge_with_timelock`]).
+pub fn verify_request_and_initiate_challenge_with_timelock(
+    payload: &ThresholdModificationRequestPayload,
+    identity_provider: &impl IdentityProvider,
+    verified_parties: &(impl VerifiedParties + ?Sized),
+    n_parties: u16,
+    requires_honest_majority: bool,
+    valid_after: u64,
+) -> Result<CommandApprovalPayload, IdentityAuthedRequestError> {
+    // Proposed threshold must be valid for the number of parties.
+    validate_proposed_threshold(payload.new_threshold, n_parties, requires_honest_majority)
+        .map_err(|_| IdentityAuthedRequestError::InvalidProposedThreshold)?;
+
+    if payload.request.command != THRESHOLD_MODIFICATION {
+        // Command doesn't match request payload.
+        return Err(IdentityAuthedRequestError::CommandMismatch);
+    }
+    let domain = threshold_domain(payload.new_threshold);
+    identity_authed_request::verify_with_domain(&payload.request, &domain, verified_parties)?;
+    Ok(quorum_approved_request::approve(
+        identity_challenge::initiate(),
+        &payload.request,
+        identity_provider,
+        valid_after,
+        Some(&domain),
+    ))
+}
+
+/// Given a list of command approval payloads, an identity provider, a threshold modification request payload,
+/// a quorum size and a list of verifying keys for the other parties,
+/// returns an ok result with a quorum approved challenge response payload
+/// or an appropriate error result (e.g approvals for a different proposed threshold).
+pub fn challenge_response(
+    approvals: &[CommandApprovalPayload],
+    identity_provider: &impl IdentityProvider,
+    payload: &ThresholdModificationRequestPayload,
+    quorum_size: usize,
+    verified_parties: &(impl VerifiedParties + ?Sized),
+) -> Result<QuorumApprovedChallengeResponsePayload, QuorumApprovedRequestError> {
+    quorum_approved_request::challenge_response(
+        approvals,
+        identity_provider,
+        &payload.request,
+        verified_parties,
+        &ApprovalPolicy::new(quorum_size).with_domain(&threshold_domain(payload.new_threshold)),
+    )
+}
+
+/// Given a quorum approved challenge response payload, a list of command approval payloads,
+/// a verifying key for challenged party, a threshold modification request payload,
+/// a quorum size and a list of verifying keys for the other parties,
+/// returns an `Ok` result for valid quorum approved challenge response, or an appropriate `Err` result otherwise.
+pub fn verify_challenge_response(
+    response: &QuorumApprovedChallengeResponsePayload,
+    approvals: &[CommandApprovalPayload],
+    verifying_key: &VerifyingKey,
+    payload: &ThresholdModificationRequestPayload,
+    quorum_size: usize,
+    verified_parties: &(impl VerifiedParties + ?Sized),
+) -> Result<(), QuorumApprovedRequestError> {
+    quorum_approved_request::verify_challenge_response(
+        response,
+        approvals,
+        verifying_key,
+        &payload.request,
+        verified_parties,
+        &ApprovalPolicy::new(quorum_size).with_domain(&threshold_domain(payload.new_threshold)),
+    )
+}
+
+/// Given a proposed new threshold, returns the domain that binds both the request and approval signatures to the threshold
+/// (see [`quorum_approved_request::verify_request_and_initiate_challenge_with_domain`]).
+pub fn threshold_domain(new_threshold: u16) -> String {
+    format!("wamu:threshold-modification:{new_threshold}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::CryptoError;
+    use crate::test_utils::MockECDSAIdentityProvider;
+
+    #[test]
+    fn threshold_modification_request_verification_works() {
+        // Generates identity providers.
+        let identity_providers: Vec<_> = (0..5)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verified_parties = utils::collect_verifying_keys(&identity_providers);
+
+        for (new_threshold, requires_honest_majority, tamper_threshold, expected_error) in [
+            // Valid proposed thresholds.
+            (1, false, None, None),
+            (2, true, None, None),
+            (4, false, None, None),
+            // Out-of-range proposed thresholds.
+            (
+                0,
+                false,
+                None,
+                Some(IdentityAuthedRequestError::InvalidProposedThreshold),
+            ),
+            (
+                5,
+                false,
+                None,
+                Some(IdentityAuthedRequestError::InvalidProposedThreshold),
+            ),
+            (
+                6,
+                false,
+                None,
+                Some(IdentityAuthedRequestError::InvalidProposedThreshold),
+            ),
+            // Proposed threshold that violates the honest majority requirement.
+            (
+                3,
+                true,
+                None,
+                Some(IdentityAuthedRequestError::InvalidProposedThreshold),
+            ),
+            // Proposed threshold that doesn't match the request signature.
+            (
+                2,
+                false,
+                Some(3),
+                Some(IdentityAuthedRequestError::Unauthorized(Error::Crypto(
+                    CryptoError::SignatureVerificationFailed,
+                ))),
+            ),
+        ] {
+            // Generates threshold modification request payload.
+            let mut payload = initiate(new_threshold, &identity_providers[0]);
+            if let Some(tampered_threshold) = tamper_threshold {
+                payload.new_threshold = tampered_threshold;
+            }
+
+            // Verifies threshold modification request payload.
+            let result = verify_request_and_initiate_challenge(
+                &payload,
+                &identity_providers[1],
+                &verified_parties,
+                5,
+                requires_honest_majority,
+            );
+
+            // Verifies expected result.
+            match expected_error {
+                None => assert!(result.is_ok()),
+                Some(error) => assert_eq!(result.err(), Some(error)),
+            }
+        }
+    }
+
+    #[test]
+    fn threshold_modification_approvals_are_bound_to_proposed_threshold() {
+        // Generates identity providers.
+        let identity_providers: Vec<_> = (0..5)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+        let verified_parties = utils::collect_verifying_keys(&identity_providers);
+        let quorum_size = 3;
+
+        // Generates threshold modification request payload and approvals.
+        let payload = initiate(2, &identity_providers[0]);
+        let approvals: Vec<CommandApprovalPayload> = identity_providers[1..quorum_size]
+            .iter()
+            .map(|identity_provider| {
+                verify_request_and_initiate_challenge(
+                    &payload,
+                    identity_provider,
+                    &verified_parties,
+                    5,
+                    true,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        // Verifies that the approvals are valid for the proposed threshold.
+        let response = challenge_response(
+            &approvals,
+            &identity_providers[0],
+            &payload,
+            quorum_size,
+            &verified_parties,
+        )
+        .unwrap();
+        assert_eq!(
+            verify_challenge_response(
+                &response,
+                &approvals,
+                &identity_providers[0].verifying_key(),
+                &payload,
+                quorum_size,
+                &verified_parties,
+            ),
+            Ok(())
+        );
+
+        // Verifies that the approvals aren't valid for a different proposed threshold
+        // or as plain quorum approved request approvals.
+        let tampered_payload = ThresholdModificationRequestPayload {
+            new_threshold: 1,
+            ..payload.clone()
+        };
+        for approval in &approvals {
+            for result in [
+                quorum_approved_request::verify_approval(
+                    approval,
+                    &payload.request,
+                    &verified_parties,
+                ),
+                quorum_approved_request::verify_approval_with_domain(
+                    approval,
+                    &tampered_payload.request,
+                    &threshold_domain(tampered_payload.new_threshold),
+                    &verified_parties,
+                ),
+            ] {
+                assert_eq!(
+                    result,
+                    Err(QuorumApprovedRequestError::Unauthorized(Error::Crypto(
+                        CryptoError::SignatureVerificationFailed,
+                    )))
+                );
+            }
+        }
+        assert!(matches!(
+            challenge_response(
+                &approvals,
+                &identity_providers[0],
+                &tampered_payload,
+                quorum_size,
+                &verified_parties,
+            ),
+            Err(QuorumApprovedRequestError::InsufficientApprovals(_))
+        ));
+    }
+}
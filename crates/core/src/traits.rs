@@ -8,7 +8,10 @@ use crate::crypto::{Signature, VerifyingKey};
 /// the only requirement for decentralized identity providers is
 /// the ability to compute cryptographic signatures for any arbitrary message in such a way that
 /// the output signature can be verified in a non-interactive manner.
-pub trait IdentityProvider: Clone + std::fmt::Debug {
+///
+/// **NOTE:** The trait is object safe, so heterogeneous identity providers (e.g a local key and an HSM)
+/// can be stored as `Box<dyn IdentityProvider>` and passed wherever `&impl IdentityProvider` is expected.
+pub trait IdentityProvider: std::fmt::Debug {
     /// Returns the verifying key (i.e public key or address) for the identity.
     fn verifying_key(&self) -> VerifyingKey;
 
@@ -18,3 +21,98 @@ pub trait IdentityProvider: Clone + std::fmt::Debug {
     /// Computes signature for a message and returns (`r`, `s`) as (`[u8; 32]`, `[u8; 32]`).
     fn sign_message_share(&self, msg: &[u8]) -> ([u8; 32], [u8; 32]);
 }
+
+impl<T: IdentityProvider + ?Sized> IdentityProvider for Box<T> {
+    fn verifying_key(&self) -> VerifyingKey {
+        (**self).verifying_key()
+    }
+
+    fn sign(&self, msg: &[u8]) -> Signature {
+        (**self).sign(msg)
+    }
+
+    fn sign_message_share(&self, msg: &[u8]) -> ([u8; 32], [u8; 32]) {
+        (**self).sign_message_share(msg)
+    }
+}
+
+/// Interface for a collection of verifying keys for verified parties.
+///
+/// **NOTE:** Implemented for slices, arrays and vectors of verifying keys (i.e linear scan membership checks)
+/// and [`VerifiedPartyIndex`](crate::utils::VerifiedPartyIndex) (i.e constant time membership checks).
+pub trait VerifiedParties {
+    /// Returns true if the verifying key belongs to a verified party.
+    fn contains_party(&self, verifying_key: &VerifyingKey) -> bool;
+}
+
+impl VerifiedParties for [VerifyingKey] {
+    fn contains_party(&self, verifying_key: &VerifyingKey) -> bool {
+        self.contains(verifying_key)
+    }
+}
+
+impl<const N: usize> VerifiedParties for [VerifyingKey; N] {
+    fn contains_party(&self, verifying_key: &VerifyingKey) -> bool {
+        self.contains(verifying_key)
+    }
+}
+
+impl VerifiedParties for Vec<VerifyingKey> {
+    fn contains_party(&self, verifying_key: &VerifyingKey) -> bool {
+        self.contains(verifying_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::caching_identity_provider::CachingIdentityProvider;
+    use crate::crypto::Random32Bytes;
+    use crate::share::SecretShare;
+    use crate::share_split_reconstruct;
+    use crate::test_utils::MockECDSAIdentityProvider;
+
+    #[test]
+    fn boxed_identity_providers_work() {
+        // Stores different identity provider implementations in the same collection.
+        let identity_providers: Vec<Box<dyn IdentityProvider>> = vec![
+            Box::new(MockECDSAIdentityProvider::generate()),
+            Box::new(CachingIdentityProvider::new(
+                MockECDSAIdentityProvider::generate(),
+            )),
+        ];
+
+        // Generates secret share.
+        let secret_share = SecretShare::from(Random32Bytes::generate_mod_q());
+
+        for identity_provider in &identity_providers {
+            // Computes "signing share" and "sub-share".
+            let (signing_share, sub_share_b) =
+                share_split_reconstruct::split(&secret_share, identity_provider).unwrap();
+
+            // Verifies reconstructed "secret share".
+            let reconstructed_secret_share = share_split_reconstruct::reconstruct(
+                &signing_share,
+                &sub_share_b,
+                identity_provider,
+            )
+            .unwrap();
+            assert_eq!(
+                reconstructed_secret_share.to_be_bytes(),
+                secret_share.to_be_bytes()
+            );
+        }
+
+        // Verifies that shares from one identity provider don't reconstruct with the other.
+        let (signing_share, sub_share_b) =
+            share_split_reconstruct::split(&secret_share, &identity_providers[0]).unwrap();
+        assert!(share_split_reconstruct::reconstruct(
+            &signing_share,
+            &sub_share_b,
+            &identity_providers[1]
+        )
+        .map_or(true, |reconstructed_secret_share| {
+            reconstructed_secret_share.to_be_bytes() != secret_share.to_be_bytes()
+        }));
+    }
+}
@@ -1,17 +1,125 @@
 //! Utilities for core sub-protocols.
 
+use std::collections::HashSet;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::crypto::VerifyingKey;
+use crate::errors::Error;
+use crate::traits::{IdentityProvider, VerifiedParties};
+
 pub const WAMU_MESSAGE_PREFIX: &str = "\x15Wamu Signed Message:\n";
 
+/// Version of the sign-able message format (i.e the output of [`prefix_message_bytes`]).
+pub const WAMU_MESSAGE_VERSION: u8 = 1;
+
 /// Add predefined prefix to a given message.
+///
+/// See [`domain_separated_message_bytes`] for the format.
 pub fn prefix_message_bytes(message: &[u8]) -> Vec<u8> {
-    let mut result = Vec::with_capacity(WAMU_MESSAGE_PREFIX.len() + message.len());
-    result.extend_from_slice(WAMU_MESSAGE_PREFIX.as_bytes());
+    domain_separated_message_bytes(WAMU_MESSAGE_PREFIX.as_bytes(), message)
+}
+
+/// Given a domain tag and a message, returns unambiguous length-prefixed sign-able message bytes
+/// i.e `version || tag length || tag || message length || message`,
+/// where the version is 1 byte and lengths are 8 byte big-endian unsigned integers.
+pub fn domain_separated_message_bytes(tag: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(1 + 8 + tag.len() + 8 + message.len());
+    result.push(WAMU_MESSAGE_VERSION);
+    result.extend_from_slice(&(tag.len() as u64).to_be_bytes());
+    result.extend_from_slice(tag);
+    result.extend_from_slice(&(message.len() as u64).to_be_bytes());
     result.extend_from_slice(message);
     result
 }
 
+/// Prefix for Ethereum `personal_sign` messages.
+///
+/// Ref: <https://eips.ethereum.org/EIPS/eip-191>.
+pub const ETH_PERSONAL_SIGN_PREFIX: &str = "\x19Ethereum Signed Message:\n";
+
+/// Given a message, returns Ethereum `personal_sign` message bytes
+/// i.e `"\x19Ethereum Signed Message:\n" || message length (as a decimal string) || message`,
+/// matching the message bytes signed by wallets like MetaMask.
+///
+/// **NOTE:** Signatures for these message bytes use the Keccak256 message digest.
+///
+/// Ref: <https://eips.ethereum.org/EIPS/eip-191>.
+pub fn eth_personal_sign_bytes(message: &[u8]) -> Vec<u8> {
+    [
+        ETH_PERSONAL_SIGN_PREFIX.as_bytes(),
+        message.len().to_string().as_bytes(),
+        message,
+    ]
+    .concat()
+}
+
+/// Given a threshold, returns the quorum size (i.e the minimum number of parties required to form a quorum).
+///
+/// **NOTE:** Quorum size = threshold + 1.
+pub fn quorum_size(threshold: u16) -> usize {
+    threshold as usize + 1
+}
+
+/// Given a threshold and the total number of parties,
+/// returns an `Ok` result if `1 <= threshold < n_parties`, or an `InvalidThreshold` error otherwise.
+pub fn validate_threshold(threshold: u16, n_parties: u16) -> Result<(), Error> {
+    if 1 <= threshold && threshold < n_parties {
+        Ok(())
+    } else {
+        Err(Error::InvalidThreshold)
+    }
+}
+
+/// Given a number of parties (e.g the length of a list of parties),
+/// returns it as a `u16`, or a `TooManyParties` error if it doesn't fit in a `u16`.
+pub fn party_count(n_parties: usize) -> Result<u16, Error> {
+    u16::try_from(n_parties).map_err(|_| Error::TooManyParties)
+}
+
+/// Given a zero-based position (e.g in a list of parties),
+/// returns the equivalent one-based party index, or a `TooManyParties` error if it doesn't fit in a `u16`.
+pub fn party_index(position: usize) -> Result<u16, Error> {
+    position
+        .checked_add(1)
+        .ok_or(Error::TooManyParties)
+        .and_then(party_count)
+}
+
+/// Given the identity providers of all parties (in party index order),
+/// returns the list of verifying keys for the verified parties (in the same order)
+/// (e.g for building the `verified_parties` of subsequent ceremonies after key generation).
+///
+/// **NOTE:** The verifying key at position `i` belongs to the party with index `i + 1` (see [`party_index`]).
+pub fn collect_verifying_keys(identity_providers: &[impl IdentityProvider]) -> Vec<VerifyingKey> {
+    identity_providers
+        .iter()
+        .map(IdentityProvider::verifying_key)
+        .collect()
+}
+
+/// An index of verifying keys for verified parties with constant time membership checks
+/// (e.g for verifying large batches of requests against a large list of verified parties).
+#[derive(Debug, Clone, Default)]
+pub struct VerifiedPartyIndex {
+    /// Verifying keys for verified parties.
+    keys: HashSet<VerifyingKey>,
+}
+
+impl VerifiedPartyIndex {
+    /// Given a list of verifying keys for verified parties, returns an index of the verifying keys.
+    pub fn new(verified_parties: &[VerifyingKey]) -> Self {
+        Self {
+            keys: verified_parties.iter().cloned().collect(),
+        }
+    }
+}
+
+impl VerifiedParties for VerifiedPartyIndex {
+    fn contains_party(&self, verifying_key: &VerifyingKey) -> bool {
+        self.keys.contains(verifying_key)
+    }
+}
+
 /// Returns the unix timestamp in seconds.
 pub fn unix_timestamp() -> u64 {
     SystemTime::now()
@@ -19,3 +127,165 @@ pub fn unix_timestamp() -> u64 {
         .expect("Time went backwards")
         .as_secs()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{
+        EllipticCurve, KeyEncoding, MessageDigest, Signature, SignatureAlgorithm, SignatureEncoding,
+    };
+    use crate::test_utils::MockECDSAIdentityProvider;
+    use crypto_bigint::{Encoding, U256};
+
+    #[test]
+    fn eth_personal_sign_bytes_works() {
+        // Known `personal_sign` signature for "Some data".
+        // Ref: <https://web3js.readthedocs.io/en/v1.10.0/web3-eth-accounts.html#sign>.
+        let secret_key =
+            U256::from_be_hex("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318");
+        let message = b"Some data";
+        let r =
+            U256::from_be_hex("b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd");
+        let s =
+            U256::from_be_hex("6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a029");
+
+        // Verifies the message bytes.
+        let message_bytes = eth_personal_sign_bytes(message);
+        assert_eq!(message_bytes, b"\x19Ethereum Signed Message:\n9Some data");
+
+        // Verifies the known signature with the Keccak256 message digest.
+        let signing_key =
+            k256::ecdsa::SigningKey::from_bytes(&secret_key.to_be_bytes().into()).unwrap();
+        let verifying_key = VerifyingKey {
+            key: signing_key.verifying_key().to_sec1_bytes().to_vec(),
+            algo: SignatureAlgorithm::ECDSA,
+            curve: EllipticCurve::Secp256k1,
+            enc: KeyEncoding::SEC1,
+        };
+        let signature = Signature {
+            sig: k256::ecdsa::Signature::from_scalars(r.to_be_bytes(), s.to_be_bytes())
+                .unwrap()
+                .to_der()
+                .as_bytes()
+                .to_vec(),
+            algo: SignatureAlgorithm::ECDSA,
+            curve: EllipticCurve::Secp256k1,
+            hash: MessageDigest::Keccak256,
+            enc: SignatureEncoding::DER,
+        };
+        assert!(
+            crate::crypto::verify_signature(&verifying_key, &message_bytes, &signature).is_ok()
+        );
+
+        // Verifies that the signature is invalid for other message bytes.
+        assert!(crate::crypto::verify_signature(
+            &verifying_key,
+            &prefix_message_bytes(message),
+            &signature
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn quorum_size_and_threshold_validation_works() {
+        // Verifies quorum size.
+        assert_eq!(quorum_size(1), 2);
+        assert_eq!(quorum_size(u16::MAX), u16::MAX as usize + 1);
+
+        // Generates test cases for threshold validation boundaries.
+        for (threshold, n_parties, expected_result) in [
+            // Minimum threshold.
+            (1, 2, Ok(())),
+            // Maximum threshold.
+            (4, 5, Ok(())),
+            // Threshold of zero.
+            (0, 5, Err(Error::InvalidThreshold)),
+            // Threshold equal to the number of parties.
+            (5, 5, Err(Error::InvalidThreshold)),
+            // Threshold greater than the number of parties.
+            (6, 5, Err(Error::InvalidThreshold)),
+        ] {
+            // Verifies expected result.
+            assert_eq!(validate_threshold(threshold, n_parties), expected_result);
+        }
+    }
+
+    #[test]
+    fn party_count_and_index_conversion_works() {
+        // Generates test cases for conversion boundaries (i.e mocked large party counts).
+        for (position, expected_count, expected_index) in [
+            (0, Ok(0), Ok(1)),
+            (u16::MAX as usize - 1, Ok(u16::MAX - 1), Ok(u16::MAX)),
+            (u16::MAX as usize, Ok(u16::MAX), Err(Error::TooManyParties)),
+            (
+                u16::MAX as usize + 1,
+                Err(Error::TooManyParties),
+                Err(Error::TooManyParties),
+            ),
+            (
+                usize::MAX,
+                Err(Error::TooManyParties),
+                Err(Error::TooManyParties),
+            ),
+        ] {
+            // Verifies expected results.
+            assert_eq!(party_count(position), expected_count);
+            assert_eq!(party_index(position), expected_index);
+        }
+    }
+
+    #[test]
+    fn collect_verifying_keys_works() {
+        // Generates identity providers.
+        let identity_providers: Vec<MockECDSAIdentityProvider> = (0..5)
+            .map(|_| MockECDSAIdentityProvider::generate())
+            .collect();
+
+        // Collects verifying keys.
+        let verifying_keys = collect_verifying_keys(&identity_providers);
+
+        // Verifies that the verifying keys match the identity providers (in the same order).
+        assert_eq!(verifying_keys.len(), identity_providers.len());
+        for (verifying_key, identity_provider) in verifying_keys.iter().zip(&identity_providers) {
+            assert_eq!(verifying_key, &identity_provider.verifying_key());
+        }
+
+        // Verifies that the ordering is stable.
+        assert_eq!(collect_verifying_keys(&identity_providers), verifying_keys);
+    }
+
+    #[test]
+    fn domain_separated_message_bytes_works() {
+        // Generates pairs of (tag, message) inputs that collide when naively concatenated.
+        for ((tag_a, message_a), (tag_b, message_b)) in [
+            ((&b"ab"[..], &b"c"[..]), (&b"a"[..], &b"bc"[..])),
+            ((&b""[..], &b"abc"[..]), (&b"abc"[..], &b""[..])),
+            (
+                (WAMU_MESSAGE_PREFIX.as_bytes(), &b"command"[..]),
+                (&b"\x15Wamu Signed"[..], &b" Message:\ncommand"[..]),
+            ),
+        ] {
+            // Verifies that the inputs previously collided.
+            assert_eq!([tag_a, message_a].concat(), [tag_b, message_b].concat());
+
+            // Verifies that the inputs now produce distinct sign-able message bytes.
+            assert_ne!(
+                domain_separated_message_bytes(tag_a, message_a),
+                domain_separated_message_bytes(tag_b, message_b)
+            );
+        }
+
+        // Verifies the versioned and length-prefixed format.
+        assert_eq!(
+            domain_separated_message_bytes(b"tag", b"msg"),
+            [
+                &[WAMU_MESSAGE_VERSION][..],
+                &3u64.to_be_bytes(),
+                b"tag",
+                &3u64.to_be_bytes(),
+                b"msg"
+            ]
+            .concat()
+        );
+    }
+}
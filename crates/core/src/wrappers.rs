@@ -4,8 +4,8 @@ use crate::crypto::{Random32Bytes, Signature, VerifyingKey};
 use crate::errors::{Error, IdentityAuthedRequestError};
 use crate::identity_authed_request;
 use crate::identity_challenge;
-use crate::payloads::IdentityAuthedRequestPayload;
-use crate::traits::IdentityProvider;
+use crate::payloads::{IdentityAuthedRequestPayload, RequestInfo};
+use crate::traits::{IdentityProvider, VerifiedParties};
 use crate::{crypto, utils};
 
 /// Given random bytes and an identity provider, returns the verifying key and a signature of the random bytes.
@@ -28,9 +28,9 @@ pub fn verify_request_with_signature(
     random_bytes: &[u8],
     verifying_key: &VerifyingKey,
     signature: &Signature,
-    verified_parties: &[VerifyingKey],
+    verified_parties: &(impl VerifiedParties + ?Sized),
 ) -> Result<(), Error> {
-    if !verified_parties.contains(verifying_key) {
+    if !verified_parties.contains_party(verifying_key) {
         // Sender must be a verified party.
         Err(Error::UnauthorizedParty)
     } else {
@@ -49,20 +49,80 @@ pub fn verify_request_with_signature(
 pub fn verify_identity_authed_request_and_initiate_challenge(
     command: &str,
     request: &IdentityAuthedRequestPayload,
-    verified_parties: &[VerifyingKey],
+    verified_parties: &(impl VerifiedParties + ?Sized),
 ) -> Result<Random32Bytes, IdentityAuthedRequestError> {
+    verify_identity_authed_request(command, request, verified_parties)?;
+    Ok(identity_challenge::initiate())
+}
+
+/// Same as [`verify_identity_authed_request_and_initiate_challenge`] but for a request namespaced to
+/// an application-wide domain (see [`identity_authed_request::initiate_with_domain`]).
+pub fn verify_identity_authed_request_and_initiate_challenge_with_domain(
+    command: &str,
+    domain: &str,
+    request: &IdentityAuthedRequestPayload,
+    verified_parties: &(impl VerifiedParties + ?Sized),
+) -> Result<Random32Bytes, IdentityAuthedRequestError> {
+    verify_identity_authed_request_with_domain(command, domain, request, verified_parties)?;
+    Ok(identity_challenge::initiate())
+}
+
+/// Given a "command", an identity authenticated request payload and a list of verifying keys for the other parties,
+/// returns an ok result with the metadata of the request (i.e the requester, command and timestamp) for a valid request
+/// or an appropriate error result for an invalid request.
+///
+/// **NOTE:** Unlike [`verify_identity_authed_request_and_initiate_challenge`], no challenge fragment is generated
+/// (e.g for deciding whether or not to participate before initiating an identity challenge).
+pub fn verify_identity_authed_request(
+    command: &str,
+    request: &IdentityAuthedRequestPayload,
+    verified_parties: &(impl VerifiedParties + ?Sized),
+) -> Result<RequestInfo, IdentityAuthedRequestError> {
+    verify_identity_authed_request_with_verifier(command, request, || {
+        identity_authed_request::verify(request, verified_parties)
+    })
+}
+
+/// Same as [`verify_identity_authed_request`] but for a request namespaced to
+/// an application-wide domain (see [`identity_authed_request::initiate_with_domain`]).
+///
+/// **NOTE:** The domain must match the one used to initiate the request.
+pub fn verify_identity_authed_request_with_domain(
+    command: &str,
+    domain: &str,
+    request: &IdentityAuthedRequestPayload,
+    verified_parties: &(impl VerifiedParties + ?Sized),
+) -> Result<RequestInfo, IdentityAuthedRequestError> {
+    verify_identity_authed_request_with_verifier(command, request, || {
+        identity_authed_request::verify_with_domain(request, domain, verified_parties)
+    })
+}
+
+/// Given a "command", an identity authenticated request payload and a function that verifies the request,
+/// returns an ok result with the metadata of the request for a valid request
+/// or an appropriate error result for an invalid request.
+fn verify_identity_authed_request_with_verifier(
+    command: &str,
+    request: &IdentityAuthedRequestPayload,
+    verify: impl FnOnce() -> Result<(), IdentityAuthedRequestError>,
+) -> Result<RequestInfo, IdentityAuthedRequestError> {
     if command != request.command {
         // Command doesn't match request payload.
         Err(IdentityAuthedRequestError::CommandMismatch)
     } else {
-        identity_authed_request::verify(request, verified_parties)?;
-        Ok(identity_challenge::initiate())
+        verify()?;
+        Ok(RequestInfo {
+            requester: request.verifying_key.clone(),
+            command: request.command.to_string(),
+            timestamp: request.timestamp,
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::command::Command;
     use crate::errors::CryptoError;
     use crate::test_utils::MockECDSAIdentityProvider;
 
@@ -87,7 +147,7 @@ mod tests {
             (
                 vec![identity_provider.verifying_key()],
                 &identity_provider.sign(b"Hello, world!"),
-                Err(Error::Crypto(CryptoError::InvalidSignature)),
+                Err(Error::Crypto(CryptoError::SignatureVerificationFailed)),
             ),
         ] {
             // Verifies random bytes signature using verifying key.
@@ -102,4 +162,110 @@ mod tests {
             assert_eq!(result, expected_result);
         }
     }
+
+    #[test]
+    fn verify_identity_authed_request_works() {
+        // Generates identity providers for a verified and an unverified party.
+        let identity_provider = MockECDSAIdentityProvider::generate();
+        let unverified_identity_provider = MockECDSAIdentityProvider::generate();
+        let verified_parties = [identity_provider.verifying_key()];
+
+        // Generates identity authenticated request payloads.
+        let command = Command::Signing;
+        let request = identity_authed_request::initiate(command, &identity_provider);
+        let unauthorized_request =
+            identity_authed_request::initiate(command, &unverified_identity_provider);
+
+        for (command, request, expected_result) in [
+            // Valid request from a verified party should return the request metadata.
+            (
+                command.as_str(),
+                &request,
+                Ok(RequestInfo {
+                    requester: identity_provider.verifying_key(),
+                    command: command.to_string(),
+                    timestamp: request.timestamp,
+                }),
+            ),
+            // Request from an unverified party should fail.
+            (
+                command.as_str(),
+                &unauthorized_request,
+                Err(IdentityAuthedRequestError::Unauthorized(
+                    Error::UnauthorizedParty,
+                )),
+            ),
+            // Request for a different command should fail.
+            (
+                "other-command",
+                &request,
+                Err(IdentityAuthedRequestError::CommandMismatch),
+            ),
+        ] {
+            // Verifies expected result.
+            assert_eq!(
+                verify_identity_authed_request(command, request, &verified_parties),
+                expected_result
+            );
+        }
+    }
+
+    #[test]
+    fn verify_identity_authed_request_with_domain_works() {
+        // Generates identity provider.
+        let identity_provider = MockECDSAIdentityProvider::generate();
+        let verified_parties = [identity_provider.verifying_key()];
+
+        // Generates identity authenticated request payloads with and without a domain.
+        let command = Command::Signing;
+        let domain_request = identity_authed_request::initiate_with_domain(
+            command,
+            "com.example.wallet",
+            &identity_provider,
+        );
+        let request = identity_authed_request::initiate(command, &identity_provider);
+
+        for (request, domain, expected_result) in [
+            // Request for the same domain should be ok.
+            (&domain_request, "com.example.wallet", Ok(())),
+            // Request for a different domain should fail.
+            (
+                &domain_request,
+                "com.example.other",
+                Err(IdentityAuthedRequestError::Unauthorized(Error::Crypto(
+                    CryptoError::SignatureVerificationFailed,
+                ))),
+            ),
+            // Request without a domain should fail.
+            (
+                &request,
+                "com.example.wallet",
+                Err(IdentityAuthedRequestError::Unauthorized(Error::Crypto(
+                    CryptoError::SignatureVerificationFailed,
+                ))),
+            ),
+        ] {
+            // Verifies expected result.
+            assert_eq!(
+                verify_identity_authed_request_with_domain(
+                    command.as_str(),
+                    domain,
+                    request,
+                    &verified_parties
+                )
+                .map(|_| ()),
+                expected_result
+            );
+            assert_eq!(
+                verify_identity_authed_request_and_initiate_challenge_with_domain(
+                    command.as_str(),
+                    domain,
+                    request,
+                    &verified_parties
+                )
+                .map(|_| ()),
+                expected_result
+            );
+        }
+    }
 }